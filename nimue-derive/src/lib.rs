@@ -0,0 +1,226 @@
+//! Derive macros for [nimue](https://docs.rs/nimue).
+//!
+//! This crate is re-exported by nimue with the feature `derive`, and should not be used directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericParam, Generics, Index, Type,
+    WherePredicate,
+};
+
+/// Derive `AbsorbPattern`, `AbsorbWriter`, and `AbsorbReader` for a struct, absorbing its fields in order.
+///
+/// The struct is also a message of shape `()` (for `TranscriptPattern`, `ToTranscript`, and `FromTranscript`),
+/// so that it can be a component of tuples, vectors, and options.
+///
+/// Each field must implement the three traits: scalars, points, bytes, arrays, or other `Absorb` structs.
+/// A field `f` of a value labeled `label` is labeled `label.f` in the IO pattern
+/// (`label.0`, `label.1`, ... for tuple structs).
+///
+/// ```ignore
+/// use nimue::{Absorb, AbsorbPattern, AbsorbReader, AbsorbWriter, DefaultHash, IOPattern};
+///
+/// #[derive(Absorb)]
+/// struct Commitment<G: CurveGroup> {
+///     point: G,
+///     nonce: [u8; 16],
+/// }
+///
+/// let io = Commitment::<G>::add_to_pattern(IOPattern::<DefaultHash>::new("📝"), 1, "commitment");
+/// let mut merlin = io.to_merlin();
+/// commitment.add_to_transcript(&mut merlin)?;
+/// let mut arthur = io.to_arthur(merlin.transcript());
+/// let commitment = Commitment::<G>::read_from_transcript(&mut arthur)?;
+/// ```
+#[proc_macro_derive(Absorb)]
+pub fn derive_absorb(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match absorb(&input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// The generics of `input`, extended with the transcript parameter `param`
+/// and the bound `bound` on the type of each field.
+fn extend_generics(
+    input: &DeriveInput,
+    param: GenericParam,
+    types: &[&Type],
+    bound: impl Fn(&Type) -> WherePredicate,
+) -> Generics {
+    let mut generics = input.generics.clone();
+    generics.params.push(param);
+    let where_clause = generics.make_where_clause();
+    for ty in types {
+        where_clause.predicates.push(bound(ty));
+    }
+    generics
+}
+
+fn absorb(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Absorb can only be derived for structs",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        })
+        .collect::<Vec<_>>();
+    let labels = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let generics = extend_generics(
+        input,
+        parse_quote!(__P),
+        &types,
+        |ty| parse_quote!(#ty: ::nimue::AbsorbPattern<__P>),
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let pattern = quote! {
+        impl #impl_generics ::nimue::AbsorbPattern<__P> for #name #ty_generics #where_clause {
+            fn add_to_pattern(mut io: __P, count: usize, label: &str) -> __P {
+                let _ = label;
+                for _ in 0..count {
+                    #(
+                        io = <#types as ::nimue::AbsorbPattern<__P>>::add_to_pattern(
+                            io,
+                            1,
+                            &::std::format!("{}.{}", label, #labels),
+                        );
+                    )*
+                }
+                io
+            }
+        }
+    };
+
+    let generics = extend_generics(
+        input,
+        parse_quote!(__W: ?Sized),
+        &types,
+        |ty| parse_quote!(#ty: ::nimue::AbsorbWriter<__W>),
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let writer = quote! {
+        impl #impl_generics ::nimue::AbsorbWriter<__W> for #name #ty_generics #where_clause {
+            fn add_to_transcript(&self, transcript: &mut __W) -> ::nimue::ProofResult<()> {
+                let _ = &transcript;
+                #(
+                    <#types as ::nimue::AbsorbWriter<__W>>::add_to_transcript(&self.#members, transcript)?;
+                )*
+                Ok(())
+            }
+        }
+    };
+
+    let reads = types
+        .iter()
+        .map(|ty| quote!(<#ty as ::nimue::AbsorbReader<__R>>::read_from_transcript(transcript)?))
+        .collect::<Vec<_>>();
+    // fields are evaluated in the order they are written, hence read in order
+    let construct = match fields {
+        Fields::Named(_) => quote!(Self { #(#members: #reads,)* }),
+        Fields::Unnamed(_) => quote!(Self(#(#reads,)*)),
+        Fields::Unit => quote!(Self),
+    };
+    let generics = extend_generics(
+        input,
+        parse_quote!(__R: ?Sized),
+        &types,
+        |ty| parse_quote!(#ty: ::nimue::AbsorbReader<__R>),
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let reader = quote! {
+        impl #impl_generics ::nimue::AbsorbReader<__R> for #name #ty_generics #where_clause {
+            fn read_from_transcript(transcript: &mut __R) -> ::nimue::ProofResult<Self> {
+                let _ = &transcript;
+                Ok(#construct)
+            }
+        }
+    };
+
+    let messages = shapeless(input);
+
+    Ok(quote! {
+        #pattern
+        #writer
+        #reader
+        #messages
+    })
+}
+
+/// The message traits, with shape `()`, delegating to the absorb traits.
+fn shapeless(input: &DeriveInput) -> TokenStream2 {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let this: Type = parse_quote!(Self);
+    let pattern = extend_generics(
+        input,
+        parse_quote!(__P),
+        &[&this],
+        |ty| parse_quote!(#ty: ::nimue::AbsorbPattern<__P>),
+    );
+    let writer = extend_generics(
+        input,
+        parse_quote!(__W: ?Sized),
+        &[&this],
+        |ty| parse_quote!(#ty: ::nimue::AbsorbWriter<__W>),
+    );
+    let reader = extend_generics(
+        input,
+        parse_quote!(__R: ?Sized),
+        &[&this],
+        |ty| parse_quote!(#ty: ::nimue::AbsorbReader<__R>),
+    );
+    let (pattern_generics, _, pattern_where) = pattern.split_for_impl();
+    let (writer_generics, _, writer_where) = writer.split_for_impl();
+    let (reader_generics, _, reader_where) = reader.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::nimue::TranscriptShape for #name #ty_generics #where_clause {
+            type Shape = ();
+        }
+
+        impl #pattern_generics ::nimue::TranscriptPattern<__P> for #name #ty_generics #pattern_where {
+            fn declare(io: __P, count: usize, _shape: &(), label: &str) -> __P {
+                <Self as ::nimue::AbsorbPattern<__P>>::add_to_pattern(io, count, label)
+            }
+        }
+
+        impl #writer_generics ::nimue::ToTranscript<__W> for #name #ty_generics #writer_where {
+            fn to_transcript(&self, transcript: &mut __W) -> ::nimue::ProofResult<()> {
+                <Self as ::nimue::AbsorbWriter<__W>>::add_to_transcript(self, transcript)
+            }
+        }
+
+        impl #reader_generics ::nimue::FromTranscript<__R> for #name #ty_generics #reader_where {
+            fn from_transcript(transcript: &mut __R, _shape: &()) -> ::nimue::ProofResult<Self> {
+                <Self as ::nimue::AbsorbReader<__R>>::read_from_transcript(transcript)
+            }
+        }
+    }
+}
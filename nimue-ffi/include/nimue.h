@@ -0,0 +1,59 @@
+/*
+ * C ABI for nimue's byte-oriented transcripts, see the documentation of the `nimue-ffi` crate.
+ *
+ * Every function returns NIMUE_OK on success, and one of the NIMUE_ERROR_* codes otherwise.
+ * Constructors return NULL on failure. Handles must be released with the corresponding _free function.
+ */
+#ifndef NIMUE_H
+#define NIMUE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define NIMUE_OK 0
+#define NIMUE_ERROR_NULL_POINTER 1
+#define NIMUE_ERROR_INVALID_ARGUMENT 2
+#define NIMUE_ERROR_IO_PATTERN 3
+#define NIMUE_ERROR_TRAILING_BYTES 4
+
+typedef struct NimueIOPattern NimueIOPattern;
+typedef struct NimueProver NimueProver;
+typedef struct NimueVerifier NimueVerifier;
+
+/* IO patterns. Strings are NUL-terminated UTF-8. */
+NimueIOPattern *nimue_io_pattern_new(const char *domain_separator);
+int nimue_io_pattern_add_bytes(NimueIOPattern *io, size_t count, const char *label);
+int nimue_io_pattern_challenge_bytes(NimueIOPattern *io, size_t count, const char *label);
+int nimue_io_pattern_ratchet(NimueIOPattern *io);
+void nimue_io_pattern_free(NimueIOPattern *io);
+
+/* Prover state. The transcript pointer is valid until the next call on the prover. */
+NimueProver *nimue_prover_new(const NimueIOPattern *io);
+int nimue_prover_add_bytes(NimueProver *prover, const uint8_t *input, size_t len);
+int nimue_prover_public_bytes(NimueProver *prover, const uint8_t *input, size_t len);
+int nimue_prover_challenge_bytes(NimueProver *prover, uint8_t *output, size_t len);
+int nimue_prover_ratchet(NimueProver *prover);
+const uint8_t *nimue_prover_transcript(const NimueProver *prover, size_t *len);
+void nimue_prover_free(NimueProver *prover);
+
+/* Verifier state. The transcript is copied. */
+NimueVerifier *nimue_verifier_new(const NimueIOPattern *io, const uint8_t *transcript, size_t len);
+int nimue_verifier_next_bytes(NimueVerifier *verifier, uint8_t *output, size_t len);
+int nimue_verifier_public_bytes(NimueVerifier *verifier, const uint8_t *input, size_t len);
+int nimue_verifier_challenge_bytes(NimueVerifier *verifier, uint8_t *output, size_t len);
+int nimue_verifier_ratchet(NimueVerifier *verifier);
+int nimue_verifier_finalize(const NimueVerifier *verifier);
+void nimue_verifier_free(NimueVerifier *verifier);
+
+/* A static description of an error code. */
+const char *nimue_error_message(int code);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NIMUE_H */
@@ -0,0 +1,566 @@
+//! A C ABI for nimue's byte-oriented transcripts.
+//!
+//! This crate exposes the IO pattern, the prover state ([`Merlin`]), and the verifier state ([`Arthur`])
+//! over opaque handles, so that verifiers written in C, C++, or Go can reuse nimue's Fiat-Shamir transform
+//! instead of re-implementing it. The hash function is [`DefaultHash`].
+//! The matching header is `include/nimue.h`.
+//!
+//! Every function returns [`NIMUE_OK`] on success, and one of the `NIMUE_ERROR_*` codes otherwise.
+//! Constructors return a null pointer on failure.
+//! Handles must be released with the corresponding `_free` function.
+//!
+//! ```c
+//! NimueIOPattern *io = nimue_io_pattern_new("📝");
+//! nimue_io_pattern_add_bytes(io, 1, "message");
+//! nimue_io_pattern_challenge_bytes(io, 16, "challenge");
+//!
+//! NimueVerifier *verifier = nimue_verifier_new(io, transcript, transcript_len);
+//! uint8_t message[1], challenge[16];
+//! int error = nimue_verifier_next_bytes(verifier, message, 1);
+//! if (!error) error = nimue_verifier_challenge_bytes(verifier, challenge, 16);
+//! if (!error) error = nimue_verifier_finalize(verifier);
+//! nimue_verifier_free(verifier);
+//! nimue_io_pattern_free(io);
+//! ```
+use std::ffi::{c_char, c_int, CStr};
+use std::mem::ManuallyDrop;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use nimue::{
+    Arthur, ByteChallenges, ByteIOPattern, BytePublic, ByteReader, ByteWriter, DefaultHash,
+    IOPattern, IOPatternError, Merlin,
+};
+
+/// The operation succeeded.
+pub const NIMUE_OK: c_int = 0;
+/// A required pointer argument is null.
+pub const NIMUE_ERROR_NULL_POINTER: c_int = 1;
+/// An argument is invalid (e.g., a label that is not UTF-8, or a count of zero).
+pub const NIMUE_ERROR_INVALID_ARGUMENT: c_int = 2;
+/// The operation does not follow the IO pattern, or the transcript is too short.
+pub const NIMUE_ERROR_IO_PATTERN: c_int = 3;
+/// The verifier did not read the whole transcript.
+pub const NIMUE_ERROR_TRAILING_BYTES: c_int = 4;
+
+/// An IO pattern under construction.
+pub struct NimueIOPattern(IOPattern<DefaultHash>);
+
+/// The prover state.
+pub struct NimueProver(Merlin<DefaultHash>);
+
+/// The verifier state, owning a copy of the transcript it reads.
+pub struct NimueVerifier {
+    // dropped before the transcript it borrows
+    arthur: ManuallyDrop<Arthur<'static, DefaultHash>>,
+    transcript: *mut [u8],
+    unread: usize,
+}
+
+impl Drop for NimueVerifier {
+    fn drop(&mut self) {
+        // SAFETY: `arthur` is not used after this point,
+        // and `transcript` was allocated by `Box::into_raw` in `nimue_verifier_new`.
+        unsafe {
+            ManuallyDrop::drop(&mut self.arthur);
+            drop(Box::from_raw(self.transcript));
+        }
+    }
+}
+
+/// Map the result of a transcript operation to an error code.
+fn code(result: Result<(), IOPatternError>) -> c_int {
+    match result {
+        Ok(()) => NIMUE_OK,
+        Err(_) => NIMUE_ERROR_IO_PATTERN,
+    }
+}
+
+/// Build the slice of `len` bytes at `ptr`, accepting a null pointer when `len` is zero.
+unsafe fn slice<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(std::slice::from_raw_parts(ptr, len)),
+    }
+}
+
+/// Build the mutable slice of `len` bytes at `ptr`, accepting a null pointer when `len` is zero.
+unsafe fn slice_mut<'a>(ptr: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Some(&mut []),
+        (true, _) => None,
+        (false, _) => Some(std::slice::from_raw_parts_mut(ptr, len)),
+    }
+}
+
+/// Append an operation to the IO pattern `io`,
+/// turning the assertions of [`IOPattern`] into [`NIMUE_ERROR_INVALID_ARGUMENT`].
+unsafe fn append(
+    io: *mut NimueIOPattern,
+    label: *const c_char,
+    op: impl FnOnce(IOPattern<DefaultHash>, &str) -> IOPattern<DefaultHash>,
+) -> c_int {
+    let (Some(io), false) = (io.as_mut(), label.is_null()) else {
+        return NIMUE_ERROR_NULL_POINTER;
+    };
+    let Ok(label) = CStr::from_ptr(label).to_str() else {
+        return NIMUE_ERROR_INVALID_ARGUMENT;
+    };
+    match catch_unwind(AssertUnwindSafe(|| op(io.0.clone(), label))) {
+        Ok(pattern) => {
+            io.0 = pattern;
+            NIMUE_OK
+        }
+        Err(_) => NIMUE_ERROR_INVALID_ARGUMENT,
+    }
+}
+
+/// Create an IO pattern with the domain separator `domain_separator`, a NUL-terminated UTF-8 string.
+///
+/// Return a null pointer if `domain_separator` is null or not UTF-8.
+///
+/// # Safety
+///
+/// `domain_separator` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_io_pattern_new(
+    domain_separator: *const c_char,
+) -> *mut NimueIOPattern {
+    if domain_separator.is_null() {
+        return std::ptr::null_mut();
+    }
+    match CStr::from_ptr(domain_separator).to_str() {
+        Ok(domain_separator) => {
+            Box::into_raw(Box::new(NimueIOPattern(IOPattern::new(domain_separator))))
+        }
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Declare a prover message of `count` bytes, with the NUL-terminated UTF-8 label `label`.
+///
+/// Public bytes are declared in the same way.
+///
+/// # Safety
+///
+/// `io` must be null or a handle returned by [`nimue_io_pattern_new`],
+/// and `label` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_io_pattern_add_bytes(
+    io: *mut NimueIOPattern,
+    count: usize,
+    label: *const c_char,
+) -> c_int {
+    append(io, label, |io, label| io.add_bytes(count, label))
+}
+
+/// Declare a challenge of `count` bytes, with the NUL-terminated UTF-8 label `label`.
+///
+/// # Safety
+///
+/// See [`nimue_io_pattern_add_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_io_pattern_challenge_bytes(
+    io: *mut NimueIOPattern,
+    count: usize,
+    label: *const c_char,
+) -> c_int {
+    append(io, label, |io, label| io.challenge_bytes(count, label))
+}
+
+/// Declare a ratchet.
+///
+/// # Safety
+///
+/// `io` must be null or a handle returned by [`nimue_io_pattern_new`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_io_pattern_ratchet(io: *mut NimueIOPattern) -> c_int {
+    match io.as_mut() {
+        Some(io) => {
+            io.0 = io.0.clone().ratchet();
+            NIMUE_OK
+        }
+        None => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Release the IO pattern `io`.
+///
+/// Provers and verifiers created from it remain valid.
+///
+/// # Safety
+///
+/// `io` must be null or a handle returned by [`nimue_io_pattern_new`], not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_io_pattern_free(io: *mut NimueIOPattern) {
+    if !io.is_null() {
+        drop(Box::from_raw(io));
+    }
+}
+
+/// Create the prover state for the IO pattern `io`.
+///
+/// Return a null pointer if `io` is null.
+///
+/// # Safety
+///
+/// `io` must be null or a handle returned by [`nimue_io_pattern_new`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_new(io: *const NimueIOPattern) -> *mut NimueProver {
+    match io.as_ref() {
+        Some(io) => Box::into_raw(Box::new(NimueProver(io.0.to_merlin()))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Send the prover message of `len` bytes at `input`, writing it to the transcript.
+///
+/// # Safety
+///
+/// `prover` must be null or a handle returned by [`nimue_prover_new`],
+/// and `input` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_add_bytes(
+    prover: *mut NimueProver,
+    input: *const u8,
+    len: usize,
+) -> c_int {
+    match (prover.as_mut(), slice(input, len)) {
+        (Some(prover), Some(input)) => code(prover.0.add_bytes(input)),
+        _ => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Absorb the `len` public bytes at `input`, without writing them to the transcript.
+///
+/// # Safety
+///
+/// See [`nimue_prover_add_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_public_bytes(
+    prover: *mut NimueProver,
+    input: *const u8,
+    len: usize,
+) -> c_int {
+    match (prover.as_mut(), slice(input, len)) {
+        (Some(prover), Some(input)) => code(prover.0.public_bytes(input)),
+        _ => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Squeeze a challenge of `len` bytes into `output`.
+///
+/// # Safety
+///
+/// `prover` must be null or a handle returned by [`nimue_prover_new`],
+/// and `output` must be null or point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_challenge_bytes(
+    prover: *mut NimueProver,
+    output: *mut u8,
+    len: usize,
+) -> c_int {
+    match (prover.as_mut(), slice_mut(output, len)) {
+        (Some(prover), Some(output)) => code(prover.0.fill_challenge_bytes(output)),
+        _ => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Ratchet the prover state.
+///
+/// # Safety
+///
+/// `prover` must be null or a handle returned by [`nimue_prover_new`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_ratchet(prover: *mut NimueProver) -> c_int {
+    match prover.as_mut() {
+        Some(prover) => code(prover.0.ratchet()),
+        None => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Return the protocol transcript, and write its length to `len`.
+///
+/// The pointer is valid until the next call on `prover`.
+/// Return a null pointer if an argument is null.
+///
+/// # Safety
+///
+/// `prover` must be null or a handle returned by [`nimue_prover_new`],
+/// and `len` must be null or point to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_transcript(
+    prover: *const NimueProver,
+    len: *mut usize,
+) -> *const u8 {
+    match (prover.as_ref(), len.as_mut()) {
+        (Some(prover), Some(len)) => {
+            *len = prover.0.transcript().len();
+            prover.0.transcript().as_ptr()
+        }
+        _ => std::ptr::null(),
+    }
+}
+
+/// Release the prover state `prover`.
+///
+/// # Safety
+///
+/// `prover` must be null or a handle returned by [`nimue_prover_new`], not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_prover_free(prover: *mut NimueProver) {
+    if !prover.is_null() {
+        drop(Box::from_raw(prover));
+    }
+}
+
+/// Create the verifier state for the IO pattern `io`, reading the transcript of `len` bytes at `transcript`.
+///
+/// The transcript is copied, and can be released right after the call.
+/// Return a null pointer if an argument is null.
+///
+/// # Safety
+///
+/// `io` must be null or a handle returned by [`nimue_io_pattern_new`],
+/// and `transcript` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_new(
+    io: *const NimueIOPattern,
+    transcript: *const u8,
+    len: usize,
+) -> *mut NimueVerifier {
+    let (Some(io), Some(transcript)) = (io.as_ref(), slice(transcript, len)) else {
+        return std::ptr::null_mut();
+    };
+    let transcript = Box::into_raw(Box::<[u8]>::from(transcript));
+    // SAFETY: the transcript is released only after `arthur`, when the verifier is dropped.
+    let arthur = io.0.to_arthur(&*transcript);
+    Box::into_raw(Box::new(NimueVerifier {
+        arthur: ManuallyDrop::new(arthur),
+        transcript,
+        unread: len,
+    }))
+}
+
+/// Read the next prover message of `len` bytes from the transcript into `output`.
+///
+/// # Safety
+///
+/// `verifier` must be null or a handle returned by [`nimue_verifier_new`],
+/// and `output` must be null or point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_next_bytes(
+    verifier: *mut NimueVerifier,
+    output: *mut u8,
+    len: usize,
+) -> c_int {
+    let (Some(verifier), Some(output)) = (verifier.as_mut(), slice_mut(output, len)) else {
+        return NIMUE_ERROR_NULL_POINTER;
+    };
+    if len > verifier.unread {
+        return NIMUE_ERROR_IO_PATTERN;
+    }
+    let result = code(verifier.arthur.fill_next_bytes(output));
+    if result == NIMUE_OK {
+        verifier.unread -= len;
+    }
+    result
+}
+
+/// Absorb the `len` public bytes at `input`.
+///
+/// # Safety
+///
+/// `verifier` must be null or a handle returned by [`nimue_verifier_new`],
+/// and `input` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_public_bytes(
+    verifier: *mut NimueVerifier,
+    input: *const u8,
+    len: usize,
+) -> c_int {
+    match (verifier.as_mut(), slice(input, len)) {
+        (Some(verifier), Some(input)) => code(verifier.arthur.public_bytes(input)),
+        _ => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Squeeze a challenge of `len` bytes into `output`.
+///
+/// # Safety
+///
+/// See [`nimue_verifier_next_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_challenge_bytes(
+    verifier: *mut NimueVerifier,
+    output: *mut u8,
+    len: usize,
+) -> c_int {
+    match (verifier.as_mut(), slice_mut(output, len)) {
+        (Some(verifier), Some(output)) => code(verifier.arthur.fill_challenge_bytes(output)),
+        _ => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Ratchet the verifier state.
+///
+/// # Safety
+///
+/// `verifier` must be null or a handle returned by [`nimue_verifier_new`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_ratchet(verifier: *mut NimueVerifier) -> c_int {
+    match verifier.as_mut() {
+        Some(verifier) => code(verifier.arthur.ratchet()),
+        None => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Check that the verifier read the whole transcript.
+///
+/// Verifiers should call it once done, since trailing bytes would make proofs malleable.
+/// The verifier still has to be released with [`nimue_verifier_free`].
+///
+/// # Safety
+///
+/// `verifier` must be null or a handle returned by [`nimue_verifier_new`].
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_finalize(verifier: *const NimueVerifier) -> c_int {
+    match verifier.as_ref() {
+        Some(verifier) if verifier.unread == 0 => NIMUE_OK,
+        Some(_) => NIMUE_ERROR_TRAILING_BYTES,
+        None => NIMUE_ERROR_NULL_POINTER,
+    }
+}
+
+/// Release the verifier state `verifier`.
+///
+/// # Safety
+///
+/// `verifier` must be null or a handle returned by [`nimue_verifier_new`], not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn nimue_verifier_free(verifier: *mut NimueVerifier) {
+    if !verifier.is_null() {
+        drop(Box::from_raw(verifier));
+    }
+}
+
+/// Return a static, NUL-terminated description of the error code `code`.
+#[no_mangle]
+pub extern "C" fn nimue_error_message(code: c_int) -> *const c_char {
+    let message: &CStr = match code {
+        NIMUE_OK => c"success",
+        NIMUE_ERROR_NULL_POINTER => c"null pointer",
+        NIMUE_ERROR_INVALID_ARGUMENT => c"invalid argument",
+        NIMUE_ERROR_IO_PATTERN => c"the operation does not follow the IO pattern",
+        NIMUE_ERROR_TRAILING_BYTES => c"the transcript has trailing bytes",
+        _ => c"unknown error",
+    };
+    message.as_ptr()
+}
+
+#[test]
+fn test_ffi_roundtrip() {
+    unsafe {
+        let io = nimue_io_pattern_new(c"📝".as_ptr());
+        assert_eq!(
+            nimue_io_pattern_add_bytes(io, 1, c"message".as_ptr()),
+            NIMUE_OK
+        );
+        assert_eq!(nimue_io_pattern_ratchet(io), NIMUE_OK);
+        assert_eq!(
+            nimue_io_pattern_challenge_bytes(io, 16, c"challenge".as_ptr()),
+            NIMUE_OK
+        );
+
+        let prover = nimue_prover_new(io);
+        let mut challenge = [0u8; 16];
+        assert_eq!(nimue_prover_add_bytes(prover, [42].as_ptr(), 1), NIMUE_OK);
+        assert_eq!(nimue_prover_ratchet(prover), NIMUE_OK);
+        assert_eq!(
+            nimue_prover_challenge_bytes(prover, challenge.as_mut_ptr(), 16),
+            NIMUE_OK
+        );
+        let mut len = 0;
+        let transcript = nimue_prover_transcript(prover, &mut len);
+        let transcript = std::slice::from_raw_parts(transcript, len).to_vec();
+        nimue_prover_free(prover);
+
+        let verifier = nimue_verifier_new(io, transcript.as_ptr(), transcript.len());
+        // the IO pattern can be released before its provers and verifiers
+        nimue_io_pattern_free(io);
+        let mut message = [0u8; 1];
+        let mut expected = [0u8; 16];
+        assert_eq!(
+            nimue_verifier_next_bytes(verifier, message.as_mut_ptr(), 1),
+            NIMUE_OK
+        );
+        assert_eq!(nimue_verifier_ratchet(verifier), NIMUE_OK);
+        assert_eq!(
+            nimue_verifier_challenge_bytes(verifier, expected.as_mut_ptr(), 16),
+            NIMUE_OK
+        );
+        assert_eq!(message, [42]);
+        assert_eq!(expected, challenge);
+        assert_eq!(nimue_verifier_finalize(verifier), NIMUE_OK);
+        nimue_verifier_free(verifier);
+    }
+}
+
+#[test]
+fn test_ffi_errors() {
+    unsafe {
+        assert!(nimue_io_pattern_new(std::ptr::null()).is_null());
+        let io = nimue_io_pattern_new(c"📝".as_ptr());
+        assert_eq!(
+            nimue_io_pattern_add_bytes(io, 0, c"message".as_ptr()),
+            NIMUE_ERROR_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            nimue_io_pattern_add_bytes(io, 1, c"1message".as_ptr()),
+            NIMUE_ERROR_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            nimue_io_pattern_add_bytes(io, 1, std::ptr::null()),
+            NIMUE_ERROR_NULL_POINTER
+        );
+        assert_eq!(
+            nimue_io_pattern_add_bytes(io, 1, c"message".as_ptr()),
+            NIMUE_OK
+        );
+
+        // squeezing before the message is declared is rejected
+        let prover = nimue_prover_new(io);
+        let mut challenge = [0u8; 1];
+        assert_eq!(
+            nimue_prover_challenge_bytes(prover, challenge.as_mut_ptr(), 1),
+            NIMUE_ERROR_IO_PATTERN
+        );
+        nimue_prover_free(prover);
+
+        // short and long transcripts are rejected
+        let verifier = nimue_verifier_new(io, std::ptr::null(), 0);
+        let mut message = [0u8; 1];
+        assert_eq!(
+            nimue_verifier_next_bytes(verifier, message.as_mut_ptr(), 1),
+            NIMUE_ERROR_IO_PATTERN
+        );
+        nimue_verifier_free(verifier);
+        let verifier = nimue_verifier_new(io, [1, 2].as_ptr(), 2);
+        assert_eq!(
+            nimue_verifier_next_bytes(verifier, message.as_mut_ptr(), 1),
+            NIMUE_OK
+        );
+        assert_eq!(
+            nimue_verifier_finalize(verifier),
+            NIMUE_ERROR_TRAILING_BYTES
+        );
+        nimue_verifier_free(verifier);
+        nimue_io_pattern_free(io);
+
+        let message = CStr::from_ptr(nimue_error_message(NIMUE_ERROR_TRAILING_BYTES));
+        assert_eq!(
+            message.to_str().unwrap(),
+            "the transcript has trailing bytes"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Print the messages of a protocol transcript, annotated with the IO pattern they follow.
+//!
+//! ```text
+//! nimue-inspect <io-pattern> <proof> [--public <label>]...
+//! ```
+//!
+//! `<io-pattern>` is a file with the IO pattern serialized by [`IOPattern::as_bytes`],
+//! and `<proof>` is a file with either the raw protocol transcript or a [`Proof`] (as encoded by [`Proof::to_bytes`]).
+//! Patterns are assumed to be byte-oriented.
+//! The IO pattern does not tell prover messages from public inputs, since both are absorbed:
+//! absorptions labeled with `--public` are skipped, as they are not in the transcript.
+//!
+//! Each prover message is printed with its offset in the transcript, its length, its label, and its value
+//! (in hex, and as a string when printable); challenges and ratchets are printed with their labels.
+//! If the transcript is shorter than the pattern, or has trailing bytes, the offset of the failure is reported,
+//! and the tool exits with status 1.
+use std::fmt::Write;
+use std::process::ExitCode;
+
+use nimue::{DefaultHash, IOPattern, Proof};
+
+/// Split an operation of the serialized IO pattern into its kind, its length, and its label.
+fn parse_op(op: &str) -> (char, usize, &str) {
+    let digits = op[1..].bytes().take_while(u8::is_ascii_digit).count();
+    let length = op[1..1 + digits].parse().unwrap_or(0);
+    (op.as_bytes()[0] as char, length, &op[1 + digits..])
+}
+
+/// Format `bytes` as hex, followed by the string they encode if it is printable.
+fn format_value(bytes: &[u8]) -> String {
+    let mut value = bytes.iter().fold(String::new(), |mut hex, byte| {
+        // writing to a String never fails
+        write!(hex, "{byte:02x}").unwrap();
+        hex
+    });
+    if let Ok(text) = core::str::from_utf8(bytes) {
+        if !text.chars().any(char::is_control) {
+            write!(value, "  {text:?}").unwrap();
+        }
+    }
+    value
+}
+
+/// Inspect `proof` following the serialized IO pattern `pattern`, printing the messages into `output`.
+///
+/// Absorptions labeled as in `public` are public inputs, and are not read from the transcript.
+/// Return a description of the first failure.
+fn inspect(
+    pattern: &[u8],
+    proof: &[u8],
+    public: &[String],
+    output: &mut String,
+) -> Result<(), String> {
+    let io_pattern = IOPattern::<DefaultHash>::from_bytes(pattern)
+        .map_err(|error| format!("Invalid IO pattern: {error}"))?;
+    let transcript = if proof.starts_with(b"NMUE") {
+        let proof = Proof::from_bytes(proof).map_err(|error| format!("Invalid proof: {error}"))?;
+        writeln!(output, "framed proof").unwrap();
+        proof
+            .transcript_for(&io_pattern)
+            .map_err(|error| format!("Invalid proof: {error}"))?
+            .to_vec()
+    } else {
+        proof.to_vec()
+    };
+
+    // the pattern was validated above, and is valid UTF-8
+    let io = core::str::from_utf8(io_pattern.as_bytes()).unwrap();
+    let mut ops = io.split('\0');
+    writeln!(
+        output,
+        "domain separator: {:?}",
+        ops.next().unwrap_or_default()
+    )
+    .unwrap();
+    let mut offset = 0;
+    for (kind, length, label) in ops.map(parse_op) {
+        match kind {
+            'A' if public.iter().any(|public| public == label) => {
+                writeln!(output, "     -  public     {length:>5}  {label}").unwrap()
+            }
+            'A' => {
+                let Some(message) = transcript.get(offset..offset + length) else {
+                    return Err(format!(
+                        "The message {label:?} at offset {offset} needs {length} bytes, but only {} are left",
+                        transcript.len() - offset
+                    ));
+                };
+                writeln!(
+                    output,
+                    "{offset:>6}  message    {length:>5}  {label}  {}",
+                    format_value(message)
+                )
+                .unwrap();
+                offset += length;
+            }
+            'S' => writeln!(output, "     -  challenge  {length:>5}  {label}").unwrap(),
+            _ => writeln!(output, "     -  ratchet").unwrap(),
+        }
+    }
+    if offset != transcript.len() {
+        return Err(format!(
+            "The transcript has {} trailing bytes at offset {offset}",
+            transcript.len() - offset
+        ));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    const USAGE: &str = "usage: nimue-inspect <io-pattern> <proof> [--public <label>]...";
+
+    let mut files = Vec::new();
+    let mut public = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg != "--public" {
+            files.push(arg);
+        } else if let Some(label) = args.next() {
+            public.push(label);
+        } else {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+    let [pattern, proof] = &files[..] else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let read = |path: &str| std::fs::read(path).map_err(|error| format!("{path}: {error}"));
+    let (pattern, proof) = match (read(pattern), read(proof)) {
+        (Ok(pattern), Ok(proof)) => (pattern, proof),
+        (Err(error), _) | (_, Err(error)) => {
+            eprintln!("error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut output = String::new();
+    let result = inspect(&pattern, &proof, &public, &mut output);
+    print!("{output}");
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[test]
+fn test_inspect() {
+    use nimue::{ByteChallenges, ByteIOPattern, BytePublic, ByteWriter};
+
+    let io = IOPattern::<DefaultHash>::new("📝")
+        .add_bytes(2, "statement")
+        .ratchet()
+        .add_bytes(3, "commitment")
+        .challenge_bytes(16, "challenge")
+        .add_bytes(1, "response");
+    let mut merlin = io.to_merlin();
+    merlin.public_bytes(&[0, 0]).unwrap();
+    merlin.ratchet().unwrap();
+    merlin.add_bytes(b"abc").unwrap();
+    merlin.challenge_bytes::<16>().unwrap();
+    merlin.add_bytes(&[0xff]).unwrap();
+    let public = ["statement".to_string()];
+
+    let mut output = String::new();
+    inspect(io.as_bytes(), merlin.transcript(), &public, &mut output).unwrap();
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        [
+            "domain separator: \"📝\"",
+            "     -  public         2  statement",
+            "     -  ratchet",
+            "     0  message        3  commitment  616263  \"abc\"",
+            "     -  challenge     16  challenge",
+            "     3  message        1  response  ff",
+        ]
+    );
+
+    // framed proofs are unwrapped
+    let proof = Proof::new(&io, merlin.transcript()).to_bytes();
+    let mut framed = String::new();
+    inspect(io.as_bytes(), &proof, &public, &mut framed).unwrap();
+    assert_eq!(framed, format!("framed proof\n{output}"));
+
+    // truncated transcripts and trailing bytes are reported with their offset
+    let mut output = String::new();
+    let error = inspect(
+        io.as_bytes(),
+        &merlin.transcript()[..2],
+        &public,
+        &mut output,
+    );
+    assert!(error.unwrap_err().contains("offset 0"));
+    let mut transcript = merlin.transcript().to_vec();
+    transcript.push(0);
+    let error = inspect(io.as_bytes(), &transcript, &public, &mut String::new());
+    assert!(error.unwrap_err().contains("1 trailing bytes at offset 4"));
+    // without declaring the public input, the messages are misaligned
+    assert!(inspect(io.as_bytes(), merlin.transcript(), &[], &mut String::new()).is_err());
+}
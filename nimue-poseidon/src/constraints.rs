@@ -0,0 +1,130 @@
+//! The Poseidon permutation as a constraints gadget, for use with [`ArthurVar`](nimue::plugins::ark::constraints::ArthurVar).
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::FieldVar;
+use ark_relations::r1cs::SynthesisError;
+use nimue::hash::Unit;
+use nimue::plugins::ark::constraints::SpongeVar;
+
+use crate::PoseidonSponge;
+
+/// Poseidon Sponge gadget.
+///
+/// The round constants and the MDS matrix are borrowed from the native [`PoseidonSponge`] it is built from,
+/// so that the two always compute the same permutation.
+#[derive(Clone)]
+pub struct PoseidonSpongeVar<const NAME: u32, F: PrimeField, const R: usize, const N: usize> {
+    /// Number of rounds in a full-round operation.
+    pub full_rounds: usize,
+    /// Number of rounds in a partial-round operation.
+    pub partial_rounds: usize,
+    /// Exponent used in S-boxes.
+    pub alpha: u64,
+    /// Additive Round keys, indexed by `ark[round_num][state_element_index]`.
+    pub ark: &'static [[F; N]],
+    /// Maximally Distance Separating (MDS) Matrix.
+    pub mds: &'static [[F; N]],
+
+    /// Sponge state
+    pub state: Vec<FpVar<F>>,
+}
+
+impl<const NAME: u32, F: PrimeField, const R: usize, const N: usize>
+    PoseidonSpongeVar<NAME, F, R, N>
+{
+    fn apply_s_box(
+        &self,
+        state: &mut [FpVar<F>],
+        is_full_round: bool,
+    ) -> Result<(), SynthesisError> {
+        // Full rounds apply the S Box (x^alpha) to every element of state
+        if is_full_round {
+            for elem in state {
+                *elem = elem.pow_by_constant([self.alpha])?;
+            }
+        }
+        // Partial rounds apply the S Box (x^alpha) to just the first element of state
+        else {
+            state[0] = state[0].pow_by_constant([self.alpha])?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn apply_ark(&self, state: &mut [FpVar<F>], round_number: usize) {
+        state.iter_mut().enumerate().for_each(|(i, state_elem)| {
+            *state_elem += self.ark[round_number][i];
+        });
+    }
+
+    fn apply_mds(&self, state: &mut [FpVar<F>]) {
+        let new_state = self
+            .mds
+            .iter()
+            .map(|row| {
+                state
+                    .iter()
+                    .zip(row)
+                    .map(|(elem, &m)| elem * m)
+                    .fold(FpVar::zero(), |acc, x| acc + x)
+            })
+            .collect::<Vec<_>>();
+        state.clone_from_slice(&new_state);
+    }
+}
+
+impl<const NAME: u32, F, const R: usize, const N: usize> SpongeVar<F>
+    for PoseidonSpongeVar<NAME, F, R, N>
+where
+    PoseidonSponge<NAME, F, R, N>: Default,
+    F: PrimeField + Unit,
+{
+    type Native = PoseidonSponge<NAME, F, R, N>;
+
+    fn new_constant(native: &Self::Native) -> Self {
+        Self {
+            full_rounds: native.full_rounds,
+            partial_rounds: native.partial_rounds,
+            alpha: native.alpha,
+            ark: native.ark,
+            mds: native.mds,
+            state: native
+                .as_ref()
+                .iter()
+                .map(|&x| FpVar::constant(x))
+                .collect(),
+        }
+    }
+
+    fn state(&self) -> &[FpVar<F>] {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut [FpVar<F>] {
+        &mut self.state
+    }
+
+    fn permute(&mut self) -> Result<(), SynthesisError> {
+        let full_rounds_over_2 = self.full_rounds / 2;
+        let mut state = self.state.clone();
+        for i in 0..full_rounds_over_2 {
+            self.apply_ark(&mut state, i);
+            self.apply_s_box(&mut state, true)?;
+            self.apply_mds(&mut state);
+        }
+
+        for i in 0..self.partial_rounds {
+            self.apply_ark(&mut state, full_rounds_over_2 + i);
+            self.apply_s_box(&mut state, false)?;
+            self.apply_mds(&mut state);
+        }
+
+        for i in 0..full_rounds_over_2 {
+            self.apply_ark(&mut state, full_rounds_over_2 + self.partial_rounds + i);
+            self.apply_s_box(&mut state, true)?;
+            self.apply_mds(&mut state);
+        }
+        self.state = state;
+        Ok(())
+    }
+}
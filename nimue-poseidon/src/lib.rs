@@ -83,6 +83,27 @@ impl<const NAME: u32, F: PrimeField, const R: usize, const N: usize> PoseidonSpo
     }
 }
 
+#[cfg(feature = "sponge")]
+impl<const NAME: u32, F: PrimeField, const R: usize, const N: usize> PoseidonSponge<NAME, F, R, N> {
+    /// The parameters of the permutation, in the format used by the sponge of
+    /// [`ark-crypto-primitives`](https://docs.rs/ark-crypto-primitives) and by its constraints gadget.
+    ///
+    /// The permutation is the same, but the arkworks sponge places the capacity *before* the rate in the state:
+    /// the two sponges only agree on the permutation, not on the absorb and squeeze operations.
+    /// For in-circuit transcripts see instead [`ArthurVar`](nimue::plugins::ark::constraints::ArthurVar).
+    pub fn config(&self) -> ark_crypto_primitives::sponge::poseidon::PoseidonConfig<F> {
+        ark_crypto_primitives::sponge::poseidon::PoseidonConfig::new(
+            self.full_rounds,
+            self.partial_rounds,
+            self.alpha,
+            self.mds.iter().map(|row| row.to_vec()).collect(),
+            self.ark.iter().map(|row| row.to_vec()).collect(),
+            R,
+            N - R,
+        )
+    }
+}
+
 impl<const NAME: u32, F: PrimeField, const R: usize, const N: usize> zeroize::Zeroize
     for PoseidonSponge<NAME, F, R, N>
 {
@@ -108,6 +129,21 @@ where
     }
 
     fn permute(&mut self) {
+        #[cfg(feature = "simd")]
+        if let Some(sponge) = (&mut *self as &mut dyn core::any::Any)
+            .downcast_mut::<crate::f64::PoseidonPermx3_64_24>()
+        {
+            if simd::permute(sponge) {
+                return;
+            }
+        }
+        self.permute_scalar();
+    }
+}
+
+impl<const NAME: u32, F: PrimeField, const R: usize, const N: usize> PoseidonSponge<NAME, F, R, N> {
+    /// The permutation, one element at a time.
+    pub(crate) fn permute_scalar(&mut self) {
         let full_rounds_over_2 = self.full_rounds / 2;
         let mut state = self.state;
         for i in 0..full_rounds_over_2 {
@@ -162,6 +198,9 @@ macro_rules! poseidon_sponge {
     };
 }
 
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
 #[cfg(feature = "bls12-381")]
 pub mod bls12_381;
 
@@ -171,6 +210,9 @@ pub mod bn254;
 #[cfg(feature = "solinas")]
 pub mod f64;
 
+#[cfg(feature = "simd")]
+mod simd;
+
 /// Unit-tests.
 #[cfg(test)]
 mod tests;
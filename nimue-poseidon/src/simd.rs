@@ -0,0 +1,430 @@
+//! Vectorized rounds for [`PoseidonPermx3_64_24`], with AVX2 on x86-64 and NEON on AArch64.
+//!
+//! The field has modulus $p = 2^{64} - 257$, so that each element fits in one 64-bit lane:
+//! AVX2 processes four elements at once, NEON two.
+//! Neither has a 64-bit multiplication with a 128-bit result, so products are assembled from 32-bit multiplications
+//! and reduced with $2^{64} \equiv 257 \pmod p$.
+//!
+//! Elements are kept in the Montgomery form of arkworks, $x R$ with $R = 2^{64}$:
+//! the round constants are added as they are,
+//! the MDS matrix is applied with its coefficients in canonical form (since $m \cdot x R = (m x) R$),
+//! and the S-box computes $(x R)^3 \cdot R^{-2} = x^3 R$.
+//! On other targets, or without AVX2 at runtime, the same rounds run on one element at a time.
+use std::sync::OnceLock;
+
+use ark_ff::{BigInt, Field, PrimeField};
+
+use crate::f64::{Field64, PoseidonPermx3_64_24};
+
+/// The width of the permutation.
+const N: usize = 24;
+/// The modulus is $p = 2^{64} - C$.
+pub(crate) const C: u64 = 257;
+/// The modulus $p$.
+pub(crate) const P: u64 = C.wrapping_neg();
+
+const _: () = assert!(
+    N % 4 == 0,
+    "The state must split into vectors of every width."
+);
+
+/// Packed field elements, in `[0, p)`, and the operations of the rounds on them.
+///
+/// # Safety
+///
+/// The methods use the instructions of the backend: callers must ensure that the target supports them.
+pub(crate) trait Packed: Copy {
+    /// The number of elements in a vector.
+    const WIDTH: usize;
+
+    /// Load the first [`Packed::WIDTH`] elements of `src`.
+    unsafe fn load(src: &[u64]) -> Self;
+    /// Store the elements into the first [`Packed::WIDTH`] entries of `dst`.
+    unsafe fn store(self, dst: &mut [u64]);
+    /// Broadcast `x` to every lane.
+    unsafe fn splat(x: u64) -> Self;
+    /// Lane-wise addition modulo $p$.
+    unsafe fn add(self, rhs: Self) -> Self;
+    /// Lane-wise multiplication modulo $p$, of integers (not of Montgomery forms).
+    unsafe fn mul(self, rhs: Self) -> Self;
+}
+
+/// One element at a time, for targets without a vector backend.
+#[derive(Clone, Copy)]
+pub(crate) struct Scalar(u64);
+
+impl Scalar {
+    fn add_mod(a: u64, b: u64) -> u64 {
+        let (sum, carry) = a.overflowing_add(b);
+        // a + b - p = a + b + C - 2^64
+        let (reduced, borrow) = sum.overflowing_add(C);
+        if carry || borrow {
+            reduced
+        } else {
+            sum
+        }
+    }
+
+    fn mul_mod(a: u64, b: u64) -> u64 {
+        let product = a as u128 * b as u128;
+        // fold the high half twice, with 2^64 = C
+        let folded = (product as u64) as u128 + (product >> 64) * C as u128;
+        let (sum, carry) = (folded as u64).overflowing_add(((folded >> 64) as u64) * C);
+        // after a carry, the sum is small enough to add C back
+        let sum = if carry { sum + C } else { sum };
+        if sum >= P {
+            sum - P
+        } else {
+            sum
+        }
+    }
+}
+
+impl Packed for Scalar {
+    const WIDTH: usize = 1;
+
+    #[inline(always)]
+    unsafe fn load(src: &[u64]) -> Self {
+        Self(src[0])
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, dst: &mut [u64]) {
+        dst[0] = self.0;
+    }
+
+    #[inline(always)]
+    unsafe fn splat(x: u64) -> Self {
+        Self(x)
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, rhs: Self) -> Self {
+        Self(Self::add_mod(self.0, rhs.0))
+    }
+
+    #[inline(always)]
+    unsafe fn mul(self, rhs: Self) -> Self {
+        Self(Self::mul_mod(self.0, rhs.0))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::{Packed, C};
+
+    /// Four elements in a 256-bit register.
+    #[derive(Clone, Copy)]
+    pub(crate) struct Avx2(__m256i);
+
+    /// The sum `a + b`, and its carry as a mask (all ones in the lanes that overflowed).
+    #[inline(always)]
+    unsafe fn add_carry(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+        let sum = _mm256_add_epi64(a, b);
+        // AVX2 only compares signed integers: flip the sign bits to compare `sum < a` as unsigned
+        let sign = _mm256_set1_epi64x(i64::MIN);
+        let carry = _mm256_cmpgt_epi64(_mm256_xor_si256(a, sign), _mm256_xor_si256(sum, sign));
+        (sum, carry)
+    }
+
+    /// Subtract $p$ from the lanes in $[p, 2^{64})$.
+    #[inline(always)]
+    unsafe fn canonicalize(x: __m256i) -> __m256i {
+        let (reduced, carry) = add_carry(x, _mm256_set1_epi64x(C as i64));
+        _mm256_blendv_epi8(x, reduced, carry)
+    }
+
+    impl Packed for Avx2 {
+        const WIDTH: usize = 4;
+
+        #[inline(always)]
+        unsafe fn load(src: &[u64]) -> Self {
+            assert!(src.len() >= Self::WIDTH);
+            Self(_mm256_loadu_si256(src.as_ptr().cast()))
+        }
+
+        #[inline(always)]
+        unsafe fn store(self, dst: &mut [u64]) {
+            assert!(dst.len() >= Self::WIDTH);
+            _mm256_storeu_si256(dst.as_mut_ptr().cast(), self.0)
+        }
+
+        #[inline(always)]
+        unsafe fn splat(x: u64) -> Self {
+            Self(_mm256_set1_epi64x(x as i64))
+        }
+
+        #[inline(always)]
+        unsafe fn add(self, rhs: Self) -> Self {
+            let (sum, carry) = add_carry(self.0, rhs.0);
+            let c = _mm256_set1_epi64x(C as i64);
+            let (reduced, borrow) = add_carry(sum, c);
+            Self(_mm256_blendv_epi8(
+                sum,
+                reduced,
+                _mm256_or_si256(carry, borrow),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(self, rhs: Self) -> Self {
+            let (a, b) = (self.0, rhs.0);
+            let low = _mm256_set1_epi64x(0xffff_ffff);
+            let c = _mm256_set1_epi64x(C as i64);
+
+            // the 128-bit product from the four products of 32-bit halves
+            let a_hi = _mm256_srli_epi64::<32>(a);
+            let b_hi = _mm256_srli_epi64::<32>(b);
+            let p00 = _mm256_mul_epu32(a, b);
+            let p01 = _mm256_mul_epu32(a, b_hi);
+            let p10 = _mm256_mul_epu32(a_hi, b);
+            let p11 = _mm256_mul_epu32(a_hi, b_hi);
+            let mid = _mm256_add_epi64(
+                _mm256_add_epi64(_mm256_and_si256(p01, low), _mm256_and_si256(p10, low)),
+                _mm256_srli_epi64::<32>(p00),
+            );
+            let lo = _mm256_or_si256(_mm256_and_si256(p00, low), _mm256_slli_epi64::<32>(mid));
+            let hi = _mm256_add_epi64(
+                _mm256_add_epi64(p11, _mm256_srli_epi64::<32>(p01)),
+                _mm256_add_epi64(_mm256_srli_epi64::<32>(p10), _mm256_srli_epi64::<32>(mid)),
+            );
+
+            // hi * 2^64 + lo = lo + hi * C, with hi * C split in two halves of 41 bits
+            let h0c = _mm256_mul_epu32(hi, c);
+            let h1c = _mm256_mul_epu32(_mm256_srli_epi64::<32>(hi), c);
+            let (x, carry0) = add_carry(lo, h0c);
+            let (x, carry1) = add_carry(x, _mm256_slli_epi64::<32>(h1c));
+            // the multiple of 2^64 left over (carries are -1 in their lanes)
+            let k = _mm256_sub_epi64(
+                _mm256_sub_epi64(_mm256_srli_epi64::<32>(h1c), carry0),
+                carry1,
+            );
+            let (x, carry2) = add_carry(x, _mm256_mul_epu32(k, c));
+            // after a carry, x is small enough to add C back
+            let x = _mm256_add_epi64(x, _mm256_and_si256(carry2, c));
+            Self(canonicalize(x))
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) mod neon {
+    use core::arch::aarch64::*;
+
+    use super::{Packed, C};
+
+    /// Two elements in a 128-bit register.
+    #[derive(Clone, Copy)]
+    pub(crate) struct Neon(uint64x2_t);
+
+    /// The sum `a + b`, and its carry as a mask (all ones in the lanes that overflowed).
+    #[inline(always)]
+    unsafe fn add_carry(a: uint64x2_t, b: uint64x2_t) -> (uint64x2_t, uint64x2_t) {
+        let sum = vaddq_u64(a, b);
+        (sum, vcltq_u64(sum, a))
+    }
+
+    /// Subtract $p$ from the lanes in $[p, 2^{64})$.
+    #[inline(always)]
+    unsafe fn canonicalize(x: uint64x2_t) -> uint64x2_t {
+        let (reduced, carry) = add_carry(x, vdupq_n_u64(C));
+        vbslq_u64(carry, reduced, x)
+    }
+
+    impl Packed for Neon {
+        const WIDTH: usize = 2;
+
+        #[inline(always)]
+        unsafe fn load(src: &[u64]) -> Self {
+            assert!(src.len() >= Self::WIDTH);
+            Self(vld1q_u64(src.as_ptr()))
+        }
+
+        #[inline(always)]
+        unsafe fn store(self, dst: &mut [u64]) {
+            assert!(dst.len() >= Self::WIDTH);
+            vst1q_u64(dst.as_mut_ptr(), self.0)
+        }
+
+        #[inline(always)]
+        unsafe fn splat(x: u64) -> Self {
+            Self(vdupq_n_u64(x))
+        }
+
+        #[inline(always)]
+        unsafe fn add(self, rhs: Self) -> Self {
+            let (sum, carry) = add_carry(self.0, rhs.0);
+            let (reduced, borrow) = add_carry(sum, vdupq_n_u64(C));
+            Self(vbslq_u64(vorrq_u64(carry, borrow), reduced, sum))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(self, rhs: Self) -> Self {
+            let (a, b) = (self.0, rhs.0);
+            let low = vdupq_n_u64(0xffff_ffff);
+            let c = vdup_n_u32(C as u32);
+
+            // the 128-bit product from the four products of 32-bit halves
+            let (a_lo, a_hi) = (vmovn_u64(a), vshrn_n_u64::<32>(a));
+            let (b_lo, b_hi) = (vmovn_u64(b), vshrn_n_u64::<32>(b));
+            let p00 = vmull_u32(a_lo, b_lo);
+            let p01 = vmull_u32(a_lo, b_hi);
+            let p10 = vmull_u32(a_hi, b_lo);
+            let p11 = vmull_u32(a_hi, b_hi);
+            let mid = vaddq_u64(
+                vaddq_u64(vandq_u64(p01, low), vandq_u64(p10, low)),
+                vshrq_n_u64::<32>(p00),
+            );
+            let lo = vorrq_u64(vandq_u64(p00, low), vshlq_n_u64::<32>(mid));
+            let hi = vaddq_u64(
+                vaddq_u64(p11, vshrq_n_u64::<32>(p01)),
+                vaddq_u64(vshrq_n_u64::<32>(p10), vshrq_n_u64::<32>(mid)),
+            );
+
+            // hi * 2^64 + lo = lo + hi * C, with hi * C split in two halves of 41 bits
+            let h0c = vmull_u32(vmovn_u64(hi), c);
+            let h1c = vmull_u32(vshrn_n_u64::<32>(hi), c);
+            let (x, carry0) = add_carry(lo, h0c);
+            let (x, carry1) = add_carry(x, vshlq_n_u64::<32>(h1c));
+            // the multiple of 2^64 left over (carries are all ones, i.e. -1, in their lanes)
+            let k = vsubq_u64(vsubq_u64(vshrq_n_u64::<32>(h1c), carry0), carry1);
+            let (x, carry2) = add_carry(x, vmull_u32(vmovn_u64(k), c));
+            // after a carry, x is small enough to add C back
+            let x = vaddq_u64(x, vandq_u64(carry2, vdupq_n_u64(C)));
+            Self(canonicalize(x))
+        }
+    }
+}
+
+/// The parameters of [`PoseidonPermx3_64_24`], in the representation of the vectorized rounds.
+struct Tables {
+    /// The parameters as declared, to recognize sponges using them.
+    reference: PoseidonPermx3_64_24,
+    /// The round constants, in Montgomery form.
+    ark: Vec<[u64; N]>,
+    /// The columns of the MDS matrix, in canonical form: `mds[j][i]` is the coefficient at row `i` and column `j`.
+    mds: [[u64; N]; N],
+    /// $R^{-2}$, in canonical form.
+    r_inv2: u64,
+}
+
+impl Tables {
+    fn get() -> &'static Self {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(|| {
+            let reference = PoseidonPermx3_64_24::default();
+            let ark = reference
+                .ark
+                .iter()
+                .map(|round| round.map(|x| x.0 .0[0]))
+                .collect();
+            let mut mds = [[0; N]; N];
+            for (i, row) in reference.mds.iter().enumerate() {
+                for (j, coefficient) in row.iter().enumerate() {
+                    mds[j][i] = coefficient.into_bigint().0[0];
+                }
+            }
+            // the Montgomery form of one is R mod p
+            let r = Field64::from(Field64::ONE.0 .0[0]);
+            let r_inv2 = r.square().inverse().unwrap().into_bigint().0[0];
+            Self {
+                reference,
+                ark,
+                mds,
+                r_inv2,
+            }
+        })
+    }
+
+    /// Whether `sponge` uses the parameters of [`PoseidonPermx3_64_24`].
+    fn matches(&self, sponge: &PoseidonPermx3_64_24) -> bool {
+        let reference = &self.reference;
+        sponge.alpha == 3
+            && sponge.full_rounds == reference.full_rounds
+            && sponge.partial_rounds == reference.partial_rounds
+            && (core::ptr::eq(sponge.ark, reference.ark) || sponge.ark == reference.ark)
+            && (core::ptr::eq(sponge.mds, reference.mds) || sponge.mds == reference.mds)
+    }
+}
+
+/// Apply the MDS matrix to `state`.
+#[inline(always)]
+unsafe fn mds<V: Packed>(state: &mut [u64; N], tables: &Tables) {
+    let input = *state;
+    for i in (0..N).step_by(V::WIDTH) {
+        let mut acc = V::splat(0);
+        for (column, x) in tables.mds.iter().zip(input) {
+            acc = acc.add(V::load(&column[i..]).mul(V::splat(x)));
+        }
+        acc.store(&mut state[i..]);
+    }
+}
+
+/// The S-box $x \mapsto x^3$, on Montgomery forms.
+#[inline(always)]
+unsafe fn s_box<V: Packed>(x: V, tables: &Tables) -> V {
+    x.mul(x).mul(x).mul(V::splat(tables.r_inv2))
+}
+
+/// Run the rounds of the permutation on `state`, with the backend `V`.
+#[inline(always)]
+unsafe fn rounds<V: Packed>(state: &mut [u64; N], tables: &Tables) {
+    let full_rounds_over_2 = tables.reference.full_rounds / 2;
+    let partial_rounds = tables.reference.partial_rounds;
+    for (round, ark) in tables.ark.iter().enumerate() {
+        let is_full_round =
+            round < full_rounds_over_2 || round >= full_rounds_over_2 + partial_rounds;
+        for i in (0..N).step_by(V::WIDTH) {
+            let x = V::load(&state[i..]).add(V::load(&ark[i..]));
+            let x = if is_full_round { s_box(x, tables) } else { x };
+            x.store(&mut state[i..]);
+        }
+        if !is_full_round {
+            state[0] = s_box(Scalar(state[0]), tables).0;
+        }
+        mds::<V>(state, tables);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn rounds_avx2(state: &mut [u64; N], tables: &Tables) {
+    rounds::<avx2::Avx2>(state, tables)
+}
+
+/// Permute `sponge` with the vectorized rounds, if it uses the parameters of [`PoseidonPermx3_64_24`].
+///
+/// Return `false`, leaving `sponge` untouched, otherwise.
+pub(crate) fn permute(sponge: &mut PoseidonPermx3_64_24) -> bool {
+    let tables = Tables::get();
+    if !tables.matches(sponge) {
+        return false;
+    }
+    let mut state = sponge.state.map(|x| x.0 .0[0]);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: AVX2 is available at runtime.
+            unsafe { rounds_avx2(&mut state, tables) };
+        } else {
+            // SAFETY: the scalar backend uses no particular instruction.
+            unsafe { rounds::<Scalar>(&mut state, tables) };
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        // SAFETY: NEON is part of the baseline of AArch64.
+        unsafe { rounds::<neon::Neon>(&mut state, tables) };
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        // SAFETY: the scalar backend uses no particular instruction.
+        unsafe { rounds::<Scalar>(&mut state, tables) };
+    }
+
+    sponge.state = state.map(|x| Field64::new_unchecked(BigInt([x])));
+    true
+}
@@ -46,6 +46,92 @@ fn test_squeeze_bytes_from_algebraic_hash() {
     );
 }
 
+#[cfg(feature = "bls12-381")]
+#[test]
+fn test_native_scalar_challenges() {
+    use nimue::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use nimue::{IOPattern, UnitTranscript};
+
+    type F = ark_bls12_381::Fr;
+    type H = crate::bls12_381::Poseidonx5_255_3;
+
+    // scalars of the sponge's field are squeezed as units, one per scalar
+    let io = FieldIOPattern::<F>::challenge_scalars(IOPattern::<H, F>::new("test"), 3, "chal");
+    assert_eq!(io.as_bytes(), b"test\0S3chal");
+    let challenges: [F; 3] = io.to_merlin().challenge_scalars().unwrap();
+    let mut units = [F::from(0); 3];
+    io.to_merlin().fill_challenge_units(&mut units).unwrap();
+    assert_eq!(challenges, units);
+    let arthur_challenges: [F; 3] = io.to_arthur(&[]).challenge_scalars().unwrap();
+    assert_eq!(challenges, arthur_challenges);
+}
+
+#[cfg(feature = "bls12-381")]
+#[test]
+fn test_sponge_parameters() {
+    use nimue::IOPattern;
+
+    type F = ark_bls12_381::Fr;
+    type H = crate::bls12_381::Poseidonx5_255_3;
+
+    // one element of capacity, of 254 bits of information
+    let io = IOPattern::<H, F>::new("test");
+    assert_eq!((io.rate(), io.capacity()), (Some(2), Some(1)));
+    assert_eq!(io.security_level(), 127);
+}
+
+#[cfg(feature = "bls12-381")]
+#[test]
+fn test_dual_transcript() {
+    use nimue::plugins::ark::dual::DualIOPattern;
+    use nimue::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use nimue::{ByteIOPattern, ByteReader, ByteWriter, DefaultHash};
+
+    type F = ark_bls12_381::Fr;
+    type H = crate::bls12_381::Poseidonx5_255_3;
+
+    type C = ark_ff::MontBackend<ark_bls12_381::FrConfig, 4>;
+
+    let io = DualIOPattern::<DefaultHash, H, C, 4>::new("test")
+        .bytes(|io| io.add_bytes(1024, "data"))
+        .cross_commit("commit")
+        .field(|io| FieldIOPattern::<F>::challenge_scalars(io, 1, "challenge"));
+    assert_eq!(
+        io.bytes_pattern().as_bytes(),
+        b"test\0A1024data\0S32commit\0A64commit"
+    );
+    assert_eq!(
+        io.field_pattern().as_bytes(),
+        b"test\0A2commit\0S2commit\0S1challenge"
+    );
+
+    let mut merlin = io.to_merlin();
+    // the algebraic sponge can't move past the cross-commitment alone
+    assert!(io.to_merlin().field().challenge_scalars::<1>().is_err());
+    merlin.bytes().add_bytes(&[42; 1024]).unwrap();
+    merlin.cross_commit().unwrap();
+    let [challenge]: [F; 1] = merlin.field().challenge_scalars().unwrap();
+
+    let (bytes, field) = merlin.transcripts();
+    assert_eq!((bytes.len(), field.len()), (1024, 0));
+    let mut arthur = io.to_arthur(bytes, field);
+    arthur.bytes().next_bytes::<1024>().unwrap();
+    arthur.cross_commit().unwrap();
+    assert_eq!(
+        arthur.field().challenge_scalars::<1>().unwrap(),
+        [challenge]
+    );
+
+    // the challenge of the algebraic sponge depends on the data hashed by the byte sponge
+    let mut arthur = io.to_arthur(&[0; 1024], &[]);
+    arthur.bytes().next_bytes::<1024>().unwrap();
+    arthur.cross_commit().unwrap();
+    assert_ne!(
+        arthur.field().challenge_scalars::<1>().unwrap(),
+        [challenge]
+    );
+}
+
 #[cfg(feature = "bls12-381")]
 #[test]
 fn test_poseidon_bls12_381() {
@@ -199,3 +285,182 @@ fn test_poseidon_f64() {
     ];
     test_vector::<PoseidonPermx3_64_24>(&tv_x5_255_3_input, &tv_x5_255_3_output);
 }
+
+#[cfg(feature = "simd")]
+fn check_packed<V: crate::simd::Packed>() {
+    use crate::f64::Field64;
+    use crate::simd::{C, P};
+    use ark_ff::{BigInteger, Field, PrimeField};
+
+    assert_eq!(Field64::MODULUS.0[0], P);
+    let edges = [
+        0,
+        1,
+        2,
+        C - 1,
+        C,
+        C + 1,
+        1 << 32,
+        (1 << 32) - 1,
+        P / 2,
+        P - 2,
+        P - 1,
+    ];
+    let pseudorandom = (1..64u64).map(|i| Field64::from(i).pow([i]).into_bigint().to_bytes_le());
+    let values = edges
+        .into_iter()
+        .chain(pseudorandom.map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap())))
+        .collect::<Vec<_>>();
+    for &a in &values {
+        for b in values.chunks(V::WIDTH).filter(|b| b.len() == V::WIDTH) {
+            let (mut sum, mut product) = (vec![0; V::WIDTH], vec![0; V::WIDTH]);
+            // SAFETY: the caller checks that the backend is available.
+            unsafe {
+                V::splat(a).add(V::load(b)).store(&mut sum);
+                V::splat(a).mul(V::load(b)).store(&mut product);
+            }
+            for (i, &b) in b.iter().enumerate() {
+                assert_eq!(Field64::from(sum[i]), Field64::from(a) + Field64::from(b));
+                assert_eq!(
+                    Field64::from(product[i]),
+                    Field64::from(a) * Field64::from(b)
+                );
+                assert!(sum[i] < P && product[i] < P);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+#[test]
+fn test_simd_arithmetic() {
+    check_packed::<crate::simd::Scalar>();
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        check_packed::<crate::simd::avx2::Avx2>();
+    }
+    #[cfg(target_arch = "aarch64")]
+    check_packed::<crate::simd::neon::Neon>();
+}
+
+#[cfg(feature = "simd")]
+#[test]
+fn test_simd_permutation() {
+    use crate::f64::{Field64, PoseidonPermx3_64_24};
+    use ark_ff::Field;
+
+    let mut state = core::array::from_fn(|i| Field64::from(i as u64 + 1).pow([0x9e37_79b9]));
+    for _ in 0..8 {
+        let mut simd = PoseidonPermx3_64_24::default();
+        let mut scalar = PoseidonPermx3_64_24::default();
+        simd.state = state;
+        scalar.state = state;
+        assert!(crate::simd::permute(&mut simd));
+        scalar.permute_scalar();
+        assert_eq!(simd.state, scalar.state);
+        state = simd.state;
+    }
+
+    // other parameters are left to the scalar rounds
+    let mut sponge = PoseidonPermx3_64_24::default();
+    sponge.partial_rounds -= 1;
+    assert!(!crate::simd::permute(&mut sponge));
+    sponge.partial_rounds += 1;
+    sponge.alpha = 5;
+    assert!(!crate::simd::permute(&mut sponge));
+}
+
+#[cfg(all(feature = "bls12-381", feature = "r1cs"))]
+#[test]
+fn test_arthur_var_challenges() {
+    use crate::bls12_381::Poseidonx5_255_3;
+    use crate::constraints::PoseidonSpongeVar;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use nimue::plugins::ark::constraints::ArthurVar;
+    use nimue::{IOPattern, UnitTranscript};
+
+    type F = ark_bls12_381::Fr;
+
+    let io = IOPattern::<Poseidonx5_255_3, F>::new("test")
+        .absorb(3, "in")
+        .squeeze(1, "c1")
+        .ratchet()
+        .absorb(1, "in")
+        .squeeze(3, "c2");
+    let messages = [F::from(1), F::from(2), F::from(3), F::from(4)];
+
+    let mut merlin = io.to_merlin();
+    let mut challenges = [F::from(0); 4];
+    merlin.add_units(&messages[..3]).unwrap();
+    merlin.fill_challenge_units(&mut challenges[..1]).unwrap();
+    merlin.ratchet().unwrap();
+    merlin.add_units(&messages[3..]).unwrap();
+    merlin.fill_challenge_units(&mut challenges[1..]).unwrap();
+
+    let cs = ConstraintSystem::<F>::new_ref();
+    let messages_var = messages
+        .iter()
+        .map(|&m| FpVar::new_witness(cs.clone(), || Ok(m)))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let mut arthur = ArthurVar::<F, PoseidonSpongeVar<255, F, 2, 3>>::new(&io);
+    let mut challenges_var = vec![FpVar::constant(F::from(0)); 4];
+    arthur.public_units(&messages_var[..3]).unwrap();
+    arthur
+        .fill_challenge_units(&mut challenges_var[..1])
+        .unwrap();
+    arthur.ratchet().unwrap();
+    arthur.public_units(&messages_var[3..]).unwrap();
+    arthur
+        .fill_challenge_units(&mut challenges_var[1..])
+        .unwrap();
+
+    assert_eq!(challenges_var.value().unwrap(), challenges);
+    assert!(cs.is_satisfied().unwrap());
+
+    // operations not following the IO pattern are rejected
+    let mut arthur = ArthurVar::<F, PoseidonSpongeVar<255, F, 2, 3>>::new(&io);
+    assert!(arthur.fill_challenge_units(&mut challenges_var).is_err());
+}
+
+#[cfg(all(feature = "bls12-381", feature = "sponge", feature = "r1cs"))]
+#[test]
+fn test_arkworks_sponge_config() {
+    use crate::bls12_381::PoseidonPermx5_255_3;
+    use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+    use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_crypto_primitives::sponge::CryptographicSponge;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    type F = ark_bls12_381::Fr;
+
+    let config = PoseidonPermx5_255_3::default().config();
+    let input = F::from(0x42);
+
+    let mut sponge = PoseidonSponge::new(&config);
+    sponge.absorb(&input);
+    let output: Vec<F> = sponge.squeeze_native_field_elements(1);
+
+    // the same permutation, with the state laid out as in arkworks (capacity first)
+    let mut native = PoseidonPermx5_255_3::default();
+    native
+        .as_mut()
+        .clone_from_slice(&[F::from(0), input, F::from(0)]);
+    native.permute();
+    assert_eq!(output[0], native.as_ref()[1]);
+
+    let cs = ConstraintSystem::<F>::new_ref();
+    let mut sponge_var = PoseidonSpongeVar::new(cs.clone(), &config);
+    let input_var = FpVar::new_witness(cs.clone(), || Ok(input)).unwrap();
+    sponge_var.absorb(&input_var).unwrap();
+    let output_var = sponge_var.squeeze_field_elements(1).unwrap();
+    assert_eq!(output_var.value().unwrap(), output);
+    assert!(cs.is_satisfied().unwrap());
+}
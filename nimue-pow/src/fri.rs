@@ -0,0 +1,214 @@
+//! Transcript helpers for the [FRI](https://eccc.weizmann.ac.il/report/2017/134/) low-degree test.
+//!
+//! A FRI proof with $k$ folding rounds has the following shape:
+//!
+//! ```text
+//!     (A32root S<F>folding)^k A<m F>final <proof-of-work> S<16 q>queries
+//! ```
+//! In each round, the prover commits to the folded codeword with a 32-byte Merkle root,
+//! and the verifier replies with a folding challenge.
+//! The prover then sends the $m$ coefficients of the final polynomial,
+//! grinds a proof of work (see [`PoWChallenge`]),
+//! and the verifier samples $q$ distinct query indices in the evaluation domain.
+use std::collections::HashMap;
+
+use ark_ff::Field;
+use nimue::plugins::ark::{FieldChallenges, FieldIOPattern, FieldReader, FieldWriter};
+use nimue::{ByteChallenges, ByteIOPattern, ByteReader, ByteWriter, ProofResult};
+
+use crate::{PoWChallenge, PoWIOPattern, PowStrategy};
+
+/// The shape of a FRI proof, shared by the IO pattern, the prover, and the verifier.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FriParameters {
+    /// The number of folding rounds.
+    pub folding_rounds: usize,
+    /// The number of coefficients of the final polynomial.
+    pub final_coeffs: usize,
+    /// The size of the evaluation domain in which queries are sampled.
+    pub domain_size: usize,
+    /// The number of (distinct) queries.
+    pub queries: usize,
+    /// The difficulty of the proof of work, in bits.
+    pub pow_bits: f64,
+}
+
+/// Sample `count` distinct indices in `[0, domain_size)` from `bytes`, uniformly without replacement.
+///
+/// Sampling follows a partial Fisher-Yates shuffle of `[0, domain_size)`:
+/// the $j$-th index is drawn from the $16$ bytes `bytes[16 j..16 (j+1)]`,
+/// interpreted as a little-endian integer and reduced modulo `domain_size - j`
+/// (with a bias at most $2^{-64}$ for domains of size up to $2^{64}$).
+///
+/// # Panics
+///
+/// Panics if `count > domain_size` or if `bytes` is shorter than `16 * count`.
+pub fn sample_without_replacement(bytes: &[u8], domain_size: usize, count: usize) -> Vec<usize> {
+    assert!(
+        count <= domain_size,
+        "Cannot sample more indices than the domain size."
+    );
+    // the positions of the (virtual) shuffled domain that differ from the identity
+    let mut swapped = HashMap::new();
+    bytes[..16 * count]
+        .chunks(16)
+        .enumerate()
+        .map(|(j, chunk)| {
+            let r = u128::from_le_bytes(chunk.try_into().unwrap());
+            let i = j + (r % (domain_size - j) as u128) as usize;
+            let index = *swapped.get(&i).unwrap_or(&i);
+            swapped.insert(i, *swapped.get(&j).unwrap_or(&j));
+            index
+        })
+        .collect()
+}
+
+/// Declare a FRI proof in the IO pattern.
+pub trait FriIOPattern<F: Field> {
+    fn add_fri(self, params: &FriParameters, label: &str) -> Self;
+}
+
+/// Prover's side of a FRI proof.
+pub trait FriWriter<F: Field> {
+    /// Add the Merkle root of the current round, and return the folding challenge.
+    fn add_fri_root(&mut self, root: &[u8; 32]) -> ProofResult<F>;
+    /// Add the coefficients of the final polynomial.
+    fn add_fri_final(&mut self, coeffs: &[F]) -> ProofResult<()>;
+}
+
+/// Verifier's side of a FRI proof.
+pub trait FriReader<F: Field> {
+    /// Read the Merkle root of the current round, and return it along with the folding challenge.
+    fn next_fri_root(&mut self) -> ProofResult<([u8; 32], F)>;
+    /// Read the coefficients of the final polynomial.
+    fn next_fri_final(&mut self, params: &FriParameters) -> ProofResult<Vec<F>>;
+}
+
+/// Proof of work and query sampling, for both the prover and the verifier.
+pub trait FriQueries {
+    /// Solve (for the prover) or check (for the verifier) the proof of work,
+    /// and sample the query indices.
+    fn challenge_fri_queries<S: PowStrategy>(
+        &mut self,
+        params: &FriParameters,
+    ) -> ProofResult<Vec<usize>>;
+}
+
+impl<F, IOPattern> FriIOPattern<F> for IOPattern
+where
+    F: Field,
+    IOPattern: FieldIOPattern<F> + ByteIOPattern + PoWIOPattern,
+{
+    fn add_fri(mut self, params: &FriParameters, label: &str) -> Self {
+        for _ in 0..params.folding_rounds {
+            self = self
+                .add_bytes(32, &format!("{}:root", label))
+                .challenge_scalars(1, &format!("{}:folding", label));
+        }
+        self.add_scalars(params.final_coeffs, &format!("{}:final", label))
+            .challenge_pow(&format!("{}:pow", label))
+            .challenge_bytes(16 * params.queries, &format!("{}:queries", label))
+    }
+}
+
+impl<F, T> FriWriter<F> for T
+where
+    F: Field,
+    T: ByteWriter + FieldWriter<F> + FieldChallenges<F>,
+{
+    fn add_fri_root(&mut self, root: &[u8; 32]) -> ProofResult<F> {
+        self.add_bytes(root)?;
+        let [folding] = self.challenge_scalars()?;
+        Ok(folding)
+    }
+
+    fn add_fri_final(&mut self, coeffs: &[F]) -> ProofResult<()> {
+        self.add_scalars(coeffs)
+    }
+}
+
+impl<F, T> FriReader<F> for T
+where
+    F: Field,
+    T: ByteReader + FieldReader<F> + FieldChallenges<F>,
+{
+    fn next_fri_root(&mut self) -> ProofResult<([u8; 32], F)> {
+        let root = self.next_bytes()?;
+        let [folding] = self.challenge_scalars()?;
+        Ok((root, folding))
+    }
+
+    fn next_fri_final(&mut self, params: &FriParameters) -> ProofResult<Vec<F>> {
+        let mut coeffs = vec![F::ZERO; params.final_coeffs];
+        self.fill_next_scalars(&mut coeffs)?;
+        Ok(coeffs)
+    }
+}
+
+impl<T> FriQueries for T
+where
+    T: PoWChallenge + ByteChallenges,
+{
+    fn challenge_fri_queries<S: PowStrategy>(
+        &mut self,
+        params: &FriParameters,
+    ) -> ProofResult<Vec<usize>> {
+        self.challenge_pow::<S>(params.pow_bits)?;
+        let mut bytes = vec![0u8; 16 * params.queries];
+        self.fill_challenge_bytes(&mut bytes)?;
+        Ok(sample_without_replacement(
+            &bytes,
+            params.domain_size,
+            params.queries,
+        ))
+    }
+}
+
+#[test]
+fn test_sample_without_replacement() {
+    let bytes = (0..=255u8).cycle().take(16 * 8).collect::<Vec<_>>();
+    let mut indices = sample_without_replacement(&bytes, 8, 8);
+    // sampling the whole domain gives a permutation
+    indices.sort_unstable();
+    assert_eq!(indices, (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_fri_transcript() {
+    use crate::blake3::Blake3PoW;
+    use ark_curve25519::Fr;
+    use nimue::{DefaultHash, IOPattern};
+
+    let params = FriParameters {
+        folding_rounds: 2,
+        final_coeffs: 2,
+        domain_size: 64,
+        queries: 10,
+        pow_bits: 5.0,
+    };
+    let iopattern = FriIOPattern::<Fr>::add_fri(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        &params,
+        "fri",
+    );
+
+    let mut merlin = iopattern.to_merlin();
+    let alpha0: Fr = merlin.add_fri_root(&[1; 32]).unwrap();
+    let alpha1: Fr = merlin.add_fri_root(&[2; 32]).unwrap();
+    merlin.add_fri_final(&[Fr::from(3), Fr::from(4)]).unwrap();
+    let queries = merlin.challenge_fri_queries::<Blake3PoW>(&params).unwrap();
+
+    let mut arthur = iopattern.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_fri_root().unwrap(), ([1; 32], alpha0));
+    assert_eq!(arthur.next_fri_root().unwrap(), ([2; 32], alpha1));
+    let final_coeffs: Vec<Fr> = arthur.next_fri_final(&params).unwrap();
+    assert_eq!(final_coeffs, vec![Fr::from(3), Fr::from(4)]);
+    let arthur_queries = arthur.challenge_fri_queries::<Blake3PoW>(&params).unwrap();
+    assert_eq!(queries, arthur_queries);
+
+    let mut distinct = queries.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct.len(), params.queries);
+    assert!(queries.iter().all(|&i| i < params.domain_size));
+}
@@ -1,4 +1,6 @@
 pub mod blake3;
+#[cfg(feature = "ark")]
+pub mod fri;
 pub mod keccak;
 
 use nimue::{
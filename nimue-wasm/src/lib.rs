@@ -0,0 +1,229 @@
+//! [wasm-bindgen](https://github.com/rustwasm/wasm-bindgen) bindings for nimue's byte-oriented transcripts.
+//!
+//! This crate exposes [`IOPattern`](nimue::IOPattern), [`Merlin`](nimue::Merlin), and [`Arthur`](nimue::Arthur)
+//! to JavaScript, with [`DefaultHash`] as hash function,
+//! so that provers and verifiers running in the browser produce (and accept) the same transcripts as native code.
+//! Errors are thrown as JavaScript `Error`s.
+//!
+//! ```js
+//! import { IOPattern, Merlin, Arthur } from "nimue-wasm";
+//!
+//! const io = new IOPattern("📝").addBytes(1, "message").challengeBytes(16, "challenge");
+//! const merlin = new Merlin(io);
+//! merlin.addBytes(new Uint8Array([42]));
+//! const challenge = merlin.challengeBytes(16);
+//!
+//! const arthur = new Arthur(io, merlin.transcript());
+//! arthur.nextBytes(1);
+//! arthur.challengeBytes(16);
+//! arthur.finalize();
+//! ```
+use std::mem::ManuallyDrop;
+
+use nimue::{ByteChallenges, ByteIOPattern, BytePublic, ByteReader, ByteWriter, DefaultHash};
+use wasm_bindgen::prelude::*;
+
+/// Convert an error of nimue to a JavaScript `Error`.
+fn js_error(error: impl std::fmt::Display) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+/// Check the arguments of an operation of the IO pattern,
+/// since panics cannot be caught on `wasm32-unknown-unknown`.
+fn check(count: usize, label: &str) -> Result<(), JsError> {
+    if count == 0 {
+        return Err(JsError::new("Count must be positive."));
+    }
+    nimue::check_label(label).map_err(js_error)
+}
+
+/// The IO pattern of a protocol, see [`nimue::IOPattern`].
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct IOPattern(nimue::IOPattern<DefaultHash>);
+
+#[wasm_bindgen]
+impl IOPattern {
+    /// Create an IO pattern with the domain separator `domain_separator`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(domain_separator: &str) -> Result<IOPattern, JsError> {
+        if domain_separator.contains('\0') {
+            return Err(JsError::new(
+                "Domain separator cannot contain the separator BYTE.",
+            ));
+        }
+        Ok(Self(nimue::IOPattern::new(domain_separator)))
+    }
+
+    /// Declare a prover message (or public input) of `count` bytes.
+    #[wasm_bindgen(js_name = addBytes)]
+    pub fn add_bytes(&self, count: usize, label: &str) -> Result<IOPattern, JsError> {
+        check(count, label)?;
+        Ok(Self(self.0.clone().add_bytes(count, label)))
+    }
+
+    /// Declare a challenge of `count` bytes.
+    #[wasm_bindgen(js_name = challengeBytes)]
+    pub fn challenge_bytes(&self, count: usize, label: &str) -> Result<IOPattern, JsError> {
+        check(count, label)?;
+        Ok(Self(self.0.clone().challenge_bytes(count, label)))
+    }
+
+    /// Declare a ratchet.
+    pub fn ratchet(&self) -> IOPattern {
+        Self(self.0.clone().ratchet())
+    }
+
+    /// The IO pattern, as bytes.
+    #[wasm_bindgen(js_name = asBytes)]
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+}
+
+/// The prover state, see [`nimue::Merlin`].
+///
+/// The private randomness is drawn from `crypto.getRandomValues`.
+#[wasm_bindgen]
+pub struct Merlin(nimue::Merlin<DefaultHash>);
+
+#[wasm_bindgen]
+impl Merlin {
+    /// Create the prover state for `io`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(io: &IOPattern) -> Merlin {
+        Self(io.0.to_merlin())
+    }
+
+    /// Send the prover message `input`, writing it to the transcript.
+    #[wasm_bindgen(js_name = addBytes)]
+    pub fn add_bytes(&mut self, input: &[u8]) -> Result<(), JsError> {
+        self.0.add_bytes(input).map_err(js_error)
+    }
+
+    /// Absorb the public input `input`, without writing it to the transcript.
+    #[wasm_bindgen(js_name = publicBytes)]
+    pub fn public_bytes(&mut self, input: &[u8]) -> Result<(), JsError> {
+        self.0.public_bytes(input).map_err(js_error)
+    }
+
+    /// Squeeze a challenge of `len` bytes.
+    #[wasm_bindgen(js_name = challengeBytes)]
+    pub fn challenge_bytes(&mut self, len: usize) -> Result<Vec<u8>, JsError> {
+        let mut output = vec![0; len];
+        self.0.fill_challenge_bytes(&mut output).map_err(js_error)?;
+        Ok(output)
+    }
+
+    /// Ratchet the prover state.
+    pub fn ratchet(&mut self) -> Result<(), JsError> {
+        self.0.ratchet().map_err(js_error)
+    }
+
+    /// The current protocol transcript.
+    pub fn transcript(&self) -> Vec<u8> {
+        self.0.transcript().to_vec()
+    }
+}
+
+/// The verifier state, see [`nimue::Arthur`].
+///
+/// It owns a copy of the transcript it reads.
+#[wasm_bindgen]
+pub struct Arthur {
+    // dropped before the transcript it borrows
+    arthur: ManuallyDrop<nimue::Arthur<'static, DefaultHash>>,
+    transcript: *mut [u8],
+    unread: usize,
+}
+
+impl Drop for Arthur {
+    fn drop(&mut self) {
+        // SAFETY: `arthur` is not used after this point,
+        // and `transcript` was allocated by `Box::into_raw` in `Arthur::new`.
+        unsafe {
+            ManuallyDrop::drop(&mut self.arthur);
+            drop(Box::from_raw(self.transcript));
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Arthur {
+    /// Create the verifier state for `io`, reading `transcript`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(io: &IOPattern, transcript: Vec<u8>) -> Arthur {
+        let unread = transcript.len();
+        let transcript = Box::into_raw(transcript.into_boxed_slice());
+        // SAFETY: the transcript is released only after `arthur`, when the verifier is dropped.
+        let arthur = io.0.to_arthur(unsafe { &*transcript });
+        Self {
+            arthur: ManuallyDrop::new(arthur),
+            transcript,
+            unread,
+        }
+    }
+
+    /// Read the next prover message, of `len` bytes.
+    #[wasm_bindgen(js_name = nextBytes)]
+    pub fn next_bytes(&mut self, len: usize) -> Result<Vec<u8>, JsError> {
+        let mut output = vec![0; len];
+        self.arthur.fill_next_bytes(&mut output).map_err(js_error)?;
+        self.unread -= len;
+        Ok(output)
+    }
+
+    /// Absorb the public input `input`.
+    #[wasm_bindgen(js_name = publicBytes)]
+    pub fn public_bytes(&mut self, input: &[u8]) -> Result<(), JsError> {
+        self.arthur.public_bytes(input).map_err(js_error)
+    }
+
+    /// Squeeze a challenge of `len` bytes.
+    #[wasm_bindgen(js_name = challengeBytes)]
+    pub fn challenge_bytes(&mut self, len: usize) -> Result<Vec<u8>, JsError> {
+        let mut output = vec![0; len];
+        self.arthur
+            .fill_challenge_bytes(&mut output)
+            .map_err(js_error)?;
+        Ok(output)
+    }
+
+    /// Ratchet the verifier state.
+    pub fn ratchet(&mut self) -> Result<(), JsError> {
+        self.arthur.ratchet().map_err(js_error)
+    }
+
+    /// Check that the whole transcript was read, since trailing bytes would make proofs malleable.
+    pub fn finalize(&self) -> Result<(), JsError> {
+        if self.unread != 0 {
+            return Err(JsError::new("The transcript has trailing bytes"));
+        }
+        Ok(())
+    }
+}
+
+// the bindings cannot throw on native targets, so only the honest run is tested here
+#[test]
+fn test_wasm_roundtrip() {
+    let io = IOPattern::new("📝")
+        .and_then(|io| io.add_bytes(1, "message"))
+        .map(|io| io.ratchet())
+        .and_then(|io| io.challenge_bytes(16, "challenge"))
+        .unwrap();
+    let mut merlin = Merlin::new(&io);
+    merlin.add_bytes(&[42]).unwrap();
+    merlin.ratchet().unwrap();
+    let challenge = merlin.challenge_bytes(16).unwrap();
+
+    // the transcript is the one of the native prover
+    let mut native = io.0.to_merlin();
+    native.add_bytes(&[42]).unwrap();
+    assert_eq!(merlin.transcript(), native.transcript());
+
+    let mut arthur = Arthur::new(&io, merlin.transcript());
+    assert_eq!(arthur.next_bytes(1).unwrap(), [42]);
+    arthur.ratchet().unwrap();
+    assert_eq!(arthur.challenge_bytes(16).unwrap(), challenge);
+    arthur.finalize().unwrap();
+}
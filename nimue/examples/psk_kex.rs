@@ -0,0 +1,50 @@
+/// Example: a key exchange with a pre-shared key, deriving the session key from the transcript.
+///
+/// Two parties sharing a secret key (the *pre-shared key*) exchange fresh nonces,
+/// and confirm to each other that they know the pre-shared key and saw the same nonces:
+///
+/// - I -> R: the initiator nonce
+/// - R -> I: the responder nonce, and the responder confirmation
+/// - I -> R: the initiator confirmation
+///
+/// Each party keeps a `nimue::Safe` sponge over the IO pattern `nimue::protocols::kex::io_pattern`,
+/// absorbing the pre-shared key and the nonces, squeezing the confirmations, and finally the session key.
+/// Unlike proofs, where the prover and the verifier play different roles over the same transcript,
+/// both parties here absorb and squeeze the same values, and the messages travel interactively.
+use nimue::protocols::kex::{self, Initiator, Responder};
+use nimue::{DefaultHash, ProofResult};
+use rand::rngs::OsRng;
+
+fn main() -> ProofResult<()> {
+    // Both parties already share this key.
+    let mut psk = [0u8; kex::KEY_SIZE];
+    rand::RngCore::fill_bytes(&mut OsRng, &mut psk);
+    println!("IO pattern: {:?}", kex::io_pattern::<DefaultHash>());
+
+    // The initiator starts, and sends its nonce.
+    let (initiator, initiator_nonce) = Initiator::<DefaultHash>::new(&psk, &mut OsRng)?;
+    println!("I -> R: {}", hex::encode(initiator_nonce));
+
+    // The responder answers with its nonce and its confirmation.
+    let (responder, responder_nonce, responder_confirmation) =
+        Responder::<DefaultHash>::new(&psk, &initiator_nonce, &mut OsRng)?;
+    println!(
+        "R -> I: {} {}",
+        hex::encode(responder_nonce),
+        hex::encode(responder_confirmation)
+    );
+
+    // The initiator checks the responder, and sends its confirmation.
+    let (initiator_confirmation, initiator_key) =
+        initiator.finish(&responder_nonce, &responder_confirmation)?;
+    println!("I -> R: {}", hex::encode(initiator_confirmation));
+
+    // The responder checks the initiator.
+    let responder_key = responder.finish(&initiator_confirmation)?;
+    assert_eq!(initiator_key, responder_key);
+    println!(
+        "Both parties derived the session key {}",
+        hex::encode(initiator_key)
+    );
+    Ok(())
+}
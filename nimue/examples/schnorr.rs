@@ -21,6 +21,7 @@
 use ark_ec::{CurveGroup, PrimeGroup};
 use ark_std::UniformRand;
 use nimue::plugins::ark::*;
+use nimue::RatchetPolicy;
 use rand::rngs::OsRng;
 
 /// Extend the IO pattern with the Schnorr protocol.
@@ -42,6 +43,8 @@ where
 {
     fn new_schnorr_proof(domsep: &str) -> Self {
         IOPattern::new(domsep)
+            // enforce the ratchet between the statement and the challenge
+            .with_ratchet_policy(RatchetPolicy::BeforeFirstChallenge)
             .add_schnorr_statement()
             .add_schnorr_io()
     }
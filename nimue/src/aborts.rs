@@ -0,0 +1,92 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Arthur, ByteIOPattern, ByteReader, ByteWriter, DuplexHash, IOPatternError, Merlin, ProofResult,
+};
+
+/// The number of bytes of the retry counter.
+const COUNTER_BYTES: usize = 4;
+
+/// Declare the retry counter of a rejection-sampling loop in the IO pattern, see [`Merlin::retry`].
+pub trait AbortIOPattern {
+    /// Declare the retry counter, to be followed by the operations of a single attempt.
+    fn add_retry_counter(self, label: &str) -> Self;
+}
+
+impl<T: ByteIOPattern> AbortIOPattern for T {
+    fn add_retry_counter(self, label: &str) -> Self {
+        self.add_bytes(COUNTER_BYTES, label)
+    }
+}
+
+impl<H, R> Merlin<H, u8, R>
+where
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    /// Run a rejection-sampling loop, for Fiat-Shamir with aborts.
+    ///
+    /// Each attempt writes its (32-bit, little-endian) index in the transcript,
+    /// then runs `attempt`, which returns `Ok(None)` to reject.
+    /// On rejection, the verifier's state and the transcript are restored to what they were before the attempt,
+    /// so that the proof only contains the last attempt, and its index binds the number of iterations into the transcript.
+    /// The prover's private coins are *not* restored: each attempt gets fresh randomness.
+    ///
+    /// Return an error if `max_attempts` attempts were rejected.
+    ///
+    /// ```
+    /// use nimue::{AbortIOPattern, ByteChallenges, ByteIOPattern, ByteWriter, DefaultHash, IOPattern};
+    /// use rand::RngCore;
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝")
+    ///     .add_retry_counter("attempt")
+    ///     .add_bytes(1, "commitment")
+    ///     .challenge_bytes(1, "challenge");
+    /// let mut merlin = io.to_merlin();
+    /// // reject until the challenge is even
+    /// let (attempt, challenge) = merlin
+    ///     .retry(64, |merlin, attempt| {
+    ///         let commitment = merlin.rng().next_u32() as u8;
+    ///         merlin.add_bytes(&[commitment])?;
+    ///         let [challenge] = merlin.challenge_bytes()?;
+    ///         Ok((challenge % 2 == 0).then_some((attempt, challenge)))
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let mut arthur = io.to_arthur(merlin.transcript());
+    /// assert_eq!(arthur.retry_counter(64).unwrap(), attempt);
+    /// ```
+    pub fn retry<T>(
+        &mut self,
+        max_attempts: u32,
+        mut attempt: impl FnMut(&mut Self, u32) -> ProofResult<Option<T>>,
+    ) -> ProofResult<T> {
+        let safe = self.safe.clone();
+        let transcript_len = self.transcript.len();
+        let dedup = self.dedup.clone();
+        for counter in 0..max_attempts {
+            self.add_bytes(&counter.to_le_bytes())?;
+            if let Some(output) = attempt(self, counter)? {
+                return Ok(output);
+            }
+            self.safe = safe.clone();
+            self.transcript.truncate(transcript_len);
+            self.dedup = dedup.clone();
+        }
+        Err(IOPatternError::from(format!("All the {max_attempts} attempts were rejected")).into())
+    }
+}
+
+impl<H: DuplexHash> Arthur<'_, H> {
+    /// Read the retry counter written by [`Merlin::retry`].
+    ///
+    /// Return an error if the counter is not smaller than `max_attempts`.
+    pub fn retry_counter(&mut self, max_attempts: u32) -> Result<u32, IOPatternError> {
+        let counter = u32::from_le_bytes(self.next_bytes::<COUNTER_BYTES>()?);
+        if counter < max_attempts {
+            Ok(counter)
+        } else {
+            Err(format!("Retry counter {counter} exceeds {max_attempts} attempts").into())
+        }
+    }
+}
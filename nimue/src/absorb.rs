@@ -0,0 +1,65 @@
+use crate::{ByteIOPattern, ByteReader, ByteWriter, ProofResult};
+
+/// Declare values of a type in the IO pattern `P`.
+///
+/// The shape of the values (and hence the IO pattern) is fully determined by their type.
+/// Together with [`AbsorbWriter`] and [`AbsorbReader`], this trait is implemented for bytes, arrays,
+/// and, in the plugins, for scalars and points.
+/// With the feature `derive`, [`derive@Absorb`](crate::Absorb) implements the three traits for structs
+/// whose fields implement them, absorbing the fields in order.
+pub trait AbsorbPattern<P> {
+    /// Declare `count` values, labeled `label`.
+    fn add_to_pattern(io: P, count: usize, label: &str) -> P;
+}
+
+/// Add a value to the protocol transcript of `W`, following the layout of [`AbsorbPattern`].
+pub trait AbsorbWriter<W: ?Sized> {
+    fn add_to_transcript(&self, transcript: &mut W) -> ProofResult<()>;
+}
+
+/// Retrieve a value from the protocol transcript of `R`, following the layout of [`AbsorbPattern`].
+pub trait AbsorbReader<R: ?Sized>: Sized {
+    fn read_from_transcript(transcript: &mut R) -> ProofResult<Self>;
+}
+
+impl<P: ByteIOPattern> AbsorbPattern<P> for u8 {
+    fn add_to_pattern(io: P, count: usize, label: &str) -> P {
+        io.add_bytes(count, label)
+    }
+}
+
+impl<W: ByteWriter + ?Sized> AbsorbWriter<W> for u8 {
+    fn add_to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+        Ok(transcript.add_bytes(&[*self])?)
+    }
+}
+
+impl<R: ByteReader + ?Sized> AbsorbReader<R> for u8 {
+    fn read_from_transcript(transcript: &mut R) -> ProofResult<Self> {
+        let [byte] = transcript.next_bytes()?;
+        Ok(byte)
+    }
+}
+
+impl<P, T: AbsorbPattern<P>, const N: usize> AbsorbPattern<P> for [T; N] {
+    fn add_to_pattern(io: P, count: usize, label: &str) -> P {
+        T::add_to_pattern(io, N * count, label)
+    }
+}
+
+impl<W: ?Sized, T: AbsorbWriter<W>, const N: usize> AbsorbWriter<W> for [T; N] {
+    fn add_to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+        self.iter()
+            .try_for_each(|value| value.add_to_transcript(transcript))
+    }
+}
+
+impl<R: ?Sized, T: AbsorbReader<R>, const N: usize> AbsorbReader<R> for [T; N] {
+    fn read_from_transcript(transcript: &mut R) -> ProofResult<Self> {
+        let values = (0..N)
+            .map(|_| T::read_from_transcript(transcript))
+            .collect::<ProofResult<Vec<_>>>()?;
+        // the length is guaranteed to be N
+        Ok(values.try_into().ok().unwrap())
+    }
+}
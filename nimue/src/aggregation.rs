@@ -0,0 +1,234 @@
+use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, BytePublic, DefaultHash, DuplexHash, IOPattern,
+    IOPatternError, Merlin, ProofResult,
+};
+
+/// The size of the digest binding each child transcript, in bytes.
+const DIGEST_SIZE: usize = 32;
+
+/// Declare the binding of aggregated proofs in the IO pattern, see [`Aggregator`].
+pub trait AggregationIOPattern {
+    /// Declare the digests of `count` child transcripts, to be followed by the aggregation challenges.
+    fn add_aggregated_proofs(self, count: usize, label: &str) -> Self;
+}
+
+impl<T: ByteIOPattern> AggregationIOPattern for T {
+    fn add_aggregated_proofs(self, count: usize, label: &str) -> Self {
+        self.add_bytes(DIGEST_SIZE * count, &format!("{label}:children"))
+    }
+}
+
+/// The namespace of the children of `io_pattern` labeled `label`.
+fn namespace<H: DuplexHash>(io_pattern: &IOPattern<H>, label: &str) -> String {
+    format!("{}/{}/", io_pattern.domain_separator(), label)
+}
+
+/// The IO pattern of the child at position `index`.
+fn child_pattern<H: DuplexHash>(
+    namespace: &str,
+    index: usize,
+    io_pattern: &IOPattern<H>,
+) -> IOPattern<H> {
+    io_pattern.namespaced(&format!("{namespace}{index}/"))
+}
+
+/// The digest of a child transcript, along with its (namespaced) IO pattern.
+fn child_digest<H: DuplexHash>(
+    io_pattern: &IOPattern<H>,
+    transcript: &[u8],
+) -> ProofResult<[u8; DIGEST_SIZE]> {
+    let mut io = IOPattern::<DefaultHash>::new("nimue/aggregation/child")
+        .add_bytes(io_pattern.as_bytes().len(), "io pattern");
+    if !transcript.is_empty() {
+        io = io.add_bytes(transcript.len(), "transcript");
+    }
+    let mut merlin = io.challenge_bytes(DIGEST_SIZE, "digest").to_merlin();
+    merlin.public_bytes(io_pattern.as_bytes())?;
+    if !transcript.is_empty() {
+        merlin.public_bytes(transcript)?;
+    }
+    Ok(merlin.challenge_bytes()?)
+}
+
+/// Check that `index` is a child that was not bound yet.
+fn check_unbound(digests: &[Option<[u8; DIGEST_SIZE]>], index: usize) -> ProofResult<()> {
+    match digests.get(index) {
+        Some(None) => Ok(()),
+        Some(Some(_)) => {
+            Err(IOPatternError::from(format!("Child {index} is already bound")).into())
+        }
+        None => Err(IOPatternError::from(format!("No child {index}")).into()),
+    }
+}
+
+/// Absorb the digests of all children, in order.
+fn bind_children<T: BytePublic>(
+    parent: &mut T,
+    digests: Vec<Option<[u8; DIGEST_SIZE]>>,
+) -> ProofResult<()> {
+    let digests = digests
+        .into_iter()
+        .enumerate()
+        .map(|(index, digest)| {
+            digest.ok_or_else(|| IOPatternError::from(format!("Child {index} is missing")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    parent.public_bytes(&digests.concat())?;
+    Ok(())
+}
+
+/// Prover's transcript manager for proof aggregation.
+///
+/// The aggregator owns the parent transcript, and lends a child transcript to each of the proofs being aggregated.
+/// The child at position $i$ follows the IO pattern of its own protocol
+/// with the domain separator prefixed by the one of the parent, the label, and $i$,
+/// so that transcripts of different children (or of different aggregations) are never confused.
+/// Once all the children are proven, [`Aggregator::finish`] absorbs in the parent a digest of each child,
+/// binding its IO pattern and its transcript, before the aggregation challenges are squeezed.
+/// The digests are not written in the parent transcript, as the verifier recomputes them.
+///
+/// ```
+/// use nimue::{
+///     AggregationIOPattern, AggregationVerifier, Aggregator, ByteChallenges, ByteIOPattern,
+///     ByteReader, ByteWriter, DefaultHash, IOPattern,
+/// };
+///
+/// let parent = IOPattern::<DefaultHash>::new("📝")
+///     .add_aggregated_proofs(2, "proofs")
+///     .challenge_bytes(16, "aggregation challenge");
+/// let child = IOPattern::<DefaultHash>::new("child")
+///     .add_bytes(1, "message")
+///     .challenge_bytes(16, "challenge");
+///
+/// let mut aggregator = Aggregator::new(&parent, "proofs", 2);
+/// for i in 0..2 {
+///     aggregator
+///         .prove_child(i, &child, |merlin| {
+///             merlin.add_bytes(&[i as u8])?;
+///             merlin.challenge_bytes::<16>()?;
+///             Ok(())
+///         })
+///         .unwrap();
+/// }
+/// let (mut merlin, children) = aggregator.finish().unwrap();
+/// let challenge = merlin.challenge_bytes::<16>().unwrap();
+///
+/// let mut verifier = AggregationVerifier::new(&parent, merlin.transcript(), "proofs", 2);
+/// for (i, transcript) in children.iter().enumerate() {
+///     verifier
+///         .verify_child(i, &child, transcript, |arthur| {
+///             assert_eq!(arthur.next_bytes()?, [i as u8]);
+///             arthur.challenge_bytes::<16>()?;
+///             Ok(())
+///         })
+///         .unwrap();
+/// }
+/// let mut arthur = verifier.finish().unwrap();
+/// assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+/// ```
+pub struct Aggregator<H = DefaultHash>
+where
+    H: DuplexHash,
+{
+    parent: Merlin<H>,
+    namespace: String,
+    digests: Vec<Option<[u8; DIGEST_SIZE]>>,
+    transcripts: Vec<Vec<u8>>,
+}
+
+impl<H: DuplexHash> Aggregator<H> {
+    /// Start the parent transcript of `io_pattern`, which declares the aggregation of `count` proofs labeled `label`
+    /// with [`AggregationIOPattern::add_aggregated_proofs`].
+    pub fn new(io_pattern: &IOPattern<H>, label: &str, count: usize) -> Self {
+        Self {
+            parent: io_pattern.to_merlin(),
+            namespace: namespace(io_pattern, label),
+            digests: vec![None; count],
+            transcripts: vec![Vec::new(); count],
+        }
+    }
+
+    /// The parent transcript, for the messages preceding the aggregated proofs.
+    pub fn parent(&mut self) -> &mut Merlin<H> {
+        &mut self.parent
+    }
+
+    /// Run `prove` on the child transcript at position `index`, following `io_pattern`.
+    ///
+    /// Return an error if there is no such child, or if it was already proven.
+    pub fn prove_child<T>(
+        &mut self,
+        index: usize,
+        io_pattern: &IOPattern<H>,
+        prove: impl FnOnce(&mut Merlin<H>) -> ProofResult<T>,
+    ) -> ProofResult<T> {
+        check_unbound(&self.digests, index)?;
+        let io_pattern = child_pattern(&self.namespace, index, io_pattern);
+        let mut merlin = io_pattern.to_merlin();
+        let output = prove(&mut merlin)?;
+        self.digests[index] = Some(child_digest(&io_pattern, merlin.transcript())?);
+        self.transcripts[index] = merlin.transcript().to_vec();
+        Ok(output)
+    }
+
+    /// Bind all the children into the parent transcript,
+    /// and return it along with the transcripts of the children, in order.
+    ///
+    /// Return an error if a child was not proven.
+    pub fn finish(mut self) -> ProofResult<(Merlin<H>, Vec<Vec<u8>>)> {
+        bind_children(&mut self.parent, self.digests)?;
+        Ok((self.parent, self.transcripts))
+    }
+}
+
+/// Verifier's transcript manager for proof aggregation, see [`Aggregator`].
+pub struct AggregationVerifier<'a, H = DefaultHash>
+where
+    H: DuplexHash,
+{
+    parent: Arthur<'a, H>,
+    namespace: String,
+    digests: Vec<Option<[u8; DIGEST_SIZE]>>,
+}
+
+impl<'a, H: DuplexHash> AggregationVerifier<'a, H> {
+    /// Start reading the parent transcript `transcript` of `io_pattern`, see [`Aggregator::new`].
+    pub fn new(io_pattern: &IOPattern<H>, transcript: &'a [u8], label: &str, count: usize) -> Self {
+        Self {
+            parent: io_pattern.to_arthur(transcript),
+            namespace: namespace(io_pattern, label),
+            digests: vec![None; count],
+        }
+    }
+
+    /// The parent transcript, for the messages preceding the aggregated proofs.
+    pub fn parent(&mut self) -> &mut Arthur<'a, H> {
+        &mut self.parent
+    }
+
+    /// Run `verify` on the child transcript `transcript` at position `index`, following `io_pattern`.
+    ///
+    /// Return an error if there is no such child, if it was already verified, or if `verify` fails.
+    pub fn verify_child<T>(
+        &mut self,
+        index: usize,
+        io_pattern: &IOPattern<H>,
+        transcript: &[u8],
+        verify: impl FnOnce(&mut Arthur<'_, H>) -> ProofResult<T>,
+    ) -> ProofResult<T> {
+        check_unbound(&self.digests, index)?;
+        let io_pattern = child_pattern(&self.namespace, index, io_pattern);
+        let mut arthur = io_pattern.to_arthur(transcript);
+        let output = verify(&mut arthur)?;
+        self.digests[index] = Some(child_digest(&io_pattern, transcript)?);
+        Ok(output)
+    }
+
+    /// Bind all the children into the parent transcript, and return it.
+    ///
+    /// Return an error if a child was not verified.
+    pub fn finish(mut self) -> ProofResult<Arthur<'a, H>> {
+        bind_children(&mut self.parent, self.digests)?;
+        Ok(self.parent)
+    }
+}
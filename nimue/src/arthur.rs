@@ -1,8 +1,10 @@
+use crate::codec::DedupDecoder;
+use crate::encoding::{Encoding, Endianness, HashToField, UnitEncoding};
 use crate::errors::IOPatternError;
 use crate::hash::{DuplexHash, Unit};
 use crate::iopattern::IOPattern;
 use crate::safe::Safe;
-use crate::traits::{ByteReader, UnitTranscript};
+use crate::traits::{ByteReader, CommonTranscript, UnitTranscript};
 use crate::DefaultHash;
 
 /// [`Arthur`] contains the verifier state.
@@ -10,12 +12,21 @@ use crate::DefaultHash;
 /// Internally, it is a wrapper around a SAFE sponge.
 /// Given as input an [`IOPattern`] and a protocol transcript, it allows to
 /// de-serialize elements from the transcript and make them available to the zero-knowledge verifier.
+///
+/// Once constructed, verification does not allocate for byte-oriented sponges:
+/// prover messages are read from the transcript slice, and challenges are squeezed into the output,
+/// including for the arkworks plugin (where elements are decoded through a stack buffer).
+/// Only the deduplicating codec ([`Arthur::with_dedup`]) and errors allocate.
 pub struct Arthur<'a, H = DefaultHash, U = u8>
 where
     H: DuplexHash<U>,
     U: Unit,
 {
     pub(crate) safe: Safe<H, U>,
+    pub(crate) encoding: Encoding,
+    pub(crate) hash_to_field: HashToField,
+    pub(crate) challenge_endianness: Endianness,
+    pub(crate) dedup: Option<DedupDecoder>,
     pub(crate) transcript: &'a [u8],
 }
 
@@ -36,9 +47,57 @@ impl<'a, U: Unit, H: DuplexHash<U>> Arthur<'a, H, U> {
     /// assert!(challenge.is_ok());
     /// assert_ne!(challenge.unwrap(), [0; 32]);
     /// ```
+    ///
+    /// If the IO pattern has an integrity tag ([`IOPattern::with_integrity_tag`]) and it mismatches,
+    /// every operation fails; see [`Arthur::try_new`] for the error.
     pub fn new(io_pattern: &IOPattern<H, U>, transcript: &'a [u8]) -> Self {
+        Self::try_new(io_pattern, transcript).unwrap_or_else(|_| {
+            let mut arthur = Self::unchecked(io_pattern, &[]);
+            arthur.safe.clear();
+            arthur
+        })
+    }
+
+    /// Creates a new [`Arthur`] instance, checking the integrity tag of the transcript (if any) before reading any element.
+    ///
+    /// Return a "corrupt proof" error if the tag is missing or mismatches, see [`IOPattern::with_integrity_tag`].
+    pub fn try_new(
+        io_pattern: &IOPattern<H, U>,
+        transcript: &'a [u8],
+    ) -> Result<Self, IOPatternError> {
+        let transcript = if io_pattern.integrity_tag() {
+            crate::integrity::check(io_pattern.as_bytes(), transcript)?
+        } else {
+            transcript
+        };
+        Ok(Self::unchecked(io_pattern, transcript))
+    }
+
+    /// Create the verifier state reading `transcript`, stripped of the integrity tag.
+    fn unchecked(io_pattern: &IOPattern<H, U>, transcript: &'a [u8]) -> Self {
         let safe = Safe::new(io_pattern);
-        Self { safe, transcript }
+        Self {
+            safe,
+            encoding: io_pattern.encoding(),
+            hash_to_field: io_pattern.hash_to_field(),
+            challenge_endianness: io_pattern.challenge_endianness(),
+            dedup: None,
+            transcript,
+        }
+    }
+
+    /// Enable the deduplicating codec, see [`crate::Merlin::with_dedup`].
+    pub fn with_dedup(mut self) -> Self {
+        self.dedup = Some(DedupDecoder::default());
+        self
+    }
+
+    /// Read the next element from the transcript (without absorbing it), using the codec if enabled.
+    pub(crate) fn read_element(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        match &mut self.dedup {
+            Some(dedup) => dedup.read(&mut self.transcript, output),
+            None => Ok(u8::read(&mut self.transcript, output)?),
+        }
     }
 
     /// Read `input.len()` elements from the transcript.
@@ -76,6 +135,27 @@ impl<H: DuplexHash<U>, U: Unit> UnitTranscript<U> for Arthur<'_, H, U> {
     }
 }
 
+impl<H: DuplexHash<U>, U: Unit> CommonTranscript<U> for Arthur<'_, H, U> {
+    #[inline]
+    fn ratchet(&mut self) -> Result<(), IOPatternError> {
+        self.safe.ratchet()
+    }
+}
+
+impl<H: DuplexHash<U>, U: Unit> UnitEncoding for Arthur<'_, H, U> {
+    fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        self.hash_to_field
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        self.challenge_endianness
+    }
+}
+
 impl<H: DuplexHash<U>, U: Unit> core::fmt::Debug for Arthur<'_, H, U> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("Arthur").field(&self.safe).finish()
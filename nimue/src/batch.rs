@@ -0,0 +1,109 @@
+use core::fmt;
+
+use crate::encoding::UnitEncoding;
+use crate::{Arthur, DuplexHash, IOPattern, ProofError, ProofResult, Safe, Unit};
+
+/// The outcome of the verification of a batch of proofs, see [`IOPattern::verify_batch`].
+///
+/// Results are in the order of the proofs, so that failures can be reported along with the index of the proof.
+#[derive(Debug)]
+pub struct BatchReport<T> {
+    results: Vec<ProofResult<T>>,
+}
+
+impl<T> BatchReport<T> {
+    /// Whether every proof of the batch verified.
+    pub fn is_valid(&self) -> bool {
+        self.results.iter().all(Result::is_ok)
+    }
+
+    /// The result of the verification of each proof.
+    pub fn results(&self) -> &[ProofResult<T>] {
+        &self.results
+    }
+
+    /// The proofs that failed to verify, by index, with their errors.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &ProofError)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.as_ref().err().map(|error| (index, error)))
+    }
+
+    /// The outputs of the verifier for each proof, or the report if any proof failed to verify.
+    pub fn into_outputs(self) -> Result<Vec<T>, Self> {
+        if !self.is_valid() {
+            return Err(self);
+        }
+        Ok(self.results.into_iter().flatten().collect())
+    }
+}
+
+impl<T> fmt::Display for BatchReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures = self.failures().count();
+        write!(f, "{} of {} proofs failed", failures, self.results.len())?;
+        for (index, error) in self.failures() {
+            write!(f, "\n  proof {index}: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
+    /// Verify a batch of independent proofs following the IO pattern, running `verifier` on each of them.
+    ///
+    /// The pattern is parsed, and the sponge initialized, only once for the whole batch;
+    /// each verifier starts from a copy of the initial state.
+    /// With the feature `parallel`, proofs are verified in parallel with rayon.
+    /// Every proof is verified, even after a failure, so that the report lists all of them.
+    ///
+    /// ```
+    /// use nimue::{ByteIOPattern, ByteReader, DefaultHash, IOPattern, ProofError};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(1, "message");
+    /// let proofs = [[1], [0], [3]];
+    /// let report = io.verify_batch(&proofs, |arthur| {
+    ///     let [message] = arthur.next_bytes()?;
+    ///     (message != 0).then_some(message).ok_or(ProofError::InvalidProof)
+    /// });
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.failures().map(|(index, _)| index).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(report.to_string(), "1 of 3 proofs failed\n  proof 1: Invalid proof");
+    /// ```
+    pub fn verify_batch<P, T, F>(&self, proofs: &[P], verifier: F) -> BatchReport<T>
+    where
+        H: Send + Sync,
+        U: Send + Sync,
+        P: AsRef<[u8]> + Sync,
+        T: Send,
+        F: Fn(&mut Arthur<'_, H, U>) -> ProofResult<T> + Sync,
+    {
+        let safe = Safe::new(self);
+        let verify = |proof: &P| -> ProofResult<T> {
+            let transcript = if self.integrity_tag() {
+                crate::integrity::check(self.as_bytes(), proof.as_ref())?
+            } else {
+                proof.as_ref()
+            };
+            let mut arthur = Arthur {
+                safe: safe.clone(),
+                encoding: self.encoding(),
+                hash_to_field: self.hash_to_field(),
+                challenge_endianness: self.challenge_endianness(),
+                dedup: None,
+                transcript,
+            };
+            verifier(&mut arthur)
+        };
+
+        #[cfg(feature = "parallel")]
+        let results = {
+            use rayon::prelude::*;
+            proofs.par_iter().map(verify).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let results = proofs.iter().map(verify).collect();
+        BatchReport { results }
+    }
+}
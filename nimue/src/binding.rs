@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::errors::IOPatternError;
+use crate::hash::{DuplexHash, Unit};
+use crate::iopattern::IOPattern;
+
+/// The metadata of the IO pattern absorbed along with the messages and the challenges:
+/// the length of each absorption (see [`IOPattern::with_length_framing`])
+/// and the label of each squeeze (see [`IOPattern::with_label_policy`]),
+/// encoded as units with [`Unit::pack_bytes`].
+#[derive(Clone, Default)]
+pub(crate) struct Binding {
+    /// Whether each absorption is prefixed by its length.
+    length_framing: bool,
+    /// The labels of the squeezes, if they are bound.
+    challenge_labels: Option<Arc<[Vec<u8>]>>,
+    /// The number of squeezes started.
+    challenges: usize,
+}
+
+impl Binding {
+    pub(crate) fn new<H: DuplexHash<U>, U: Unit>(io_pattern: &IOPattern<H, U>) -> Self {
+        Self {
+            length_framing: io_pattern.length_framing(),
+            challenge_labels: io_pattern.challenge_labels(),
+            challenges: 0,
+        }
+    }
+
+    /// The units to absorb before an absorption of `length` units, if framing is enabled.
+    pub(crate) fn length_prefix<U: Unit>(
+        &self,
+        length: usize,
+    ) -> Result<Option<Vec<U>>, IOPatternError> {
+        if !self.length_framing {
+            return Ok(None);
+        }
+        U::pack_bytes(&(length as u64).to_le_bytes())
+            .map(Some)
+            .ok_or_else(|| "Length framing is not supported by the units of the sponge".into())
+    }
+
+    /// The units to absorb before the next squeeze, if labels are bound.
+    pub(crate) fn next_label<U: Unit>(&mut self) -> Result<Option<Vec<U>>, IOPatternError> {
+        let Some(labels) = &self.challenge_labels else {
+            return Ok(None);
+        };
+        let label = labels.get(self.challenges).map_or(&[][..], Vec::as_slice);
+        self.challenges += 1;
+        U::pack_bytes(label)
+            .map(Some)
+            .ok_or_else(|| "Label binding is not supported by the units of the sponge".into())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{ByteChallenges, ByteIOPattern, IOPatternError};
+
+/// The number of bytes squeezed for each position drawn in a shuffle.
+const POSITION_BYTES: usize = 16;
+
+/// Squeeze bit, fixed-weight, permutation, and subset challenges in the IO pattern.
+///
+/// Some protocols (e.g., MPC-in-the-head or lattice-based proofs) have challenges that are not field elements.
+/// For these, the pattern accounts for all the entropy squeezed from the sponge:
+/// - a challenge of `n` bits squeezes $\lceil n / 8 \rceil$ bytes;
+/// - a challenge of length `n` and weight `w` squeezes 16 bytes for each of the `w` nonzero positions,
+///   and $\lceil w / 8 \rceil$ bytes for their signs;
+/// - a permutation of `n` elements, or a subset of `k` elements, squeezes 16 bytes for each element drawn.
+pub trait BitIOPattern {
+    fn challenge_bits(self, count: usize, label: &str) -> Self;
+
+    /// # Panics
+    ///
+    /// Panics if `weight > len`.
+    fn challenge_fixed_weight(self, len: usize, weight: usize, label: &str) -> Self;
+
+    /// A permutation of `n` elements squeezes 16 bytes for each element.
+    fn challenge_permutation(self, n: usize, label: &str) -> Self;
+
+    /// A subset of `k` out of `n` elements squeezes 16 bytes for each element of the subset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n`.
+    fn challenge_subset(self, n: usize, k: usize, label: &str) -> Self;
+}
+
+/// Interpret verifier messages as bits and fixed-weight vectors, see [`BitIOPattern`].
+pub trait BitChallenges {
+    /// Fill `output` with uniformly random bits.
+    fn fill_challenge_bits(&mut self, output: &mut [bool]) -> Result<(), IOPatternError>;
+
+    /// Return a vector of length `len` with exactly `weight` entries in $\{-1, 1\}$, and all the others set to zero.
+    ///
+    /// The vector is (statistically close to) uniform among all such vectors:
+    /// the nonzero positions are drawn with a partial Fisher-Yates shuffle,
+    /// each step reducing a 128-bit integer modulo at most `len`.
+    fn challenge_fixed_weight(
+        &mut self,
+        len: usize,
+        weight: usize,
+    ) -> Result<Vec<i8>, IOPatternError>;
+
+    /// Return a uniformly random permutation of $\{0, \dots, n-1\}$, as the list of the images of $0, \dots, n-1$.
+    ///
+    /// The permutation is drawn with a Fisher-Yates shuffle, drawing each position from a 128-bit integer:
+    /// the statistical distance from uniform is at most $n^2 / 2^{128}$.
+    fn challenge_permutation(&mut self, n: usize) -> Result<Vec<usize>, IOPatternError>;
+
+    /// Return `k` distinct elements of $\{0, \dots, n-1\}$, sampled uniformly without replacement, in the order they were drawn.
+    ///
+    /// This is the beginning of a shuffle as in [`BitChallenges::challenge_permutation`],
+    /// and is used by cut-and-choose protocols to open a random subset of their repetitions.
+    fn challenge_subset(&mut self, n: usize, k: usize) -> Result<Vec<usize>, IOPatternError>;
+
+    fn challenge_bits<const N: usize>(&mut self) -> Result<[bool; N], IOPatternError> {
+        let mut output = [false; N];
+        self.fill_challenge_bits(&mut output).map(|()| output)
+    }
+
+    /// Squeeze `n` bits, for when their number is known only at runtime.
+    fn challenge_bits_vec(&mut self, n: usize) -> Result<Vec<bool>, IOPatternError> {
+        let mut output = vec![false; n];
+        self.fill_challenge_bits(&mut output).map(|()| output)
+    }
+}
+
+fn bits_to_bytes(count: usize) -> usize {
+    count.div_ceil(8)
+}
+
+/// Shuffle the first `buf.len() / POSITION_BYTES` positions of $\{0, \dots, len-1\}$ with a partial Fisher-Yates shuffle.
+fn partial_shuffle(buf: &[u8], len: usize) -> Vec<usize> {
+    let mut positions = (0..len).collect::<Vec<_>>();
+    for (j, chunk) in buf.chunks(POSITION_BYTES).enumerate() {
+        let r = u128::from_le_bytes(chunk.try_into().unwrap());
+        positions.swap(j, j + (r % (len - j) as u128) as usize);
+    }
+    positions
+}
+
+impl<T: ByteIOPattern> BitIOPattern for T {
+    fn challenge_bits(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(bits_to_bytes(count), label)
+    }
+
+    fn challenge_fixed_weight(self, len: usize, weight: usize, label: &str) -> Self {
+        assert!(weight <= len, "The weight cannot exceed the length.");
+        self.challenge_bytes(POSITION_BYTES * weight + bits_to_bytes(weight), label)
+    }
+
+    fn challenge_permutation(self, n: usize, label: &str) -> Self {
+        self.challenge_bytes(POSITION_BYTES * n, label)
+    }
+
+    fn challenge_subset(self, n: usize, k: usize, label: &str) -> Self {
+        assert!(k <= n, "The subset cannot be larger than the set.");
+        self.challenge_bytes(POSITION_BYTES * k, label)
+    }
+}
+
+impl<T: ByteChallenges> BitChallenges for T {
+    fn fill_challenge_bits(&mut self, output: &mut [bool]) -> Result<(), IOPatternError> {
+        let mut buf = vec![0u8; bits_to_bytes(output.len())];
+        self.fill_challenge_bytes(&mut buf)?;
+        for (i, bit) in output.iter_mut().enumerate() {
+            *bit = (buf[i / 8] >> (i % 8)) & 1 == 1;
+        }
+        Ok(())
+    }
+
+    fn challenge_fixed_weight(
+        &mut self,
+        len: usize,
+        weight: usize,
+    ) -> Result<Vec<i8>, IOPatternError> {
+        if weight > len {
+            return Err("The weight cannot exceed the length.".into());
+        }
+        let mut buf = vec![0u8; POSITION_BYTES * weight];
+        self.fill_challenge_bytes(&mut buf)?;
+        let mut signs = vec![false; weight];
+        self.fill_challenge_bits(&mut signs)?;
+
+        let positions = partial_shuffle(&buf, len);
+        let mut output = vec![0i8; len];
+        for (&position, &negative) in positions.iter().zip(&signs) {
+            output[position] = if negative { -1 } else { 1 };
+        }
+        Ok(output)
+    }
+
+    fn challenge_permutation(&mut self, n: usize) -> Result<Vec<usize>, IOPatternError> {
+        self.challenge_subset(n, n)
+    }
+
+    fn challenge_subset(&mut self, n: usize, k: usize) -> Result<Vec<usize>, IOPatternError> {
+        if k > n {
+            return Err("The subset cannot be larger than the set.".into());
+        }
+        let mut buf = vec![0u8; POSITION_BYTES * k];
+        self.fill_challenge_bytes(&mut buf)?;
+        let mut positions = partial_shuffle(&buf, n);
+        positions.truncate(k);
+        Ok(positions)
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::errors::IOPatternError;
+use crate::merlin::TranscriptBuffer;
+use crate::Unit;
+
+/// Tag preceding an element that has not been seen before in the transcript.
+const FRESH: u8 = 0x00;
+/// Tag preceding a back-reference to an element already written in the transcript.
+const REPEAT: u8 = 0x01;
+
+/// Prover side of the deduplicating codec.
+///
+/// Each element is written in the protocol transcript either as
+/// `0x00 || encoding`, the first time it appears, or as
+/// `0x01 || index` (the index being a 32-bit little-endian integer) for every subsequent appearance.
+/// The codec only affects the bytes stored in the protocol transcript:
+/// the sponge absorbs the full element every time.
+/// Each transcript has a single valid encoding: the verifier rejects an element written as fresh
+/// if it already appeared, otherwise proofs would be malleable.
+#[derive(Clone, Default)]
+pub(crate) struct DedupEncoder {
+    seen: HashMap<Vec<u8>, u32>,
+}
+
+/// Verifier side of the deduplicating codec, see [`DedupEncoder`].
+#[derive(Default)]
+pub(crate) struct DedupDecoder {
+    /// The elements read so far, in order.
+    seen: Vec<Arc<[u8]>>,
+    /// The elements of `seen` (sharing their storage), to reject fresh elements that are repeated.
+    known: HashSet<Arc<[u8]>>,
+}
+
+/// The index of the element following `len` elements.
+fn next_index(len: usize) -> Result<u32, IOPatternError> {
+    u32::try_from(len).map_err(|_| "Too many elements for the deduplicating codec".into())
+}
+
+impl DedupEncoder {
+    /// Encode `element` into `transcript`, replacing it with a back-reference if it was already written.
+    ///
+    /// Return an error if $2^{32}$ distinct elements have already been written.
+    pub(crate) fn write(
+        &mut self,
+        element: &[u8],
+        transcript: &mut TranscriptBuffer,
+    ) -> Result<(), IOPatternError> {
+        if let Some(index) = self.seen.get(element) {
+            transcript.push(REPEAT);
+            transcript.extend_from_slice(&index.to_le_bytes());
+        } else {
+            let index = next_index(self.seen.len())?;
+            self.seen.insert(element.to_vec(), index);
+            transcript.push(FRESH);
+            transcript.extend_from_slice(element);
+        }
+        Ok(())
+    }
+}
+
+impl DedupDecoder {
+    /// Decode the next element from `transcript` into `output`, resolving back-references.
+    pub(crate) fn read(
+        &mut self,
+        transcript: &mut &[u8],
+        output: &mut [u8],
+    ) -> Result<(), IOPatternError> {
+        let mut tag = [0u8; 1];
+        u8::read(transcript, &mut tag)?;
+        match tag[0] {
+            FRESH => {
+                u8::read(transcript, output)?;
+                if self.known.contains(&*output) {
+                    return Err("Repeated element not encoded as a back-reference".into());
+                }
+                next_index(self.seen.len())?;
+                let element: Arc<[u8]> = Arc::from(&*output);
+                self.known.insert(element.clone());
+                self.seen.push(element);
+                Ok(())
+            }
+            REPEAT => {
+                let mut index = [0u8; 4];
+                u8::read(transcript, &mut index)?;
+                match self.seen.get(u32::from_le_bytes(index) as usize) {
+                    Some(element) if element.len() == output.len() => {
+                        output.copy_from_slice(element);
+                        Ok(())
+                    }
+                    _ => Err("Invalid back-reference in the transcript".into()),
+                }
+            }
+            _ => Err("Invalid codec tag in the transcript".into()),
+        }
+    }
+}
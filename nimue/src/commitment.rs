@@ -0,0 +1,132 @@
+use crate::hash::Keccak;
+use crate::{ByteIOPattern, ByteReader, ByteWriter, DuplexHash, IOPatternError};
+
+/// The size of a commitment, in bytes.
+const DIGEST_SIZE: usize = 32;
+
+/// The digest of `data`, committed to in the transcript.
+fn digest(data: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mut sponge = Keccak::default();
+    // every input is prefixed by its length, so that the data cannot be confused with the domain
+    for input in [b"nimue/commitment".as_slice(), data] {
+        sponge.absorb_unchecked(&(input.len() as u64).to_le_bytes());
+        sponge.absorb_unchecked(input);
+    }
+    let mut digest = [0; DIGEST_SIZE];
+    sponge.squeeze_unchecked(&mut digest);
+    digest
+}
+
+/// A commitment to data, written in the transcript, to be opened later in the protocol.
+///
+/// Some protocols bind the prover to data early, and reveal the data only later (e.g., after a challenge);
+/// the commitment is absorbed right away, and the opening is checked against it by the verifier.
+/// Commitments are returned by [`CommitWriter::add_commitment`] and [`CommitReader::next_commitment`],
+/// and consumed when opened, so that an opening can be checked only against the commitment it belongs to, and only once.
+///
+/// Commitments are binding, but **not hiding**: the digest reveals low-entropy data. Protocols committing to secrets
+/// must include enough randomness in the data.
+///
+/// ```
+/// use nimue::{ByteChallenges, ByteIOPattern, CommitIOPattern, CommitReader, CommitWriter, DefaultHash, IOPattern};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝")
+///     .add_commitment("commitment")
+///     .challenge_bytes(16, "challenge")
+///     .add_opening(5, "opening");
+/// let mut merlin = io.to_merlin();
+/// let commitment = merlin.add_commitment(b"hello").unwrap();
+/// let challenge = merlin.challenge_bytes::<16>().unwrap();
+/// merlin.add_opening(commitment, b"hello").unwrap();
+///
+/// let mut arthur = io.to_arthur(merlin.transcript());
+/// let commitment = arthur.next_commitment().unwrap();
+/// assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+/// assert_eq!(arthur.next_opening(commitment, 5).unwrap(), b"hello");
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "a commitment must be opened"]
+pub struct Commitment([u8; DIGEST_SIZE]);
+
+impl Commitment {
+    /// The digest written in the transcript.
+    pub fn as_bytes(&self) -> &[u8; DIGEST_SIZE] {
+        &self.0
+    }
+}
+
+/// Declare commitments and their openings in the IO pattern, see [`Commitment`].
+pub trait CommitIOPattern {
+    /// Declare a commitment.
+    fn add_commitment(self, label: &str) -> Self;
+    /// Declare the opening of a commitment to `len` bytes.
+    fn add_opening(self, len: usize, label: &str) -> Self;
+}
+
+/// Commit to data and open it, writing both in the protocol transcript, see [`Commitment`].
+pub trait CommitWriter {
+    /// Write the commitment to `data`.
+    fn add_commitment(&mut self, data: &[u8]) -> Result<Commitment, IOPatternError>;
+    /// Write `data`, the opening of `commitment`.
+    ///
+    /// Return an error if `data` is not the data committed to, before writing anything.
+    fn add_opening(&mut self, commitment: Commitment, data: &[u8]) -> Result<(), IOPatternError>;
+}
+
+/// Read commitments and their openings from the protocol transcript, see [`Commitment`].
+pub trait CommitReader {
+    /// Read a commitment.
+    fn next_commitment(&mut self) -> Result<Commitment, IOPatternError>;
+    /// Read the opening of `commitment`, of `len` bytes.
+    ///
+    /// Return an error if the opening does not match the commitment.
+    fn next_opening(
+        &mut self,
+        commitment: Commitment,
+        len: usize,
+    ) -> Result<Vec<u8>, IOPatternError>;
+}
+
+impl<T: ByteIOPattern> CommitIOPattern for T {
+    fn add_commitment(self, label: &str) -> Self {
+        self.add_bytes(DIGEST_SIZE, label)
+    }
+
+    fn add_opening(self, len: usize, label: &str) -> Self {
+        self.add_bytes(len, label)
+    }
+}
+
+impl<T: ByteWriter> CommitWriter for T {
+    fn add_commitment(&mut self, data: &[u8]) -> Result<Commitment, IOPatternError> {
+        let digest = digest(data);
+        self.add_bytes(&digest)?;
+        Ok(Commitment(digest))
+    }
+
+    fn add_opening(&mut self, commitment: Commitment, data: &[u8]) -> Result<(), IOPatternError> {
+        if !crate::ct::eq(&digest(data), &commitment.0) {
+            return Err("The opening does not match the commitment".into());
+        }
+        self.add_bytes(data)
+    }
+}
+
+impl<T: ByteReader> CommitReader for T {
+    fn next_commitment(&mut self) -> Result<Commitment, IOPatternError> {
+        self.next_bytes().map(Commitment)
+    }
+
+    fn next_opening(
+        &mut self,
+        commitment: Commitment,
+        len: usize,
+    ) -> Result<Vec<u8>, IOPatternError> {
+        let mut data = vec![0; len];
+        self.fill_next_bytes(&mut data)?;
+        if !crate::ct::eq(&digest(&data), &commitment.0) {
+            return Err("The opening does not match the commitment".into());
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,217 @@
+//! A compliance suite for [`DuplexHash`] implementations.
+//!
+//! The SAFE API builds on a duplex sponge whose behavior does not depend on how the calls are split,
+//! since [`Safe`] absorbs and squeezes in whatever chunks the prover and the verifier use.
+//! The checks below pin the properties the rest of the crate relies on:
+//!
+//! - determinism, and separation of the initialization vectors (from which the tag of the IO pattern is derived),
+//! - streaming: absorbing (or squeezing) at once or in pieces is the same,
+//! - binding: changing one absorbed unit changes the output,
+//! - ratcheting: a ratchet separates what is absorbed before and after it,
+//!   and outputs after a ratchet are not the continuation of the previous squeezes.
+//!
+//! Third-party backends run them with [`check`] (or [`check_bytes`] for byte-oriented sponges):
+//!
+//! ```
+//! use nimue::compliance;
+//! use nimue::hash::Keccak;
+//!
+//! compliance::check_bytes::<Keccak>().unwrap();
+//! ```
+//!
+//! Inputs should span a few blocks of the sponge, so that every check crosses block boundaries.
+use core::fmt::Debug;
+
+use crate::{DuplexHash, IOPattern, Safe, Unit};
+
+/// The initialization vector used by the checks.
+const IV: [u8; 32] = [0x5a; 32];
+
+/// Absorb `input` in a fresh sponge, and squeeze `len` units.
+fn output<H: DuplexHash<U>, U: Unit + Default>(iv: [u8; 32], input: &[U], len: usize) -> Vec<U> {
+    let mut output = vec![U::default(); len];
+    H::new(iv)
+        .absorb_unchecked(input)
+        .squeeze_unchecked(&mut output);
+    output
+}
+
+/// Check that the output depends only on the initialization vector and the calls, and that initialization vectors are separated.
+pub fn check_determinism<H, U>(units: &[U]) -> Result<(), String>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default + PartialEq + Debug,
+{
+    let expected = output::<H, U>(IV, units, units.len());
+    if output::<H, U>(IV, units, units.len()) != expected {
+        return Err("The output is not deterministic".to_string());
+    }
+    let mut sponge = H::new(IV);
+    sponge.absorb_unchecked(units);
+    let mut clone = sponge.clone();
+    let mut first = vec![U::default(); units.len()];
+    let mut second = vec![U::default(); units.len()];
+    sponge.squeeze_unchecked(&mut first);
+    clone.squeeze_unchecked(&mut second);
+    if first != second || first != expected {
+        return Err("A clone of the sponge squeezes a different output".to_string());
+    }
+    let mut iv = IV;
+    iv[31] ^= 1;
+    if output::<H, U>(iv, units, units.len()) == expected {
+        return Err("Different initialization vectors give the same output".to_string());
+    }
+    Ok(())
+}
+
+/// Check that absorbing and squeezing at once or in two pieces is the same, for every split.
+pub fn check_streaming<H, U>(units: &[U]) -> Result<(), String>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default + PartialEq + Debug,
+{
+    let expected = output::<H, U>(IV, units, units.len());
+    for i in 0..=units.len() {
+        let mut sponge = H::new(IV);
+        let (head, tail) = units.split_at(i);
+        sponge.absorb_unchecked(head).absorb_unchecked(tail);
+        let mut output = vec![U::default(); units.len()];
+        sponge.squeeze_unchecked(&mut output);
+        if output != expected {
+            return Err(format!(
+                "Absorbing in two pieces split at {i} changes the output"
+            ));
+        }
+
+        let mut sponge = H::new(IV);
+        sponge.absorb_unchecked(units);
+        let mut output = vec![U::default(); units.len()];
+        let (head, tail) = output.split_at_mut(i);
+        sponge.squeeze_unchecked(head).squeeze_unchecked(tail);
+        if output != expected {
+            return Err(format!(
+                "Squeezing in two pieces split at {i} changes the output"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check that changing any absorbed unit changes the output.
+///
+/// Each unit is replaced by the next one (cyclically); positions where the two are equal are skipped.
+pub fn check_binding<H, U>(units: &[U]) -> Result<(), String>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default + PartialEq + Debug,
+{
+    let expected = output::<H, U>(IV, units, units.len());
+    for i in 0..units.len() {
+        let next = &units[(i + 1) % units.len()];
+        if *next == units[i] {
+            continue;
+        }
+        let mut changed = units.to_vec();
+        changed[i] = next.clone();
+        if output::<H, U>(IV, &changed, units.len()) == expected {
+            return Err(format!("Changing the unit {i} does not change the output"));
+        }
+    }
+    Ok(())
+}
+
+/// Check that a ratchet separates the units absorbed before and after it,
+/// that squeezes after a ratchet do not continue the previous ones,
+/// and, if the state can be exported, that the exported state binds the units absorbed.
+pub fn check_ratchet<H, U>(units: &[U]) -> Result<(), String>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default + PartialEq + Debug,
+{
+    let (head, tail) = units.split_at(units.len() / 2);
+    let expected = output::<H, U>(IV, units, units.len());
+    let mut output = vec![U::default(); units.len()];
+    H::new(IV)
+        .absorb_unchecked(head)
+        .ratchet_unchecked()
+        .absorb_unchecked(tail)
+        .squeeze_unchecked(&mut output);
+    if output == expected {
+        return Err("A ratchet between two absorptions does not change the output".to_string());
+    }
+
+    let mut sponge = H::new(IV);
+    sponge.absorb_unchecked(units);
+    let mut continuation = vec![U::default(); 2 * units.len()];
+    sponge.clone().squeeze_unchecked(&mut continuation);
+    let (first, second) = output.split_at_mut(head.len());
+    sponge
+        .squeeze_unchecked(first)
+        .ratchet_unchecked()
+        .squeeze_unchecked(second);
+    if second[..] == continuation[head.len()..units.len()] {
+        return Err("Squeezes after a ratchet continue the previous ones".to_string());
+    }
+
+    let mut sponge = H::new(IV);
+    sponge.absorb_unchecked(units).ratchet_unchecked();
+    if let Some(state) = sponge.export_unchecked() {
+        let mut changed = units.to_vec();
+        changed.rotate_left(1);
+        let mut other = H::new(IV);
+        other.absorb_unchecked(&changed).ratchet_unchecked();
+        if other.export_unchecked().as_ref() == Some(&state) && changed != units {
+            return Err("The exported state does not bind the units absorbed".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Check that the challenges of [`Safe`] depend on the IO pattern, through the tag it is initialized with.
+pub fn check_tag<H, U>() -> Result<(), String>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default + PartialEq + Debug,
+{
+    let challenge = |domain_separator: &str, label: &str| {
+        let io = IOPattern::<H, U>::new(domain_separator).squeeze(16, label);
+        let mut output = vec![U::default(); 16];
+        // the IO pattern is followed exactly
+        Safe::new(&io).squeeze(&mut output).unwrap();
+        output
+    };
+    let expected = challenge("domain separator", "challenge");
+    if challenge("domain separator", "challenge") != expected {
+        return Err("The same IO pattern gives different challenges".to_string());
+    }
+    if challenge("another domain separator", "challenge") == expected {
+        return Err("Different domain separators give the same challenges".to_string());
+    }
+    if challenge("domain separator", "another challenge") == expected {
+        return Err("Different labels give the same challenges".to_string());
+    }
+    Ok(())
+}
+
+/// Run all the checks of the suite with the input `units`, returning a description of the first failure.
+///
+/// The units should be mostly distinct, and span a few blocks of the sponge.
+pub fn check<H, U>(units: &[U]) -> Result<(), String>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default + PartialEq + Debug,
+{
+    check_determinism::<H, U>(units)?;
+    check_streaming::<H, U>(units)?;
+    check_binding::<H, U>(units)?;
+    check_ratchet::<H, U>(units)?;
+    check_tag::<H, U>()
+}
+
+/// Run all the checks of the suite on a byte-oriented sponge, with about a kilobyte of input.
+pub fn check_bytes<H: DuplexHash>() -> Result<(), String> {
+    let units = (0..1000u32)
+        .map(|i| (i.wrapping_mul(167) % 251) as u8)
+        .collect::<Vec<_>>();
+    check::<H, u8>(&units)
+}
@@ -0,0 +1,72 @@
+use crate::{ByteIOPattern, BytePublic, IOPatternError};
+
+/// A context value bound into the transcript, e.g., for replay protection.
+///
+/// Context values are public: both parties absorb them (as 64-bit little-endian integers),
+/// and they are not written in the protocol transcript.
+/// Each kind of value is declared in the IO pattern with its own label,
+/// so that, e.g., a proof bound to epoch 1 is not accepted for chain 1.
+///
+/// ```
+/// use nimue::{ByteChallenges, ByteIOPattern, Context, ContextIOPattern, ContextPublic, DefaultHash, IOPattern};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝")
+///     .add_context(Context::ChainId)
+///     .add_context(Context::Timestamp)
+///     .challenge_bytes(16, "challenge");
+/// let mut merlin = io.to_merlin();
+/// merlin.bind_context(Context::ChainId, 1).unwrap();
+/// merlin.bind_context(Context::Timestamp, 1_700_000_000).unwrap();
+/// let challenge = merlin.challenge_bytes::<16>().unwrap();
+///
+/// // a replay at a later time gives another challenge
+/// let mut arthur = io.to_arthur(merlin.transcript());
+/// arthur.bind_context(Context::ChainId, 1).unwrap();
+/// arthur.bind_context(Context::Timestamp, 1_700_000_060).unwrap();
+/// assert_ne!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Context {
+    /// A time, e.g., in seconds since the Unix epoch.
+    Timestamp,
+    /// The identifier of a chain (e.g., EIP-155 chain ids).
+    ChainId,
+    /// An epoch (or round) number.
+    Epoch,
+}
+
+impl Context {
+    /// The label of the value in the IO pattern.
+    pub fn label(self) -> &'static str {
+        match self {
+            Context::Timestamp => "context.timestamp",
+            Context::ChainId => "context.chain-id",
+            Context::Epoch => "context.epoch",
+        }
+    }
+}
+
+/// Declare context values in the IO pattern, see [`Context`].
+pub trait ContextIOPattern {
+    fn add_context(self, context: Context) -> Self;
+}
+
+/// Absorb context values, see [`Context`].
+pub trait ContextPublic {
+    /// Absorb `value`, declared with [`ContextIOPattern::add_context`].
+    ///
+    /// Values must be bound in the order they were declared: `context` documents the call, but only the length is checked.
+    fn bind_context(&mut self, context: Context, value: u64) -> Result<(), IOPatternError>;
+}
+
+impl<T: ByteIOPattern> ContextIOPattern for T {
+    fn add_context(self, context: Context) -> Self {
+        self.add_bytes(8, context.label())
+    }
+}
+
+impl<T: BytePublic> ContextPublic for T {
+    fn bind_context(&mut self, _context: Context, value: u64) -> Result<(), IOPatternError> {
+        self.public_bytes(&value.to_le_bytes())
+    }
+}
@@ -0,0 +1,14 @@
+/// Return whether `a` and `b` are equal.
+///
+/// With the feature `ct`, the comparison takes time depending only on the lengths of `a` and `b`,
+/// rather than on the position of the first difference.
+pub(crate) fn eq(a: &[u8], b: &[u8]) -> bool {
+    #[cfg(feature = "ct")]
+    {
+        subtle::ConstantTimeEq::ct_eq(a, b).into()
+    }
+    #[cfg(not(feature = "ct"))]
+    {
+        a == b
+    }
+}
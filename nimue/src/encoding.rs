@@ -0,0 +1,165 @@
+/// The serialization format used when writing elements into the protocol transcript.
+///
+/// Different proof systems (and different libraries implementing the same proof system)
+/// disagree on how group elements and scalars should be laid out as bytes.
+/// The encoding is fixed in the [`IOPattern`](crate::IOPattern), since it affects the length of the messages,
+/// and is inherited by the [`Merlin`](crate::Merlin) and [`Arthur`](crate::Arthur) instances built from it.
+///
+/// Native units (bytes, or field elements for algebraic hashes) are always written with [`Unit::write`](crate::Unit::write):
+/// the encoding only affects structured types handled by the plugins.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// The smallest encoding available (e.g., point compression), as implemented by the underlying library.
+    #[default]
+    Compressed,
+    /// The uncompressed encoding, as implemented by the underlying library.
+    Uncompressed,
+    /// Every coordinate is written as a fixed-width little-endian integer, without flags or tags.
+    ///
+    /// Points are written as their affine coordinates $(x, y)$, and the point at infinity as zeros.
+    /// Unlike [`Encoding::Uncompressed`], no bit of the encoding depends on the library:
+    /// arkworks stores flags in the spare bits of $y$, and SEC1 prefixes a tag byte.
+    /// Field elements are written as with the other encodings of arkworks.
+    FixedWidthLE,
+}
+
+impl Encoding {
+    /// The name of the encoding in the configuration of an [`IOPattern`](crate::IOPattern).
+    pub(crate) const fn tag(self) -> &'static str {
+        match self {
+            Self::Compressed => "compressed",
+            Self::Uncompressed => "uncompressed",
+            Self::FixedWidthLE => "fixed-width-le",
+        }
+    }
+
+    /// The encoding named `tag`, see [`Encoding::tag`].
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        [Self::Compressed, Self::Uncompressed, Self::FixedWidthLE]
+            .into_iter()
+            .find(|encoding| encoding.tag() == tag)
+    }
+}
+
+/// The method used to map the bytes squeezed from the sponge into field element challenges.
+///
+/// All methods interpret the bytes as an integer (big-endian, unless set otherwise with [`Endianness`]) and reduce it modulo the characteristic;
+/// elements of extension fields are built coefficient by coefficient.
+/// They differ in the number of bytes squeezed for each coefficient.
+/// The number of bytes is part of the squeeze declared in the [`IOPattern`](crate::IOPattern),
+/// so that prover and verifier cannot disagree on the method.
+///
+/// The default method squeezes at least $\lceil \log_2 p \rceil + 128$ bits per coefficient,
+/// so that each challenge is at statistical distance at most $2^{-128}$ from uniform, whatever the field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HashToField {
+    /// Squeeze $\lfloor (\log_2 p + 128) / 8 \rfloor$ bytes.
+    ///
+    /// Depending on the size of the field, this leaves less than 128 bits of statistical security
+    /// (e.g., 121 bits for the scalar field of BLS12-381);
+    /// it is kept for compatibility with the transcripts of earlier versions.
+    Reduce,
+    /// Follow the `hash_to_field` procedure of [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html#section-5.2)
+    /// with security parameter $k = 128$, squeezing $L = \lceil (\lceil \log_2 p \rceil + 128) / 8 \rceil$ bytes.
+    ///
+    /// The output of `expand_message_xmd` is replaced by the bytes squeezed from the sponge.
+    #[default]
+    Rfc9380,
+    /// Squeeze $\lceil (\lceil \log_2 p \rceil + k) / 8 \rceil$ bytes, for the given statistical security parameter $k$.
+    Statistical(u32),
+}
+
+impl HashToField {
+    /// The name of the method in the configuration of an [`IOPattern`](crate::IOPattern).
+    pub(crate) fn tag(self) -> String {
+        match self {
+            Self::Reduce => "reduce".to_string(),
+            Self::Rfc9380 => "rfc9380".to_string(),
+            Self::Statistical(k) => format!("statistical-{k}"),
+        }
+    }
+
+    /// The method named `tag`, see [`HashToField::tag`].
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "reduce" => Some(Self::Reduce),
+            "rfc9380" => Some(Self::Rfc9380),
+            _ => tag
+                .strip_prefix("statistical-")?
+                .parse()
+                .ok()
+                .map(Self::Statistical),
+        }
+    }
+
+    /// The number of bytes squeezed for each coefficient in a prime field of `modulus_bits` bits.
+    pub const fn challenge_bytes(self, modulus_bits: u32) -> usize {
+        let modulus_bits = modulus_bits as usize;
+        match self {
+            Self::Reduce => (modulus_bits + 128) / 8,
+            Self::Rfc9380 => (modulus_bits + 128).div_ceil(8),
+            Self::Statistical(k) => (modulus_bits + k as usize).div_ceil(8),
+        }
+    }
+
+    /// The statistical security $s$ of each coefficient in a prime field of `modulus_bits` bits.
+    ///
+    /// Reducing a uniform integer of $8L$ bits modulo $p < 2^{n}$ gives an element at statistical distance at most $2^{n - 8L}$ from uniform,
+    /// so each coefficient is at distance at most $2^{-s}$ from uniform, where $s = 8L - n$.
+    /// The distance for $m$ coefficients (e.g., $m$ challenges in the prime field, or a challenge in an extension of degree $m$)
+    /// is at most $m \cdot 2^{-s}$.
+    pub const fn statistical_security(self, modulus_bits: u32) -> usize {
+        8 * self.challenge_bytes(modulus_bits) - modulus_bits as usize
+    }
+}
+
+/// The byte order in which squeezed bytes are read as integers, before being reduced into field element challenges.
+///
+/// Challenges are big-endian by default, as with EVM verifiers (which read words as big-endian integers) and gnark.
+/// Verifiers built on little-endian serializations, such as the `CanonicalSerialize` encoding of arkworks,
+/// can match them without reversing bytes by hand.
+/// As the encoding and the hash-to-field method, the byte order is recorded in the IO pattern,
+/// so that patterns with different byte orders derive different challenges from the start.
+/// It applies to the scalar challenges of the `ark` and `group` plugins;
+/// short challenges (e.g., `ShortFieldChallenges` of the `ark` plugin) are exempt,
+/// and are always read as little-endian integers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// The first byte squeezed is the most significant one.
+    #[default]
+    Big,
+    /// The first byte squeezed is the least significant one.
+    Little,
+}
+
+impl Endianness {
+    /// The name of the byte order in the configuration of an [`IOPattern`](crate::IOPattern).
+    pub(crate) const fn tag(self) -> &'static str {
+        match self {
+            Self::Big => "big",
+            Self::Little => "little",
+        }
+    }
+
+    /// The byte order named `tag`, see [`Endianness::tag`].
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        [Self::Big, Self::Little]
+            .into_iter()
+            .find(|endianness| endianness.tag() == tag)
+    }
+}
+
+/// Types that carry an [`Encoding`] policy for the elements they serialize.
+///
+/// This trait is implemented by [`IOPattern`](crate::IOPattern), [`Merlin`](crate::Merlin), and [`Arthur`](crate::Arthur),
+/// and it is used by the plugins to decide how to serialize and deserialize elements.
+pub trait UnitEncoding {
+    /// Return the encoding used for elements in the protocol transcript.
+    fn encoding(&self) -> Encoding;
+
+    /// Return the method used to derive field element challenges.
+    fn hash_to_field(&self) -> HashToField;
+
+    /// Return the byte order of the integers reduced into field element challenges.
+    fn challenge_endianness(&self) -> Endianness;
+}
@@ -0,0 +1,268 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Arthur, ByteReader, ByteWriter, CommonTranscript, DuplexHash, Encoding, Endianness,
+    HashToField, IOPatternError, Merlin, UnitEncoding, UnitTranscript,
+};
+
+/// The object-safe interface of a byte-oriented [`Merlin`].
+trait ErasedMerlin: core::fmt::Debug {
+    fn add_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError>;
+    fn public_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError>;
+    fn fill_challenge_bytes(&mut self, output: &mut [u8]) -> Result<(), IOPatternError>;
+    fn ratchet(&mut self) -> Result<(), IOPatternError>;
+    fn transcript(&self) -> &[u8];
+    fn rng(&mut self) -> &mut dyn RngCore;
+    fn encoding(&self) -> Encoding;
+    fn hash_to_field(&self) -> HashToField;
+    fn challenge_endianness(&self) -> Endianness;
+}
+
+impl<H, R> ErasedMerlin for Merlin<H, u8, R>
+where
+    H: DuplexHash<u8>,
+    R: RngCore + CryptoRng,
+{
+    fn add_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        ByteWriter::add_bytes(self, input)
+    }
+
+    fn public_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.public_units(input)
+    }
+
+    fn fill_challenge_bytes(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        self.fill_challenge_units(output)
+    }
+
+    fn ratchet(&mut self) -> Result<(), IOPatternError> {
+        Merlin::ratchet(self)
+    }
+
+    fn transcript(&self) -> &[u8] {
+        Merlin::transcript(self)
+    }
+
+    fn rng(&mut self) -> &mut dyn RngCore {
+        Merlin::rng(self)
+    }
+
+    fn encoding(&self) -> Encoding {
+        UnitEncoding::encoding(self)
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        UnitEncoding::hash_to_field(self)
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        UnitEncoding::challenge_endianness(self)
+    }
+}
+
+/// The object-safe interface of a byte-oriented [`Arthur`].
+trait ErasedArthur: core::fmt::Debug {
+    fn fill_next_bytes(&mut self, input: &mut [u8]) -> Result<(), IOPatternError>;
+    fn public_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError>;
+    fn fill_challenge_bytes(&mut self, output: &mut [u8]) -> Result<(), IOPatternError>;
+    fn ratchet(&mut self) -> Result<(), IOPatternError>;
+    fn encoding(&self) -> Encoding;
+    fn hash_to_field(&self) -> HashToField;
+    fn challenge_endianness(&self) -> Endianness;
+}
+
+impl<H: DuplexHash<u8>> ErasedArthur for Arthur<'_, H, u8> {
+    fn fill_next_bytes(&mut self, input: &mut [u8]) -> Result<(), IOPatternError> {
+        ByteReader::fill_next_bytes(self, input)
+    }
+
+    fn public_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.public_units(input)
+    }
+
+    fn fill_challenge_bytes(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        self.fill_challenge_units(output)
+    }
+
+    fn ratchet(&mut self) -> Result<(), IOPatternError> {
+        Arthur::ratchet(self)
+    }
+
+    fn encoding(&self) -> Encoding {
+        UnitEncoding::encoding(self)
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        UnitEncoding::hash_to_field(self)
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        UnitEncoding::challenge_endianness(self)
+    }
+}
+
+/// A byte-oriented [`Merlin`], with the hash function and the random number generator erased.
+///
+/// Plugin architectures and FFI layers can pass it across crate boundaries
+/// without carrying the type parameters of the prover state.
+/// It supports the byte traits and, through [`UnitTranscript`] and [`UnitEncoding`],
+/// the blanket implementations built on them (e.g., public scalars and scalar challenges in the plugins).
+///
+/// ```
+/// use nimue::{
+///     AnyArthur, AnyMerlin, ByteChallenges, ByteIOPattern, ByteReader, ByteWriter, DefaultHash,
+///     DigestBridge, IOPattern,
+/// };
+///
+/// fn io<H: nimue::DuplexHash>() -> IOPattern<H> {
+///     IOPattern::new("📝").add_bytes(1, "message").challenge_bytes(16, "challenge")
+/// }
+///
+/// let keccak = io::<DefaultHash>();
+/// let sha2 = io::<DigestBridge<sha2::Sha256>>();
+/// let mut provers = [AnyMerlin::new(keccak.to_merlin()), AnyMerlin::new(sha2.to_merlin())];
+/// let mut challenges = Vec::new();
+/// for merlin in provers.iter_mut() {
+///     merlin.add_bytes(&[42]).unwrap();
+///     challenges.push(merlin.challenge_bytes::<16>().unwrap());
+/// }
+///
+/// let mut verifiers = [
+///     AnyArthur::new(keccak.to_arthur(provers[0].transcript())),
+///     AnyArthur::new(sha2.to_arthur(provers[1].transcript())),
+/// ];
+/// for (arthur, challenge) in verifiers.iter_mut().zip(challenges) {
+///     assert_eq!(arthur.next_bytes().unwrap(), [42]);
+///     assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AnyMerlin {
+    inner: Box<dyn ErasedMerlin>,
+}
+
+impl AnyMerlin {
+    /// Erase the type parameters of `merlin`.
+    pub fn new<H, R>(merlin: Merlin<H, u8, R>) -> Self
+    where
+        H: DuplexHash<u8> + 'static,
+        R: RngCore + CryptoRng + 'static,
+    {
+        Self {
+            inner: Box::new(merlin),
+        }
+    }
+
+    /// Return the current protocol transcript, see [`Merlin::transcript`].
+    pub fn transcript(&self) -> &[u8] {
+        self.inner.transcript()
+    }
+
+    /// Return the (cryptographically secure) random number generator of the prover, see [`Merlin::rng`].
+    pub fn rng(&mut self) -> &mut dyn RngCore {
+        self.inner.rng()
+    }
+}
+
+impl<H, R> From<Merlin<H, u8, R>> for AnyMerlin
+where
+    H: DuplexHash<u8> + 'static,
+    R: RngCore + CryptoRng + 'static,
+{
+    fn from(merlin: Merlin<H, u8, R>) -> Self {
+        Self::new(merlin)
+    }
+}
+
+impl UnitTranscript<u8> for AnyMerlin {
+    fn public_units(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.inner.public_bytes(input)
+    }
+
+    fn fill_challenge_units(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        self.inner.fill_challenge_bytes(output)
+    }
+}
+
+impl UnitEncoding for AnyMerlin {
+    fn encoding(&self) -> Encoding {
+        self.inner.encoding()
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        self.inner.hash_to_field()
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        self.inner.challenge_endianness()
+    }
+}
+
+impl CommonTranscript<u8> for AnyMerlin {
+    fn ratchet(&mut self) -> Result<(), IOPatternError> {
+        self.inner.ratchet()
+    }
+}
+
+impl ByteWriter for AnyMerlin {
+    fn add_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.inner.add_bytes(input)
+    }
+}
+
+/// A byte-oriented [`Arthur`], with the hash function erased, see [`AnyMerlin`].
+#[derive(Debug)]
+pub struct AnyArthur<'a> {
+    inner: Box<dyn ErasedArthur + 'a>,
+}
+
+impl<'a> AnyArthur<'a> {
+    /// Erase the type parameters of `arthur`.
+    pub fn new<H: DuplexHash<u8> + 'a>(arthur: Arthur<'a, H, u8>) -> Self {
+        Self {
+            inner: Box::new(arthur),
+        }
+    }
+}
+
+impl<'a, H: DuplexHash<u8> + 'a> From<Arthur<'a, H, u8>> for AnyArthur<'a> {
+    fn from(arthur: Arthur<'a, H, u8>) -> Self {
+        Self::new(arthur)
+    }
+}
+
+impl UnitTranscript<u8> for AnyArthur<'_> {
+    fn public_units(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.inner.public_bytes(input)
+    }
+
+    fn fill_challenge_units(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        self.inner.fill_challenge_bytes(output)
+    }
+}
+
+impl UnitEncoding for AnyArthur<'_> {
+    fn encoding(&self) -> Encoding {
+        self.inner.encoding()
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        self.inner.hash_to_field()
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        self.inner.challenge_endianness()
+    }
+}
+
+impl CommonTranscript<u8> for AnyArthur<'_> {
+    fn ratchet(&mut self) -> Result<(), IOPatternError> {
+        self.inner.ratchet()
+    }
+}
+
+impl ByteReader for AnyArthur<'_> {
+    fn fill_next_bytes(&mut self, input: &mut [u8]) -> Result<(), IOPatternError> {
+        self.inner.fill_next_bytes(input)
+    }
+}
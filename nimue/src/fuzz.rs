@@ -0,0 +1,311 @@
+//! Random protocols and mutation harnesses, for property-based testing and fuzzing.
+//!
+//! A [`Script`] is a random byte-oriented protocol: a sequence of prover messages, challenges, and ratchets.
+//! After each challenge, the prover echoes it back, so that the verifier of a script
+//! rejects (with overwhelming probability) any transcript that was not produced by the honest prover.
+//! Scripts are generated by [`scripts`] (with the feature `proptest`) or via `arbitrary::Arbitrary` (with the feature `arbitrary`),
+//! and exercise the [`DuplexHash`] implementation and the IO pattern checks.
+//!
+//! New [`DuplexHash`] backends are tested against a reference one with [`check_duplex`],
+//! which drives both through the same raw absorb, squeeze, and ratchet calls ([`DuplexOp`]), bypassing the IO pattern.
+//!
+//! For downstream protocols, [`check_mutation`] asserts that a verifier accepts an honest transcript
+//! and rejects it once a single byte is mutated.
+//!
+//! ```
+//! use nimue::fuzz::{check_mutation, Script, ScriptOp};
+//! use nimue::DefaultHash;
+//!
+//! let script = Script::new(
+//!     "📝",
+//!     vec![ScriptOp::Message(vec![1, 2, 3]), ScriptOp::Ratchet, ScriptOp::Challenge(16)],
+//! );
+//! let io = script.io_pattern::<DefaultHash>();
+//! let mut merlin = io.to_merlin();
+//! script.prove(&mut merlin).unwrap();
+//!
+//! let delta = 1.try_into().unwrap();
+//! check_mutation(&io, merlin.transcript(), |arthur| script.verify(arthur), 2, delta).unwrap();
+//! ```
+use core::num::NonZeroU8;
+
+use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, ByteReader, ByteWriter, DuplexHash, IOPattern, Merlin,
+    ProofError, ProofResult, Unit,
+};
+
+/// The maximum length of messages and challenges generated.
+const MAX_LEN: usize = 32;
+/// The maximum number of operations generated (before the final challenge).
+const MAX_OPS: usize = 8;
+/// The maximum length of raw absorptions and squeezes generated, a few blocks of Keccak.
+#[cfg(any(feature = "proptest", feature = "arbitrary"))]
+const MAX_DUPLEX_LEN: usize = 512;
+
+/// An operation of a [`Script`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScriptOp {
+    /// The prover sends this message.
+    Message(Vec<u8>),
+    /// The verifier sends a challenge of this many bytes, which the prover then echoes back.
+    Challenge(usize),
+    /// Both parties ratchet.
+    Ratchet,
+}
+
+/// A random byte-oriented protocol, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Script {
+    domain_separator: String,
+    ops: Vec<ScriptOp>,
+}
+
+impl Script {
+    /// Create a script with the domain separator `domain_separator` running `ops`.
+    ///
+    /// Messages and challenges must be non-empty.
+    /// For mutations to be detected, the last operation should be a challenge.
+    pub fn new(domain_separator: &str, ops: Vec<ScriptOp>) -> Self {
+        Self {
+            domain_separator: domain_separator.to_string(),
+            ops,
+        }
+    }
+
+    /// The operations of the script.
+    pub fn ops(&self) -> &[ScriptOp] {
+        &self.ops
+    }
+
+    /// The IO pattern of the script.
+    pub fn io_pattern<H: DuplexHash>(&self) -> IOPattern<H> {
+        self.ops
+            .iter()
+            .fold(IOPattern::new(&self.domain_separator), |io, op| match op {
+                ScriptOp::Message(message) => io.add_bytes(message.len(), "message"),
+                ScriptOp::Challenge(len) => io
+                    .challenge_bytes(*len, "challenge")
+                    .add_bytes(*len, "echo"),
+                ScriptOp::Ratchet => io.ratchet(),
+            })
+    }
+
+    /// Run the honest prover of the script.
+    pub fn prove<H: DuplexHash>(&self, merlin: &mut Merlin<H>) -> ProofResult<()> {
+        for op in &self.ops {
+            match op {
+                ScriptOp::Message(message) => merlin.add_bytes(message)?,
+                ScriptOp::Challenge(len) => {
+                    let challenge = merlin.challenge_bytes_vec(*len)?;
+                    merlin.add_bytes(&challenge)?;
+                }
+                ScriptOp::Ratchet => merlin.ratchet()?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the verifier of the script.
+    ///
+    /// Return [`ProofError::InvalidProof`] if the prover did not echo a challenge.
+    /// Messages are arbitrary, but they determine all the following challenges.
+    pub fn verify<H: DuplexHash>(&self, arthur: &mut Arthur<'_, H>) -> ProofResult<()> {
+        for op in &self.ops {
+            match op {
+                ScriptOp::Message(message) => {
+                    let mut received = vec![0; message.len()];
+                    arthur.fill_next_bytes(&mut received)?;
+                }
+                ScriptOp::Challenge(len) => {
+                    let challenge = arthur.challenge_bytes_vec(*len)?;
+                    let mut echo = vec![0; *len];
+                    arthur.fill_next_bytes(&mut echo)?;
+                    if echo != challenge {
+                        return Err(ProofError::InvalidProof);
+                    }
+                }
+                ScriptOp::Ratchet => arthur.ratchet()?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check that `verify` accepts `transcript`, and rejects it once the byte at `position` (modulo its length)
+/// is xored with `delta`.
+///
+/// Return a description of the failure otherwise.
+pub fn check_mutation<H, F>(
+    io_pattern: &IOPattern<H>,
+    transcript: &[u8],
+    verify: F,
+    position: usize,
+    delta: NonZeroU8,
+) -> Result<(), String>
+where
+    H: DuplexHash,
+    F: Fn(&mut Arthur<'_, H>) -> ProofResult<()>,
+{
+    verify(&mut io_pattern.to_arthur(transcript))
+        .map_err(|error| format!("The honest transcript is rejected: {error}"))?;
+    if transcript.is_empty() {
+        return Ok(());
+    }
+    let mut mutated = transcript.to_vec();
+    let position = position % transcript.len();
+    mutated[position] ^= delta.get();
+    match verify(&mut io_pattern.to_arthur(&mutated)) {
+        Ok(()) => Err(format!(
+            "The transcript with byte {position} xored with {delta} is accepted"
+        )),
+        Err(_) => Ok(()),
+    }
+}
+
+/// A raw call to a [`DuplexHash`], see [`check_duplex`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DuplexOp<U = u8> {
+    /// Absorb these units.
+    Absorb(Vec<U>),
+    /// Squeeze this many units.
+    Squeeze(usize),
+    /// Ratchet.
+    Ratchet,
+}
+
+/// Run `ops` on the hash functions `A` (e.g., a new backend) and `B` (e.g., a reference implementation),
+/// both initialized with `iv`, and check that they squeeze the same units.
+///
+/// A final squeeze of 32 units is compared as well, so that diverging absorptions are caught
+/// even if `ops` does not squeeze afterwards.
+/// Return a description of the first divergence otherwise.
+///
+/// ```
+/// use nimue::fuzz::{check_duplex, DuplexOp};
+/// use nimue::hash::Keccak;
+///
+/// let ops = [DuplexOp::Absorb(vec![1, 2, 3]), DuplexOp::Ratchet, DuplexOp::Squeeze(200)];
+/// check_duplex::<Keccak, Keccak, u8>([0; 32], &ops).unwrap();
+/// ```
+pub fn check_duplex<A, B, U>(iv: [u8; 32], ops: &[DuplexOp<U>]) -> Result<(), String>
+where
+    A: DuplexHash<U>,
+    B: DuplexHash<U>,
+    U: Unit + Default + PartialEq + core::fmt::Debug,
+{
+    let mut a = A::new(iv);
+    let mut b = B::new(iv);
+    for (i, op) in ops.iter().chain([&DuplexOp::Squeeze(32)]).enumerate() {
+        match op {
+            DuplexOp::Absorb(input) => {
+                a.absorb_unchecked(input);
+                b.absorb_unchecked(input);
+            }
+            DuplexOp::Squeeze(len) => {
+                let mut output_a = vec![U::default(); *len];
+                let mut output_b = vec![U::default(); *len];
+                a.squeeze_unchecked(&mut output_a);
+                b.squeeze_unchecked(&mut output_b);
+                if output_a != output_b {
+                    return Err(format!(
+                        "Operation {i} ({op:?}) diverges: {output_a:?} != {output_b:?}"
+                    ));
+                }
+            }
+            DuplexOp::Ratchet => {
+                a.ratchet_unchecked();
+                b.ratchet_unchecked();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A strategy generating random sequences of raw calls to a byte-oriented [`DuplexHash`], for [`proptest`].
+///
+/// Absorptions and squeezes are up to a few blocks long, so that block boundaries are crossed in any position.
+#[cfg(feature = "proptest")]
+pub fn duplex_ops() -> impl proptest::strategy::Strategy<Value = Vec<DuplexOp>> {
+    use proptest::prelude::*;
+
+    let op = prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..=MAX_DUPLEX_LEN).prop_map(DuplexOp::Absorb),
+        (0..=MAX_DUPLEX_LEN).prop_map(DuplexOp::Squeeze),
+        Just(DuplexOp::Ratchet),
+    ];
+    prop::collection::vec(op, 0..=MAX_OPS)
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DuplexOp {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=2)? {
+            0 => {
+                let len = u.int_in_range(0..=MAX_DUPLEX_LEN)?;
+                DuplexOp::Absorb(u.bytes(len)?.to_vec())
+            }
+            1 => DuplexOp::Squeeze(u.int_in_range(0..=MAX_DUPLEX_LEN)?),
+            _ => DuplexOp::Ratchet,
+        })
+    }
+}
+
+/// A strategy generating random scripts ending with a challenge, for [`proptest`].
+#[cfg(feature = "proptest")]
+pub fn scripts() -> impl proptest::strategy::Strategy<Value = Script> {
+    use proptest::prelude::*;
+
+    let op = prop_oneof![
+        prop::collection::vec(any::<u8>(), 1..=MAX_LEN).prop_map(ScriptOp::Message),
+        (1..=MAX_LEN).prop_map(ScriptOp::Challenge),
+        Just(ScriptOp::Ratchet),
+    ];
+    (
+        "[a-z0-9.]{1,16}",
+        prop::collection::vec(op, 0..=MAX_OPS),
+        1..=MAX_LEN,
+    )
+        .prop_map(|(domain_separator, mut ops, last)| {
+            ops.push(ScriptOp::Challenge(last));
+            Script {
+                domain_separator,
+                ops,
+            }
+        })
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for Script {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        scripts().boxed()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Script {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let domain_separator = format!("fuzz.{}", u.arbitrary::<u32>()?);
+        let mut ops = Vec::new();
+        for _ in 0..u.int_in_range(0..=MAX_OPS)? {
+            let op = match u.int_in_range(0..=2)? {
+                0 => {
+                    let len = u.int_in_range(1..=MAX_LEN)?;
+                    ScriptOp::Message(u.bytes(len)?.to_vec())
+                }
+                1 => ScriptOp::Challenge(u.int_in_range(1..=MAX_LEN)?),
+                _ => ScriptOp::Ratchet,
+            };
+            ops.push(op);
+        }
+        ops.push(ScriptOp::Challenge(u.int_in_range(1..=MAX_LEN)?));
+        Ok(Self {
+            domain_separator,
+            ops,
+        })
+    }
+}
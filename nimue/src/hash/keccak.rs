@@ -9,7 +9,7 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 /// using [`DuplexSponge`].
 pub type Keccak = DuplexSponge<AlignedKeccakState>;
 
-fn transmute_state(st: &mut AlignedKeccakState) -> &mut [u64; 25] {
+pub(super) fn transmute_state(st: &mut AlignedKeccakState) -> &mut [u64; 25] {
     unsafe { &mut *(st as *mut AlignedKeccakState as *mut [u64; 25]) }
 }
 
@@ -18,7 +18,7 @@ fn transmute_state(st: &mut AlignedKeccakState) -> &mut [u64; 25] {
 /// (since u64 words must be 8-byte aligned)
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 #[repr(align(8))]
-pub struct AlignedKeccakState([u8; 200]);
+pub struct AlignedKeccakState(pub(super) [u8; 200]);
 
 impl Sponge for AlignedKeccakState {
     type U = u8;
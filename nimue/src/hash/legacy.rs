@@ -115,6 +115,9 @@ impl<D: BlockSizeUser + Digest + Clone + FixedOutputReset> Default for DigestBri
 }
 
 impl<D: BlockSizeUser + Digest + Clone + FixedOutputReset> DuplexHash<u8> for DigestBridge<D> {
+    /// The collision resistance of the digest, half its output size.
+    const SECURITY_LEVEL: usize = D::OutputSize::USIZE * 4;
+
     fn new(tag: [u8; 32]) -> Self {
         // debug_assert!(size_of::<D::OutputSize>() >= 32);
         let mut bridge = Self::default();
@@ -7,6 +7,7 @@
 //! - [`hash::sponge::DuplexSponge`] allows to implement a [`crate::DuplexHash`] using a secure permutation function, specifying the rate `R` and the width `N`.
 //! This is done using the standard duplex sponge cosntruction in overwrite mode (cf. [Wikipedia](https://en.wikipedia.org/wiki/Sponge_function#Duplex_construction)).
 //! - [`hash::legacy::DigestBridge`] takes as input any hash function implementing the NIST API via the standard [`digest::Digest`] trait and makes it suitable for usage in duplex mode for continuous absorb/squeeze.
+//! - [`hash::strobe::Strobe128`] implements [`crate::DuplexHash`] with the STROBE-128/1600 framing, for protocols specified with STROBE.
 
 /// A wrapper around the Keccak-f\[1600\] permutation.
 pub mod keccak;
@@ -14,6 +15,8 @@ pub mod keccak;
 pub mod legacy;
 /// Sponge functions.
 pub mod sponge;
+/// The STROBE-128/1600 protocol framework.
+pub mod strobe;
 
 // Re-export the supported hash functions.
 pub use keccak::Keccak;
@@ -23,10 +26,24 @@ pub use keccak::Keccak;
 /// We require the units to have a precise size in memory, to be cloneable,
 /// and that we can zeroize them.
 pub trait Unit: Clone + Sized + zeroize::Zeroize {
+    /// The number of bits of information carried by a unit, i.e., $\lfloor \log_2 \rfloor$ of the number of values it can take.
+    ///
+    /// Defaults to the size of the unit in memory, which overestimates it for units with unused values (e.g., field elements).
+    const BITS: usize = 8 * core::mem::size_of::<Self>();
+
     /// Write a bunch of units in the wire.
     fn write(bunch: &[Self], w: &mut impl std::io::Write) -> Result<(), std::io::Error>;
     /// Read a bunch of units from the wire
     fn read(r: &mut impl std::io::Read, bunch: &mut [Self]) -> Result<(), std::io::Error>;
+
+    /// Encode `bytes` as units, to absorb the metadata of an IO pattern:
+    /// the lengths of [`IOPattern::with_length_framing`](crate::IOPattern::with_length_framing)
+    /// and the labels of [`LabelPolicy::BindChallenges`](crate::LabelPolicy::BindChallenges).
+    ///
+    /// Return `None` if the units cannot encode bytes, the default.
+    fn pack_bytes(_bytes: &[u8]) -> Option<Vec<Self>> {
+        None
+    }
 }
 
 /// A [`DuplexHash`] is an abstract interface for absorbing and squeezing data.
@@ -41,6 +58,21 @@ pub trait DuplexHash<U = u8>: Default + Clone + zeroize::Zeroize
 where
     U: Unit,
 {
+    /// The rate of the sponge, in units, or `None` if the hash function is not a sponge.
+    const RATE: Option<usize> = None;
+
+    /// The capacity of the sponge, in units, or `None` if the hash function is not a sponge.
+    const CAPACITY: Option<usize> = None;
+
+    /// The claimed security level of the hash function, in bits.
+    ///
+    /// For sponges, this is half the capacity (in bits), the generic bound on the security of the sponge construction.
+    /// Defaults to that bound if [`DuplexHash::CAPACITY`] is set, and to 128 bits otherwise.
+    const SECURITY_LEVEL: usize = match Self::CAPACITY {
+        Some(capacity) => capacity * U::BITS / 2,
+        None => 128,
+    };
+
     /// Initializes a new sponge, setting up the state.
     fn new(iv: [u8; 32]) -> Self;
 
@@ -60,15 +92,19 @@ where
     /// The resulting state is compressed.
     fn ratchet_unchecked(&mut self) -> &mut Self;
 
-    // /// Exports the hash state, allowing for preprocessing.
-    // ///
-    // /// This function can be used for duplicating the state of the sponge,
-    // /// but is limited to exporting the state in a way that is compatible
-    // /// with the `load` function.
-    // fn tag(self) -> &'static [Self::U];
+    /// Export the capacity part of the state.
+    ///
+    /// Right after [`DuplexHash::ratchet_unchecked`], the rate is zero and the capacity determines the whole state,
+    /// so that the exported elements bind everything processed so far.
+    /// Return `None` if the hash function does not expose its state.
+    fn export_unchecked(&self) -> Option<Vec<U>> {
+        None
+    }
 }
 
 impl Unit for u8 {
+    const BITS: usize = 8;
+
     fn write(bunch: &[Self], w: &mut impl std::io::Write) -> Result<(), std::io::Error> {
         w.write_all(bunch)
     }
@@ -76,4 +112,8 @@ impl Unit for u8 {
     fn read(r: &mut impl std::io::Read, bunch: &mut [Self]) -> Result<(), std::io::Error> {
         r.read_exact(bunch)
     }
+
+    fn pack_bytes(bytes: &[u8]) -> Option<Vec<Self>> {
+        Some(bytes.to_vec())
+    }
 }
@@ -45,6 +45,10 @@ pub struct DuplexSponge<C: Sponge> {
 }
 
 impl<U: Unit, C: Sponge<U = U>> DuplexHash<U> for DuplexSponge<C> {
+    const RATE: Option<usize> = Some(C::R);
+    const CAPACITY: Option<usize> = Some(C::N - C::R);
+    const SECURITY_LEVEL: usize = (C::N - C::R) * U::BITS / 2;
+
     fn new(iv: [u8; 32]) -> Self {
         assert!(C::N > C::R, "Capacity of the sponge should be > 0.");
         Self {
@@ -95,9 +99,9 @@ impl<U: Unit, C: Sponge<U = U>> DuplexHash<U> for DuplexSponge<C> {
         self.squeeze_unchecked(rest)
     }
 
-    // fn tag(self) -> &'static [Self::U] {
-    //     &self.state[C::RATE..]
-    // }
+    fn export_unchecked(&self) -> Option<Vec<U>> {
+        Some(self.sponge.as_ref()[C::R..].to_vec())
+    }
 
     fn ratchet_unchecked(&mut self) -> &mut Self {
         self.sponge.permute();
@@ -0,0 +1,181 @@
+//! [STROBE-128/1600](https://strobe.sourceforge.io/specs/), as a [`DuplexHash`].
+//!
+//! This is the same STROBE framing used by [merlin](https://merlin.cool):
+//! absorbs are mapped to `AD` operations, squeezes to `PRF` operations, and ratchets to `RATCHET` operations
+//! (zeroing 16 bytes of the state).
+//! Consecutive absorbs (resp. squeezes) continue the same operation, as with STROBE's `more` flag,
+//! so that absorbing (resp. squeezing) in chunks is the same as doing it at once.
+//!
+//! When used from an [`IOPattern`](crate::IOPattern), the protocol label is the 32-byte tag of the IO pattern.
+//! Protocols that specify their own label can use [`Strobe128::new`] directly.
+use zeroize::Zeroize;
+
+use super::keccak::{transmute_state, AlignedKeccakState};
+use super::DuplexHash;
+
+/// The rate of STROBE-128/1600, in bytes.
+const STROBE_R: u8 = 166;
+
+const FLAG_I: u8 = 1;
+const FLAG_A: u8 = 1 << 1;
+const FLAG_C: u8 = 1 << 2;
+const FLAG_T: u8 = 1 << 3;
+const FLAG_M: u8 = 1 << 4;
+const FLAG_K: u8 = 1 << 5;
+
+/// The number of bytes zeroed by a ratchet (the security level, in bytes).
+const RATCHET_BYTES: usize = 16;
+
+/// A STROBE-128/1600 instance.
+#[derive(Clone, Zeroize)]
+pub struct Strobe128 {
+    state: AlignedKeccakState,
+    pos: u8,
+    pos_begin: u8,
+    cur_flags: u8,
+}
+
+impl Strobe128 {
+    /// Initialize STROBE and absorb `protocol_label` as meta-data.
+    pub fn new(protocol_label: &[u8]) -> Self {
+        let mut state = AlignedKeccakState::default();
+        state.0[0..6].copy_from_slice(&[1, STROBE_R + 2, 1, 0, 1, 96]);
+        state.0[6..18].copy_from_slice(b"STROBEv1.0.2");
+        keccak::f1600(transmute_state(&mut state));
+
+        let mut strobe = Self {
+            state,
+            pos: 0,
+            pos_begin: 0,
+            cur_flags: 0,
+        };
+        strobe.meta_ad(protocol_label, false);
+        strobe
+    }
+
+    /// The `meta-AD` operation.
+    pub fn meta_ad(&mut self, data: &[u8], more: bool) {
+        self.begin_op(FLAG_M | FLAG_A, more);
+        self.absorb(data);
+    }
+
+    /// The `AD` operation.
+    pub fn ad(&mut self, data: &[u8], more: bool) {
+        self.begin_op(FLAG_A, more);
+        self.absorb(data);
+    }
+
+    /// The `PRF` operation.
+    pub fn prf(&mut self, data: &mut [u8], more: bool) {
+        self.begin_op(FLAG_I | FLAG_A | FLAG_C, more);
+        self.squeeze(data);
+    }
+
+    /// The `KEY` operation.
+    pub fn key(&mut self, data: &[u8], more: bool) {
+        self.begin_op(FLAG_A | FLAG_C, more);
+        self.overwrite(data);
+    }
+
+    /// The `RATCHET` operation, zeroing `count` bytes of the state.
+    pub fn ratchet(&mut self, count: usize, more: bool) {
+        self.begin_op(FLAG_C, more);
+        for _ in 0..count {
+            self.state.0[self.pos as usize] = 0;
+            self.advance();
+        }
+    }
+
+    fn run_f(&mut self) {
+        self.state.0[self.pos as usize] ^= self.pos_begin;
+        self.state.0[(self.pos + 1) as usize] ^= 0x04;
+        self.state.0[(STROBE_R + 1) as usize] ^= 0x80;
+        keccak::f1600(transmute_state(&mut self.state));
+        self.pos = 0;
+        self.pos_begin = 0;
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+        if self.pos == STROBE_R {
+            self.run_f();
+        }
+    }
+
+    fn absorb(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state.0[self.pos as usize] ^= byte;
+            self.advance();
+        }
+    }
+
+    fn overwrite(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state.0[self.pos as usize] = *byte;
+            self.advance();
+        }
+    }
+
+    fn squeeze(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte = self.state.0[self.pos as usize];
+            self.state.0[self.pos as usize] = 0;
+            self.advance();
+        }
+    }
+
+    fn begin_op(&mut self, flags: u8, more: bool) {
+        if more {
+            assert_eq!(
+                self.cur_flags, flags,
+                "Continued a STROBE operation with different flags"
+            );
+            return;
+        }
+        assert_eq!(flags & FLAG_T, 0, "Transport operations are not supported");
+
+        let old_begin = self.pos_begin;
+        self.pos_begin = self.pos + 1;
+        self.cur_flags = flags;
+        self.absorb(&[old_begin, flags]);
+
+        let force_f = flags & (FLAG_C | FLAG_K) != 0;
+        if force_f && self.pos != 0 {
+            self.run_f();
+        }
+    }
+}
+
+impl Default for Strobe128 {
+    fn default() -> Self {
+        Self::new(&[0u8; 32])
+    }
+}
+
+impl DuplexHash for Strobe128 {
+    const RATE: Option<usize> = Some(STROBE_R as usize);
+    // the two bytes left out of the rate and the capacity hold STROBE's framing
+    const CAPACITY: Option<usize> = Some(32);
+    const SECURITY_LEVEL: usize = 128;
+
+    fn new(iv: [u8; 32]) -> Self {
+        Self::new(&iv)
+    }
+
+    fn absorb_unchecked(&mut self, input: &[u8]) -> &mut Self {
+        let more = self.cur_flags == FLAG_A;
+        self.ad(input, more);
+        self
+    }
+
+    fn squeeze_unchecked(&mut self, output: &mut [u8]) -> &mut Self {
+        let more = self.cur_flags == FLAG_I | FLAG_A | FLAG_C;
+        self.prf(output, more);
+        self
+    }
+
+    fn ratchet_unchecked(&mut self) -> &mut Self {
+        self.ratchet(RATCHET_BYTES, false);
+        self
+    }
+}
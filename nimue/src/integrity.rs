@@ -0,0 +1,62 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::hash::Keccak;
+use crate::{DuplexHash, IOPatternError, Merlin, Unit};
+
+/// The size of the integrity tag, in bytes.
+pub(crate) const TAG_SIZE: usize = 32;
+
+/// The sponge computing the integrity tag of the transcripts following `io_pattern`.
+pub(crate) fn tag_sponge(io_pattern: &[u8]) -> Keccak {
+    let mut sponge = Keccak::default();
+    // every input is prefixed by its length, so that the transcript cannot be confused with the pattern
+    for input in [b"nimue/integrity".as_slice(), io_pattern] {
+        sponge.absorb_unchecked(&(input.len() as u64).to_le_bytes());
+        sponge.absorb_unchecked(input);
+    }
+    sponge
+}
+
+/// The integrity tag of `transcript`, squeezed from `sponge`.
+fn tag(mut sponge: Keccak, transcript: &[u8]) -> [u8; TAG_SIZE] {
+    sponge.absorb_unchecked(&(transcript.len() as u64).to_le_bytes());
+    sponge.absorb_unchecked(transcript);
+    let mut tag = [0; TAG_SIZE];
+    sponge.squeeze_unchecked(&mut tag);
+    tag
+}
+
+/// Check the integrity tag ending `transcript`, and return the transcript without it.
+pub(crate) fn check(io_pattern: &[u8], transcript: &[u8]) -> Result<&[u8], IOPatternError> {
+    if transcript.len() < TAG_SIZE {
+        return Err("Corrupt proof: the integrity tag is missing".into());
+    }
+    let (transcript, expected) = transcript.split_at(transcript.len() - TAG_SIZE);
+    if !crate::ct::eq(&tag(tag_sponge(io_pattern), transcript), expected) {
+        return Err("Corrupt proof: the integrity tag mismatches".into());
+    }
+    Ok(transcript)
+}
+
+impl<H, U, R> Merlin<H, U, R>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+    R: RngCore + CryptoRng,
+{
+    /// Append the integrity tag to the protocol transcript, see [`IOPattern::with_integrity_tag`](crate::IOPattern::with_integrity_tag).
+    ///
+    /// Return an error if the IO pattern is not complete, has no integrity tag, or if the transcript is already sealed.
+    pub fn seal(&mut self) -> Result<(), IOPatternError> {
+        if !self.safe.is_finished() {
+            return Err("The IO pattern is not complete".into());
+        }
+        let sponge = self
+            .integrity
+            .take()
+            .ok_or("The IO pattern has no integrity tag, or the transcript is already sealed")?;
+        let tag = tag(sponge, self.transcript());
+        self.transcript.extend_from_slice(&tag);
+        Ok(())
+    }
+}
@@ -3,17 +3,84 @@
 // which was a pain to use
 // (plain integers don't cast to NonZeroUsize automatically)
 
-use crate::ByteIOPattern;
+use crate::{ByteIOPattern, Encoding, Endianness, HashToField, UnitEncoding};
 use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::sync::{Arc, OnceLock};
 
 use super::errors::IOPatternError;
 use super::hash::{DuplexHash, Unit};
 
 /// This is the separator between operations in the IO Pattern
-/// and as such is the only forbidden character in labels.
+/// and as such is forbidden in domain separators and labels.
 const SEP_BYTE: &str = "\0";
 
+/// This precedes each setting of the configuration recorded after the domain separator
+/// (see [`IOPattern::with_encoding`]), and as such is forbidden in domain separators.
+const CONFIG_BYTE: &str = "\x1f";
+
+/// Why `label` cannot be used in an IO pattern, if it cannot.
+fn label_error(label: &str) -> Option<&'static str> {
+    if label.contains(SEP_BYTE) {
+        Some("Label cannot contain the separator BYTE.")
+    } else if label.chars().any(char::is_control) {
+        Some("Label cannot contain control characters.")
+    } else if label.starts_with(|c: char| c.is_ascii_digit()) {
+        Some("Label cannot start with a digit.")
+    } else {
+        None
+    }
+}
+
+/// Check that `label` can be used in an IO pattern.
+///
+/// Labels are Unicode strings, absorbed as their UTF-8 encoding, without normalization:
+/// canonically equivalent strings (e.g., a precomposed `é`, and `e` followed by a combining accent) are distinct labels.
+/// They cannot contain control characters (the separator byte `\0` included),
+/// nor start with an ASCII digit, which would be read as part of the length of the operation.
+/// Arbitrary strings can be turned into valid labels with [`escape_label`].
+///
+/// ```
+/// use nimue::check_label;
+///
+/// assert!(check_label("commitment").is_ok());
+/// assert!(check_label("clé publique").is_ok());
+/// assert!(check_label("line\nbreak").is_err());
+/// assert!(check_label("1st").is_err());
+/// ```
+pub fn check_label(label: &str) -> Result<(), IOPatternError> {
+    label_error(label).map_or(Ok(()), |message| Err(message.into()))
+}
+
+/// Escape an arbitrary string into a valid label (see [`check_label`]).
+///
+/// Backslashes are doubled, while control characters, and an ASCII digit at the start, are written as `\u{...}`
+/// with their code point in hexadecimal.
+/// The escaping is injective, so distinct strings give distinct labels, and thus distinct IO patterns;
+/// valid labels without backslashes are left unchanged.
+///
+/// ```
+/// use nimue::{check_label, escape_label};
+///
+/// assert_eq!(escape_label("commitment"), "commitment");
+/// assert_eq!(escape_label("1st\tround"), "\\u{31}st\\u{9}round");
+/// assert_eq!(escape_label("a\\u{0}"), "a\\\\u{0}");
+/// assert!(check_label(&escape_label("\0")).is_ok());
+/// ```
+pub fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for (index, char) in label.chars().enumerate() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            char if char.is_control() || (index == 0 && char.is_ascii_digit()) => {
+                escaped.extend(char.escape_unicode())
+            }
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
 /// The IO Pattern of an interactive protocol.
 ///
 /// An IO pattern is a string that specifies the protocol in a simple,
@@ -27,7 +94,7 @@ const SEP_BYTE: &str = "\0";
 /// The letter `R` indicates a ratcheting operation: ratcheting means invoking the hash function even on an incomplete block.
 /// It provides forward secrecy and allows it to start from a clean rate.
 /// After the operation type, is the number of elements in base 10 that are being absorbed/squeezed.
-/// Then, follows the label associated with the element being absorbed/squeezed. This often comes from the underlying description of the protocol. The label cannot start with a digit or contain the NULL byte.
+/// Then, follows the label associated with the element being absorbed/squeezed. This often comes from the underlying description of the protocol. The label cannot start with a digit or contain control characters, the NULL byte included (see [`check_label`]).
 ///
 /// ## Guarantees
 ///
@@ -41,9 +108,69 @@ where
     H: DuplexHash<U>,
 {
     io: String,
+    /// The operations of `io`, parsed (and merged) on first use, and shared by the sponges built from the pattern.
+    ops: OnceLock<Arc<[Op]>>,
+    encoding: Encoding,
+    hash_to_field: HashToField,
+    challenge_endianness: Endianness,
+    ratchet_policy: RatchetPolicy,
+    length_framing: bool,
+    label_policy: LabelPolicy,
+    integrity_tag: bool,
     _hash: PhantomData<(H, U)>,
 }
 
+/// A policy on where an [`IOPattern`] must ratchet, checked as the operations are added.
+///
+/// Ratcheting after the statement is absorbed, and before the prover messages that depend on it,
+/// ensures that the challenges are derived from a clean state (see, e.g., the Schnorr example).
+/// Squeezes violating the policy panic, like any other malformed operation of the pattern.
+///
+/// ```
+/// use nimue::{ByteIOPattern, DefaultHash, IOPattern, RatchetPolicy};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝")
+///     .with_ratchet_policy(RatchetPolicy::BeforeFirstChallenge)
+///     .add_bytes(32, "statement")
+///     .ratchet()
+///     .add_bytes(32, "commitment")
+///     .challenge_bytes(16, "challenge");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RatchetPolicy {
+    /// Ratchets are never required.
+    #[default]
+    Optional,
+    /// A ratchet must separate the first absorptions (the statement) from the first squeeze.
+    BeforeFirstChallenge,
+    /// A ratchet must separate the absorptions of every round from the squeeze ending it.
+    EveryRound,
+}
+
+/// A policy on whether the labels of the squeezes are absorbed before squeezing, see [`IOPattern::with_label_policy`].
+///
+/// The IO pattern (labels included) is absorbed once, in the initialization vector of the sponge,
+/// so the labels otherwise play no part in which challenge is which:
+/// code squeezing two challenges of the same length in the opposite order of their declaration still runs,
+/// and prover and verifier agree on the swapped challenges.
+/// Binding the labels makes every challenge depend on the label it was declared with, right before it is squeezed.
+///
+/// # Migration
+///
+/// Binding labels changes the challenges, and thus the proofs: prover and verifier must switch together,
+/// and proofs produced under [`LabelPolicy::Unbound`] are rejected under [`LabelPolicy::BindChallenges`] (and vice versa).
+/// Under [`LabelPolicy::BindChallenges`], consecutive squeezes are no longer merged,
+/// so each declared challenge must be squeezed on its own (possibly in several calls), and never along with the next one.
+/// Protocols that squeeze two declared challenges with a single call must split the call, or merge the declarations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LabelPolicy {
+    /// The labels are bound only through the initialization vector.
+    #[default]
+    Unbound,
+    /// The label of each squeeze is absorbed right before it.
+    BindChallenges,
+}
+
 /// Sponge operations.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub(crate) enum Op {
@@ -76,62 +203,335 @@ impl Op {
     }
 }
 
+/// The progress of a transcript through the operations of an IO pattern,
+/// for transcripts that follow an [`IOPattern`] without a duplex sponge (e.g., with a fixed hash function).
+#[derive(Clone, Debug)]
+pub(crate) struct OpCursor {
+    /// The operations of the IO pattern, shared with the pattern.
+    ops: Arc<[Op]>,
+    /// The index of the next operation in `ops`.
+    position: usize,
+    /// The units of the next operation already absorbed or squeezed.
+    consumed: usize,
+}
+
+impl OpCursor {
+    pub(crate) fn new<H: DuplexHash<U>, U: Unit>(io_pattern: &IOPattern<H, U>) -> Self {
+        Self {
+            ops: io_pattern.ops(),
+            position: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Consume `expected` from the IO pattern, skipping ratchets.
+    ///
+    /// After an error, any further operation fails.
+    pub(crate) fn consume(&mut self, expected: Op) -> Result<(), IOPatternError> {
+        while let Some(Op::Ratchet) = self.ops.get(self.position) {
+            self.position += 1;
+        }
+        match (self.ops.get(self.position).copied(), expected) {
+            (Some(Op::Absorb(n)), Op::Absorb(m)) | (Some(Op::Squeeze(n)), Op::Squeeze(m))
+                if self.consumed + m <= n =>
+            {
+                self.consumed += m;
+                if self.consumed == n {
+                    self.position += 1;
+                    self.consumed = 0;
+                }
+                Ok(())
+            }
+            (op, _) => {
+                let op = op.map(|op| match op {
+                    Op::Absorb(n) => Op::Absorb(n - self.consumed),
+                    Op::Squeeze(n) => Op::Squeeze(n - self.consumed),
+                    Op::Ratchet => Op::Ratchet,
+                });
+                self.position = self.ops.len();
+                self.consumed = 0;
+                Err(format!("Invalid tag. Got {:?}, expected {:?}", expected, op).into())
+            }
+        }
+    }
+
+    /// Whether all the operations of the IO pattern have been consumed.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.ops[self.position..]
+            .iter()
+            .all(|op| *op == Op::Ratchet)
+    }
+}
+
 impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
     fn from_string(io: String) -> Self {
         Self {
             io,
+            ops: OnceLock::new(),
+            encoding: Encoding::default(),
+            hash_to_field: HashToField::default(),
+            challenge_endianness: Endianness::default(),
+            ratchet_policy: RatchetPolicy::default(),
+            length_framing: false,
+            label_policy: LabelPolicy::default(),
+            integrity_tag: false,
             _hash: PhantomData,
         }
     }
 
+    /// Whether any operation was added after the domain separator.
+    fn has_operations(&self) -> bool {
+        self.io.contains(SEP_BYTE)
+    }
+
+    /// The settings that differ from their default, as recorded after the domain separator.
+    fn configuration(&self) -> String {
+        let mut settings = Vec::new();
+        if self.encoding != Encoding::default() {
+            settings.push(format!("encoding={}", self.encoding.tag()));
+        }
+        if self.hash_to_field != HashToField::default() {
+            settings.push(format!("hash-to-field={}", self.hash_to_field.tag()));
+        }
+        if self.challenge_endianness != Endianness::default() {
+            settings.push(format!("endianness={}", self.challenge_endianness.tag()));
+        }
+        settings
+            .iter()
+            .map(|setting| format!("{CONFIG_BYTE}{setting}"))
+            .collect()
+    }
+
+    /// Record the configuration after the domain separator, replacing the previous one.
+    ///
+    /// The operations come after the configuration, so it can only be changed before adding any of them.
+    fn configure(mut self) -> Self {
+        self.io = format!("{}{}", self.domain_separator(), self.configuration());
+        self.ops = OnceLock::new();
+        self
+    }
+
+    /// Read back the configuration recorded by [`IOPattern::configure`].
+    ///
+    /// Return an error on unknown settings, and on configurations that [`IOPattern::configure`] would not write
+    /// (e.g., with default settings, or out of order), so that each configuration has a single serialization.
+    fn parse_configuration(&mut self) -> Result<(), IOPatternError> {
+        let head = self.io.split(SEP_BYTE).next().unwrap();
+        for setting in head.split(CONFIG_BYTE).skip(1) {
+            match setting.split_once('=') {
+                Some(("encoding", tag)) => {
+                    self.encoding = Encoding::from_tag(tag).ok_or("Unknown encoding")?;
+                }
+                Some(("hash-to-field", tag)) => {
+                    self.hash_to_field =
+                        HashToField::from_tag(tag).ok_or("Unknown hash-to-field method")?;
+                }
+                Some(("endianness", tag)) => {
+                    self.challenge_endianness =
+                        Endianness::from_tag(tag).ok_or("Unknown endianness")?;
+                }
+                _ => return Err(format!("Unknown setting {:?}", setting).into()),
+            }
+        }
+        if head != format!("{}{}", self.domain_separator(), self.configuration()) {
+            return Err("The configuration of the IO pattern is not canonical".into());
+        }
+        Ok(())
+    }
+
+    /// Append an operation to the IO Pattern, preserving the configuration.
+    fn append(mut self, op: &str) -> Self {
+        self.io.push_str(SEP_BYTE);
+        self.io.push_str(op);
+        self.ops = OnceLock::new();
+        self
+    }
+
     /// Create a new IOPattern with the domain separator.
+    ///
+    /// Domain separators and labels can have any length, e.g., to embed the metadata of the protocol.
     pub fn new(domsep: &str) -> Self {
         assert!(
             !domsep.contains(SEP_BYTE),
             "Domain separator cannot contain the separator BYTE."
         );
+        assert!(
+            !domsep.contains(CONFIG_BYTE),
+            "Domain separator cannot contain the configuration BYTE."
+        );
         Self::from_string(domsep.to_string())
     }
 
     /// Absorb `count` native elements.
     pub fn absorb(self, count: usize, label: &str) -> Self {
+        assert!(count > 0, "Count must be positive.");
+        if let Some(message) = label_error(label) {
+            panic!("{}", message);
+        }
+
+        self.append(&(format!("A{}", count) + label))
+    }
+
+    /// Squeeze `count` native elements.
+    pub fn squeeze(self, count: usize, label: &str) -> Self {
         assert!(count > 0, "Count must be positive.");
         assert!(
-            !label.contains(SEP_BYTE),
-            "Label cannot contain the separator BYTE."
+            self.follows_ratchet_policy(),
+            "Squeeze not preceded by a ratchet, as required by {:?}.",
+            self.ratchet_policy
         );
+        if let Some(message) = label_error(label) {
+            panic!("{}", message);
+        }
+
+        self.append(&(format!("S{}", count) + label))
+    }
+
+    /// Ratchet the state.
+    pub fn ratchet(self) -> Self {
+        self.append("R")
+    }
+
+    /// Set the [`Encoding`] used for serializing structured elements (e.g. group elements) into the transcript.
+    ///
+    /// The encoding must be set before adding any operation,
+    /// as it affects the number of bytes the plugins will declare for each element.
+    /// Panics otherwise.
+    ///
+    /// Encodings other than the default are recorded after the domain separator,
+    /// so that they change the IO pattern (and thus every challenge), and survive [`IOPattern::from_bytes`].
+    ///
+    /// ```
+    /// use nimue::{IOPattern, DefaultHash, Encoding, UnitEncoding};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").with_encoding(Encoding::Uncompressed);
+    /// assert_eq!(io.encoding(), Encoding::Uncompressed);
+    /// assert_eq!(io.to_merlin().encoding(), Encoding::Uncompressed);
+    /// ```
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
         assert!(
-            match label.chars().next() {
-                Some(char) => !char.is_ascii_digit(),
-                None => true,
-            },
-            "Label cannot start with a digit."
+            !self.has_operations(),
+            "The encoding must be set before adding any operation."
         );
+        self.encoding = encoding;
+        self.configure()
+    }
 
-        Self::from_string(self.io + SEP_BYTE + &format!("A{}", count) + label)
+    /// Set the [`RatchetPolicy`] checked on the squeezes added afterwards.
+    pub fn with_ratchet_policy(mut self, ratchet_policy: RatchetPolicy) -> Self {
+        self.ratchet_policy = ratchet_policy;
+        self
     }
 
-    /// Squeeze `count` native elements.
-    pub fn squeeze(self, count: usize, label: &str) -> Self {
-        assert!(count > 0, "Count must be positive.");
+    /// Whether a squeeze can be added at the end of the pattern under its [`RatchetPolicy`].
+    fn follows_ratchet_policy(&self) -> bool {
+        if self.ratchet_policy == RatchetPolicy::Optional {
+            return true;
+        }
+        let mut absorbed = false;
+        // walk back to the last ratchet or squeeze
+        for op in self.io.split(SEP_BYTE).skip(1).rev() {
+            match op.as_bytes()[0] {
+                b'R' => return true,
+                // consecutive squeezes are the same challenge
+                b'S' => return !absorbed || self.ratchet_policy != RatchetPolicy::EveryRound,
+                _ => absorbed = true,
+            }
+        }
+        !absorbed
+    }
+
+    /// Set the [`HashToField`] method used for deriving field element challenges.
+    ///
+    /// As for [`IOPattern::with_encoding`], it must be set before adding any operation,
+    /// and it is recorded after the domain separator.
+    ///
+    /// ```
+    /// use nimue::{IOPattern, DefaultHash, HashToField, UnitEncoding};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").with_hash_to_field(HashToField::Rfc9380);
+    /// assert_eq!(io.to_arthur(&[]).hash_to_field(), HashToField::Rfc9380);
+    /// ```
+    pub fn with_hash_to_field(mut self, hash_to_field: HashToField) -> Self {
         assert!(
-            !label.contains(SEP_BYTE),
-            "Label cannot contain the separator BYTE."
+            !self.has_operations(),
+            "The hash-to-field method must be set before adding any operation."
         );
+        self.hash_to_field = hash_to_field;
+        self.configure()
+    }
+
+    /// Set the [`Endianness`] of the integers reduced into field element challenges.
+    ///
+    /// As for [`IOPattern::with_encoding`], it must be set before adding any operation,
+    /// and it is recorded after the domain separator.
+    ///
+    /// ```
+    /// use nimue::{IOPattern, DefaultHash, Endianness, UnitEncoding};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").with_challenge_endianness(Endianness::Little);
+    /// assert_eq!(io.to_merlin().challenge_endianness(), Endianness::Little);
+    /// ```
+    pub fn with_challenge_endianness(mut self, endianness: Endianness) -> Self {
         assert!(
-            match label.chars().next() {
-                Some(char) => !char.is_ascii_digit(),
-                None => true,
-            },
-            "Label cannot start with a digit."
+            !self.has_operations(),
+            "The challenge endianness must be set before adding any operation."
         );
+        self.challenge_endianness = endianness;
+        self.configure()
+    }
 
-        Self::from_string(self.io + SEP_BYTE + &format!("S{}", count) + label)
+    /// End the protocol transcript with an integrity tag over its bytes.
+    ///
+    /// The prover appends the tag with [`Merlin::seal`](crate::Merlin::seal) once the pattern is complete,
+    /// and the verifier checks it in [`Arthur::try_new`](crate::Arthur::try_new) before reading any element,
+    /// so that corrupted (or truncated) proofs are rejected before any deserialization runs.
+    /// The tag does not change the challenges.
+    ///
+    /// ```
+    /// use nimue::{Arthur, ByteIOPattern, ByteWriter, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").with_integrity_tag().add_bytes(1, "message");
+    /// let mut merlin = io.to_merlin();
+    /// merlin.add_bytes(&[42]).unwrap();
+    /// merlin.seal().unwrap();
+    ///
+    /// let mut transcript = merlin.transcript().to_vec();
+    /// assert!(Arthur::try_new(&io, &transcript).is_ok());
+    /// transcript[0] ^= 1;
+    /// assert!(Arthur::try_new(&io, &transcript).is_err());
+    /// ```
+    pub fn with_integrity_tag(mut self) -> Self {
+        self.integrity_tag = true;
+        self
     }
 
-    /// Ratchet the state.
-    pub fn ratchet(self) -> Self {
-        Self::from_string(self.io + SEP_BYTE + "R")
+    /// The rate of the sponge, in units, or `None` if the hash function is not a sponge, see [`DuplexHash::RATE`].
+    pub fn rate(&self) -> Option<usize> {
+        H::RATE
+    }
+
+    /// The capacity of the sponge, in units, or `None` if the hash function is not a sponge, see [`DuplexHash::CAPACITY`].
+    pub fn capacity(&self) -> Option<usize> {
+        H::CAPACITY
+    }
+
+    /// The claimed security level of the hash function, in bits, see [`DuplexHash::SECURITY_LEVEL`].
+    ///
+    /// Challenges are at most as secure as the hash function; field element challenges squeezed as bytes
+    /// also lose some statistical security in the reduction, see [`HashToField::statistical_security`].
+    ///
+    /// ```
+    /// use nimue::{DefaultHash, IOPattern, UnitEncoding};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").squeeze(48, "challenge");
+    /// assert_eq!((io.rate(), io.capacity()), (Some(136), Some(64)));
+    /// // challenges in a 255-bit field meet 128-bit security
+    /// assert!(io.security_level() >= 128);
+    /// assert!(io.hash_to_field().statistical_security(255) >= 128);
+    /// ```
+    pub fn security_level(&self) -> usize {
+        H::SECURITY_LEVEL
     }
 
     /// Return the IO Pattern as bytes.
@@ -139,14 +539,144 @@ impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
         self.io.as_bytes()
     }
 
+    /// Parse an IO pattern serialized with [`IOPattern::as_bytes`], e.g., to inspect transcripts of other programs.
+    ///
+    /// The [`Encoding`], [`HashToField`] method, and [`Endianness`] are read back from the serialization;
+    /// the other settings (e.g., the [`RatchetPolicy`]) are left to their default.
+    /// Return an error if `bytes` is not a valid IO pattern.
+    ///
+    /// ```
+    /// use nimue::{ByteIOPattern, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(1, "message");
+    /// let parsed = IOPattern::<DefaultHash>::from_bytes(io.as_bytes()).unwrap();
+    /// assert_eq!(parsed.as_bytes(), io.as_bytes());
+    /// assert!(IOPattern::<DefaultHash>::from_bytes(b"\0A0message").is_err());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IOPatternError> {
+        let io = String::from_utf8(bytes.to_vec())
+            .map_err(|_| IOPatternError::from("The IO pattern is not valid UTF-8"))?;
+        if io.split(SEP_BYTE).skip(1).any(str::is_empty) {
+            return Err("The IO pattern has an empty operation".into());
+        }
+        Self::parse_io(io.as_bytes(), true, true)?;
+        let mut pattern = Self::from_string(io);
+        pattern.parse_configuration()?;
+        for (_, label) in pattern.entries() {
+            check_label(label)?;
+        }
+        Ok(pattern)
+    }
+
+    /// Return the domain separator.
+    pub(crate) fn domain_separator(&self) -> &str {
+        let head = self.io.split(SEP_BYTE).next().unwrap();
+        head.split(CONFIG_BYTE).next().unwrap()
+    }
+
+    /// The operations of the IO pattern, as declared (without merging), with their labels.
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (Op, &str)> {
+        self.io.split(SEP_BYTE).skip(1).map(|part| {
+            let digits = part[1..].bytes().take_while(u8::is_ascii_digit).count();
+            let length = part[1..1 + digits].parse().unwrap_or(0);
+            // guaranteed to succeed as instances are all valid iopatterns
+            let op = Op::new(part.as_bytes()[0] as char, Some(length))
+                .expect("Internal error. Please submit issue to m@orru.net");
+            (op, &part[1 + digits..])
+        })
+    }
+
+    /// The progress of a sponge (see `Safe::progress`) right before the first operation labeled `label`,
+    /// among the ones from the progress `from` on.
+    pub(crate) fn progress_before(
+        &self,
+        label: &str,
+        from: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let (mut units, mut ratchets) = (0, 0);
+        for (op, op_label) in self.entries() {
+            if op_label == label && units >= from.0 && ratchets >= from.1 {
+                return Some((units, ratchets));
+            }
+            match op {
+                Op::Absorb(length) | Op::Squeeze(length) => units += length,
+                Op::Ratchet => ratchets += 1,
+            }
+        }
+        None
+    }
+
+    /// Prepend `namespace` to the domain separator, preserving the operations and the configuration.
+    ///
+    /// The namespace is prefixed by its length (e.g., `2:ab` for `ab`),
+    /// so that it cannot run into the domain separator: `ab` with `c` and `a` with `bc` give distinct patterns.
+    pub(crate) fn namespaced(&self, namespace: &str) -> Self {
+        assert!(
+            !namespace.contains(SEP_BYTE),
+            "Namespace cannot contain the separator BYTE."
+        );
+        assert!(
+            !namespace.contains(CONFIG_BYTE),
+            "Namespace cannot contain the configuration BYTE."
+        );
+        Self {
+            io: format!("{}:{}{}", namespace.len(), namespace, self.io),
+            ..self.clone()
+        }
+    }
+
     /// Parse the givern IO Pattern into a sequence of [`Op`]'s.
     pub(crate) fn finalize(&self) -> VecDeque<Op> {
         // Guaranteed to succeed as instances are all valid iopatterns
-        Self::parse_io(self.io.as_bytes())
+        Self::parse_io(self.io.as_bytes(), true, true)
             .expect("Internal error. Please submit issue to m@orru.net")
     }
 
-    fn parse_io(io_pattern: &[u8]) -> Result<VecDeque<Op>, IOPatternError> {
+    /// The sequence of [`Op`]'s of the IO Pattern, parsed only once.
+    ///
+    /// With length framing, consecutive absorptions are kept apart, as each of them is prefixed by its length;
+    /// with bound labels, so are consecutive squeezes, as each of them is preceded by its label.
+    pub(crate) fn ops(&self) -> Arc<[Op]> {
+        self.ops
+            .get_or_init(|| {
+                // Guaranteed to succeed as instances are all valid iopatterns
+                Self::parse_io(
+                    self.io.as_bytes(),
+                    !self.length_framing,
+                    self.label_policy == LabelPolicy::Unbound,
+                )
+                .expect("Internal error. Please submit issue to m@orru.net")
+                .into_iter()
+                .collect()
+            })
+            .clone()
+    }
+
+    /// Whether each absorption is prefixed by its length, see [`IOPattern::with_length_framing`].
+    pub(crate) fn length_framing(&self) -> bool {
+        self.length_framing
+    }
+
+    /// The labels of the squeezes, in order, if they are bound (see [`IOPattern::with_label_policy`]).
+    pub(crate) fn challenge_labels(&self) -> Option<Arc<[Vec<u8>]>> {
+        (self.label_policy == LabelPolicy::BindChallenges).then(|| {
+            self.entries()
+                .filter(|(op, _)| matches!(op, Op::Squeeze(_)))
+                .map(|(_, label)| label.as_bytes().to_vec())
+                .collect()
+        })
+    }
+
+    /// Whether the transcript ends with an integrity tag, see [`IOPattern::with_integrity_tag`].
+    pub(crate) fn integrity_tag(&self) -> bool {
+        self.integrity_tag
+    }
+
+    fn parse_io(
+        io_pattern: &[u8],
+        merge_absorbs: bool,
+        merge_squeezes: bool,
+    ) -> Result<VecDeque<Op>, IOPatternError> {
         let mut stack = VecDeque::new();
 
         // skip the domain separator
@@ -155,7 +685,10 @@ impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
             let next_length = part[1..]
                 .iter()
                 .take_while(|x| x.is_ascii_digit())
-                .fold(0, |acc, x| acc * 10 + (x - b'0') as usize);
+                .try_fold(0usize, |acc, x| {
+                    acc.checked_mul(10)?.checked_add((x - b'0') as usize)
+                })
+                .ok_or_else(|| IOPatternError::from("The length of an operation overflows"))?;
 
             // check that next_length != 0 is performed internally on Op::new
             let next_op = Op::new(next_id, Some(next_length))?;
@@ -165,13 +698,23 @@ impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
         // consecutive calls are merged into one
         match stack.pop_front() {
             None => Ok(stack),
-            Some(x) => Self::simplify_stack(VecDeque::from([x]), stack),
+            Some(x) => {
+                Self::simplify_stack(VecDeque::from([x]), stack, merge_absorbs, merge_squeezes)
+            }
         }
     }
 
+    /// The length of two merged operations, or an error if it overflows.
+    fn checked_length(a: usize, b: usize) -> Result<usize, IOPatternError> {
+        a.checked_add(b)
+            .ok_or_else(|| "The length of merged operations overflows".into())
+    }
+
     fn simplify_stack(
         mut dst: VecDeque<Op>,
         mut stack: VecDeque<Op>,
+        merge_absorbs: bool,
+        merge_squeezes: bool,
     ) -> Result<VecDeque<Op>, IOPatternError> {
         if stack.is_empty() {
             Ok(dst)
@@ -182,20 +725,20 @@ impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
             let next = stack.pop_front().unwrap();
 
             match (previous, next) {
-                (Op::Squeeze(a), Op::Squeeze(b)) => {
-                    dst.push_back(Op::Squeeze(a + b));
-                    Self::simplify_stack(dst, stack)
+                (Op::Squeeze(a), Op::Squeeze(b)) if merge_squeezes => {
+                    dst.push_back(Op::Squeeze(Self::checked_length(a, b)?));
+                    Self::simplify_stack(dst, stack, merge_absorbs, merge_squeezes)
                 }
-                (Op::Absorb(a), Op::Absorb(b)) => {
-                    dst.push_back(Op::Absorb(a + b));
-                    Self::simplify_stack(dst, stack)
+                (Op::Absorb(a), Op::Absorb(b)) if merge_absorbs => {
+                    dst.push_back(Op::Absorb(Self::checked_length(a, b)?));
+                    Self::simplify_stack(dst, stack, merge_absorbs, merge_squeezes)
                 }
                 // (Op::Divide, Op::Divide)
                 // is useless but unharmful
                 (a, b) => {
                     dst.push_back(a);
                     dst.push_back(b);
-                    Self::simplify_stack(dst, stack)
+                    Self::simplify_stack(dst, stack, merge_absorbs, merge_squeezes)
                 }
             }
         }
@@ -219,6 +762,85 @@ impl<U: Unit, H: DuplexHash<U>> core::fmt::Debug for IOPattern<H, U> {
     }
 }
 
+impl<U: Unit, H: DuplexHash<U>> UnitEncoding for IOPattern<H, U> {
+    fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        self.hash_to_field
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        self.challenge_endianness
+    }
+}
+
+impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
+    /// Prefix each absorption with its length, a 64-bit little-endian integer encoded with [`Unit::pack_bytes`].
+    ///
+    /// The IO pattern already fixes the length of each message, so prover and verifier always agree on how the
+    /// absorbed bytes are split. Framing additionally makes the absorbed bytes themselves unambiguous
+    /// (e.g., for messages whose lengths are influenced by an attacker),
+    /// at the price of 8 more bytes (or, e.g., one more field element) absorbed per message.
+    /// It changes the challenges, so prover and verifier must agree on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the units of the sponge cannot encode bytes.
+    ///
+    /// ```
+    /// use nimue::{ByteChallenges, ByteIOPattern, ByteWriter, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").with_length_framing()
+    ///     .add_bytes(2, "first")
+    ///     .add_bytes(1, "second")
+    ///     .challenge_bytes(16, "challenge");
+    /// let mut merlin = io.to_merlin();
+    /// // messages can still be written at once
+    /// merlin.add_bytes(&[1, 2, 3]).unwrap();
+    /// merlin.challenge_bytes::<16>().unwrap();
+    /// assert_eq!(merlin.transcript(), [1, 2, 3]);
+    /// ```
+    pub fn with_length_framing(mut self) -> Self {
+        assert!(
+            U::pack_bytes(&[]).is_some(),
+            "Length framing is not supported by the units of the sponge"
+        );
+        self.length_framing = true;
+        self.ops = OnceLock::new();
+        self
+    }
+
+    /// Set the [`LabelPolicy`], e.g., to absorb the label of each squeeze right before it.
+    ///
+    /// Labels are encoded with [`Unit::pack_bytes`]: binding them panics if the units of the sponge cannot encode bytes.
+    ///
+    /// ```
+    /// use nimue::{ByteChallenges, ByteIOPattern, DefaultHash, IOPattern, LabelPolicy};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝")
+    ///     .challenge_bytes(16, "alpha")
+    ///     .challenge_bytes(16, "beta");
+    /// let bound = io.clone().with_label_policy(LabelPolicy::BindChallenges);
+    /// let mut merlin = io.to_merlin();
+    /// let mut bound_merlin = bound.to_merlin();
+    /// // binding the labels changes the challenges
+    /// assert_ne!(merlin.challenge_bytes::<16>().unwrap(), bound_merlin.challenge_bytes::<16>().unwrap());
+    /// // and challenges are squeezed one at a time
+    /// assert!(bound.to_merlin().challenge_bytes::<32>().is_err());
+    /// ```
+    pub fn with_label_policy(mut self, label_policy: LabelPolicy) -> Self {
+        assert!(
+            label_policy == LabelPolicy::Unbound || U::pack_bytes(&[]).is_some(),
+            "Label binding is not supported by the units of the sponge"
+        );
+        self.label_policy = label_policy;
+        self.ops = OnceLock::new();
+        self
+    }
+}
+
 impl<H: DuplexHash> ByteIOPattern for IOPattern<H> {
     #[inline]
     fn add_bytes(self, count: usize, label: &str) -> Self {
@@ -0,0 +1,118 @@
+use core::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{Arthur, DuplexHash, IOPatternError, Merlin, Unit};
+
+/// A challenge declared in the IO pattern, whose squeeze is deferred.
+///
+/// A handle is obtained with [`Arthur::challenge_handle`] (or [`Merlin::challenge_handle`]),
+/// which checks the squeeze against the IO pattern right away.
+/// The challenge is computed on [`Arthur::resolve`], or as soon as the sponge is used again,
+/// since later operations depend on it.
+/// Verifiers that abort on a failed check thus skip the work for the challenges they never resolve,
+/// including the computation attached with [`ChallengeHandle::map`] (e.g., the reduction to field elements).
+///
+/// A handle must be resolved with the state that issued it.
+///
+/// ```
+/// use nimue::{ByteIOPattern, ByteReader, ByteWriter, DefaultHash, IOPattern};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝")
+///     .add_bytes(1, "message")
+///     .challenge_bytes(8, "first")
+///     .challenge_bytes(8, "second");
+/// let mut merlin = io.to_merlin();
+/// merlin.add_bytes(&[42]).unwrap();
+/// let first = merlin.challenge_handle(8).unwrap().map(|c| u64::from_le_bytes(c.try_into().unwrap()));
+/// let first = merlin.resolve(first).unwrap();
+///
+/// let mut arthur = io.to_arthur(merlin.transcript());
+/// arthur.next_bytes::<1>().unwrap();
+/// let handle = arthur.challenge_handle(8).unwrap();
+/// // the second challenge is never computed
+/// let _ = arthur.challenge_handle(8).unwrap();
+/// assert_eq!(arthur.resolve(handle).unwrap(), first.to_le_bytes());
+/// ```
+#[must_use = "the challenge is computed only once resolved"]
+pub struct ChallengeHandle<U, F = fn(Vec<U>) -> Vec<U>> {
+    index: usize,
+    map: F,
+    _unit: PhantomData<U>,
+}
+
+impl<U, F> ChallengeHandle<U, F> {
+    /// Attach the computation `f` to the challenge, to be run when it is resolved.
+    pub fn map<T, S>(self, f: impl FnOnce(T) -> S) -> ChallengeHandle<U, impl FnOnce(Vec<U>) -> S>
+    where
+        F: FnOnce(Vec<U>) -> T,
+    {
+        let map = self.map;
+        ChallengeHandle {
+            index: self.index,
+            map: move |units| f(map(units)),
+            _unit: PhantomData,
+        }
+    }
+}
+
+impl<U, F> core::fmt::Debug for ChallengeHandle<U, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ChallengeHandle")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<U: Unit> ChallengeHandle<U> {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            map: |units| units,
+            _unit: PhantomData,
+        }
+    }
+}
+
+impl<H, U, R> Merlin<H, U, R>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default,
+    R: RngCore + CryptoRng,
+{
+    /// Declare a challenge of `len` units, deferring its computation, see [`ChallengeHandle`].
+    pub fn challenge_handle(&mut self, len: usize) -> Result<ChallengeHandle<U>, IOPatternError> {
+        let index = self.safe.squeeze_deferred(vec![U::default(); len])?;
+        Ok(ChallengeHandle::new(index))
+    }
+
+    /// Compute the challenge of `handle`, and run the computation attached to it.
+    pub fn resolve<T>(
+        &mut self,
+        handle: ChallengeHandle<U, impl FnOnce(Vec<U>) -> T>,
+    ) -> Result<T, IOPatternError> {
+        let units = self.safe.resolve_deferred(handle.index)?;
+        Ok((handle.map)(units))
+    }
+}
+
+impl<H, U> Arthur<'_, H, U>
+where
+    H: DuplexHash<U>,
+    U: Unit + Default,
+{
+    /// Declare a challenge of `len` units, deferring its computation, see [`ChallengeHandle`].
+    pub fn challenge_handle(&mut self, len: usize) -> Result<ChallengeHandle<U>, IOPatternError> {
+        let index = self.safe.squeeze_deferred(vec![U::default(); len])?;
+        Ok(ChallengeHandle::new(index))
+    }
+
+    /// Compute the challenge of `handle`, and run the computation attached to it.
+    pub fn resolve<T>(
+        &mut self,
+        handle: ChallengeHandle<U, impl FnOnce(Vec<U>) -> T>,
+    ) -> Result<T, IOPatternError> {
+        let units = self.safe.resolve_deferred(handle.index)?;
+        Ok((handle.map)(units))
+    }
+}
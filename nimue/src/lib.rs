@@ -40,7 +40,8 @@
 //! (note: length is expressed in terms of [`hash::Unit`], native elements over which the hash function works).
 //! A label is added at the end of each absorb/squeeze, to describe the *type* and
 //! *the variable* as used in the protocol. Operations are separated by a NULL byte and therefore labels cannot contain
-//! NULL bytes themselves, nor they can start with an ASCII digit.
+//! NULL bytes themselves (nor any other control character), nor they can start with an ASCII digit.
+//! Arbitrary strings can be escaped into valid labels with [`escape_label`].
 //!
 //! # Batteries included
 //! The library comes with support for algebraic objects over arkworks and zkcrypto:
@@ -48,6 +49,20 @@
 //! - with feature flag `--feature=group`, the module [`plugins::group`] provides extension traits for zkcrypto's field and group traits.
 //! See the [`plugins`] module for more information.
 //!
+//! # Side channels
+//!
+//! The prover handles its secrets (the private randomness of [`Merlin::rng`], nonces, signing keys) without secret-dependent branches,
+//! as far as the underlying arithmetic allows: arkworks, for instance, does not promise constant-time serialization.
+//! With feature flag `--feature=ct`, byte comparisons performed by the verifiers in this crate
+//! (e.g., signature checks) also run in constant time, using [`subtle`](https://docs.rs/subtle).
+//!
+//! # Panics
+//!
+//! Building an [`IOPattern`] panics on malformed operations, since patterns are fixed by the protocol.
+//! Proving and verifying do not: transcripts that are malformed (or adversarial), and operations that do not follow the pattern,
+//! are reported as errors, so that a verifying service cannot be brought down by a proof.
+//! The sponge, prover, and verifier states deny `unwrap` and `expect` under clippy.
+//!
 //!
 //! # Protocol transcripts
 //!
@@ -99,6 +114,8 @@
 //! let chal = arthur.challenge_bytes::<16>().expect("Squeezing 128 bits");
 //! ```
 //!
+//! The traits needed by most protocols (including those of the plugins) can be imported at once from the [`prelude`].
+//!
 //! # Acknowledgements
 //!
 //! This work is heavily inspired from:
@@ -119,34 +136,124 @@ This crate doesn't support big-endian targets.
 "#
 );
 
+/// Fiat-Shamir with aborts.
+mod aborts;
+/// Absorbing and reading values whose shape is determined by their type.
+mod absorb;
+/// Transcript management for proof aggregation.
+mod aggregation;
 /// Verifier state and transcript deserialization.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
 mod arthur;
+/// Verification of batches of independent proofs.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
+mod batch;
+/// The metadata of the IO pattern absorbed along with the messages and challenges.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
+mod binding;
+/// Bit, fixed-weight, permutation, and subset challenges.
+mod bits;
+/// Deduplicating codec for the protocol transcript.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
+mod codec;
+/// Commitments to data, opened later in the protocol.
+mod commitment;
+/// Compliance suite for hash function backends.
+pub mod compliance;
+/// Binding of context values (timestamps, chain ids, epochs) into transcripts.
+mod context;
+/// Constant-time comparisons.
+mod ct;
+/// Encoding policies for elements in the protocol transcript.
+mod encoding;
+/// Type-erased prover and verifier states.
+mod erased;
 /// Built-in proof results.
 mod errors;
+/// Random protocols and mutation harnesses, for property-based testing and fuzzing.
+pub mod fuzz;
 /// Hash functions traits and implementations.
 pub mod hash;
+/// Integrity tags over protocol transcripts.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
+mod integrity;
 /// IO Pattern
 mod iopattern;
+/// Challenges squeezed lazily, on first use.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
+mod lazy;
 /// Prover's internal state and transcript generation.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
 mod merlin;
+/// Composite messages, with their shape bound in the IO pattern.
+mod message;
+/// Deterministic and hedged nonce derivation for the prover.
+mod nonce;
+/// Partial verification, up to a labeled operation.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
+mod partial;
 /// APIs for common zkp libraries.
 pub mod plugins;
+/// The traits needed by most protocols, to be glob-imported.
+pub mod prelude;
+/// Framing of protocol transcripts for transport.
+mod proof;
+/// Generic protocols, compiled with nimue.
+pub mod protocols;
+/// Registry of well-known domain separators.
+pub mod registry;
+/// Rendering of IO patterns as diagrams.
+mod render;
 /// SAFE API.
+#[deny(clippy::unwrap_used, clippy::expect_used)]
 mod safe;
+/// Discrete Gaussian and bounded-uniform challenges.
+mod samplers;
+/// Binding of transcripts to a session identifier.
+mod session;
+/// Length-prefixed UTF-8 strings.
+mod strings;
 /// Unit-tests.
 #[cfg(test)]
 mod tests;
 
+/// Labels of the operations, for tracing transcripts.
+#[cfg(feature = "tracing")]
+mod trace;
 /// Traits for byte support.
 pub mod traits;
+/// Recording and replaying golden test vectors.
+mod vectors;
 
+pub use aborts::AbortIOPattern;
+pub use absorb::{AbsorbPattern, AbsorbReader, AbsorbWriter};
+pub use aggregation::{AggregationIOPattern, AggregationVerifier, Aggregator};
 pub use arthur::Arthur;
+pub use batch::BatchReport;
+pub use bits::{BitChallenges, BitIOPattern};
+pub use commitment::{CommitIOPattern, CommitReader, CommitWriter, Commitment};
+pub use context::{Context, ContextIOPattern, ContextPublic};
+pub use encoding::{Encoding, Endianness, HashToField, UnitEncoding};
+pub use erased::{AnyArthur, AnyMerlin};
 pub use errors::{IOPatternError, ProofError, ProofResult};
 pub use hash::{legacy::DigestBridge, DuplexHash, Unit};
-pub use iopattern::IOPattern;
+pub use iopattern::{check_label, escape_label, IOPattern, LabelPolicy, RatchetPolicy};
+pub use lazy::ChallengeHandle;
 pub use merlin::Merlin;
+pub use message::{FromTranscript, ToTranscript, TranscriptPattern, TranscriptShape};
+pub use proof::Proof;
 pub use safe::Safe;
+pub use samplers::{DiscreteGaussian, SamplerChallenges, SamplerIOPattern};
+pub use strings::{StringIOPattern, StringPublic, StringReader, StringWriter};
 pub use traits::*;
+pub use vectors::{Operation, TestVector};
+
+#[cfg(feature = "derive")]
+pub use nimue_derive::Absorb;
+
+// Derived implementations refer to `::nimue`, also within this crate.
+#[cfg(feature = "derive")]
+extern crate self as nimue;
 
 /// Default random number generator used ([`rand::rngs::OsRng`]).
 pub type DefaultRng = rand::rngs::OsRng;
@@ -1,11 +1,31 @@
 use rand::{CryptoRng, RngCore};
 
+use crate::codec::DedupEncoder;
 use crate::hash::Unit;
-use crate::{ByteWriter, IOPattern, Safe, UnitTranscript};
+use crate::{
+    ByteWriter, CommonTranscript, Encoding, Endianness, HashToField, IOPattern, Safe, UnitEncoding,
+    UnitTranscript,
+};
 
 use super::hash::{DuplexHash, Keccak};
 use super::{DefaultHash, DefaultRng, IOPatternError};
 
+/// The size, in bytes, up to which the protocol transcript is stored inline with the feature `smallvec`.
+///
+/// It fits a group element and a scalar of a 256-bit curve (e.g., a Schnorr signature)
+/// with room for a few more messages.
+#[cfg(feature = "smallvec")]
+const INLINE_TRANSCRIPT_SIZE: usize = 128;
+
+/// The storage of the protocol transcript.
+///
+/// With the feature `smallvec`, transcripts of at most [`INLINE_TRANSCRIPT_SIZE`] bytes live on the stack,
+/// and larger ones spill over to the heap.
+#[cfg(feature = "smallvec")]
+pub(crate) type TranscriptBuffer = smallvec::SmallVec<[u8; INLINE_TRANSCRIPT_SIZE]>;
+#[cfg(not(feature = "smallvec"))]
+pub(crate) type TranscriptBuffer = Vec<u8>;
+
 /// A cryptographically-secure random number generator that is bound to the protocol transcript.
 ///
 /// For most public-coin protocols it is *vital* not to have two different verifier messages for the same prover message.
@@ -45,7 +65,8 @@ impl<R: RngCore + CryptoRng> RngCore for ProverRng<R> {
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
-        self.sponge.squeeze_unchecked(dest);
+        // reseed and ratchet as well, so that no output can be recovered from a later state
+        self.fill_bytes(dest);
         Ok(())
     }
 }
@@ -66,7 +87,15 @@ where
         Self {
             rng,
             safe,
-            transcript: Vec::new(),
+            encoding: io_pattern.encoding(),
+            hash_to_field: io_pattern.hash_to_field(),
+            challenge_endianness: io_pattern.challenge_endianness(),
+            dedup: None,
+            transcript: TranscriptBuffer::new(),
+            scratch: Vec::new(),
+            integrity: io_pattern
+                .integrity_tag()
+                .then(|| crate::integrity::tag_sponge(io_pattern.as_bytes())),
         }
     }
 }
@@ -88,6 +117,18 @@ where
 /// Unless otherwise specified,
 /// [`Merlin`] is set to work over bytes with [`DefaultHash`] and
 /// rely on the default random number generator [`DefaultRng`].
+///
+/// With the feature `smallvec`, protocol transcripts of up to 128 bytes are stored inline,
+/// so that small proofs (e.g., Schnorr signatures) do not allocate the transcript on the heap.
+///
+/// [`Merlin`] does not implement `Clone`: two copies of the prover state would draw the same private coins,
+/// and answering two different challenges with the same nonce leaks the witness (e.g., the secret key of a Schnorr signature).
+/// Forking a transcript goes through [`Merlin::fork`], which reseeds the copy.
+///
+/// ```compile_fail
+/// let merlin = nimue::IOPattern::<nimue::DefaultHash>::new("📝").to_merlin();
+/// let copy = merlin.clone();
+/// ```
 pub struct Merlin<H = DefaultHash, U = u8, R = DefaultRng>
 where
     U: Unit,
@@ -98,8 +139,20 @@ where
     pub(crate) rng: ProverRng<R>,
     /// The public coins for the protocol
     pub(crate) safe: Safe<H, U>,
+    /// The encoding used for structured elements.
+    pub(crate) encoding: Encoding,
+    /// The method used for deriving field element challenges.
+    pub(crate) hash_to_field: HashToField,
+    /// The byte order of the integers reduced into field element challenges.
+    pub(crate) challenge_endianness: Endianness,
+    /// The deduplicating codec, if enabled.
+    pub(crate) dedup: Option<DedupEncoder>,
     /// The encoded data.
-    pub(crate) transcript: Vec<u8>,
+    pub(crate) transcript: TranscriptBuffer,
+    /// A buffer for serializing structured elements, reused across calls to avoid an allocation per message.
+    pub(crate) scratch: Vec<u8>,
+    /// The sponge computing the integrity tag, until the transcript is sealed.
+    pub(crate) integrity: Option<Keccak>,
 }
 
 impl<H, U, R> Merlin<H, U, R>
@@ -127,8 +180,7 @@ where
         // self.merlin.sponge.absorb_unchecked(&serialized);
         let old_len = self.transcript.len();
         self.safe.absorb(input)?;
-        // write never fails on Vec<u8>
-        U::write(input, &mut self.transcript).unwrap();
+        U::write(input, &mut self.transcript)?;
         self.rng
             .sponge
             .absorb_unchecked(&self.transcript[old_len..]);
@@ -142,6 +194,28 @@ where
         self.safe.ratchet()
     }
 
+    /// Enable the deduplicating codec for the protocol transcript.
+    ///
+    /// Structured elements (scalars and group elements) written by the plugins that already
+    /// appeared in the transcript are replaced by a back-reference.
+    /// This shrinks proofs with many repeated elements (e.g. aggregated proofs) and does not change the challenges,
+    /// but every element pays one byte of overhead. The verifier must enable the codec with [`crate::Arthur::with_dedup`].
+    pub fn with_dedup(mut self) -> Self {
+        self.dedup = Some(DedupEncoder::default());
+        self
+    }
+
+    /// Write an (already absorbed) element in the protocol transcript, using the codec if enabled.
+    ///
+    /// Return an error if the codec cannot index any more elements.
+    pub(crate) fn write_element(&mut self, element: &[u8]) -> Result<(), IOPatternError> {
+        match &mut self.dedup {
+            Some(dedup) => dedup.write(element, &mut self.transcript)?,
+            None => self.transcript.extend_from_slice(element),
+        }
+        Ok(())
+    }
+
     /// Return a reference to the random number generator associated to the protocol transcript.
     ///
     /// ```
@@ -178,6 +252,42 @@ where
     pub fn transcript(&self) -> &[u8] {
         self.transcript.as_slice()
     }
+
+    /// Fork the prover state, with private coins seeded by `csrng` (e.g., a fresh [`DefaultRng`]).
+    ///
+    /// The fork continues the same protocol transcript, but draws private coins distinct from the ones of `self`
+    /// (and of any other fork): they depend on the transcript so far, on the fork, and on `csrng`.
+    /// This is only as good as `csrng`: two forks seeded with the same deterministic generator draw the same coins.
+    ///
+    /// ```
+    /// use nimue::{ByteIOPattern, ByteWriter, DefaultHash, DefaultRng, IOPattern};
+    /// use rand::RngCore;
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(1, "message");
+    /// let mut merlin = io.to_merlin();
+    /// let mut fork = merlin.fork(DefaultRng::default());
+    /// assert_ne!(merlin.rng().next_u64(), fork.rng().next_u64());
+    ///
+    /// merlin.add_bytes(&[1]).unwrap();
+    /// fork.add_bytes(&[2]).unwrap();
+    /// ```
+    pub fn fork<S: RngCore + CryptoRng>(&self, csrng: S) -> Merlin<H, U, S> {
+        let mut sponge = self.rng.sponge.clone();
+        // separate the coins of the fork from the ones of the original state
+        sponge.absorb_unchecked(b"nimue/fork");
+        sponge.ratchet_unchecked();
+        Merlin {
+            rng: ProverRng { sponge, csrng },
+            safe: self.safe.clone(),
+            encoding: self.encoding,
+            hash_to_field: self.hash_to_field,
+            challenge_endianness: self.challenge_endianness,
+            dedup: self.dedup.clone(),
+            transcript: self.transcript.clone(),
+            scratch: Vec::new(),
+            integrity: self.integrity.clone(),
+        }
+    }
 }
 
 impl<H, U, R> UnitTranscript<U> for Merlin<H, U, R>
@@ -211,6 +321,37 @@ where
     }
 }
 
+impl<H, U, R> CommonTranscript<U> for Merlin<H, U, R>
+where
+    U: Unit,
+    H: DuplexHash<U>,
+    R: RngCore + CryptoRng,
+{
+    #[inline]
+    fn ratchet(&mut self) -> Result<(), IOPatternError> {
+        self.safe.ratchet()
+    }
+}
+
+impl<H, U, R> UnitEncoding for Merlin<H, U, R>
+where
+    U: Unit,
+    H: DuplexHash<U>,
+    R: RngCore + CryptoRng,
+{
+    fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    fn hash_to_field(&self) -> HashToField {
+        self.hash_to_field
+    }
+
+    fn challenge_endianness(&self) -> Endianness {
+        self.challenge_endianness
+    }
+}
+
 impl<R: RngCore + CryptoRng> CryptoRng for ProverRng<R> {}
 
 impl<H, U, R> core::fmt::Debug for Merlin<H, U, R>
@@ -0,0 +1,242 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::{Arthur, DuplexHash, IOPattern, Merlin, ProofResult, Unit};
+
+/// The shape of a message, i.e., everything the verifier must know in advance in order to read it.
+///
+/// Values whose layout is fixed by their type, like bytes, scalars, points, and [`AbsorbPattern`](crate::AbsorbPattern) structs,
+/// have shape `()`.
+/// Composite messages have the shape of their components:
+/// - `(A, B, ...)` has shape `(A::Shape, B::Shape, ...)`;
+/// - `[T; N]` has the shape `T::Shape` shared by its elements;
+/// - `Vec<T>` has shape `(len, T::Shape)`, i.e., its length and the shape shared by its elements;
+/// - `Option<T>` has shape `Option<T::Shape>`, i.e., `None` if the value is absent.
+///
+/// The shape is bound in the IO pattern by [`TranscriptPattern`], so that, e.g., vectors of different lengths
+/// belong to different protocols.
+pub trait TranscriptShape {
+    type Shape;
+}
+
+/// Declare messages of a given shape in the IO pattern `P`.
+pub trait TranscriptPattern<P>: TranscriptShape {
+    /// Declare `count` messages of shape `shape`, labeled `label`.
+    ///
+    /// The components of tuples are labeled `label.0`, `label.1`, ...
+    fn declare(io: P, count: usize, shape: &Self::Shape, label: &str) -> P;
+}
+
+/// Add a message to the protocol transcript of `W`.
+///
+/// The message must have the shape declared in the IO pattern.
+pub trait ToTranscript<W: ?Sized> {
+    fn to_transcript(&self, transcript: &mut W) -> ProofResult<()>;
+}
+
+/// Retrieve a message of a given shape from the protocol transcript of `R`.
+pub trait FromTranscript<R: ?Sized>: TranscriptShape + Sized {
+    fn from_transcript(transcript: &mut R, shape: &Self::Shape) -> ProofResult<Self>;
+}
+
+/// Implement the message traits with shape `()` for a type implementing the absorb traits.
+macro_rules! shapeless {
+    ([$($generics:tt)*] $T:ty) => {
+        impl<$($generics)*> $crate::TranscriptShape for $T {
+            type Shape = ();
+        }
+
+        impl<__P, $($generics)*> $crate::TranscriptPattern<__P> for $T
+        where
+            $T: $crate::AbsorbPattern<__P>,
+        {
+            fn declare(io: __P, count: usize, _shape: &(), label: &str) -> __P {
+                // empty vectors are not declared at all
+                if count == 0 {
+                    io
+                } else {
+                    <$T as $crate::AbsorbPattern<__P>>::add_to_pattern(io, count, label)
+                }
+            }
+        }
+
+        impl<__W: ?Sized, $($generics)*> $crate::ToTranscript<__W> for $T
+        where
+            $T: $crate::AbsorbWriter<__W>,
+        {
+            fn to_transcript(&self, transcript: &mut __W) -> $crate::ProofResult<()> {
+                <$T as $crate::AbsorbWriter<__W>>::add_to_transcript(self, transcript)
+            }
+        }
+
+        impl<__R: ?Sized, $($generics)*> $crate::FromTranscript<__R> for $T
+        where
+            $T: $crate::AbsorbReader<__R>,
+        {
+            fn from_transcript(transcript: &mut __R, _shape: &()) -> $crate::ProofResult<Self> {
+                <$T as $crate::AbsorbReader<__R>>::read_from_transcript(transcript)
+            }
+        }
+    };
+}
+
+pub(crate) use shapeless;
+
+shapeless!([] u8);
+
+impl<T: TranscriptShape, const N: usize> TranscriptShape for [T; N] {
+    type Shape = T::Shape;
+}
+
+impl<P, T: TranscriptPattern<P>, const N: usize> TranscriptPattern<P> for [T; N] {
+    fn declare(io: P, count: usize, shape: &T::Shape, label: &str) -> P {
+        T::declare(io, N * count, shape, label)
+    }
+}
+
+impl<W: ?Sized, T: ToTranscript<W>, const N: usize> ToTranscript<W> for [T; N] {
+    fn to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+        self.iter()
+            .try_for_each(|value| value.to_transcript(transcript))
+    }
+}
+
+impl<R: ?Sized, T: FromTranscript<R>, const N: usize> FromTranscript<R> for [T; N] {
+    fn from_transcript(transcript: &mut R, shape: &T::Shape) -> ProofResult<Self> {
+        let values = (0..N)
+            .map(|_| T::from_transcript(transcript, shape))
+            .collect::<ProofResult<Vec<_>>>()?;
+        // the length is guaranteed to be N
+        Ok(values.try_into().ok().unwrap())
+    }
+}
+
+impl<T: TranscriptShape> TranscriptShape for Vec<T> {
+    type Shape = (usize, T::Shape);
+}
+
+impl<P, T: TranscriptPattern<P>> TranscriptPattern<P> for Vec<T> {
+    fn declare(io: P, count: usize, (len, shape): &(usize, T::Shape), label: &str) -> P {
+        T::declare(io, len * count, shape, label)
+    }
+}
+
+impl<W: ?Sized, T: ToTranscript<W>> ToTranscript<W> for Vec<T> {
+    fn to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+        self.iter()
+            .try_for_each(|value| value.to_transcript(transcript))
+    }
+}
+
+impl<R: ?Sized, T: FromTranscript<R>> FromTranscript<R> for Vec<T> {
+    fn from_transcript(transcript: &mut R, (len, shape): &(usize, T::Shape)) -> ProofResult<Self> {
+        (0..*len)
+            .map(|_| T::from_transcript(transcript, shape))
+            .collect()
+    }
+}
+
+impl<T: TranscriptShape> TranscriptShape for Option<T> {
+    type Shape = Option<T::Shape>;
+}
+
+impl<P, T: TranscriptPattern<P>> TranscriptPattern<P> for Option<T> {
+    fn declare(io: P, count: usize, shape: &Option<T::Shape>, label: &str) -> P {
+        match shape {
+            Some(shape) => T::declare(io, count, shape, label),
+            None => io,
+        }
+    }
+}
+
+impl<W: ?Sized, T: ToTranscript<W>> ToTranscript<W> for Option<T> {
+    fn to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+        match self {
+            Some(value) => value.to_transcript(transcript),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R: ?Sized, T: FromTranscript<R>> FromTranscript<R> for Option<T> {
+    fn from_transcript(transcript: &mut R, shape: &Option<T::Shape>) -> ProofResult<Self> {
+        shape
+            .as_ref()
+            .map(|shape| T::from_transcript(transcript, shape))
+            .transpose()
+    }
+}
+
+/// Implement the message traits for tuples whose components implement them.
+macro_rules! tuple_messages {
+    ($($T:ident $i:tt),+) => {
+        impl<$($T: TranscriptShape),+> TranscriptShape for ($($T,)+) {
+            type Shape = ($($T::Shape,)+);
+        }
+
+        impl<P, $($T: TranscriptPattern<P>),+> TranscriptPattern<P> for ($($T,)+) {
+            fn declare(mut io: P, count: usize, shape: &Self::Shape, label: &str) -> P {
+                for _ in 0..count {
+                    $(io = $T::declare(io, 1, &shape.$i, &format!("{}.{}", label, $i));)+
+                }
+                io
+            }
+        }
+
+        impl<W: ?Sized, $($T: ToTranscript<W>),+> ToTranscript<W> for ($($T,)+) {
+            fn to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+                $(self.$i.to_transcript(transcript)?;)+
+                Ok(())
+            }
+        }
+
+        impl<R: ?Sized, $($T: FromTranscript<R>),+> FromTranscript<R> for ($($T,)+) {
+            fn from_transcript(transcript: &mut R, shape: &Self::Shape) -> ProofResult<Self> {
+                Ok(($($T::from_transcript(transcript, &shape.$i)?,)+))
+            }
+        }
+    };
+}
+
+tuple_messages!(A 0);
+tuple_messages!(A 0, B 1);
+tuple_messages!(A 0, B 1, C 2);
+tuple_messages!(A 0, B 1, C 2, D 3);
+tuple_messages!(A 0, B 1, C 2, D 3, E 4);
+tuple_messages!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
+    /// Declare a message of type `M` and shape `shape`, see [`TranscriptShape`].
+    ///
+    /// ```
+    /// use nimue::{ByteIOPattern, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝")
+    ///     .add_message::<(u8, Vec<[u8; 2]>)>(&((), (3, ())), "message");
+    /// let expected = IOPattern::<DefaultHash>::new("📝")
+    ///     .add_bytes(1, "message.0")
+    ///     .add_bytes(6, "message.1");
+    /// assert_eq!(io.as_bytes(), expected.as_bytes());
+    /// ```
+    pub fn add_message<M: TranscriptPattern<Self>>(self, shape: &M::Shape, label: &str) -> Self {
+        M::declare(self, 1, shape, label)
+    }
+}
+
+impl<H, U, R> Merlin<H, U, R>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+    R: RngCore + CryptoRng,
+{
+    /// Add a message to the protocol transcript, in the shape declared with [`IOPattern::add_message`].
+    pub fn add_message<M: ToTranscript<Self> + ?Sized>(&mut self, message: &M) -> ProofResult<()> {
+        message.to_transcript(self)
+    }
+}
+
+impl<H: DuplexHash<U>, U: Unit> Arthur<'_, H, U> {
+    /// Read a message of shape `shape` from the protocol transcript, see [`IOPattern::add_message`].
+    pub fn next_message<M: FromTranscript<Self>>(&mut self, shape: &M::Shape) -> ProofResult<M> {
+        M::from_transcript(self, shape)
+    }
+}
@@ -0,0 +1,63 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::hash::Unit;
+use crate::{DuplexHash, Merlin};
+
+/// Domain separator for the nonce derivation.
+const NONCE_DOMAIN: &[u8] = b"nimue/nonce";
+
+impl<H, U, R> Merlin<H, U, R>
+where
+    U: Unit,
+    H: DuplexHash<U>,
+    R: RngCore + CryptoRng,
+{
+    /// Fill `output` with a nonce derived deterministically from `secret`, `message`, and the protocol transcript so far,
+    /// in the spirit of [RFC 6979](https://www.rfc-editor.org/rfc/rfc6979).
+    ///
+    /// The nonce is squeezed from a copy of the prover's private sponge,
+    /// which has absorbed every message (public or not) of the prover:
+    /// the same secret, message, and transcript always give the same nonce,
+    /// and changing any of them gives an independent one.
+    /// The operating system randomness is not used, and the state of [`Merlin::rng`] is not modified.
+    ///
+    /// ```
+    /// use nimue::{ByteWriter, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").absorb(1, "message");
+    /// let (mut merlin, mut other) = (io.to_merlin(), io.to_merlin());
+    /// let (mut k, mut k_other) = ([0u8; 32], [0u8; 32]);
+    /// merlin.deterministic_nonce(b"secret", b"message", &mut k);
+    /// other.deterministic_nonce(b"secret", b"message", &mut k_other);
+    /// assert_eq!(k, k_other);
+    ///
+    /// other.add_bytes(&[0x42]).unwrap();
+    /// other.deterministic_nonce(b"secret", b"message", &mut k_other);
+    /// assert_ne!(k, k_other);
+    /// ```
+    pub fn deterministic_nonce(&mut self, secret: &[u8], message: &[u8], output: &mut [u8]) {
+        self.derive_nonce(secret, message, &[], output)
+    }
+
+    /// Fill `output` with a hedged nonce: as [`Merlin::deterministic_nonce`],
+    /// but additionally seeded with 32 bytes from the cryptographically-secure random number generator.
+    ///
+    /// The nonce remains secure if either the random number generator, or the secret, is good.
+    pub fn hedged_nonce(&mut self, secret: &[u8], message: &[u8], output: &mut [u8]) {
+        let mut seed = [0u8; 32];
+        self.rng.csrng.fill_bytes(&mut seed);
+        self.derive_nonce(secret, message, &seed, output);
+        zeroize::Zeroize::zeroize(&mut seed);
+    }
+
+    fn derive_nonce(&mut self, secret: &[u8], message: &[u8], seed: &[u8], output: &mut [u8]) {
+        let mut sponge = self.rng.sponge.clone();
+        // every input is length-prefixed, so that the encoding is injective
+        for input in [NONCE_DOMAIN, secret, message, seed] {
+            sponge.absorb_unchecked(&(input.len() as u64).to_le_bytes());
+            sponge.absorb_unchecked(input);
+        }
+        sponge.ratchet_unchecked();
+        sponge.squeeze_unchecked(output);
+    }
+}
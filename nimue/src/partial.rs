@@ -0,0 +1,64 @@
+use crate::{Arthur, DuplexHash, IOPattern, IOPatternError, ProofResult, Unit};
+
+impl<H, U> Arthur<'_, H, U>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    /// Run `verifier` on the transcript up to the next operation labeled `label`, leaving the verifier state there.
+    ///
+    /// Services can pre-screen proofs cheaply (e.g., check the first round) before committing to full verification,
+    /// which resumes from the same state.
+    /// `io_pattern` must be the pattern the verifier state was created with.
+    ///
+    /// Return an error if no operation labeled `label` is left, if `verifier` fails,
+    /// or if it does not stop right before the operation labeled `label`; the verifier state can't be resumed after an error.
+    ///
+    /// ```
+    /// use nimue::{ByteChallenges, ByteIOPattern, ByteReader, ByteWriter, DefaultHash, IOPattern, ProofError};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝")
+    ///     .add_bytes(1, "commitment")
+    ///     .challenge_bytes(16, "challenge")
+    ///     .add_bytes(1, "response");
+    /// let mut merlin = io.to_merlin();
+    /// merlin.add_bytes(&[42]).unwrap();
+    /// merlin.challenge_bytes::<16>().unwrap();
+    /// merlin.add_bytes(&[43]).unwrap();
+    ///
+    /// let mut arthur = io.to_arthur(merlin.transcript());
+    /// // pre-screen: the commitment must be 42
+    /// arthur.verify_until(&io, "response", |arthur| {
+    ///     let [commitment] = arthur.next_bytes()?;
+    ///     arthur.challenge_bytes::<16>()?;
+    ///     (commitment == 42).then_some(()).ok_or(ProofError::InvalidProof)
+    /// }).unwrap();
+    /// // resume the verification later on
+    /// assert_eq!(arthur.next_bytes().unwrap(), [43]);
+    /// ```
+    pub fn verify_until<T>(
+        &mut self,
+        io_pattern: &IOPattern<H, U>,
+        label: &str,
+        verifier: impl FnOnce(&mut Self) -> ProofResult<T>,
+    ) -> ProofResult<T> {
+        let Some(target) = io_pattern.progress_before(label, self.safe.progress()) else {
+            self.safe.clear();
+            return Err(
+                IOPatternError::from(format!("No operation labeled {label:?} is left")).into(),
+            );
+        };
+        let result = verifier(self);
+        if result.is_ok() && self.safe.progress() != target {
+            self.safe.clear();
+            return Err(IOPatternError::from(format!(
+                "The verifier did not stop right before the operation labeled {label:?}"
+            ))
+            .into());
+        }
+        if result.is_err() {
+            self.safe.clear();
+        }
+        result
+    }
+}
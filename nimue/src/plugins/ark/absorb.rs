@@ -0,0 +1,81 @@
+use ark_ec::short_weierstrass::{Projective as SWProjective, SWCurveConfig};
+use ark_ec::twisted_edwards::{Projective as TEProjective, TECurveConfig};
+use ark_ff::{Fp, FpConfig};
+
+use super::{FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupReader, GroupWriter};
+use crate::message::shapeless;
+use crate::{AbsorbPattern, AbsorbReader, AbsorbWriter, ProofResult};
+
+impl<P, C, const N: usize> AbsorbPattern<P> for Fp<C, N>
+where
+    C: FpConfig<N>,
+    P: FieldIOPattern<Fp<C, N>>,
+{
+    fn add_to_pattern(io: P, count: usize, label: &str) -> P {
+        io.add_scalars(count, label)
+    }
+}
+
+impl<W, C, const N: usize> AbsorbWriter<W> for Fp<C, N>
+where
+    C: FpConfig<N>,
+    W: FieldWriter<Fp<C, N>> + ?Sized,
+{
+    fn add_to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+        transcript.add_scalars(&[*self])
+    }
+}
+
+impl<R, C, const N: usize> AbsorbReader<R> for Fp<C, N>
+where
+    C: FpConfig<N>,
+    R: FieldReader<Fp<C, N>> + ?Sized,
+{
+    fn read_from_transcript(transcript: &mut R) -> ProofResult<Self> {
+        let [scalar] = transcript.next_scalars()?;
+        Ok(scalar)
+    }
+}
+
+shapeless!([C: FpConfig<N>, const N: usize] Fp<C, N>);
+
+/// Implement the absorb and message traits for a family of projective points.
+macro_rules! absorb_points {
+    ($Projective:ident, $Config:ident) => {
+        impl<P, C> AbsorbPattern<P> for $Projective<C>
+        where
+            C: $Config,
+            P: GroupIOPattern<$Projective<C>>,
+        {
+            fn add_to_pattern(io: P, count: usize, label: &str) -> P {
+                io.add_points(count, label)
+            }
+        }
+
+        impl<W, C> AbsorbWriter<W> for $Projective<C>
+        where
+            C: $Config,
+            W: GroupWriter<$Projective<C>> + ?Sized,
+        {
+            fn add_to_transcript(&self, transcript: &mut W) -> ProofResult<()> {
+                transcript.add_points(&[*self])
+            }
+        }
+
+        impl<R, C> AbsorbReader<R> for $Projective<C>
+        where
+            C: $Config,
+            R: GroupReader<$Projective<C>> + ?Sized,
+        {
+            fn read_from_transcript(transcript: &mut R) -> ProofResult<Self> {
+                let [point] = transcript.next_points()?;
+                Ok(point)
+            }
+        }
+
+        shapeless!([C: $Config] $Projective<C>);
+    };
+}
+
+absorb_points!(SWProjective, SWCurveConfig);
+absorb_points!(TEProjective, TECurveConfig);
@@ -0,0 +1,55 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Fp, FpConfig};
+use rand::{CryptoRng, RngCore};
+
+use super::common::{point_size, serialize_point};
+use super::GroupWriter;
+use crate::{DuplexHash, Merlin, ProofError, ProofResult, UnitTranscript};
+
+/// Add points given in affine form to the protocol transcript.
+///
+/// The transcript is the same as the one produced by [`GroupWriter::add_points`] for the corresponding projective points,
+/// so the IO pattern is declared with [`GroupIOPattern::add_points`](super::GroupIOPattern::add_points)
+/// and the verifier reads the points with [`GroupReader`](super::GroupReader).
+/// Points are serialized directly, instead of being converted to projective and normalized back.
+pub trait AffineGroupWriter<G: CurveGroup>: GroupWriter<G> {
+    fn add_points_affine(&mut self, input: &[G::Affine]) -> ProofResult<()>;
+}
+
+impl<G, H, R> AffineGroupWriter<G> for Merlin<H, u8, R>
+where
+    G: CurveGroup,
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+    Merlin<H, u8, R>: GroupWriter<G>,
+{
+    fn add_points_affine(&mut self, input: &[G::Affine]) -> ProofResult<()> {
+        let size = point_size::<G::Affine>(self.encoding);
+        let mut buf = Vec::with_capacity(size * input.len());
+        for point in input {
+            serialize_point(point, self.encoding, &mut buf)?;
+        }
+        self.public_units(&buf)?;
+        buf.chunks(size)
+            .try_for_each(|element| self.write_element(element))?;
+        Ok(())
+    }
+}
+
+impl<G, H, R, C: FpConfig<N>, const N: usize> AffineGroupWriter<G> for Merlin<H, Fp<C, N>, R>
+where
+    G: CurveGroup<BaseField = Fp<C, N>>,
+    H: DuplexHash<Fp<C, N>>,
+    R: RngCore + CryptoRng,
+    Merlin<H, Fp<C, N>, R>: GroupWriter<G>,
+{
+    fn add_points_affine(&mut self, input: &[G::Affine]) -> ProofResult<()> {
+        for point in input {
+            // the point at infinity has no affine coordinates
+            let (x, y) = point.xy().ok_or(ProofError::SerializationError)?;
+            self.public_units(&[x, y])?;
+            serialize_point(point, self.encoding, &mut self.transcript)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! A transcript following [barretenberg](https://github.com/AztecProtocol/barretenberg)'s Honk transcript
+//! with Keccak-256 (as in `UltraKeccakFlavor`, used by the Solidity verifiers generated by Noir), with feature flag `barretenberg`.
+//!
+//! Barretenberg's transcript is organized in rounds: the prover sends labelled elements to the verifier,
+//! and a round ends when challenges are requested. The challenges of a round are derived as
+//! $$
+//! h_i = \mathsf{keccak256}(h_{i-1} \Vert \mathit{round}_i) \bmod r,
+//! $$
+//! where $\mathit{round}_i$ is the serialization of the elements sent since the previous challenge,
+//! and $h_{-1}$ is the empty string.
+//! Each hash gives two 128-bit challenges: its lower 128 bits, then the upper ones.
+//! If an odd number of challenges is requested, the last hash only gives its lower half.
+//!
+//! Elements are serialized as barretenberg does for BN254 on the EVM:
+//! field elements as 32-byte big-endian integers, points as their affine coordinates $x \Vert y$
+//! (with $(0, 0)$ for the point at infinity).
+//!
+//! The transcript records a [`Manifest`]: for each round, the challenges requested and the labels and sizes of the elements sent.
+//! As in barretenberg's tests, comparing the manifests of the prover and the verifier (or of a reference implementation)
+//! pinpoints where two transcripts diverge.
+//!
+//! Legacy UltraPlonk transcripts, based on Pedersen hashes over Grumpkin, are not supported.
+//!
+//! ```
+//! use ark_bn254::{Fr, G1Projective as G1};
+//! use ark_ec::PrimeGroup;
+//! use nimue::plugins::ark::barretenberg::BarretenbergTranscript;
+//!
+//! let mut prover = BarretenbergTranscript::new();
+//! prover.send_to_verifier("circuit_size", &[Fr::from(1u64 << 10)]).unwrap();
+//! prover.send_points_to_verifier("W_1", &[G1::generator()]).unwrap();
+//! let [eta, eta_two]: [Fr; 2] = prover.get_challenges(&["eta", "eta_two"]).unwrap();
+//!
+//! let mut verifier = BarretenbergTranscript::from_proof(prover.proof_data());
+//! let [_circuit_size]: [Fr; 1] = verifier.receive_from_prover("circuit_size").unwrap();
+//! let [_w_1]: [G1; 1] = verifier.receive_points_from_prover("W_1").unwrap();
+//! assert_eq!(verifier.get_challenges(&["eta", "eta_two"]).unwrap(), [eta, eta_two]);
+//! assert_eq!(prover.manifest(), verifier.manifest());
+//! ```
+use core::fmt;
+
+use ark_ec::short_weierstrass::{Affine, Projective, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use sha3::{Digest, Keccak256};
+
+use crate::{IOPatternError, ProofError, ProofResult};
+
+/// The size of a serialized field element.
+const WORD: usize = 32;
+
+/// The data of a round of the transcript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoundData {
+    /// The labels of the challenges requested at the end of the round.
+    pub challenges: Vec<String>,
+    /// The labels and sizes (in bytes) of the elements sent during the round.
+    pub entries: Vec<(String, usize)>,
+}
+
+/// The structure of a transcript, round by round.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub rounds: Vec<RoundData>,
+}
+
+impl Manifest {
+    fn current_round(&mut self, index: usize) -> &mut RoundData {
+        if self.rounds.len() <= index {
+            self.rounds.resize(index + 1, RoundData::default());
+        }
+        &mut self.rounds[index]
+    }
+}
+
+/// Print the manifest in the same format as barretenberg's `TranscriptManifest::print`.
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, round) in self.rounds.iter().enumerate() {
+            writeln!(f, "Round: {index}")?;
+            for label in &round.challenges {
+                writeln!(f, "\tchallenge: {label}")?;
+            }
+            for (label, size) in &round.entries {
+                writeln!(f, "\telement ({size}): {label}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A barretenberg Honk transcript with Keccak-256.
+///
+/// The prover creates it with [`BarretenbergTranscript::new`] and sends elements with `send_*`;
+/// the verifier creates it with [`BarretenbergTranscript::from_proof`] and reads them with `receive_*`.
+#[derive(Clone, Debug, Default)]
+pub struct BarretenbergTranscript {
+    proof_data: Vec<u8>,
+    read_position: usize,
+    round_data: Vec<u8>,
+    previous_challenge: Option<[u8; WORD]>,
+    round_index: usize,
+    manifest: Manifest,
+}
+
+impl BarretenbergTranscript {
+    /// The transcript of the prover.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The transcript of the verifier, reading the elements sent from `proof_data`.
+    pub fn from_proof(proof_data: &[u8]) -> Self {
+        Self {
+            proof_data: proof_data.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// The elements sent by the prover, serialized.
+    pub fn proof_data(&self) -> &[u8] {
+        &self.proof_data
+    }
+
+    /// The manifest of the rounds so far.
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    fn add_to_round(&mut self, label: &str, bytes: &[u8]) {
+        self.round_data.extend_from_slice(bytes);
+        self.manifest
+            .current_round(self.round_index)
+            .entries
+            .push((label.to_string(), bytes.len()));
+    }
+
+    /// Send the field elements `input`, labelled `label`, to the verifier.
+    pub fn send_to_verifier<F: PrimeField>(&mut self, label: &str, input: &[F]) -> ProofResult<()> {
+        let bytes = scalars_bytes(input)?;
+        self.proof_data.extend_from_slice(&bytes);
+        self.add_to_round(label, &bytes);
+        Ok(())
+    }
+
+    /// Send the points `input`, labelled `label`, to the verifier.
+    pub fn send_points_to_verifier<G>(&mut self, label: &str, input: &[G]) -> ProofResult<()>
+    where
+        G: CurveGroup,
+        G::BaseField: PrimeField,
+    {
+        let bytes = points_bytes(input)?;
+        self.proof_data.extend_from_slice(&bytes);
+        self.add_to_round(label, &bytes);
+        Ok(())
+    }
+
+    fn read(&mut self, size: usize) -> ProofResult<Vec<u8>> {
+        let end = self.read_position + size;
+        let bytes = self
+            .proof_data
+            .get(self.read_position..end)
+            .ok_or_else(|| IOPatternError::from("Proof data too short"))?
+            .to_vec();
+        self.read_position = end;
+        Ok(bytes)
+    }
+
+    /// Receive the field elements labelled `label` from the prover.
+    ///
+    /// Return an error if the proof data is too short, or the elements are not canonical.
+    pub fn receive_from_prover<F: PrimeField, const N: usize>(
+        &mut self,
+        label: &str,
+    ) -> ProofResult<[F; N]> {
+        let bytes = self.read(N * WORD)?;
+        let mut output = [F::zero(); N];
+        for (o, chunk) in output.iter_mut().zip(bytes.chunks(WORD)) {
+            *o = read_word(chunk)?;
+        }
+        self.add_to_round(label, &bytes);
+        Ok(output)
+    }
+
+    /// Receive the points labelled `label` from the prover.
+    ///
+    /// Return an error if the proof data is too short, or the points are not canonical,
+    /// not on the curve, or not in the prime-order subgroup.
+    pub fn receive_points_from_prover<P, const N: usize>(
+        &mut self,
+        label: &str,
+    ) -> ProofResult<[Projective<P>; N]>
+    where
+        P: SWCurveConfig,
+        P::BaseField: PrimeField,
+    {
+        let bytes = self.read(N * 2 * WORD)?;
+        let mut output = [Projective::<P>::zero(); N];
+        for (o, chunk) in output.iter_mut().zip(bytes.chunks(2 * WORD)) {
+            let x = read_word::<P::BaseField>(&chunk[..WORD])?;
+            let y = read_word::<P::BaseField>(&chunk[WORD..])?;
+            if x.is_zero() && y.is_zero() {
+                *o = Projective::zero();
+                continue;
+            }
+            let point = Affine::<P>::new_unchecked(x, y);
+            if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+                return Err(ProofError::SerializationError);
+            }
+            *o = point.into();
+        }
+        self.add_to_round(label, &bytes);
+        Ok(output)
+    }
+
+    /// Derive the challenges `labels`, ending the current round.
+    pub fn get_challenges<F: PrimeField, const N: usize>(
+        &mut self,
+        labels: &[&str; N],
+    ) -> ProofResult<[F; N]> {
+        self.manifest.current_round(self.round_index).challenges =
+            labels.iter().map(|label| label.to_string()).collect();
+
+        let mut output = [F::zero(); N];
+        for pair in output.chunks_mut(2) {
+            let challenge = self.next_challenge_buffer::<F>();
+            let (hi, lo) = challenge.split_at(WORD / 2);
+            pair[0] = F::from_be_bytes_mod_order(lo);
+            if let Some(second) = pair.get_mut(1) {
+                *second = F::from_be_bytes_mod_order(hi);
+            }
+        }
+        self.round_index += 1;
+        Ok(output)
+    }
+
+    /// Hash the previous challenge and the round data, and reduce the result modulo the field.
+    fn next_challenge_buffer<F: PrimeField>(&mut self) -> [u8; WORD] {
+        let mut hasher = Keccak256::new();
+        if let Some(previous) = &self.previous_challenge {
+            hasher.update(previous);
+        }
+        hasher.update(&self.round_data);
+        self.round_data.clear();
+        let digest = F::from_be_bytes_mod_order(&hasher.finalize());
+        let mut challenge = [0u8; WORD];
+        let bytes = digest.into_bigint().to_bytes_be();
+        challenge[WORD - bytes.len()..].copy_from_slice(&bytes);
+        self.previous_challenge = Some(challenge);
+        challenge
+    }
+}
+
+/// The 32-byte big-endian encoding of a field element.
+fn word<F: PrimeField>(element: &F) -> ProofResult<[u8; WORD]> {
+    let bytes = element.into_bigint().to_bytes_be();
+    let (high, low) = bytes.split_at(bytes.len().saturating_sub(WORD));
+    if high.iter().any(|&b| b != 0) || F::MODULUS_BIT_SIZE > 256 {
+        return Err(ProofError::SerializationError);
+    }
+    let mut output = [0u8; WORD];
+    output[WORD - low.len()..].copy_from_slice(low);
+    Ok(output)
+}
+
+/// Decode a canonical 32-byte big-endian encoding of a field element.
+fn read_word<F: PrimeField>(bytes: &[u8]) -> ProofResult<F> {
+    let element = F::from_be_bytes_mod_order(bytes);
+    if word(&element)? == bytes {
+        Ok(element)
+    } else {
+        Err(ProofError::SerializationError)
+    }
+}
+
+fn scalars_bytes<F: PrimeField>(input: &[F]) -> ProofResult<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(input.len() * WORD);
+    for scalar in input {
+        bytes.extend(word(scalar)?);
+    }
+    Ok(bytes)
+}
+
+fn points_bytes<G>(input: &[G]) -> ProofResult<Vec<u8>>
+where
+    G: CurveGroup,
+    G::BaseField: PrimeField,
+{
+    let mut bytes = Vec::with_capacity(input.len() * 2 * WORD);
+    for point in G::normalize_batch(input) {
+        let (x, y) = point
+            .xy()
+            .unwrap_or((G::BaseField::zero(), G::BaseField::zero()));
+        bytes.extend(word(&x)?);
+        bytes.extend(word(&y)?);
+    }
+    Ok(bytes)
+}
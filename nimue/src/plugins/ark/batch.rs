@@ -0,0 +1,113 @@
+use ark_ec::short_weierstrass::{Affine as SWAffine, Projective as SWCurve, SWCurveConfig};
+use ark_ec::twisted_edwards::{Affine as EdwardsAffine, Projective as EdwardsCurve, TECurveConfig};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_serialize::{Compress, Validate};
+use rand::{CryptoRng, Rng, RngCore};
+
+use super::common::{deserialize_point, point_size};
+use crate::{Arthur, DuplexHash, Encoding, ProofError, ProofResult, UnitTranscript};
+
+/// Receive group elements from the protocol transcript, deferring the subgroup checks.
+///
+/// Points are first deserialized and checked to be on the curve,
+/// then the subgroup membership of all of them is tested at once,
+/// on a random linear combination with 128-bit coefficients drawn from `rng`.
+/// If some point lies outside of the prime-order subgroup, the combination is still in the subgroup
+/// with probability at most $1/\ell$, where $\ell$ is the smallest prime factor of the cofactor.
+/// This is therefore only a faithful replacement for [`GroupReader`](super::GroupReader) over curves whose cofactor has no small prime factors
+/// (or for which the cofactor is 1).
+///
+/// The randomness **MUST** be unknown to the prover.
+pub trait BatchedGroupReader<G: CurveGroup> {
+    fn fill_next_points_batched(
+        &mut self,
+        output: &mut [G],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> ProofResult<()>;
+}
+
+impl<H: DuplexHash> Arthur<'_, H> {
+    /// Read `count` points from the transcript, without validating them.
+    fn next_points_unchecked<A: AffineRepr>(&mut self, count: usize) -> ProofResult<Vec<A>> {
+        let mut buf = vec![0u8; point_size::<A>(self.encoding)];
+        let mut points = Vec::with_capacity(count);
+        for _ in 0..count {
+            self.read_element(&mut buf)?;
+            self.public_units(&buf)?;
+            let point = match self.encoding {
+                Encoding::Compressed => {
+                    A::deserialize_with_mode(buf.as_slice(), Compress::Yes, Validate::No)?
+                }
+                Encoding::Uncompressed => {
+                    A::deserialize_with_mode(buf.as_slice(), Compress::No, Validate::No)?
+                }
+                // the canonicity of fixed-width encodings is checked on the validated point
+                Encoding::FixedWidthLE => deserialize_point(buf.as_slice(), self.encoding)?,
+            };
+            points.push(point);
+        }
+        Ok(points)
+    }
+}
+
+/// Combine `points` with random 128-bit coefficients.
+fn random_combination<G: CurveGroup>(
+    points: &[G::Affine],
+    rng: &mut (impl CryptoRng + RngCore),
+) -> G::Affine {
+    let coefficients = points
+        .iter()
+        .map(|_| G::ScalarField::from(rng.gen::<u128>()))
+        .collect::<Vec<_>>();
+    G::msm_unchecked(points, &coefficients).into_affine()
+}
+
+impl<P, H> BatchedGroupReader<SWCurve<P>> for Arthur<'_, H>
+where
+    P: SWCurveConfig,
+    H: DuplexHash,
+{
+    fn fill_next_points_batched(
+        &mut self,
+        output: &mut [SWCurve<P>],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> ProofResult<()> {
+        let points: Vec<SWAffine<P>> = self.next_points_unchecked(output.len())?;
+        if !points.iter().all(SWAffine::is_on_curve)
+            || !random_combination::<SWCurve<P>>(&points, rng)
+                .is_in_correct_subgroup_assuming_on_curve()
+        {
+            return Err(ProofError::SerializationError);
+        }
+        output
+            .iter_mut()
+            .zip(points)
+            .for_each(|(o, point)| *o = point.into());
+        Ok(())
+    }
+}
+
+impl<P, H> BatchedGroupReader<EdwardsCurve<P>> for Arthur<'_, H>
+where
+    P: TECurveConfig,
+    H: DuplexHash,
+{
+    fn fill_next_points_batched(
+        &mut self,
+        output: &mut [EdwardsCurve<P>],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> ProofResult<()> {
+        let points: Vec<EdwardsAffine<P>> = self.next_points_unchecked(output.len())?;
+        if !points.iter().all(EdwardsAffine::is_on_curve)
+            || !random_combination::<EdwardsCurve<P>>(&points, rng)
+                .is_in_correct_subgroup_assuming_on_curve()
+        {
+            return Err(ProofError::SerializationError);
+        }
+        output
+            .iter_mut()
+            .zip(points)
+            .for_each(|(o, point)| *o = point.into());
+        Ok(())
+    }
+}
@@ -6,15 +6,188 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError
 use rand::{CryptoRng, RngCore};
 
 use super::{FieldChallenges, FieldPublic, GroupPublic};
-use crate::plugins::bytes_uniform_modp;
+use crate::plugins::bytes_challenge_modp;
 use crate::{
-    Arthur, ByteChallenges, BytePublic, DuplexHash, IOPatternError, Merlin, ProofError,
-    ProofResult, Unit, UnitTranscript,
+    Arthur, ByteChallenges, BytePublic, DuplexHash, Encoding, Endianness, IOPatternError, Merlin,
+    ProofError, ProofResult, Unit, UnitEncoding, UnitTranscript,
 };
 
+/// Serialize `value` into `w` following the given [`Encoding`].
+///
+/// Values other than points are written in the same way with [`Encoding::FixedWidthLE`] and [`Encoding::Uncompressed`];
+/// points go through [`serialize_point`].
+pub(super) fn serialize_with<T: CanonicalSerialize>(
+    value: &T,
+    encoding: Encoding,
+    w: impl io::Write,
+) -> Result<(), SerializationError> {
+    match encoding {
+        Encoding::Compressed => value.serialize_compressed(w),
+        Encoding::Uncompressed | Encoding::FixedWidthLE => value.serialize_uncompressed(w),
+    }
+}
+
+/// Deserialize (and validate) an element from `r` following the given [`Encoding`].
+pub(super) fn deserialize_with<T: CanonicalDeserialize>(
+    r: impl io::Read,
+    encoding: Encoding,
+) -> Result<T, SerializationError> {
+    match encoding {
+        Encoding::Compressed => T::deserialize_compressed(r),
+        Encoding::Uncompressed | Encoding::FixedWidthLE => T::deserialize_uncompressed(r),
+    }
+}
+
+/// The number of bytes `value` occupies once serialized with the given [`Encoding`].
+pub(super) fn encoded_size<T: CanonicalSerialize>(value: &T, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Compressed => value.compressed_size(),
+        Encoding::Uncompressed | Encoding::FixedWidthLE => value.uncompressed_size(),
+    }
+}
+
+/// The number of bytes a point occupies once serialized with the given [`Encoding`].
+pub(super) fn point_size<A: AffineRepr>(encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::FixedWidthLE => 2 * A::BaseField::ZERO.uncompressed_size(),
+        _ => encoded_size(&A::default(), encoding),
+    }
+}
+
+/// Serialize `point` into `w` following the given [`Encoding`].
+///
+/// With [`Encoding::FixedWidthLE`], the affine coordinates are written one after the other,
+/// and the point at infinity (which has none) as zeros.
+pub(super) fn serialize_point<A: AffineRepr>(
+    point: &A,
+    encoding: Encoding,
+    mut w: impl io::Write,
+) -> Result<(), SerializationError> {
+    match (encoding, point.xy()) {
+        (Encoding::FixedWidthLE, Some((x, y))) => {
+            x.serialize_uncompressed(&mut w)?;
+            y.serialize_uncompressed(w)
+        }
+        (Encoding::FixedWidthLE, None) => Ok(w.write_all(&vec![0u8; point_size::<A>(encoding)])?),
+        _ => serialize_with(point, encoding, w),
+    }
+}
+
+/// Deserialize (and validate) a point from `r` following the given [`Encoding`].
+pub(super) fn deserialize_point<A: AffineRepr>(
+    mut r: impl io::Read,
+    encoding: Encoding,
+) -> Result<A, SerializationError> {
+    if encoding != Encoding::FixedWidthLE {
+        return deserialize_with(r, encoding);
+    }
+    let size = point_size::<A>(encoding);
+    // room for the flags of the uncompressed encoding of arkworks, if they do not fit in the spare bits of y
+    let mut bytes = vec![0u8; size.max(A::default().uncompressed_size())];
+    r.read_exact(&mut bytes[..size])?;
+    let point = if bytes.iter().all(|&b| b == 0) {
+        A::zero()
+    } else {
+        // the coordinates, followed by cleared flags, are an uncompressed encoding
+        A::deserialize_uncompressed(&bytes[..])?
+    };
+    // reject the encodings that are not written back identically (e.g., with flags set)
+    let mut encoded = Vec::with_capacity(size);
+    serialize_point(&point, encoding, &mut encoded)?;
+    if encoded != bytes[..size] {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(point)
+}
+
+/// The size of the buffers that the verifier allocates on the stack, in bytes.
+const STACK_BUFFER_SIZE: usize = 512;
+
+/// Run `f` on a zeroed buffer of `len` bytes, allocated on the stack if it fits in [`STACK_BUFFER_SIZE`] bytes.
+pub(super) fn with_buffer<T>(len: usize, f: impl FnOnce(&mut [u8]) -> T) -> T {
+    if len <= STACK_BUFFER_SIZE {
+        f(&mut [0u8; STACK_BUFFER_SIZE][..len])
+    } else {
+        f(&mut vec![0u8; len])
+    }
+}
+
+/// Interpret `bytes` as a big-endian integer and reduce it modulo the characteristic of `F`.
+///
+/// Equivalent to [`PrimeField::from_be_bytes_mod_order`], but without allocating:
+/// the integer is accumulated in 64-bit limbs with Horner's rule.
+pub(super) fn from_be_bytes_mod_order<F: PrimeField>(bytes: &[u8]) -> F {
+    let shift = F::from(1u128 << 64);
+    let limb = |chunk: &[u8]| F::from(chunk.iter().fold(0u64, |acc, &b| acc << 8 | b as u64));
+    let (head, tail) = bytes.split_at(bytes.len() % 8);
+    tail.chunks(8)
+        .fold(limb(head), |acc, chunk| acc * shift + limb(chunk))
+}
+
+/// The number of elements above which [`serialize_all`] serializes in parallel (with the feature `parallel`).
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 1024;
+
+/// Serialize every element of `input` at the end of `buf`, following the given [`Encoding`].
+///
+/// With the feature `parallel`, large slices are serialized in parallel with rayon
+/// (field and group elements have a fixed size, so each element is written in place).
+pub(super) fn serialize_all<T: CanonicalSerialize + Sync>(
+    input: &[T],
+    encoding: Encoding,
+    buf: &mut Vec<u8>,
+) -> Result<(), SerializationError> {
+    let size = input
+        .first()
+        .map_or(0, |element| encoded_size(element, encoding));
+    serialize_all_with(input, size, buf, |element, chunk| {
+        serialize_with(element, encoding, chunk)
+    })
+}
+
+/// Serialize every point of `input` at the end of `buf`, as [`serialize_all`].
+pub(super) fn serialize_all_points<A: AffineRepr>(
+    input: &[A],
+    encoding: Encoding,
+    buf: &mut Vec<u8>,
+) -> Result<(), SerializationError> {
+    serialize_all_with(input, point_size::<A>(encoding), buf, |point, chunk| {
+        serialize_point(point, encoding, chunk)
+    })
+}
+
+/// Write every element of `input` with `serialize`, in a chunk of `size` bytes at the end of `buf`.
+fn serialize_all_with<T: Sync>(
+    input: &[T],
+    size: usize,
+    buf: &mut Vec<u8>,
+    serialize: impl Fn(&T, &mut [u8]) -> Result<(), SerializationError> + Sync,
+) -> Result<(), SerializationError> {
+    if input.is_empty() {
+        return Ok(());
+    }
+    let start = buf.len();
+    buf.resize(start + input.len() * size, 0);
+    #[cfg(feature = "parallel")]
+    if input.len() >= PARALLEL_THRESHOLD {
+        use rayon::prelude::*;
+
+        return buf[start..]
+            .par_chunks_mut(size)
+            .zip(input.par_iter())
+            .try_for_each(|(chunk, element)| serialize(element, chunk));
+    }
+    buf[start..]
+        .chunks_mut(size)
+        .zip(input)
+        .try_for_each(|(chunk, element)| serialize(element, chunk))
+}
+
 // Implementation of basic traits for bridging arkworks and nimue
 
 impl<C: FpConfig<N>, const N: usize> Unit for Fp<C, N> {
+    const BITS: usize = Fp::<C, N>::MODULUS_BIT_SIZE as usize - 1;
+
     fn write(bunch: &[Self], mut w: &mut impl io::Write) -> Result<(), io::Error> {
         for b in bunch {
             b.serialize_compressed(&mut w)
@@ -32,6 +205,18 @@ impl<C: FpConfig<N>, const N: usize> Unit for Fp<C, N> {
         }
         Ok(())
     }
+
+    /// Bytes are packed in little-endian chunks of `BITS / 8` bytes, one per field element:
+    /// e.g., a length is absorbed as a single field element.
+    fn pack_bytes(bytes: &[u8]) -> Option<Vec<Self>> {
+        let chunk_size = (<Self as Unit>::BITS / 8).max(1);
+        Some(
+            bytes
+                .chunks(chunk_size)
+                .map(Self::from_le_bytes_mod_order)
+                .collect(),
+        )
+    }
 }
 
 impl From<SerializationError> for ProofError {
@@ -45,15 +230,15 @@ impl From<SerializationError> for ProofError {
 impl<T, G> GroupPublic<G> for T
 where
     G: CurveGroup,
-    T: UnitTranscript<u8>,
+    T: UnitTranscript<u8> + UnitEncoding,
 {
     type Repr = Vec<u8>;
 
     fn public_points(&mut self, input: &[G]) -> ProofResult<Self::Repr> {
+        let encoding = self.encoding();
         let mut buf = Vec::new();
-        for i in input {
-            i.serialize_compressed(&mut buf)?;
-        }
+        // a single inversion for the whole slice, instead of one per point
+        serialize_all_points(&G::normalize_batch(input), encoding, &mut buf)?;
         Ok(self.public_bytes(&buf).map(|()| buf)?)
     }
 }
@@ -61,15 +246,14 @@ where
 impl<T, F> FieldPublic<F> for T
 where
     F: Field,
-    T: UnitTranscript<u8>,
+    T: UnitTranscript<u8> + UnitEncoding,
 {
     type Repr = Vec<u8>;
 
     fn public_scalars(&mut self, input: &[F]) -> ProofResult<Self::Repr> {
+        let encoding = self.encoding();
         let mut buf = Vec::new();
-        for i in input {
-            i.serialize_compressed(&mut buf)?;
-        }
+        serialize_all(input, encoding, &mut buf)?;
         self.public_bytes(&buf)?;
         Ok(buf)
     }
@@ -78,44 +262,87 @@ where
 impl<F, T> FieldChallenges<F> for T
 where
     F: Field,
-    T: UnitTranscript<u8>,
+    T: UnitTranscript<u8> + UnitEncoding,
 {
     fn fill_challenge_scalars(&mut self, output: &mut [F]) -> ProofResult<()> {
-        let base_field_size = bytes_uniform_modp(F::BasePrimeField::MODULUS_BIT_SIZE);
-        let mut buf = vec![0u8; F::extension_degree() as usize * base_field_size];
-
-        for o in output.iter_mut() {
-            self.fill_challenge_bytes(&mut buf)?;
-            *o = F::from_base_prime_field_elems(
-                buf.chunks(base_field_size)
-                    .map(F::BasePrimeField::from_be_bytes_mod_order),
-            )
-            .expect("Could not convert");
+        if output.is_empty() {
+            return Ok(());
         }
-        Ok(())
+        let base_field_size =
+            bytes_challenge_modp(F::BasePrimeField::MODULUS_BIT_SIZE, self.hash_to_field());
+        let size = F::extension_degree() as usize * base_field_size;
+        // squeeze as many elements as fit in a stack buffer at once, in full-rate blocks of the sponge,
+        // equivalent to one squeeze per element since byte sponges are streaming-friendly
+        let batch = usize::max(1, STACK_BUFFER_SIZE / size).min(output.len());
+        let little_endian = self.challenge_endianness() == Endianness::Little;
+        with_buffer(batch * size, |buf| {
+            for outputs in output.chunks_mut(batch) {
+                let buf = &mut buf[..outputs.len() * size];
+                self.fill_challenge_bytes(buf)?;
+                if little_endian {
+                    buf.chunks_mut(base_field_size).for_each(<[u8]>::reverse);
+                }
+                for (o, chunk) in outputs.iter_mut().zip(buf.chunks(size)) {
+                    *o = F::from_base_prime_field_elems(
+                        chunk
+                            .chunks(base_field_size)
+                            .map(from_be_bytes_mod_order::<F::BasePrimeField>),
+                    )
+                    .expect("Could not convert");
+                }
+            }
+            Ok(())
+        })
     }
 }
 
-impl<H, C, const N: usize> FieldChallenges<Fp<C, N>> for Arthur<'_, H, Fp<C, N>>
+/// Squeeze field elements from an algebraic sponge over their base prime field.
+///
+/// Each element of `F` is built from [`Field::extension_degree`] units, following the tower encoding (see [`FieldPublic`]).
+pub(super) fn fill_challenge_scalars_modp<F, T, C, const N: usize>(
+    transcript: &mut T,
+    output: &mut [F],
+) -> ProofResult<()>
 where
+    F: Field<BasePrimeField = Fp<C, N>>,
+    C: FpConfig<N>,
+    T: UnitTranscript<Fp<C, N>>,
+{
+    if output.is_empty() {
+        return Ok(());
+    }
+    let degree = F::extension_degree() as usize;
+    // a single squeeze for the whole slice, in full-rate blocks of the sponge
+    let mut buf = vec![Fp::from(0); output.len() * degree];
+    transcript
+        .fill_challenge_units(&mut buf)
+        .map_err(ProofError::InvalidIO)?;
+    for (o, chunk) in output.iter_mut().zip(buf.chunks(degree)) {
+        *o = F::from_base_prime_field_elems(chunk.iter().copied()).expect("Could not convert");
+    }
+    Ok(())
+}
+
+impl<F, H, C, const N: usize> FieldChallenges<F> for Arthur<'_, H, Fp<C, N>>
+where
+    F: Field<BasePrimeField = Fp<C, N>>,
     C: FpConfig<N>,
     H: DuplexHash<Fp<C, N>>,
 {
-    fn fill_challenge_scalars(&mut self, output: &mut [Fp<C, N>]) -> ProofResult<()> {
-        self.fill_challenge_units(output)
-            .map_err(ProofError::InvalidIO)
+    fn fill_challenge_scalars(&mut self, output: &mut [F]) -> ProofResult<()> {
+        fill_challenge_scalars_modp(self, output)
     }
 }
 
-impl<H, C, R, const N: usize> FieldChallenges<Fp<C, N>> for Merlin<H, Fp<C, N>, R>
+impl<F, H, C, R, const N: usize> FieldChallenges<F> for Merlin<H, Fp<C, N>, R>
 where
+    F: Field<BasePrimeField = Fp<C, N>>,
     C: FpConfig<N>,
     H: DuplexHash<Fp<C, N>>,
     R: CryptoRng + RngCore,
 {
-    fn fill_challenge_scalars(&mut self, output: &mut [Fp<C, N>]) -> ProofResult<()> {
-        self.fill_challenge_units(output)
-            .map_err(ProofError::InvalidIO)
+    fn fill_challenge_scalars(&mut self, output: &mut [F]) -> ProofResult<()> {
+        fill_challenge_scalars_modp(self, output)
     }
 }
 
@@ -187,8 +414,9 @@ where
     type Repr = ();
 
     fn public_points(&mut self, input: &[G]) -> ProofResult<Self::Repr> {
-        for point in input {
-            let (x, y) = point.into_affine().xy().unwrap();
+        for point in G::normalize_batch(input) {
+            // the point at infinity has no affine coordinates
+            let (x, y) = point.xy().ok_or(ProofError::SerializationError)?;
             self.public_units(&[x, y])?;
         }
         Ok(())
@@ -204,8 +432,9 @@ where
     type Repr = ();
 
     fn public_points(&mut self, input: &[G]) -> ProofResult<Self::Repr> {
-        for point in input {
-            let (x, y) = point.into_affine().xy().unwrap();
+        for point in G::normalize_batch(input) {
+            // the point at infinity has no affine coordinates
+            let (x, y) = point.xy().ok_or(ProofError::SerializationError)?;
             self.public_units(&[x, y])?;
         }
         Ok(())
@@ -0,0 +1,188 @@
+//! In-circuit verifier transcripts, for recursive verification with [`ark-r1cs-std`](https://docs.rs/ark-r1cs-std).
+//!
+//! [`ArthurVar`] is the constraint-system version of [`Arthur`](crate::Arthur) for algebraic sponges
+//! whose native field is the field of the constraint system.
+//! It follows the same [`IOPattern`] and squeezes the same challenges as the native verifier,
+//! as long as the gadget implementing [`SpongeVar`] computes the same permutation as its [`SpongeVar::Native`] counterpart.
+//! To make this easy, gadgets are built out of a native sponge (see [`SpongeVar::new_constant`]),
+//! so that the two share the same parameters.
+//!
+//! Prover messages are not read from a transcript: they are variables (typically witnesses) of the circuit,
+//! and are absorbed with [`ArthurVar::public_units`] (or its typed variants) at the position where the native verifier reads them.
+//!
+//! A mismatch between the operations performed and the IO pattern is logged,
+//! and results in [`SynthesisError::Unsatisfiable`]:
+//! no transcript respecting the IO pattern can satisfy such a circuit.
+use std::collections::VecDeque;
+
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+use ark_r1cs_std::groups::curves::short_weierstrass::ProjectiveVar;
+use ark_relations::r1cs::SynthesisError;
+
+use crate::hash::sponge::{DuplexSponge, Sponge};
+use crate::iopattern::Op;
+use crate::{IOPattern, Safe, Unit};
+
+/// The in-circuit version of a [`Sponge`].
+///
+/// Implementors **MUST** compute the same permutation as [`SpongeVar::Native`].
+pub trait SpongeVar<F: PrimeField>: Clone {
+    /// The native sponge, computing the same permutation outside of the circuit.
+    type Native: Sponge<U = F>;
+
+    /// Build the gadget with the same parameters and (constant) state as `native`.
+    fn new_constant(native: &Self::Native) -> Self;
+
+    /// The state of the sponge, of length [`Sponge::N`].
+    fn state(&self) -> &[FpVar<F>];
+
+    /// The state of the sponge, of length [`Sponge::N`].
+    fn state_mut(&mut self) -> &mut [FpVar<F>];
+
+    /// Permute the state of the sponge.
+    fn permute(&mut self) -> Result<(), SynthesisError>;
+}
+
+/// The verifier's transcript, in-circuit.
+pub struct ArthurVar<F, C>
+where
+    F: PrimeField + Unit,
+    C: SpongeVar<F>,
+{
+    sponge: C,
+    absorb_pos: usize,
+    squeeze_pos: usize,
+    stack: VecDeque<Op>,
+}
+
+impl<F, C> ArthurVar<F, C>
+where
+    F: PrimeField + Unit,
+    C: SpongeVar<F>,
+{
+    const N: usize = <C::Native as Sponge>::N;
+    const R: usize = <C::Native as Sponge>::R;
+
+    /// Create a new in-circuit verifier for `io_pattern`.
+    ///
+    /// The IO pattern is a constant of the circuit, so the initialization is free.
+    pub fn new(io_pattern: &IOPattern<DuplexSponge<C::Native>, F>) -> Self {
+        assert!(Self::N > Self::R, "Capacity of the sponge should be > 0.");
+        let tag = Safe::<DuplexSponge<C::Native>, F>::generate_tag(io_pattern.as_bytes());
+        Self {
+            sponge: C::new_constant(&C::Native::new(tag)),
+            absorb_pos: 0,
+            squeeze_pos: Self::R,
+            stack: io_pattern.finalize(),
+        }
+    }
+
+    /// Check that `op` is the next operation in the IO pattern, and consume it.
+    fn consume(&mut self, op: Op) -> Result<(), SynthesisError> {
+        match (self.stack.pop_front(), op) {
+            (Some(Op::Absorb(length)), Op::Absorb(count)) if count <= length => {
+                if count < length {
+                    self.stack.push_front(Op::Absorb(length - count));
+                }
+                Ok(())
+            }
+            (Some(Op::Squeeze(length)), Op::Squeeze(count)) if count <= length => {
+                if count < length {
+                    self.stack.push_front(Op::Squeeze(length - count));
+                }
+                Ok(())
+            }
+            (Some(Op::Ratchet), Op::Ratchet) => Ok(()),
+            (expected, op) => {
+                log::error!("Invalid tag. Got {:?}, expected {:?}", op, expected);
+                self.stack.clear();
+                Err(SynthesisError::Unsatisfiable)
+            }
+        }
+    }
+
+    /// Absorb the prover messages (or public inputs) `input`.
+    pub fn public_units(&mut self, mut input: &[FpVar<F>]) -> Result<(), SynthesisError> {
+        self.consume(Op::Absorb(input.len()))?;
+        while !input.is_empty() {
+            if self.absorb_pos == Self::R {
+                self.sponge.permute()?;
+                self.absorb_pos = 0;
+            } else {
+                let chunk_len = usize::min(input.len(), Self::R - self.absorb_pos);
+                let (chunk, rest) = input.split_at(chunk_len);
+                self.sponge.state_mut()[self.absorb_pos..self.absorb_pos + chunk_len]
+                    .clone_from_slice(chunk);
+                self.absorb_pos += chunk_len;
+                input = rest;
+            }
+        }
+        self.squeeze_pos = Self::R;
+        Ok(())
+    }
+
+    /// Squeeze the verifier's challenges into `output`.
+    pub fn fill_challenge_units(&mut self, output: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
+        self.consume(Op::Squeeze(output.len()))?;
+        for o in output.iter_mut() {
+            if self.squeeze_pos == Self::R {
+                self.squeeze_pos = 0;
+                self.absorb_pos = 0;
+                self.sponge.permute()?;
+            }
+            *o = self.sponge.state()[self.squeeze_pos].clone();
+            self.squeeze_pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Ratchet the state of the sponge.
+    pub fn ratchet(&mut self) -> Result<(), SynthesisError> {
+        self.consume(Op::Ratchet)?;
+        self.sponge.permute()?;
+        self.sponge.state_mut()[..Self::R]
+            .iter_mut()
+            .for_each(|x| *x = FpVar::zero());
+        self.squeeze_pos = Self::R;
+        Ok(())
+    }
+
+    /// Absorb field elements, as [`FieldReader`](super::FieldReader) does for the native verifier.
+    pub fn public_scalars(&mut self, input: &[FpVar<F>]) -> Result<(), SynthesisError> {
+        self.public_units(input)
+    }
+
+    /// Absorb curve points over `F`, as [`GroupReader`](super::GroupReader) does for the native verifier.
+    ///
+    /// Each point is absorbed as its affine coordinates $(x, y)$.
+    /// As for the native verifier, points **MUST** be different from the identity.
+    pub fn public_points<P>(
+        &mut self,
+        input: &[ProjectiveVar<P, FpVar<F>>],
+    ) -> Result<(), SynthesisError>
+    where
+        P: SWCurveConfig<BaseField = F>,
+    {
+        for point in input {
+            let affine = point.to_affine()?;
+            self.public_units(&[affine.x, affine.y])?;
+        }
+        Ok(())
+    }
+
+    /// Squeeze field elements, as [`FieldChallenges`](super::FieldChallenges) does for the native verifier.
+    pub fn fill_challenge_scalars(
+        &mut self,
+        output: &mut [FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        self.fill_challenge_units(output)
+    }
+
+    pub fn challenge_scalars<const N: usize>(&mut self) -> Result<[FpVar<F>; N], SynthesisError> {
+        let mut output = core::array::from_fn(|_| FpVar::zero());
+        self.fill_challenge_scalars(&mut output)?;
+        Ok(output)
+    }
+}
@@ -0,0 +1,137 @@
+//! Designated-verifier proofs, whose challenges depend on a key shared with the verifier.
+//!
+//! The prover samples an ephemeral key $r$, writes $R = rG$ in the transcript,
+//! and keys the duplex with a key derived from the Diffie-Hellman secret $rX = xR$, where $X = xG$ is the public key of the verifier.
+//! The key is absorbed by the sponge, but never written in the transcript:
+//! in the IO pattern, it is declared as an absorption of 32 bytes labeled `designated-key`:
+//!
+//! ```text
+//!     A<G>label:ephemeral A32label:designated-key
+//! ```
+//! All the challenges squeezed afterwards depend on the key. Without $x$ (or $r$), they cannot be recomputed,
+//! so that the proof convinces the designated verifier only.
+//! For the proof to be *deniable*, i.e. for the verifier to be able to produce a proof of its own,
+//! prove the statement OR the knowledge of $x$, for instance with [`Or`](crate::protocols::sigma::Or).
+//!
+//! ```
+//! use ark_curve25519::EdwardsProjective as G;
+//! use ark_ec::PrimeGroup;
+//! use ark_std::UniformRand;
+//! use nimue::plugins::ark::designated::{
+//!     DesignatedVerifierIOPattern, DesignatedVerifierReader, DesignatedVerifierWriter,
+//! };
+//! use nimue::plugins::ark::*;
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! type F = <G as PrimeGroup>::ScalarField;
+//! let mut rng = ark_std::test_rng();
+//! let (sk, pk) = {
+//!     let sk = F::rand(&mut rng);
+//!     (sk, G::generator() * sk)
+//! };
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝");
+//! let io = DesignatedVerifierIOPattern::<G>::add_designated_verifier(io, "dv")
+//!     .add_bytes(1, "message")
+//!     .challenge_bytes(16, "challenge");
+//! let mut merlin = io.to_merlin();
+//! merlin.add_designated_verifier(&pk).unwrap();
+//! merlin.add_bytes(b"!").unwrap();
+//! let challenge = merlin.challenge_bytes::<16>().unwrap();
+//!
+//! // the designated verifier recomputes the same challenge
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! DesignatedVerifierReader::<G>::next_designated_verifier(&mut arthur, &sk).unwrap();
+//! assert_eq!(arthur.next_bytes().unwrap(), *b"!");
+//! assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+//! ```
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use rand::{CryptoRng, RngCore};
+
+use super::{GroupIOPattern, GroupReader, GroupWriter};
+use crate::{
+    ByteChallenges, ByteIOPattern, BytePublic, DefaultHash, DuplexHash, IOPattern, Merlin,
+    ProofResult, Unit,
+};
+
+/// The size of the key absorbed in the duplex, in bytes.
+const KEY_SIZE: usize = 32;
+
+/// Declare the designation of a verifier in the IO pattern.
+pub trait DesignatedVerifierIOPattern<G: CurveGroup> {
+    /// Declare the ephemeral public key of the prover, followed by the shared key absorbed in the duplex.
+    fn add_designated_verifier(self, label: &str) -> Self;
+}
+
+/// Prover's side of a designated-verifier proof.
+pub trait DesignatedVerifierWriter<G: CurveGroup> {
+    /// Add an ephemeral public key to the transcript, and key the duplex with the secret shared with the verifier of public key `pk`.
+    fn add_designated_verifier(&mut self, pk: &G) -> ProofResult<()>;
+}
+
+/// Verifier's side of a designated-verifier proof.
+pub trait DesignatedVerifierReader<G: CurveGroup> {
+    /// Read the ephemeral public key of the prover, and key the duplex with the secret shared with the prover.
+    fn next_designated_verifier(&mut self, sk: &G::ScalarField) -> ProofResult<()>;
+}
+
+/// Derive the key absorbed in the duplex from the Diffie-Hellman secret `shared`.
+fn shared_key<G: CurveGroup>(shared: G) -> ProofResult<[u8; KEY_SIZE]> {
+    let mut bytes = Vec::new();
+    shared.into_affine().serialize_compressed(&mut bytes)?;
+    let io = IOPattern::<DefaultHash>::new("nimue/designated-verifier")
+        .add_bytes(bytes.len(), "shared secret")
+        .challenge_bytes(KEY_SIZE, "key");
+    let mut merlin = io.to_merlin();
+    merlin.public_bytes(&bytes)?;
+    // the sponge is erased on drop, the serialized secret must be erased too
+    zeroize::Zeroize::zeroize(&mut bytes);
+    Ok(merlin.challenge_bytes()?)
+}
+
+impl<G, H, U> DesignatedVerifierIOPattern<G> for IOPattern<H, U>
+where
+    G: CurveGroup,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: GroupIOPattern<G> + ByteIOPattern,
+{
+    fn add_designated_verifier(self, label: &str) -> Self {
+        self.add_points(1, &format!("{}:ephemeral", label))
+            .add_bytes(KEY_SIZE, &format!("{}:designated-key", label))
+    }
+}
+
+impl<G, H, U, R> DesignatedVerifierWriter<G> for Merlin<H, U, R>
+where
+    G: CurveGroup,
+    U: Unit,
+    H: DuplexHash<U>,
+    R: RngCore + CryptoRng,
+    Merlin<H, U, R>: GroupWriter<G> + BytePublic,
+{
+    fn add_designated_verifier(&mut self, pk: &G) -> ProofResult<()> {
+        let mut r = G::ScalarField::rand(self.rng());
+        self.add_points(&[G::generator() * r])?;
+        let key = shared_key(*pk * r);
+        zeroize::Zeroize::zeroize(&mut r);
+        let key = key?;
+        self.public_bytes(&key)?;
+        Ok(())
+    }
+}
+
+impl<G, T> DesignatedVerifierReader<G> for T
+where
+    G: CurveGroup,
+    T: GroupReader<G> + BytePublic,
+{
+    fn next_designated_verifier(&mut self, sk: &G::ScalarField) -> ProofResult<()> {
+        let [ephemeral] = self.next_points()?;
+        let key = shared_key(ephemeral * sk)?;
+        self.public_bytes(&key)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+//! Transcripts kept over two sponges: a byte sponge and an algebraic one, in lockstep.
+//!
+//! Recursive systems want their challenges from an algebraic sponge (e.g., Poseidon), cheap to verify in a circuit,
+//! while hashing large amounts of data (e.g., Merkle trees or raw messages) is cheap only with a byte-oriented sponge (e.g., Keccak).
+//! A dual transcript holds one sponge of each kind, each with its own IO pattern and its own protocol transcript:
+//! every message goes to one of the two, and the two are bound together by *cross-commitments*.
+//!
+//! In a cross-commitment, the byte sponge squeezes a 32-byte digest, absorbed by the algebraic sponge
+//! (packed in field elements, injectively), which then squeezes as many field elements, absorbed by the byte sponge.
+//! After a cross-commitment, each sponge depends on everything absorbed by the other so far.
+//! Cross-commitments are declared in both IO patterns at once, so that the two proceed in lockstep:
+//! a party (prover or verifier) can't move one sponge past a cross-commitment without the other.
+//!
+//! A cross-commitment labeled `commit` over a field whose elements take 32 bytes (and pack 31) declares:
+//!
+//! ```text
+//!     bytes: S32commit A64commit
+//!     field: A2commit S2commit
+//! ```
+//! See `nimue-poseidon` for an example with an algebraic sponge.
+use ark_ff::{Fp, FpConfig, PrimeField};
+
+use crate::{
+    Arthur, ByteIOPattern, DefaultRng, DuplexHash, IOPattern, Merlin, ProofError, ProofResult,
+    Unit, UnitTranscript,
+};
+
+/// The size of the digest squeezed from the byte sponge, in bytes.
+const DIGEST_SIZE: usize = 32;
+
+/// The number of bytes packed into a field element, less than the modulus so that packing is injective.
+fn packed_bytes<C: FpConfig<N>, const N: usize>() -> usize {
+    (Fp::<C, N>::MODULUS_BIT_SIZE as usize - 1) / 8
+}
+
+/// The number of field elements exchanged in each direction by a cross-commitment.
+fn cross_units<C: FpConfig<N>, const N: usize>() -> usize {
+    DIGEST_SIZE.div_ceil(packed_bytes::<C, N>())
+}
+
+/// The size of the serialization of `count` field elements, see [`Unit::write`].
+fn serialized_size<C: FpConfig<N>, const N: usize>(count: usize) -> usize {
+    let mut bytes = Vec::new();
+    // write never fails on Vec<u8>
+    Fp::<C, N>::write(&vec![Fp::from(0); count], &mut bytes).unwrap();
+    bytes.len()
+}
+
+/// Cross-commit the byte transcript `bytes` and the algebraic transcript `field`, see the [module documentation](self).
+fn cross_commit<C: FpConfig<N>, const N: usize>(
+    bytes: &mut impl UnitTranscript<u8>,
+    field: &mut impl UnitTranscript<Fp<C, N>>,
+) -> ProofResult<()> {
+    let mut digest = [0u8; DIGEST_SIZE];
+    bytes.fill_challenge_units(&mut digest)?;
+    let packed = digest
+        .chunks(packed_bytes::<C, N>())
+        .map(Fp::from_le_bytes_mod_order)
+        .collect::<Vec<_>>();
+    field.public_units(&packed)?;
+
+    let mut units = vec![Fp::from(0); cross_units::<C, N>()];
+    field.fill_challenge_units(&mut units)?;
+    let mut serialized = Vec::new();
+    Fp::write(&units, &mut serialized).map_err(|_| ProofError::SerializationError)?;
+    bytes.public_units(&serialized)?;
+    Ok(())
+}
+
+/// The IO patterns of a dual transcript, see the [module documentation](self).
+#[derive(Clone)]
+pub struct DualIOPattern<B, A, C, const N: usize>
+where
+    B: DuplexHash,
+    A: DuplexHash<Fp<C, N>>,
+    C: FpConfig<N>,
+{
+    bytes: IOPattern<B>,
+    field: IOPattern<A, Fp<C, N>>,
+}
+
+impl<B, A, C, const N: usize> DualIOPattern<B, A, C, N>
+where
+    B: DuplexHash,
+    A: DuplexHash<Fp<C, N>>,
+    C: FpConfig<N>,
+{
+    /// Create the IO patterns of a dual transcript, both with the domain separator `domsep`.
+    pub fn new(domsep: &str) -> Self {
+        Self {
+            bytes: IOPattern::new(domsep),
+            field: IOPattern::new(domsep),
+        }
+    }
+
+    /// Add operations to the pattern of the byte sponge.
+    pub fn bytes(mut self, f: impl FnOnce(IOPattern<B>) -> IOPattern<B>) -> Self {
+        self.bytes = f(self.bytes);
+        self
+    }
+
+    /// Add operations to the pattern of the algebraic sponge.
+    pub fn field(
+        mut self,
+        f: impl FnOnce(IOPattern<A, Fp<C, N>>) -> IOPattern<A, Fp<C, N>>,
+    ) -> Self {
+        self.field = f(self.field);
+        self
+    }
+
+    /// Declare a cross-commitment in both patterns.
+    pub fn cross_commit(self, label: &str) -> Self {
+        let units = cross_units::<C, N>();
+        Self {
+            bytes: self
+                .bytes
+                .challenge_bytes(DIGEST_SIZE, label)
+                .add_bytes(serialized_size::<C, N>(units), label),
+            field: self.field.absorb(units, label).squeeze(units, label),
+        }
+    }
+
+    /// The pattern of the byte sponge.
+    pub fn bytes_pattern(&self) -> &IOPattern<B> {
+        &self.bytes
+    }
+
+    /// The pattern of the algebraic sponge.
+    pub fn field_pattern(&self) -> &IOPattern<A, Fp<C, N>> {
+        &self.field
+    }
+
+    /// Create the prover state.
+    pub fn to_merlin(&self) -> DualMerlin<B, A, C, N> {
+        DualMerlin {
+            bytes: self.bytes.to_merlin(),
+            field: self.field.to_merlin(),
+        }
+    }
+
+    /// Create the verifier state, from the transcripts of the byte sponge and of the algebraic sponge.
+    pub fn to_arthur<'a>(&self, bytes: &'a [u8], field: &'a [u8]) -> DualArthur<'a, B, A, C, N> {
+        DualArthur {
+            bytes: self.bytes.to_arthur(bytes),
+            field: self.field.to_arthur(field),
+        }
+    }
+}
+
+/// The prover state of a dual transcript, see the [module documentation](self).
+pub struct DualMerlin<B, A, C, const N: usize>
+where
+    B: DuplexHash,
+    A: DuplexHash<Fp<C, N>>,
+    C: FpConfig<N>,
+{
+    bytes: Merlin<B, u8, DefaultRng>,
+    field: Merlin<A, Fp<C, N>, DefaultRng>,
+}
+
+impl<B, A, C, const N: usize> DualMerlin<B, A, C, N>
+where
+    B: DuplexHash,
+    A: DuplexHash<Fp<C, N>>,
+    C: FpConfig<N>,
+{
+    /// The prover state of the byte sponge.
+    pub fn bytes(&mut self) -> &mut Merlin<B, u8, DefaultRng> {
+        &mut self.bytes
+    }
+
+    /// The prover state of the algebraic sponge.
+    pub fn field(&mut self) -> &mut Merlin<A, Fp<C, N>, DefaultRng> {
+        &mut self.field
+    }
+
+    /// Perform the next cross-commitment, declared with [`DualIOPattern::cross_commit`].
+    pub fn cross_commit(&mut self) -> ProofResult<()> {
+        cross_commit(&mut self.bytes, &mut self.field)
+    }
+
+    /// The protocol transcripts of the byte sponge and of the algebraic sponge.
+    pub fn transcripts(&self) -> (&[u8], &[u8]) {
+        (self.bytes.transcript(), self.field.transcript())
+    }
+}
+
+/// The verifier state of a dual transcript, see the [module documentation](self).
+pub struct DualArthur<'a, B, A, C, const N: usize>
+where
+    B: DuplexHash,
+    A: DuplexHash<Fp<C, N>>,
+    C: FpConfig<N>,
+{
+    bytes: Arthur<'a, B>,
+    field: Arthur<'a, A, Fp<C, N>>,
+}
+
+impl<'a, B, A, C, const N: usize> DualArthur<'a, B, A, C, N>
+where
+    B: DuplexHash,
+    A: DuplexHash<Fp<C, N>>,
+    C: FpConfig<N>,
+{
+    /// The verifier state of the byte sponge.
+    pub fn bytes(&mut self) -> &mut Arthur<'a, B> {
+        &mut self.bytes
+    }
+
+    /// The verifier state of the algebraic sponge.
+    pub fn field(&mut self) -> &mut Arthur<'a, A, Fp<C, N>> {
+        &mut self.field
+    }
+
+    /// Perform the next cross-commitment, declared with [`DualIOPattern::cross_commit`].
+    pub fn cross_commit(&mut self) -> ProofResult<()> {
+        cross_commit(&mut self.bytes, &mut self.field)
+    }
+}
@@ -0,0 +1,298 @@
+//! BLS12-381 encodings of [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537), with feature flag `eip2537`.
+//!
+//! The EIP-2537 precompiles take points uncompressed, with every base field element
+//! padded to 64 bytes (16 zero bytes, followed by the 48-byte big-endian integer):
+//!
+//! - a $\mathbb{G}_1$ point is $x \Vert y$, 128 bytes;
+//! - a $\mathbb{G}_2$ point is $x_{c_0} \Vert x_{c_1} \Vert y_{c_0} \Vert y_{c_1}$, 256 bytes;
+//! - the point at infinity is encoded as all zeroes;
+//! - scalars are 32-byte big-endian integers.
+//!
+//! Absorbing points in this format, the transcript bytes are exactly the calldata of the precompile,
+//! so that a contract binding the transcript on-chain and a nimue verifier off-chain derive the same challenges.
+//! Points and scalars are validated when decoding: padding must be zero, integers must be canonical,
+//! and points must be on the curve and in the prime-order subgroup.
+//!
+//! ```
+//! use ark_bls12_381::{G1Projective as G1, G2Projective as G2};
+//! use ark_ec::PrimeGroup;
+//! use nimue::plugins::ark::eip2537::{Eip2537IOPattern, Eip2537Reader, Eip2537Writer};
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝")
+//!     .add_eip2537_g1_points(1, "g1")
+//!     .add_eip2537_g2_points(1, "g2");
+//! let mut merlin = io.to_merlin();
+//! merlin.add_eip2537_g1_points(&[G1::generator()]).unwrap();
+//! merlin.add_eip2537_g2_points(&[G2::generator()]).unwrap();
+//! assert_eq!(merlin.transcript().len(), 128 + 256);
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let [g1] = arthur.next_eip2537_g1_points().unwrap();
+//! let [g2] = arthur.next_eip2537_g2_points().unwrap();
+//! assert_eq!((g1, g2), (G1::generator(), G2::generator()));
+//! ```
+use ark_bls12_381::{Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::{
+    ByteIOPattern, BytePublic, ByteReader, ByteWriter, DuplexHash, IOPattern, ProofError,
+    ProofResult,
+};
+
+/// The size of a padded base field element.
+pub const FP_SIZE: usize = 64;
+/// The size of an encoded $\mathbb{G}_1$ point.
+pub const G1_SIZE: usize = 2 * FP_SIZE;
+/// The size of an encoded $\mathbb{G}_2$ point.
+pub const G2_SIZE: usize = 4 * FP_SIZE;
+/// The size of an encoded scalar.
+pub const SCALAR_SIZE: usize = 32;
+
+/// Declare EIP-2537-encoded points and scalars in the IO pattern.
+pub trait Eip2537IOPattern {
+    fn add_eip2537_g1_points(self, count: usize, label: &str) -> Self;
+    fn add_eip2537_g2_points(self, count: usize, label: &str) -> Self;
+    fn add_eip2537_scalars(self, count: usize, label: &str) -> Self;
+}
+
+/// Add EIP-2537-encoded points and scalars to the protocol transcript.
+pub trait Eip2537Writer {
+    fn add_eip2537_g1_points(&mut self, input: &[G1Projective]) -> ProofResult<()>;
+    fn add_eip2537_g2_points(&mut self, input: &[G2Projective]) -> ProofResult<()>;
+    fn add_eip2537_scalars(&mut self, input: &[Fr]) -> ProofResult<()>;
+}
+
+/// Absorb EIP-2537-encoded points and scalars, without writing them in the protocol transcript.
+pub trait Eip2537Public {
+    fn public_eip2537_g1_points(&mut self, input: &[G1Projective]) -> ProofResult<()>;
+    fn public_eip2537_g2_points(&mut self, input: &[G2Projective]) -> ProofResult<()>;
+    fn public_eip2537_scalars(&mut self, input: &[Fr]) -> ProofResult<()>;
+}
+
+/// Receive (and validate) EIP-2537-encoded points and scalars from the protocol transcript.
+pub trait Eip2537Reader {
+    fn fill_next_eip2537_g1_points(&mut self, output: &mut [G1Projective]) -> ProofResult<()>;
+    fn fill_next_eip2537_g2_points(&mut self, output: &mut [G2Projective]) -> ProofResult<()>;
+    fn fill_next_eip2537_scalars(&mut self, output: &mut [Fr]) -> ProofResult<()>;
+
+    fn next_eip2537_g1_points<const N: usize>(&mut self) -> ProofResult<[G1Projective; N]> {
+        let mut output = [G1Projective::default(); N];
+        self.fill_next_eip2537_g1_points(&mut output)
+            .map(|()| output)
+    }
+
+    fn next_eip2537_g2_points<const N: usize>(&mut self) -> ProofResult<[G2Projective; N]> {
+        let mut output = [G2Projective::default(); N];
+        self.fill_next_eip2537_g2_points(&mut output)
+            .map(|()| output)
+    }
+
+    fn next_eip2537_scalars<const N: usize>(&mut self) -> ProofResult<[Fr; N]> {
+        let mut output = [Fr::default(); N];
+        self.fill_next_eip2537_scalars(&mut output).map(|()| output)
+    }
+}
+
+impl<H: DuplexHash> Eip2537IOPattern for IOPattern<H> {
+    fn add_eip2537_g1_points(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * G1_SIZE, label)
+    }
+
+    fn add_eip2537_g2_points(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * G2_SIZE, label)
+    }
+
+    fn add_eip2537_scalars(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * SCALAR_SIZE, label)
+    }
+}
+
+impl<T: ByteWriter> Eip2537Writer for T {
+    fn add_eip2537_g1_points(&mut self, input: &[G1Projective]) -> ProofResult<()> {
+        self.add_bytes(&encode_g1_points(input))?;
+        Ok(())
+    }
+
+    fn add_eip2537_g2_points(&mut self, input: &[G2Projective]) -> ProofResult<()> {
+        self.add_bytes(&encode_g2_points(input))?;
+        Ok(())
+    }
+
+    fn add_eip2537_scalars(&mut self, input: &[Fr]) -> ProofResult<()> {
+        self.add_bytes(&encode_scalars(input))?;
+        Ok(())
+    }
+}
+
+impl<T: BytePublic> Eip2537Public for T {
+    fn public_eip2537_g1_points(&mut self, input: &[G1Projective]) -> ProofResult<()> {
+        self.public_bytes(&encode_g1_points(input))?;
+        Ok(())
+    }
+
+    fn public_eip2537_g2_points(&mut self, input: &[G2Projective]) -> ProofResult<()> {
+        self.public_bytes(&encode_g2_points(input))?;
+        Ok(())
+    }
+
+    fn public_eip2537_scalars(&mut self, input: &[Fr]) -> ProofResult<()> {
+        self.public_bytes(&encode_scalars(input))?;
+        Ok(())
+    }
+}
+
+impl<T: ByteReader> Eip2537Reader for T {
+    fn fill_next_eip2537_g1_points(&mut self, output: &mut [G1Projective]) -> ProofResult<()> {
+        let mut buf = vec![0u8; output.len() * G1_SIZE];
+        self.fill_next_bytes(&mut buf)?;
+        for (o, chunk) in output.iter_mut().zip(buf.chunks(G1_SIZE)) {
+            *o = decode_g1(chunk)?.into();
+        }
+        Ok(())
+    }
+
+    fn fill_next_eip2537_g2_points(&mut self, output: &mut [G2Projective]) -> ProofResult<()> {
+        let mut buf = vec![0u8; output.len() * G2_SIZE];
+        self.fill_next_bytes(&mut buf)?;
+        for (o, chunk) in output.iter_mut().zip(buf.chunks(G2_SIZE)) {
+            *o = decode_g2(chunk)?.into();
+        }
+        Ok(())
+    }
+
+    fn fill_next_eip2537_scalars(&mut self, output: &mut [Fr]) -> ProofResult<()> {
+        let mut buf = vec![0u8; output.len() * SCALAR_SIZE];
+        self.fill_next_bytes(&mut buf)?;
+        for (o, chunk) in output.iter_mut().zip(buf.chunks(SCALAR_SIZE)) {
+            *o = decode_scalar(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn encode_g1_points(input: &[G1Projective]) -> Vec<u8> {
+    G1Projective::normalize_batch(input)
+        .iter()
+        .flat_map(encode_g1)
+        .collect()
+}
+
+fn encode_g2_points(input: &[G2Projective]) -> Vec<u8> {
+    G2Projective::normalize_batch(input)
+        .iter()
+        .flat_map(encode_g2)
+        .collect()
+}
+
+fn encode_scalars(input: &[Fr]) -> Vec<u8> {
+    input.iter().flat_map(encode_scalar).collect()
+}
+
+/// The EIP-2537 encoding of a $\mathbb{G}_1$ point.
+pub fn encode_g1(point: &G1Affine) -> [u8; G1_SIZE] {
+    let mut output = [0u8; G1_SIZE];
+    if let Some((x, y)) = point.xy() {
+        encode_fp(&x, &mut output[..FP_SIZE]);
+        encode_fp(&y, &mut output[FP_SIZE..]);
+    }
+    output
+}
+
+/// The EIP-2537 encoding of a $\mathbb{G}_2$ point.
+pub fn encode_g2(point: &G2Affine) -> [u8; G2_SIZE] {
+    let mut output = [0u8; G2_SIZE];
+    if let Some((x, y)) = point.xy() {
+        encode_fp2(&x, &mut output[..2 * FP_SIZE]);
+        encode_fp2(&y, &mut output[2 * FP_SIZE..]);
+    }
+    output
+}
+
+/// The EIP-2537 encoding of a scalar.
+pub fn encode_scalar(scalar: &Fr) -> [u8; SCALAR_SIZE] {
+    let mut output = [0u8; SCALAR_SIZE];
+    output.copy_from_slice(&scalar.into_bigint().to_bytes_be());
+    output
+}
+
+/// Decode a $\mathbb{G}_1$ point from its EIP-2537 encoding.
+///
+/// Return an error if the encoding is not canonical, or the point is not in the prime-order subgroup.
+pub fn decode_g1(bytes: &[u8]) -> ProofResult<G1Affine> {
+    if bytes.len() != G1_SIZE {
+        return Err(ProofError::SerializationError);
+    }
+    if bytes.iter().all(|&b| b == 0) {
+        return Ok(G1Affine::identity());
+    }
+    let x = decode_fp(&bytes[..FP_SIZE])?;
+    let y = decode_fp(&bytes[FP_SIZE..])?;
+    let point = G1Affine::new_unchecked(x, y);
+    if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        Ok(point)
+    } else {
+        Err(ProofError::SerializationError)
+    }
+}
+
+/// Decode a $\mathbb{G}_2$ point from its EIP-2537 encoding.
+///
+/// Return an error if the encoding is not canonical, or the point is not in the prime-order subgroup.
+pub fn decode_g2(bytes: &[u8]) -> ProofResult<G2Affine> {
+    if bytes.len() != G2_SIZE {
+        return Err(ProofError::SerializationError);
+    }
+    if bytes.iter().all(|&b| b == 0) {
+        return Ok(G2Affine::identity());
+    }
+    let x = decode_fp2(&bytes[..2 * FP_SIZE])?;
+    let y = decode_fp2(&bytes[2 * FP_SIZE..])?;
+    let point = G2Affine::new_unchecked(x, y);
+    if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        Ok(point)
+    } else {
+        Err(ProofError::SerializationError)
+    }
+}
+
+/// Decode a scalar from its EIP-2537 encoding.
+///
+/// The precompiles accept any 32-byte integer, but here scalars are required to be canonical (i.e., reduced),
+/// so that the transcript is not malleable.
+pub fn decode_scalar(bytes: &[u8]) -> ProofResult<Fr> {
+    let scalar = Fr::from_be_bytes_mod_order(bytes);
+    if bytes.len() == SCALAR_SIZE && encode_scalar(&scalar) == bytes {
+        Ok(scalar)
+    } else {
+        Err(ProofError::SerializationError)
+    }
+}
+
+fn encode_fp(element: &Fq, output: &mut [u8]) {
+    let bytes = element.into_bigint().to_bytes_be();
+    output[FP_SIZE - bytes.len()..].copy_from_slice(&bytes);
+}
+
+fn encode_fp2(element: &Fq2, output: &mut [u8]) {
+    encode_fp(&element.c0, &mut output[..FP_SIZE]);
+    encode_fp(&element.c1, &mut output[FP_SIZE..]);
+}
+
+fn decode_fp(bytes: &[u8]) -> ProofResult<Fq> {
+    let element = Fq::from_be_bytes_mod_order(bytes);
+    let mut canonical = [0u8; FP_SIZE];
+    encode_fp(&element, &mut canonical);
+    if canonical == bytes {
+        Ok(element)
+    } else {
+        Err(ProofError::SerializationError)
+    }
+}
+
+fn decode_fp2(bytes: &[u8]) -> ProofResult<Fq2> {
+    let c0 = decode_fp(&bytes[..FP_SIZE])?;
+    let c1 = decode_fp(&bytes[FP_SIZE..])?;
+    Ok(Fq2::new(c0, c1))
+}
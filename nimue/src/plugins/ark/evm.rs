@@ -0,0 +1,256 @@
+//! An EVM-friendly transcript, derived with keccak256 as common on-chain verifiers do, with feature flag `evm`.
+//!
+//! Hashing with a duplex sponge is expensive in the EVM, so this transcript keeps a 32-byte state
+//! $s_0 = \mathsf{keccak256}(\mathit{io})$, where $\mathit{io}$ is the [`IOPattern`] tag,
+//! and buffers the absorbed elements as 32-byte big-endian words:
+//! scalars are one word, and points two words $(x, y)$ (with $(0, 0)$ for the point at infinity).
+//! Each challenge updates the state as $s \gets \mathsf{keccak256}(s \Vert \mathit{buffer})$, as `abi.encodePacked`,
+//! clears the buffer, and returns $s$ interpreted as a big-endian integer, reduced modulo the scalar field.
+//! The reduction is biased for fields smaller than $2^{256}$ (e.g., by $\approx 2^{-2}$ for BN254),
+//! but this is what on-chain verifiers usually do, and matching them is the point of this module.
+//!
+//! The IO pattern is declared with [`EvmIOPattern`], and [`solidity_library`] emits the matching Solidity library:
+//! one function for each squeeze, taking as input the words absorbed since the previous one.
+//! Ratchets are ignored.
+//!
+//! ```
+//! use ark_bn254::{Fr, G1Projective as G1};
+//! use ark_ec::PrimeGroup;
+//! use nimue::plugins::ark::evm::{solidity_library, EvmIOPattern, EvmTranscript};
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝");
+//! let io = EvmIOPattern::<G1>::add_evm_points(io, 1, "commitment");
+//! let io = EvmIOPattern::<G1>::challenge_evm_scalars(io, 1, "challenge");
+//!
+//! let mut transcript = EvmTranscript::new(&io);
+//! transcript.add_points(&[G1::generator()]).unwrap();
+//! let [c]: [Fr; 1] = transcript.challenge_scalars().unwrap();
+//!
+//! let solidity = solidity_library::<Fr, _>(&io, "Transcript").unwrap();
+//! assert!(solidity.contains("function squeeze_0_challenge(bytes32 state, uint256[2] memory absorbed)"));
+//! ```
+use std::fmt::{self, Write};
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use sha3::{Digest, Keccak256};
+
+use crate::iopattern::{Op, OpCursor};
+use crate::{DuplexHash, IOPattern, IOPatternError, ProofResult};
+
+/// The size of an EVM word, in bytes.
+const WORD: usize = 32;
+
+/// Declare EVM-encoded scalars, points, and challenges in the IO pattern.
+pub trait EvmIOPattern<G: CurveGroup> {
+    fn add_evm_scalars(self, count: usize, label: &str) -> Self;
+    fn add_evm_points(self, count: usize, label: &str) -> Self;
+    fn challenge_evm_scalars(self, count: usize, label: &str) -> Self;
+}
+
+impl<G: CurveGroup, H: DuplexHash> EvmIOPattern<G> for IOPattern<H> {
+    fn add_evm_scalars(self, count: usize, label: &str) -> Self {
+        self.absorb(count * WORD, label)
+    }
+
+    fn add_evm_points(self, count: usize, label: &str) -> Self {
+        self.absorb(count * 2 * WORD, label)
+    }
+
+    fn challenge_evm_scalars(self, count: usize, label: &str) -> Self {
+        self.squeeze(count * WORD, label)
+    }
+}
+
+/// A keccak256 transcript, following an [`IOPattern`] declared with [`EvmIOPattern`].
+///
+/// The same transcript is used by the prover and the verifier:
+/// as on-chain, sending the elements to the verifier is up to the caller.
+#[derive(Clone, Debug)]
+pub struct EvmTranscript {
+    state: [u8; WORD],
+    buffer: Vec<u8>,
+    cursor: OpCursor,
+}
+
+impl EvmTranscript {
+    pub fn new<H: DuplexHash>(io_pattern: &IOPattern<H>) -> Self {
+        Self {
+            state: Keccak256::digest(io_pattern.as_bytes()).into(),
+            buffer: Vec::new(),
+            cursor: OpCursor::new(io_pattern),
+        }
+    }
+
+    /// The current state, as `bytes32` in the Solidity library.
+    pub fn state(&self) -> [u8; WORD] {
+        self.state
+    }
+
+    pub fn add_scalars<F: PrimeField>(&mut self, input: &[F]) -> ProofResult<()> {
+        self.cursor.consume(Op::Absorb(input.len() * WORD))?;
+        for scalar in input {
+            self.buffer.extend(word(scalar)?);
+        }
+        Ok(())
+    }
+
+    pub fn add_points<G>(&mut self, input: &[G]) -> ProofResult<()>
+    where
+        G: CurveGroup,
+        G::BaseField: PrimeField,
+    {
+        self.cursor.consume(Op::Absorb(input.len() * 2 * WORD))?;
+        for point in G::normalize_batch(input) {
+            let (x, y) = point
+                .xy()
+                .unwrap_or((G::BaseField::zero(), G::BaseField::zero()));
+            self.buffer.extend(word(&x)?);
+            self.buffer.extend(word(&y)?);
+        }
+        Ok(())
+    }
+
+    pub fn fill_challenge_scalars<F: PrimeField>(&mut self, output: &mut [F]) -> ProofResult<()> {
+        self.cursor.consume(Op::Squeeze(output.len() * WORD))?;
+        for o in output.iter_mut() {
+            let mut hasher = Keccak256::new();
+            hasher.update(self.state);
+            hasher.update(&self.buffer);
+            self.state = hasher.finalize().into();
+            self.buffer.clear();
+            *o = F::from_be_bytes_mod_order(&self.state);
+        }
+        Ok(())
+    }
+
+    pub fn challenge_scalars<F: PrimeField, const N: usize>(&mut self) -> ProofResult<[F; N]> {
+        let mut output = [F::zero(); N];
+        self.fill_challenge_scalars(&mut output).map(|()| output)
+    }
+}
+
+/// The 32-byte big-endian encoding of a field element.
+fn word<F: PrimeField>(element: &F) -> Result<[u8; WORD], IOPatternError> {
+    let bytes = element.into_bigint().to_bytes_be();
+    let (high, low) = bytes.split_at(bytes.len().saturating_sub(WORD));
+    if high.iter().any(|&b| b != 0) || F::MODULUS_BIT_SIZE > 256 {
+        return Err("Field elements do not fit in an EVM word".into());
+    }
+    let mut output = [0u8; WORD];
+    output[WORD - low.len()..].copy_from_slice(low);
+    Ok(output)
+}
+
+/// Emit a Solidity library `name` deriving the challenges of `io_pattern` over the scalar field `F`,
+/// as [`EvmTranscript`].
+///
+/// Return an error if the IO pattern absorbs or squeezes a number of bytes that is not a multiple of 32.
+pub fn solidity_library<F: PrimeField, H: DuplexHash>(
+    io_pattern: &IOPattern<H>,
+    name: &str,
+) -> Result<String, IOPatternError> {
+    // the squeezes, with the number of words absorbed before each of them
+    let mut squeezes = Vec::new();
+    let mut absorbed = 0;
+    for (op, label) in io_pattern.entries() {
+        match op {
+            Op::Absorb(count) | Op::Squeeze(count) if count % WORD != 0 => {
+                return Err(format!("Operation {label} is not a multiple of {WORD} bytes").into());
+            }
+            Op::Absorb(count) => absorbed += count / WORD,
+            Op::Squeeze(count) => {
+                let identifier = label
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect::<String>();
+                squeezes.push((identifier, absorbed, count / WORD));
+                absorbed = 0;
+            }
+            Op::Ratchet => (),
+        }
+    }
+
+    let initial_state: [u8; WORD] = Keccak256::digest(io_pattern.as_bytes()).into();
+    let mut output = String::new();
+    write_library::<F>(&mut output, name, &initial_state, &squeezes)
+        .map_err(|e| format!("Could not write the Solidity library: {e}"))?;
+    Ok(output)
+}
+
+/// Write the Solidity library of [`solidity_library`] into `output`.
+fn write_library<F: PrimeField>(
+    output: &mut String,
+    name: &str,
+    initial_state: &[u8; WORD],
+    squeezes: &[(String, usize, usize)],
+) -> fmt::Result {
+    writeln!(output, "// SPDX-License-Identifier: MIT")?;
+    writeln!(output, "// Generated by nimue. Do not edit.")?;
+    writeln!(output, "pragma solidity ^0.8.0;")?;
+    writeln!(output)?;
+    writeln!(output, "library {name} {{")?;
+    writeln!(
+        output,
+        "    uint256 internal constant MODULUS = {};",
+        F::MODULUS
+    )?;
+    writeln!(
+        output,
+        "    bytes32 internal constant INITIAL_STATE = 0x{};",
+        hex::encode(initial_state)
+    )?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "    function init() internal pure returns (bytes32) {{"
+    )?;
+    writeln!(output, "        return INITIAL_STATE;")?;
+    writeln!(output, "    }}")?;
+
+    for (i, (identifier, absorbed, challenges)) in squeezes.iter().enumerate() {
+        let (input, buffer) = if *absorbed == 0 {
+            (String::new(), "")
+        } else {
+            (
+                format!(", uint256[{absorbed}] memory absorbed"),
+                ", absorbed",
+            )
+        };
+        writeln!(output)?;
+        writeln!(
+            output,
+            "    /// Squeeze {challenges} challenge(s) `{identifier}`, after absorbing {absorbed} word(s)."
+        )?;
+        writeln!(
+            output,
+            "    function squeeze_{i}_{identifier}(bytes32 state{input})"
+        )?;
+        writeln!(
+            output,
+            "        internal pure returns (bytes32 next, uint256[{challenges}] memory challenges)"
+        )?;
+        writeln!(output, "    {{")?;
+        writeln!(
+            output,
+            "        next = keccak256(abi.encodePacked(state{buffer}));"
+        )?;
+        writeln!(output, "        challenges[0] = uint256(next) % MODULUS;")?;
+        writeln!(
+            output,
+            "        for (uint256 i = 1; i < {challenges}; i++) {{"
+        )?;
+        writeln!(
+            output,
+            "            next = keccak256(abi.encodePacked(next));"
+        )?;
+        writeln!(
+            output,
+            "            challenges[i] = uint256(next) % MODULUS;"
+        )?;
+        writeln!(output, "        }}")?;
+        writeln!(output, "    }}")?;
+    }
+    writeln!(output, "}}")
+}
@@ -0,0 +1,124 @@
+//! Transcript helpers for the [GKR](https://doi.org/10.1145/2699436) protocol, layer by layer.
+//!
+//! The circuit has layers $0, \dots, L$, from the output to the input layer, and layer $i$ has $2^{k_i}$ gates.
+//! The verifier first squeezes a random point $r_0 \in F^{k_0}$ for the output layer.
+//! Then, for each layer $i < L$, the claim about $\tilde V_i$ is reduced to a claim about $\tilde V_{i+1}$:
+//! 1. the parties run a [sumcheck](super::sumcheck) over $2 k_{i+1}$ variables, with round polynomials of degree 2,
+//!    ending at a point $(b, c)$;
+//! 2. the prover sends the claims $\tilde V_{i+1}(b)$ and $\tilde V_{i+1}(c)$;
+//! 3. the verifier squeezes the combining challenges $(\alpha, \beta)$,
+//!    and the claim for the next layer becomes $\alpha \tilde V_{i+1}(b) + \beta \tilde V_{i+1}(c)$.
+//!
+//! ```text
+//!     S<k_0 F>output (sumcheck A<2 F>claims S<2 F>combine)^L
+//! ```
+//! Checking the wiring predicates at the end of each sumcheck, and the input layer against the claims,
+//! is left to the caller (see [`GkrLayer::check_claims`]).
+use ark_ff::Field;
+
+use super::sumcheck::{SumcheckClaim, SumcheckIOPattern, SumcheckReader};
+use super::{FieldChallenges, FieldIOPattern, FieldReader, FieldWriter};
+use crate::{DuplexHash, IOPattern, ProofError, ProofResult, Unit};
+
+/// The outcome of one GKR layer for the verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GkrLayer<F: Field> {
+    /// The claim left by the sumcheck, over the point $(b, c)$.
+    pub sumcheck: SumcheckClaim<F>,
+    /// The claims $\tilde V_{i+1}(b)$ and $\tilde V_{i+1}(c)$ sent by the prover.
+    pub claims: (F, F),
+    /// The combining challenges $(\alpha, \beta)$.
+    pub combine: (F, F),
+}
+
+impl<F: Field> GkrLayer<F> {
+    /// The points $b$ and $c$ at which the next layer is evaluated.
+    pub fn points(&self) -> (&[F], &[F]) {
+        self.sumcheck.point.split_at(self.sumcheck.point.len() / 2)
+    }
+
+    /// The claimed sum for the next layer, $\alpha \tilde V_{i+1}(b) + \beta \tilde V_{i+1}(c)$.
+    pub fn next_claim(&self) -> F {
+        self.combine.0 * self.claims.0 + self.combine.1 * self.claims.1
+    }
+
+    /// Check the claims against the evaluations of $\tilde V_{i+1}$ at $b$ and $c$,
+    /// for instance computed by the verifier on the input layer.
+    pub fn check_claims(&self, eval_b: F, eval_c: F) -> ProofResult<()> {
+        if self.claims == (eval_b, eval_c) {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidProof)
+        }
+    }
+}
+
+/// Declare a GKR proof in the IO pattern.
+pub trait GkrIOPattern<F: Field> {
+    /// Declare a GKR proof for a circuit whose layer $i$ has $2^{k_i}$ gates, where `layer_vars` is $(k_0, \dots, k_L)$.
+    fn add_gkr(self, layer_vars: &[usize], label: &str) -> Self;
+}
+
+/// Prover's side of a GKR proof.
+///
+/// The sumcheck rounds are handled with [`SumcheckWriter`](super::sumcheck::SumcheckWriter).
+pub trait GkrWriter<F: Field> {
+    /// Add the claims about the next layer, and return the combining challenges.
+    fn add_gkr_claims(&mut self, eval_b: F, eval_c: F) -> ProofResult<(F, F)>;
+}
+
+/// Verifier's side of a GKR proof.
+pub trait GkrReader<F: Field> {
+    /// Run the verifier for the layer reduction to a layer with $2^{k}$ gates (`next_vars` being $k$),
+    /// starting from the claimed sum `claim`.
+    fn next_gkr_layer(&mut self, next_vars: usize, claim: F) -> ProofResult<GkrLayer<F>>;
+}
+
+impl<F, H, U> GkrIOPattern<F> for IOPattern<H, U>
+where
+    F: Field,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: FieldIOPattern<F>,
+{
+    fn add_gkr(mut self, layer_vars: &[usize], label: &str) -> Self {
+        if let Some(&output_vars) = layer_vars.first() {
+            self = self.challenge_scalars(output_vars, &format!("{}:output", label));
+        }
+        for &next_vars in layer_vars.iter().skip(1) {
+            self = SumcheckIOPattern::<F>::add_sumcheck(self, 2 * next_vars, 2, label)
+                .add_scalars(2, &format!("{}:claims", label))
+                .challenge_scalars(2, &format!("{}:combine", label));
+        }
+        self
+    }
+}
+
+impl<F, T> GkrWriter<F> for T
+where
+    F: Field,
+    T: FieldWriter<F> + FieldChallenges<F>,
+{
+    fn add_gkr_claims(&mut self, eval_b: F, eval_c: F) -> ProofResult<(F, F)> {
+        self.add_scalars(&[eval_b, eval_c])?;
+        let [alpha, beta] = self.challenge_scalars()?;
+        Ok((alpha, beta))
+    }
+}
+
+impl<F, T> GkrReader<F> for T
+where
+    F: Field,
+    T: FieldReader<F> + FieldChallenges<F>,
+{
+    fn next_gkr_layer(&mut self, next_vars: usize, claim: F) -> ProofResult<GkrLayer<F>> {
+        let sumcheck = self.next_sumcheck(2 * next_vars, 2, claim)?;
+        let [eval_b, eval_c] = self.next_scalars()?;
+        let [alpha, beta] = self.challenge_scalars()?;
+        Ok(GkrLayer {
+            sumcheck,
+            claims: (eval_b, eval_c),
+            combine: (alpha, beta),
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! A transcript compatible with [gnark](https://github.com/Consensys/gnark)'s `fiatshamir` package,
+//! with feature flag `gnark`.
+//!
+//! gnark's transcript is not a duplex sponge: challenges are declared by name upfront,
+//! values are bound to a challenge, and the $i$-th challenge is computed as
+//! $$
+//! c_i = H(\mathit{name}_i \Vert c_{i-1} \Vert \mathit{bindings}_i),
+//! $$
+//! where $c_{-1}$ is the empty string and the hash $H$ is reset for each challenge (SHA-256 in gnark's PLONK verifier).
+//! Challenges must be computed in the order they were declared.
+//!
+//! Elements are bound with gnark's `Marshal` encoding:
+//! field elements as fixed-width big-endian integers,
+//! and (non-zero) points as their big-endian affine coordinates $x \Vert y$.
+//! Field challenges are obtained interpreting the challenge bytes as a big-endian integer modulo the characteristic,
+//! as gnark's `SetBytes`.
+//!
+//! ```
+//! use ark_bls12_381::{Fr, G1Projective as G1};
+//! use ark_ec::PrimeGroup;
+//! use nimue::plugins::ark::gnark::GnarkSha256;
+//!
+//! let mut transcript = GnarkSha256::new(&["gamma", "beta"]);
+//! transcript.bind_points("gamma", &[G1::generator()]).unwrap();
+//! let gamma: Fr = transcript.challenge_scalar("gamma").unwrap();
+//! let beta: Fr = transcript.challenge_scalar("beta").unwrap();
+//! assert_ne!(gamma, beta);
+//! // once computed, challenges cannot be bound to anymore
+//! assert!(transcript.bind("gamma", b"late").is_err());
+//! ```
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use digest::Digest;
+
+use crate::{IOPatternError, ProofResult};
+
+/// A gnark transcript with SHA-256, as used by gnark's PLONK and Groth16 verifiers.
+pub type GnarkSha256 = GnarkTranscript<sha2::Sha256>;
+
+#[derive(Clone, Debug)]
+struct Challenge {
+    name: String,
+    bindings: Vec<Vec<u8>>,
+    value: Option<Vec<u8>>,
+}
+
+/// A transcript following gnark's `fiatshamir.Transcript`.
+#[derive(Clone, Debug)]
+pub struct GnarkTranscript<D> {
+    challenges: Vec<Challenge>,
+    _hash: core::marker::PhantomData<D>,
+}
+
+impl<D: Digest> GnarkTranscript<D> {
+    /// Declare the challenges of the protocol, in the order in which they will be computed.
+    pub fn new(challenge_names: &[&str]) -> Self {
+        let challenges = challenge_names
+            .iter()
+            .map(|name| Challenge {
+                name: name.to_string(),
+                bindings: Vec::new(),
+                value: None,
+            })
+            .collect();
+        Self {
+            challenges,
+            _hash: core::marker::PhantomData,
+        }
+    }
+
+    fn position(&self, name: &str) -> Result<usize, IOPatternError> {
+        self.challenges
+            .iter()
+            .position(|challenge| challenge.name == name)
+            .ok_or_else(|| format!("Challenge {name} not declared").into())
+    }
+
+    /// Bind `value` to the challenge `name`.
+    ///
+    /// Return an error if the challenge was not declared or has already been computed.
+    pub fn bind(&mut self, name: &str, value: &[u8]) -> ProofResult<()> {
+        let position = self.position(name)?;
+        let challenge = &mut self.challenges[position];
+        if challenge.value.is_some() {
+            return Err(IOPatternError::from(format!("Challenge {name} already computed")).into());
+        }
+        challenge.bindings.push(value.to_vec());
+        Ok(())
+    }
+
+    /// Bind each field element of `input` to the challenge `name`.
+    pub fn bind_scalars<F: PrimeField>(&mut self, name: &str, input: &[F]) -> ProofResult<()> {
+        for scalar in input {
+            self.bind(name, &scalar_bytes(scalar))?;
+        }
+        Ok(())
+    }
+
+    /// Bind each point of `input` to the challenge `name`.
+    ///
+    /// Return an error if one of the points is the point at infinity.
+    pub fn bind_points<G>(&mut self, name: &str, input: &[G]) -> ProofResult<()>
+    where
+        G: CurveGroup,
+        G::BaseField: PrimeField,
+    {
+        for point in G::normalize_batch(input) {
+            let (x, y) = point
+                .xy()
+                .ok_or_else(|| IOPatternError::from("Cannot bind the point at infinity"))?;
+            self.bind(name, &[scalar_bytes(&x), scalar_bytes(&y)].concat())?;
+        }
+        Ok(())
+    }
+
+    /// Compute the challenge `name` (or return it, if it has already been computed).
+    ///
+    /// Return an error if the challenge was not declared or the previous challenge has not been computed.
+    pub fn compute_challenge(&mut self, name: &str) -> ProofResult<Vec<u8>> {
+        let position = self.position(name)?;
+        if let Some(value) = &self.challenges[position].value {
+            return Ok(value.clone());
+        }
+
+        let mut hasher = D::new();
+        hasher.update(name.as_bytes());
+        if position != 0 {
+            let previous = self.challenges[position - 1]
+                .value
+                .as_ref()
+                .ok_or_else(|| {
+                    IOPatternError::from(format!("Challenge before {name} not computed"))
+                })?;
+            hasher.update(previous);
+        }
+        for binding in &self.challenges[position].bindings {
+            hasher.update(binding);
+        }
+        let value = hasher.finalize().to_vec();
+        self.challenges[position].value = Some(value.clone());
+        Ok(value)
+    }
+
+    /// Compute the challenge `name` and map it to a field element.
+    pub fn challenge_scalar<F: PrimeField>(&mut self, name: &str) -> ProofResult<F> {
+        let value = self.compute_challenge(name)?;
+        Ok(F::from_be_bytes_mod_order(&value))
+    }
+}
+
+/// The fixed-width big-endian encoding of a field element.
+fn scalar_bytes<F: PrimeField>(scalar: &F) -> Vec<u8> {
+    scalar.into_bigint().to_bytes_be()[..]
+        [(F::BigInt::NUM_LIMBS * 8 - (F::MODULUS_BIT_SIZE as usize).div_ceil(8))..]
+        .to_vec()
+}
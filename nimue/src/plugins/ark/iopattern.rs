@@ -1,8 +1,9 @@
 use ark_ec::CurveGroup;
 use ark_ff::{Field, Fp, FpConfig, PrimeField};
 
+use super::common::point_size;
 use super::*;
-use crate::plugins::{bytes_modp, bytes_uniform_modp};
+use crate::plugins::{bytes_challenge_modp, bytes_modp};
 
 impl<F, H> FieldIOPattern<F> for IOPattern<H>
 where
@@ -19,10 +20,10 @@ where
     }
 
     fn challenge_scalars(self, count: usize, label: &str) -> Self {
+        let base_field_size =
+            bytes_challenge_modp(F::BasePrimeField::MODULUS_BIT_SIZE, self.hash_to_field());
         self.challenge_bytes(
-            count
-                * F::extension_degree() as usize
-                * bytes_uniform_modp(F::BasePrimeField::MODULUS_BIT_SIZE),
+            count * F::extension_degree() as usize * base_field_size,
             label,
         )
     }
@@ -65,7 +66,8 @@ where
     H: DuplexHash,
 {
     fn add_points(self, count: usize, label: &str) -> Self {
-        self.add_bytes(count * G::default().compressed_size(), label)
+        let size = point_size::<G::Affine>(self.encoding());
+        self.add_bytes(count * size, label)
     }
 }
 
@@ -120,6 +122,6 @@ fn test_iopattern() {
 
     assert_eq!(
         io_pattern.as_bytes(),
-        b"github.com/mmaker/nimue\0A32g\0A32pk\0R\0A32com\0S47chal\0A32resp"
+        b"github.com/mmaker/nimue\0A32g\0A32pk\0R\0A32com\0S48chal\0A32resp"
     )
 }
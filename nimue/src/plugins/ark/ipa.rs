@@ -0,0 +1,127 @@
+//! Transcript helpers for (Bulletproofs-style) inner-product arguments.
+//!
+//! For vectors of length $n = 2^k$, the argument runs in $k$ rounds.
+//! In each round, the prover sends two points $L, R$ and the verifier replies with a challenge $x$.
+//! Finally, the prover sends the two folded scalars $a, b$:
+//!
+//! ```text
+//!     (A<2 G>round S<F>challenge)^k A<2 F>final
+//! ```
+//! See `examples/bulletproof.rs` for a complete prover and verifier.
+use ark_ec::CurveGroup;
+
+use super::{
+    FieldChallenges, FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupReader,
+    GroupWriter,
+};
+use crate::{DuplexHash, IOPattern, ProofResult, Unit};
+
+/// The messages of an inner-product argument, as read by the verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IpaTranscript<G: CurveGroup> {
+    /// The points $(L_i, R_i)$ sent in each round.
+    pub rounds: Vec<(G, G)>,
+    /// The challenges $x_i$ squeezed in each round.
+    pub challenges: Vec<G::ScalarField>,
+    /// The final folded scalars $(a, b)$.
+    pub last: (G::ScalarField, G::ScalarField),
+}
+
+/// Number of rounds of an inner-product argument for vectors of length `len`.
+///
+/// # Panics
+///
+/// Panics if `len` is not a power of two.
+pub fn ipa_rounds(len: usize) -> usize {
+    assert!(len.is_power_of_two(), "Length must be a power of two.");
+    len.trailing_zeros() as usize
+}
+
+/// Declare an inner-product argument in the IO pattern.
+pub trait IpaIOPattern<G: CurveGroup> {
+    /// Declare an inner-product argument for vectors of length `len`, which must be a power of two.
+    fn add_ipa(self, len: usize, label: &str) -> Self;
+}
+
+/// Prover's side of an inner-product argument.
+pub trait IpaWriter<G: CurveGroup> {
+    /// Add the points of one round and return the round challenge.
+    fn add_ipa_round(&mut self, left: G, right: G) -> ProofResult<G::ScalarField>;
+    /// Add the final folded scalars.
+    fn add_ipa_last(&mut self, a: G::ScalarField, b: G::ScalarField) -> ProofResult<()>;
+}
+
+/// Verifier's side of an inner-product argument.
+pub trait IpaReader<G: CurveGroup> {
+    /// Read the points of one round and return them along with the round challenge.
+    fn next_ipa_round(&mut self) -> ProofResult<(G, G, G::ScalarField)>;
+    /// Read the final folded scalars.
+    fn next_ipa_last(&mut self) -> ProofResult<(G::ScalarField, G::ScalarField)>;
+
+    /// Read all the messages of an inner-product argument for vectors of length `len`.
+    fn next_ipa(&mut self, len: usize) -> ProofResult<IpaTranscript<G>> {
+        let rounds = ipa_rounds(len);
+        let mut transcript = IpaTranscript {
+            rounds: Vec::with_capacity(rounds),
+            challenges: Vec::with_capacity(rounds),
+            last: Default::default(),
+        };
+        for _ in 0..rounds {
+            let (left, right, challenge) = self.next_ipa_round()?;
+            transcript.rounds.push((left, right));
+            transcript.challenges.push(challenge);
+        }
+        transcript.last = self.next_ipa_last()?;
+        Ok(transcript)
+    }
+}
+
+impl<G, H, U> IpaIOPattern<G> for IOPattern<H, U>
+where
+    G: CurveGroup,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: GroupIOPattern<G> + FieldIOPattern<G::ScalarField>,
+{
+    fn add_ipa(mut self, len: usize, label: &str) -> Self {
+        for _ in 0..ipa_rounds(len) {
+            self = self
+                .add_points(2, &format!("{}:round", label))
+                .challenge_scalars(1, &format!("{}:challenge", label));
+        }
+        self.add_scalars(2, &format!("{}:last", label))
+    }
+}
+
+impl<G, T> IpaWriter<G> for T
+where
+    G: CurveGroup,
+    T: GroupWriter<G> + FieldWriter<G::ScalarField> + FieldChallenges<G::ScalarField>,
+{
+    fn add_ipa_round(&mut self, left: G, right: G) -> ProofResult<G::ScalarField> {
+        self.add_points(&[left, right])?;
+        let [challenge] = self.challenge_scalars()?;
+        Ok(challenge)
+    }
+
+    fn add_ipa_last(&mut self, a: G::ScalarField, b: G::ScalarField) -> ProofResult<()> {
+        self.add_scalars(&[a, b])
+    }
+}
+
+impl<G, T> IpaReader<G> for T
+where
+    G: CurveGroup,
+    T: GroupReader<G> + FieldReader<G::ScalarField> + FieldChallenges<G::ScalarField>,
+{
+    fn next_ipa_round(&mut self) -> ProofResult<(G, G, G::ScalarField)> {
+        let [left, right] = self.next_points()?;
+        let [challenge] = self.challenge_scalars()?;
+        Ok((left, right, challenge))
+    }
+
+    fn next_ipa_last(&mut self) -> ProofResult<(G::ScalarField, G::ScalarField)> {
+        let [a, b] = self.next_scalars()?;
+        Ok((a, b))
+    }
+}
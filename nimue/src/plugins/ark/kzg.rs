@@ -0,0 +1,205 @@
+//! Transcript helpers for the [KZG](https://www.iacr.org/archive/asiacrypt2010/6477178/6477178.pdf) polynomial commitment scheme.
+//!
+//! An opening proof for a single polynomial has the following shape:
+//!
+//! ```text
+//!     A<G1>commitment S<F>point A<F>evaluation A<G1>proof
+//! ```
+//! The prover absorbs a commitment $C$, the verifier replies with an evaluation point $z$,
+//! and the prover sends the evaluation $y$ and the opening proof $\pi$.
+//! For batched openings of many polynomials at the same point,
+//! after receiving the evaluations the verifier also sends a challenge $\gamma$
+//! used to combine the commitments and evaluations as $\sum_i \gamma^i C_i$ and $\sum_i \gamma^i y_i$.
+//!
+//! ```
+//! use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+//! use ark_ec::PrimeGroup;
+//! use ark_ff::Field;
+//! use nimue::{DefaultHash, IOPattern};
+//! use nimue::plugins::ark::kzg::{KzgIOPattern, KzgReader, KzgWriter};
+//!
+//! let io = KzgIOPattern::<Bls12_381>::add_kzg_opening(IOPattern::<DefaultHash>::new("📝"), "kzg");
+//! let mut merlin = io.to_merlin();
+//! // a (bogus) commitment and opening proof, for the sake of the example
+//! let commitment = G1::generator();
+//! let z: Fr = KzgWriter::<Bls12_381>::add_kzg_commitments(&mut merlin, &[commitment]).unwrap();
+//! KzgWriter::<Bls12_381>::add_kzg_evaluation(&mut merlin, z.square()).unwrap();
+//! KzgWriter::<Bls12_381>::add_kzg_proof(&mut merlin, commitment).unwrap();
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let opening = KzgReader::<Bls12_381>::next_kzg_opening(&mut arthur).unwrap();
+//! assert_eq!(opening.point, z);
+//! assert_eq!(opening.evaluation, z.square());
+//! ```
+//! Checking the pairing equation is left to the caller.
+use ark_ec::pairing::Pairing;
+
+use super::{
+    FieldChallenges, FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupReader,
+    GroupWriter,
+};
+use crate::plugins::Powers;
+use crate::{DuplexHash, IOPattern, ProofResult, Unit};
+
+/// An opening of a single polynomial, as read by the verifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KzgOpening<P: Pairing> {
+    pub commitment: P::G1,
+    pub point: P::ScalarField,
+    pub evaluation: P::ScalarField,
+    pub proof: P::G1,
+}
+
+/// A batched opening of many polynomials at the same point, as read by the verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KzgBatchOpening<P: Pairing> {
+    pub commitments: Vec<P::G1>,
+    pub point: P::ScalarField,
+    pub evaluations: Vec<P::ScalarField>,
+    /// The challenge used to combine commitments and evaluations.
+    pub gamma: P::ScalarField,
+    pub proof: P::G1,
+}
+
+impl<P: Pairing> KzgBatchOpening<P> {
+    /// Return the combined commitment $\sum_i \gamma^i C_i$ and combined evaluation $\sum_i \gamma^i y_i$,
+    /// to be checked against the opening proof as a single opening.
+    pub fn combine(&self) -> (P::G1, P::ScalarField) {
+        let powers = Powers::new(P::ScalarField::ONE, self.gamma);
+        let commitment = self
+            .commitments
+            .iter()
+            .zip(powers.clone())
+            .map(|(&c, gamma_i)| c * gamma_i)
+            .sum();
+        let evaluation = self
+            .evaluations
+            .iter()
+            .zip(powers)
+            .map(|(&y, gamma_i)| y * gamma_i)
+            .sum();
+        (commitment, evaluation)
+    }
+}
+
+/// Declare KZG openings in the IO pattern.
+pub trait KzgIOPattern<P: Pairing> {
+    /// An opening of a single polynomial.
+    fn add_kzg_opening(self, label: &str) -> Self;
+    /// A batched opening of `count` polynomials at the same point.
+    fn add_kzg_batch_opening(self, count: usize, label: &str) -> Self;
+}
+
+/// Prover's side of a KZG opening.
+///
+/// The methods must be called in order:
+/// [`add_kzg_commitments`](KzgWriter::add_kzg_commitments),
+/// then [`add_kzg_evaluation`](KzgWriter::add_kzg_evaluation) (or [`add_kzg_batch_evaluations`](KzgWriter::add_kzg_batch_evaluations) for batched openings),
+/// and finally [`add_kzg_proof`](KzgWriter::add_kzg_proof).
+pub trait KzgWriter<P: Pairing> {
+    /// Add the commitments to the transcript, and return the evaluation point.
+    fn add_kzg_commitments(&mut self, commitments: &[P::G1]) -> ProofResult<P::ScalarField>;
+    /// Add the evaluation of a single polynomial.
+    fn add_kzg_evaluation(&mut self, evaluation: P::ScalarField) -> ProofResult<()>;
+    /// Add the evaluations of a batch of polynomials, and return the combining challenge.
+    fn add_kzg_batch_evaluations(
+        &mut self,
+        evaluations: &[P::ScalarField],
+    ) -> ProofResult<P::ScalarField>;
+    /// Add the opening proof.
+    fn add_kzg_proof(&mut self, proof: P::G1) -> ProofResult<()>;
+}
+
+/// Verifier's side of a KZG opening.
+pub trait KzgReader<P: Pairing> {
+    fn next_kzg_opening(&mut self) -> ProofResult<KzgOpening<P>>;
+    fn next_kzg_batch_opening(&mut self, count: usize) -> ProofResult<KzgBatchOpening<P>>;
+}
+
+impl<P, H, U> KzgIOPattern<P> for IOPattern<H, U>
+where
+    P: Pairing,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: GroupIOPattern<P::G1> + FieldIOPattern<P::ScalarField>,
+{
+    fn add_kzg_opening(self, label: &str) -> Self {
+        self.add_points(1, &format!("{}:commitment", label))
+            .challenge_scalars(1, &format!("{}:point", label))
+            .add_scalars(1, &format!("{}:evaluation", label))
+            .add_points(1, &format!("{}:proof", label))
+    }
+
+    fn add_kzg_batch_opening(self, count: usize, label: &str) -> Self {
+        self.add_points(count, &format!("{}:commitments", label))
+            .challenge_scalars(1, &format!("{}:point", label))
+            .add_scalars(count, &format!("{}:evaluations", label))
+            .challenge_scalars(1, &format!("{}:gamma", label))
+            .add_points(1, &format!("{}:proof", label))
+    }
+}
+
+impl<P, T> KzgWriter<P> for T
+where
+    P: Pairing,
+    T: GroupWriter<P::G1> + FieldWriter<P::ScalarField> + FieldChallenges<P::ScalarField>,
+{
+    fn add_kzg_commitments(&mut self, commitments: &[P::G1]) -> ProofResult<P::ScalarField> {
+        self.add_points(commitments)?;
+        let [point] = self.challenge_scalars()?;
+        Ok(point)
+    }
+
+    fn add_kzg_evaluation(&mut self, evaluation: P::ScalarField) -> ProofResult<()> {
+        self.add_scalars(&[evaluation])
+    }
+
+    fn add_kzg_batch_evaluations(
+        &mut self,
+        evaluations: &[P::ScalarField],
+    ) -> ProofResult<P::ScalarField> {
+        self.add_scalars(evaluations)?;
+        let [gamma] = self.challenge_scalars()?;
+        Ok(gamma)
+    }
+
+    fn add_kzg_proof(&mut self, proof: P::G1) -> ProofResult<()> {
+        self.add_points(&[proof])
+    }
+}
+
+impl<P, T> KzgReader<P> for T
+where
+    P: Pairing,
+    T: GroupReader<P::G1> + FieldReader<P::ScalarField> + FieldChallenges<P::ScalarField>,
+{
+    fn next_kzg_opening(&mut self) -> ProofResult<KzgOpening<P>> {
+        let [commitment] = self.next_points()?;
+        let [point] = self.challenge_scalars()?;
+        let [evaluation] = self.next_scalars()?;
+        let [proof] = self.next_points()?;
+        Ok(KzgOpening {
+            commitment,
+            point,
+            evaluation,
+            proof,
+        })
+    }
+
+    fn next_kzg_batch_opening(&mut self, count: usize) -> ProofResult<KzgBatchOpening<P>> {
+        let mut commitments = vec![P::G1::default(); count];
+        self.fill_next_points(&mut commitments)?;
+        let [point] = self.challenge_scalars()?;
+        let mut evaluations = vec![P::ScalarField::default(); count];
+        self.fill_next_scalars(&mut evaluations)?;
+        let [gamma] = self.challenge_scalars()?;
+        let [proof] = self.next_points()?;
+        Ok(KzgBatchOpening {
+            commitments,
+            point,
+            evaluations,
+            gamma,
+            proof,
+        })
+    }
+}
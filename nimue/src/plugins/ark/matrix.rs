@@ -0,0 +1,150 @@
+use ark_ff::Field;
+
+use super::{FieldChallenges, FieldIOPattern, FieldReader, FieldWriter};
+use crate::{DuplexHash, IOPattern, IOPatternError, ProofResult, Unit};
+
+/// Declare matrices of scalars in the IO pattern.
+///
+/// The dimensions are bound into the label as `label[rows x cols]`,
+/// so that two protocols absorbing the same number of scalars with different shapes
+/// produce different IO patterns.
+/// Matrices are absorbed row by row.
+///
+/// Challenge matrices are declared as a single squeeze, as many as the scalars they are derived from, see [`MatrixChallenges`].
+pub trait MatrixIOPattern<F: Field> {
+    fn add_scalar_matrix(self, rows: usize, cols: usize, label: &str) -> Self;
+    /// Declare a matrix of `rows * cols` uniformly random scalars.
+    fn challenge_scalar_matrix(self, rows: usize, cols: usize, label: &str) -> Self;
+    /// Declare a Vandermonde matrix, of `rows` random scalars.
+    fn challenge_vandermonde_matrix(self, rows: usize, cols: usize, label: &str) -> Self;
+    /// Declare the tensor product of two random vectors, of `rows + cols` random scalars.
+    fn challenge_tensor_matrix(self, rows: usize, cols: usize, label: &str) -> Self;
+}
+
+/// Add matrices of scalars to the protocol transcript.
+pub trait MatrixWriter<F: Field> {
+    /// Add `matrix`, given as a list of rows, to the protocol transcript.
+    ///
+    /// Return an error if the rows of the matrix are not all of length `cols`,
+    /// or if there are not exactly `rows` of them.
+    fn add_scalar_matrix(&mut self, rows: usize, cols: usize, matrix: &[Vec<F>])
+        -> ProofResult<()>;
+}
+
+/// Squeeze matrices of challenges, for linear combinations of many vectors (e.g., in Ligero or Brakedown).
+///
+/// Matrices are returned as a list of rows.
+pub trait MatrixChallenges<F: Field> {
+    /// Squeeze a matrix of uniformly random scalars.
+    fn challenge_scalar_matrix(&mut self, rows: usize, cols: usize) -> ProofResult<Vec<Vec<F>>>;
+
+    /// Squeeze one scalar $c_i$ per row, and return the Vandermonde matrix whose rows are $(1, c_i, c_i^2, \dots, c_i^{cols - 1})$.
+    fn challenge_vandermonde_matrix(
+        &mut self,
+        rows: usize,
+        cols: usize,
+    ) -> ProofResult<Vec<Vec<F>>>;
+
+    /// Squeeze two vectors $a$ (of length `rows`) and $b$ (of length `cols`), in this order,
+    /// and return their tensor product, the matrix of entries $a_i b_j$.
+    fn challenge_tensor_matrix(&mut self, rows: usize, cols: usize) -> ProofResult<Vec<Vec<F>>>;
+}
+
+/// Retrieve matrices of scalars from the protocol transcript.
+pub trait MatrixReader<F: Field> {
+    fn next_scalar_matrix(&mut self, rows: usize, cols: usize) -> ProofResult<Vec<Vec<F>>>;
+}
+
+impl<F, H, U> MatrixIOPattern<F> for IOPattern<H, U>
+where
+    F: Field,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: FieldIOPattern<F>,
+{
+    fn add_scalar_matrix(self, rows: usize, cols: usize, label: &str) -> Self {
+        self.add_scalars(rows * cols, &format!("{}[{}x{}]", label, rows, cols))
+    }
+
+    fn challenge_scalar_matrix(self, rows: usize, cols: usize, label: &str) -> Self {
+        self.challenge_scalars(rows * cols, &format!("{}[{}x{}]", label, rows, cols))
+    }
+
+    fn challenge_vandermonde_matrix(self, rows: usize, cols: usize, label: &str) -> Self {
+        self.challenge_scalars(rows, &format!("{}[vandermonde {}x{}]", label, rows, cols))
+    }
+
+    fn challenge_tensor_matrix(self, rows: usize, cols: usize, label: &str) -> Self {
+        self.challenge_scalars(rows + cols, &format!("{}[tensor {}x{}]", label, rows, cols))
+    }
+}
+
+/// Split the `rows * cols` scalars of a matrix into its rows, which are empty if `cols` is zero.
+fn to_rows<F: Clone>(scalars: &[F], rows: usize, cols: usize) -> Vec<Vec<F>> {
+    if cols == 0 {
+        return vec![Vec::new(); rows];
+    }
+    scalars.chunks(cols).map(<[F]>::to_vec).collect()
+}
+
+impl<F, T> MatrixWriter<F> for T
+where
+    F: Field,
+    T: FieldWriter<F>,
+{
+    fn add_scalar_matrix(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        matrix: &[Vec<F>],
+    ) -> ProofResult<()> {
+        if matrix.len() != rows || matrix.iter().any(|row| row.len() != cols) {
+            return Err(IOPatternError::from("Matrix dimensions do not match").into());
+        }
+        self.add_scalars(&matrix.concat())
+    }
+}
+
+impl<F, T> MatrixChallenges<F> for T
+where
+    F: Field,
+    T: FieldChallenges<F>,
+{
+    fn challenge_scalar_matrix(&mut self, rows: usize, cols: usize) -> ProofResult<Vec<Vec<F>>> {
+        let scalars = self.challenge_scalars_vec(rows * cols)?;
+        Ok(to_rows(&scalars, rows, cols))
+    }
+
+    fn challenge_vandermonde_matrix(
+        &mut self,
+        rows: usize,
+        cols: usize,
+    ) -> ProofResult<Vec<Vec<F>>> {
+        let scalars = self.challenge_scalars_vec(rows)?;
+        Ok(scalars
+            .into_iter()
+            .map(|c| crate::plugins::Powers::new(F::ONE, c).take(cols).collect())
+            .collect())
+    }
+
+    fn challenge_tensor_matrix(&mut self, rows: usize, cols: usize) -> ProofResult<Vec<Vec<F>>> {
+        let scalars = self.challenge_scalars_vec(rows + cols)?;
+        let (left, right) = scalars.split_at(rows);
+        Ok(left
+            .iter()
+            .map(|a| right.iter().map(|b| *a * b).collect())
+            .collect())
+    }
+}
+
+impl<F, T> MatrixReader<F> for T
+where
+    F: Field,
+    T: FieldReader<F>,
+{
+    fn next_scalar_matrix(&mut self, rows: usize, cols: usize) -> ProofResult<Vec<Vec<F>>> {
+        let mut scalars = vec![F::ZERO; rows * cols];
+        self.fill_next_scalars(&mut scalars)?;
+        Ok(to_rows(&scalars, rows, cols))
+    }
+}
@@ -117,13 +117,55 @@
 //! ```
 //! Now the above code should work with algebraic hashes such as `PoseidonHash` just as well as [`Keccak`][`crate::hash::Keccak`].
 //!
+/// Absorbing scalars and points as typed values.
+mod absorb;
+/// Absorbing points in affine form.
+mod affine;
+#[cfg(feature = "barretenberg")]
+pub mod barretenberg;
+/// Batched deserialization of group elements.
+mod batch;
 /// Add public elements (field or group elements) to the protocol transcript.
 mod common;
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+pub mod designated;
+pub mod dual;
+#[cfg(feature = "eip2537")]
+pub mod eip2537;
+#[cfg(feature = "evm")]
+pub mod evm;
+pub mod gkr;
+#[cfg(feature = "gnark")]
+pub mod gnark;
 /// IO Pattern utilities.
 mod iopattern;
+pub mod ipa;
+pub mod kzg;
+/// Absorbing and reading matrices of scalars.
+mod matrix;
+pub mod musig;
+/// Utilities for pairing-friendly curves.
+mod pairing;
+/// Batching of pairing equations with a challenge from the transcript.
+mod pairing_batch;
+pub mod pcs;
+/// Absorbing and reading polynomials.
+mod poly;
 
 /// Veririfer's utilities for decoding a transcript.
 mod reader;
+/// Exporting the sponge state for recursive proofs.
+mod recursion;
+/// Absorbing and reading arbitrary serializable values.
+mod serializable;
+/// Short challenges for cheaper scalar multiplications.
+mod short;
+pub mod sigma;
+pub mod signatures;
+pub mod sumcheck;
+/// Exactly uniform challenges via rejection sampling.
+mod uniform;
 /// Prover's utilities for encoding into a transcript.
 mod writer;
 
@@ -132,7 +174,20 @@ mod writer;
 mod tests;
 
 pub use crate::traits::*;
-pub use crate::{hash::Unit, Arthur, DuplexHash, IOPattern, Merlin, ProofError, ProofResult, Safe};
+pub use crate::{
+    hash::Unit, Arthur, DuplexHash, Encoding, HashToField, IOPattern, Merlin, ProofError,
+    ProofResult, Safe, UnitEncoding,
+};
+
+pub use affine::AffineGroupWriter;
+pub use batch::BatchedGroupReader;
+pub use matrix::{MatrixChallenges, MatrixIOPattern, MatrixReader, MatrixWriter};
+pub use pairing::{PairingIOPattern, PairingReader, PairingWriter};
+pub use pairing_batch::{PairingBatch, PairingBatchIOPattern};
+pub use poly::{PolynomialIOPattern, PolynomialReader, PolynomialWriter};
+pub use serializable::{SerializableIOPattern, SerializableReader, SerializableWriter};
+pub use short::{ShortFieldChallenges, ShortFieldIOPattern};
+pub use uniform::{UniformFieldChallenges, UniformFieldIOPattern};
 
 super::traits::field_traits!(ark_ff::Field);
 super::traits::group_traits!(ark_ec::CurveGroup, Scalar: ark_ff::PrimeField);
@@ -149,26 +204,3 @@ pub fn swap_field<F1: ark_ff::PrimeField, F2: ark_ff::PrimeField>(a_f1: F1) -> P
         .then_some(a_f2)
         .ok_or(ProofError::SerializationError)
 }
-
-// pub trait PairingReader<P: ark_ec::pairing::Pairing>: GroupReader<P::G1> + GroupReader<P::G2>  {
-//     fn fill_next_g1_points(&mut self, input: &mut [P::G1]) -> crate::ProofResult<()> {
-//         GroupReader::<P::G1>::fill_next_points(self, input)
-//     }
-
-//     fn fill_next_g2_points(&mut self, input: &mut [P::G2]) -> crate::ProofResult<()> {
-//         GroupReader::<P::G2>::fill_next_points(self, input)
-//     }
-// }
-// pub trait PairingWriter<P: ark_ec::pairing::Pairing> {
-//     fn add_g1_points(&mut self, input: &[P::G1]) -> crate::ProofResult<()> {
-//         GroupWriter::<P::G1>::add_points(self, input)
-//     }
-
-//     fn add_g2_points(&mut self, input: &[P::G2]) -> crate::ProofResult<()> {
-//         GroupWriter::<P::G2>::add_points(self, input)
-//     }
-// }
-
-// impl<'a, P: ark_ec::pairing::Pairing, H, U> PairingWriter<P> for Arthur<'a, H, U> where
-// U: Unit, H: DuplexHash<U>,
-// Arthur<'a, H, U>:  GroupWriter<P::G1> + GroupWriter<P::G2>  {}
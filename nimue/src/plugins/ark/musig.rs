@@ -0,0 +1,272 @@
+//! Two-round multi-signatures, in the style of [MuSig2](https://eprint.iacr.org/2020/1261),
+//! producing [`signatures`](super::signatures) that verify under the aggregate public key.
+//!
+//! For $n$ signers with public keys $X_1, \dots, X_n$ (sorted by their compressed encoding):
+//!
+//! 1. **Key aggregation.** Each signer $i$ gets a coefficient $a_i$ squeezed from a transcript absorbing all the public keys,
+//!    then its index $i$ and its key $X_i$. The aggregate key is $\tilde X = \sum_i a_i X_i$. See [`KeyAggregation`].
+//! 2. **Nonce commitments.** Each signer samples two nonces $(r_{i,1}, r_{i,2})$ and sends $(R_{i,1}, R_{i,2})$ (see [`nonce_commitments`]).
+//!    The commitments are absorbed in the order of the public keys, and their sums $(R_1, R_2)$
+//!    determine the binding factor $b$ and the joint nonce $R = R_1 + b R_2$. See [`SigningSession`].
+//! 3. **Signing.** The joint challenge $c$ is the one of a [Schnorr signature](super::signatures) on $(\tilde X, m, R)$,
+//!    and each signer answers $s_i = r_{i,1} + b r_{i,2} + c a_i x_i$. The signature is $(R, \sum_i s_i)$.
+//!
+//! Each partial signature can be checked against the signer's own commitments and key,
+//! so that a signer sending an invalid one is identified.
+//!
+//! ```
+//! use ark_curve25519::EdwardsProjective as G;
+//! use nimue::plugins::ark::musig::{nonce_commitments, KeyAggregation, SigningSession};
+//! use nimue::plugins::ark::signatures::{keygen, verify, SchnorrIOPattern};
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! let mut rng = rand::rngs::OsRng;
+//! let io = SchnorrIOPattern::<G>::add_schnorr_signature(IOPattern::<DefaultHash>::new("📝"));
+//! let signers = (0..3).map(|_| keygen::<G>(&mut rng)).collect::<Vec<_>>();
+//! let keys = signers.iter().map(|(_, pk)| *pk).collect::<Vec<_>>();
+//! let aggregation = KeyAggregation::<G>::new::<DefaultHash>(&keys).unwrap();
+//!
+//! // first round: each signer sends its nonce commitments, in the order of aggregation.keys
+//! let mut signers = signers;
+//! signers.sort_by_key(|(_, pk)| aggregation.position(pk).unwrap());
+//! let nonces = signers.iter().map(|_| nonce_commitments::<G>(&mut rng)).collect::<Vec<_>>();
+//! let commitments = nonces.iter().map(|(_, commitment)| *commitment).collect::<Vec<_>>();
+//! let session = SigningSession::new(&io, &aggregation, &commitments, b"hello").unwrap();
+//!
+//! // second round: each signer sends its partial signature
+//! let partials = signers
+//!     .iter()
+//!     .zip(nonces)
+//!     .enumerate()
+//!     .map(|(i, ((sk, _), (secret, _)))| session.sign(&aggregation, i, *sk, secret))
+//!     .collect::<Vec<_>>();
+//! let signature = session.aggregate(&io, &aggregation, &partials).unwrap();
+//! assert!(verify(&io, aggregation.aggregate_key, b"hello", &signature).is_ok());
+//! ```
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_serialize::CanonicalSerialize;
+use rand::{CryptoRng, RngCore};
+
+use super::signatures::message_digest;
+use super::{FieldChallenges, FieldIOPattern, FieldWriter, GroupIOPattern, GroupWriter};
+use crate::{
+    ByteIOPattern, BytePublic, DuplexHash, IOPattern, IOPatternError, Merlin, ProofError,
+    ProofResult,
+};
+
+/// The public keys of the signers, in canonical order, with their aggregation coefficients.
+#[derive(Clone, Debug)]
+pub struct KeyAggregation<G: CurveGroup> {
+    /// The public keys, sorted by their compressed encoding.
+    pub keys: Vec<G>,
+    /// The coefficient $a_i$ of each public key.
+    pub coefficients: Vec<G::ScalarField>,
+    /// The aggregate key $\tilde X = \sum_i a_i X_i$.
+    pub aggregate_key: G,
+}
+
+impl<G: CurveGroup> KeyAggregation<G> {
+    /// Sort `keys` and derive their aggregation coefficients with the hash `H`.
+    ///
+    /// Return an error if `keys` is empty or contains duplicates.
+    pub fn new<H>(keys: &[G]) -> ProofResult<Self>
+    where
+        H: DuplexHash,
+        IOPattern<H>: GroupIOPattern<G> + FieldIOPattern<G::ScalarField>,
+        Merlin<H>: GroupWriter<G> + FieldChallenges<G::ScalarField>,
+    {
+        let mut encoded = keys
+            .iter()
+            .map(|key| {
+                let mut bytes = Vec::new();
+                key.serialize_compressed(&mut bytes)?;
+                Ok((bytes, *key))
+            })
+            .collect::<ProofResult<Vec<_>>>()?;
+        encoded.sort_by(|a, b| a.0.cmp(&b.0));
+        if encoded.is_empty() || encoded.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(IOPatternError::from("Public keys must be non-empty and distinct").into());
+        }
+        let keys = encoded.into_iter().map(|(_, key)| key).collect::<Vec<_>>();
+
+        let io = IOPattern::<H>::new("nimue/musig/key-aggregation")
+            .add_points(keys.len(), "keys")
+            .add_bytes(8, "index")
+            .add_points(1, "key")
+            .challenge_scalars(1, "coefficient");
+        let coefficients = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let mut merlin = io.to_merlin();
+                merlin.public_points(&keys)?;
+                merlin.public_bytes(&(i as u64).to_le_bytes())?;
+                merlin.public_points(&[*key])?;
+                let [a] = merlin.challenge_scalars()?;
+                Ok(a)
+            })
+            .collect::<ProofResult<Vec<_>>>()?;
+        let aggregate_key = keys
+            .iter()
+            .zip(&coefficients)
+            .map(|(key, a)| *key * a)
+            .sum();
+        Ok(Self {
+            keys,
+            coefficients,
+            aggregate_key,
+        })
+    }
+
+    /// The index of `key` in [`KeyAggregation::keys`].
+    pub fn position(&self, key: &G) -> Option<usize> {
+        self.keys.iter().position(|k| k == key)
+    }
+}
+
+/// Sample the two secret nonces of a signer, and their commitments.
+///
+/// The secret nonces **MUST** be used for one signature only.
+pub fn nonce_commitments<G: CurveGroup>(
+    rng: &mut (impl CryptoRng + RngCore),
+) -> ([G::ScalarField; 2], [G; 2]) {
+    let nonces = [G::ScalarField::rand(rng), G::ScalarField::rand(rng)];
+    (nonces, nonces.map(|r| G::generator() * r))
+}
+
+/// The joint values of a signing session: binding factor, joint nonce, and joint challenge.
+#[derive(Clone, Debug)]
+pub struct SigningSession<G: CurveGroup> {
+    /// The nonce commitments of each signer, in the order of [`KeyAggregation::keys`].
+    pub commitments: Vec<[G; 2]>,
+    /// The binding factor $b$.
+    pub binding_factor: G::ScalarField,
+    /// The joint nonce $R = R_1 + b R_2$.
+    pub nonce: G,
+    /// The joint challenge $c$.
+    pub challenge: G::ScalarField,
+    message_digest: [u8; 32],
+}
+
+impl<G: CurveGroup> SigningSession<G> {
+    /// Aggregate the nonce `commitments` of the signers of `aggregation` (in the same order as [`KeyAggregation::keys`]),
+    /// and derive the joint challenge for signing `message` with the IO pattern `io_pattern`
+    /// (declared with [`SchnorrIOPattern`](super::signatures::SchnorrIOPattern)).
+    pub fn new<H>(
+        io_pattern: &IOPattern<H>,
+        aggregation: &KeyAggregation<G>,
+        commitments: &[[G; 2]],
+        message: &[u8],
+    ) -> ProofResult<Self>
+    where
+        H: DuplexHash,
+        IOPattern<H>: GroupIOPattern<G> + FieldIOPattern<G::ScalarField>,
+        Merlin<H>: GroupWriter<G> + FieldWriter<G::ScalarField> + FieldChallenges<G::ScalarField>,
+    {
+        if commitments.len() != aggregation.keys.len() {
+            return Err(IOPatternError::from("One commitment per signer is required").into());
+        }
+        let message_digest = message_digest::<H>(message);
+        let aggregate = commitments
+            .iter()
+            .fold([G::zero(); 2], |[r1, r2], [c1, c2]| [r1 + c1, r2 + c2]);
+
+        let io = IOPattern::<H>::new("nimue/musig/binding-factor")
+            .add_points(1, "aggregate key")
+            .add_points(2, "aggregate nonces")
+            .add_bytes(32, "message")
+            .challenge_scalars(1, "binding factor");
+        let mut merlin = io.to_merlin();
+        merlin.public_points(&[aggregation.aggregate_key])?;
+        merlin.public_points(&aggregate)?;
+        merlin.public_bytes(&message_digest)?;
+        let [binding_factor] = merlin.challenge_scalars()?;
+        let nonce = aggregate[0] + aggregate[1] * binding_factor;
+
+        let mut merlin = Self::signature_transcript(io_pattern, aggregation, &message_digest)?;
+        merlin.add_points(&[nonce])?;
+        let [challenge] = merlin.challenge_scalars()?;
+        Ok(Self {
+            commitments: commitments.to_vec(),
+            binding_factor,
+            nonce,
+            challenge,
+            message_digest,
+        })
+    }
+
+    /// The signature transcript up to the commitment, as in [`sign`](super::signatures::sign).
+    fn signature_transcript<H>(
+        io_pattern: &IOPattern<H>,
+        aggregation: &KeyAggregation<G>,
+        message_digest: &[u8; 32],
+    ) -> ProofResult<Merlin<H>>
+    where
+        H: DuplexHash,
+        Merlin<H>: GroupWriter<G>,
+    {
+        let mut merlin = io_pattern.to_merlin();
+        merlin.public_points(&[aggregation.aggregate_key])?;
+        merlin.public_bytes(message_digest)?;
+        merlin.ratchet()?;
+        Ok(merlin)
+    }
+
+    /// The partial signature of the signer at position `index`, with secret key `sk` and secret nonces `nonces`.
+    pub fn sign(
+        &self,
+        aggregation: &KeyAggregation<G>,
+        index: usize,
+        sk: G::ScalarField,
+        nonces: [G::ScalarField; 2],
+    ) -> G::ScalarField {
+        let [r1, r2] = nonces;
+        r1 + self.binding_factor * r2 + self.challenge * aggregation.coefficients[index] * sk
+    }
+
+    /// Check the partial signature of the signer at position `index`.
+    ///
+    /// Return an error if the signer misbehaved.
+    pub fn verify_partial(
+        &self,
+        aggregation: &KeyAggregation<G>,
+        index: usize,
+        partial: G::ScalarField,
+    ) -> ProofResult<()> {
+        let ([r1, r2], key, a) = match (
+            self.commitments.get(index),
+            aggregation.keys.get(index),
+            aggregation.coefficients.get(index),
+        ) {
+            (Some(commitment), Some(key), Some(a)) => (commitment, key, a),
+            _ => return Err(IOPatternError::from("No such signer").into()),
+        };
+        if G::generator() * partial == *r1 + *r2 * self.binding_factor + *key * (self.challenge * a)
+        {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidProof)
+        }
+    }
+
+    /// Sum up the partial signatures into a signature, verifying under [`KeyAggregation::aggregate_key`].
+    ///
+    /// Invalid partial signatures give an invalid signature: use [`SigningSession::verify_partial`] to find out the culprit.
+    pub fn aggregate<H>(
+        &self,
+        io_pattern: &IOPattern<H>,
+        aggregation: &KeyAggregation<G>,
+        partials: &[G::ScalarField],
+    ) -> ProofResult<Vec<u8>>
+    where
+        H: DuplexHash,
+        Merlin<H>: GroupWriter<G> + FieldWriter<G::ScalarField> + FieldChallenges<G::ScalarField>,
+    {
+        let mut merlin = Self::signature_transcript(io_pattern, aggregation, &self.message_digest)?;
+        merlin.add_points(&[self.nonce])?;
+        let [_]: [G::ScalarField; 1] = merlin.challenge_scalars()?;
+        merlin.add_scalars(&[partials.iter().sum()])?;
+        Ok(merlin.transcript().to_vec())
+    }
+}
@@ -0,0 +1,125 @@
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::{Field, PrimeField};
+
+use super::{FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupReader, GroupWriter};
+use crate::{DuplexHash, IOPattern, ProofError, ProofResult, Unit};
+
+/// Send elements of the source and target groups of a pairing in the IO pattern.
+///
+/// Labels are prefixed with `G1:`, `G2:`, or `GT:`, so that messages in the different groups
+/// are always distinguishable within the IO pattern.
+pub trait PairingIOPattern<P: Pairing> {
+    fn add_g1_points(self, count: usize, label: &str) -> Self;
+    fn add_g2_points(self, count: usize, label: &str) -> Self;
+    fn add_gt_elements(self, count: usize, label: &str) -> Self;
+}
+
+/// Add elements of the source and target groups of a pairing to the protocol transcript.
+///
+/// Target group elements are written as elements of [`Pairing::TargetField`].
+/// Extension fields use the *tower encoding*: an element is flattened into
+/// its coefficients over the base prime field, in the order given by [`ark_ff::Field::to_base_prime_field_elements`]
+/// (for $\mathbb{F}_{q^{12}} = \mathbb{F}_{q^6}[w]$, the coefficient `c0` before `c1`, recursively down to $\mathbb{F}_q$),
+/// and each coefficient is written as a fixed-width little-endian integer.
+/// Algebraic hashes absorb the same coefficients, in the same order, as native units.
+pub trait PairingWriter<P: Pairing> {
+    fn add_g1_points(&mut self, input: &[P::G1]) -> ProofResult<()>;
+    fn add_g2_points(&mut self, input: &[P::G2]) -> ProofResult<()>;
+    fn add_gt_elements(&mut self, input: &[PairingOutput<P>]) -> ProofResult<()>;
+}
+
+/// Receive (and deserialize) elements of the source and target groups of a pairing from the protocol transcript.
+///
+/// As for [`GroupReader`], the points decoded are validated.
+/// Target group elements are checked to lie in the subgroup of order $r$ of [`Pairing::TargetField`],
+/// i.e., to have an $r$-th power equal to one.
+pub trait PairingReader<P: Pairing> {
+    fn fill_next_g1_points(&mut self, output: &mut [P::G1]) -> ProofResult<()>;
+    fn fill_next_g2_points(&mut self, output: &mut [P::G2]) -> ProofResult<()>;
+    fn fill_next_gt_elements(&mut self, output: &mut [PairingOutput<P>]) -> ProofResult<()>;
+
+    fn next_g1_points<const N: usize>(&mut self) -> ProofResult<[P::G1; N]> {
+        let mut output = [P::G1::default(); N];
+        self.fill_next_g1_points(&mut output).map(|()| output)
+    }
+
+    fn next_g2_points<const N: usize>(&mut self) -> ProofResult<[P::G2; N]> {
+        let mut output = [P::G2::default(); N];
+        self.fill_next_g2_points(&mut output).map(|()| output)
+    }
+
+    fn next_gt_elements<const N: usize>(&mut self) -> ProofResult<[PairingOutput<P>; N]> {
+        let mut output = [PairingOutput::default(); N];
+        self.fill_next_gt_elements(&mut output).map(|()| output)
+    }
+}
+
+impl<P, H, U> PairingIOPattern<P> for IOPattern<H, U>
+where
+    P: Pairing,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: GroupIOPattern<P::G1> + GroupIOPattern<P::G2> + FieldIOPattern<P::TargetField>,
+{
+    fn add_g1_points(self, count: usize, label: &str) -> Self {
+        GroupIOPattern::<P::G1>::add_points(self, count, &format!("G1:{}", label))
+    }
+
+    fn add_g2_points(self, count: usize, label: &str) -> Self {
+        GroupIOPattern::<P::G2>::add_points(self, count, &format!("G2:{}", label))
+    }
+
+    fn add_gt_elements(self, count: usize, label: &str) -> Self {
+        FieldIOPattern::<P::TargetField>::add_scalars(self, count, &format!("GT:{}", label))
+    }
+}
+
+impl<P, T> PairingWriter<P> for T
+where
+    P: Pairing,
+    T: GroupWriter<P::G1> + GroupWriter<P::G2> + FieldWriter<P::TargetField>,
+{
+    fn add_g1_points(&mut self, input: &[P::G1]) -> ProofResult<()> {
+        GroupWriter::<P::G1>::add_points(self, input)
+    }
+
+    fn add_g2_points(&mut self, input: &[P::G2]) -> ProofResult<()> {
+        GroupWriter::<P::G2>::add_points(self, input)
+    }
+
+    fn add_gt_elements(&mut self, input: &[PairingOutput<P>]) -> ProofResult<()> {
+        let elements = input.iter().map(|gt| gt.0).collect::<Vec<_>>();
+        FieldWriter::<P::TargetField>::add_scalars(self, &elements)
+    }
+}
+
+impl<P, T> PairingReader<P> for T
+where
+    P: Pairing,
+    T: GroupReader<P::G1> + GroupReader<P::G2> + FieldReader<P::TargetField>,
+{
+    fn fill_next_g1_points(&mut self, output: &mut [P::G1]) -> ProofResult<()> {
+        GroupReader::<P::G1>::fill_next_points(self, output)
+    }
+
+    fn fill_next_g2_points(&mut self, output: &mut [P::G2]) -> ProofResult<()> {
+        GroupReader::<P::G2>::fill_next_points(self, output)
+    }
+
+    fn fill_next_gt_elements(&mut self, output: &mut [PairingOutput<P>]) -> ProofResult<()> {
+        let mut elements = vec![P::TargetField::default(); output.len()];
+        FieldReader::<P::TargetField>::fill_next_scalars(self, &mut elements)?;
+        let modulus = <P::ScalarField as PrimeField>::MODULUS;
+        if elements
+            .iter()
+            .any(|element| element.pow(modulus) != P::TargetField::ONE)
+        {
+            return Err(ProofError::SerializationError);
+        }
+        output
+            .iter_mut()
+            .zip(elements)
+            .for_each(|(o, element)| *o = PairingOutput(element));
+        Ok(())
+    }
+}
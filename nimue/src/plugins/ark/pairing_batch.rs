@@ -0,0 +1,90 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, Zero};
+
+use super::{FieldChallenges, FieldIOPattern};
+use crate::plugins::Powers;
+use crate::{DuplexHash, IOPattern, ProofError, ProofResult, Unit};
+
+/// Declare the challenge used to batch pairing equations in the IO pattern.
+pub trait PairingBatchIOPattern<P: Pairing> {
+    fn challenge_pairing_batch(self, label: &str) -> Self;
+}
+
+/// A batch of pairing equations, to be checked with a single multi-pairing.
+///
+/// Each equation has the form $\prod_i e(A_i, B_i) = 1$.
+/// Once all equations are collected, [`PairingBatch::check`] squeezes a challenge $\gamma$ from the transcript
+/// and checks $\prod_j \prod_i e(\gamma^j A_{j, i}, B_{j, i}) = 1$.
+/// If one of the equations does not hold, the check passes with probability at most $m / |F|$,
+/// for $m$ equations, over the choice of $\gamma$.
+///
+/// For this to be sound, the equations must be fixed before $\gamma$ is squeezed:
+/// they can depend on public inputs and on the prover messages read so far,
+/// but the challenge **MUST** be squeezed after all the prover messages they depend upon.
+#[derive(Clone, Debug)]
+pub struct PairingBatch<P: Pairing> {
+    g1: Vec<Vec<P::G1>>,
+    g2: Vec<Vec<P::G2>>,
+}
+
+impl<P: Pairing> Default for PairingBatch<P> {
+    fn default() -> Self {
+        Self {
+            g1: Vec::new(),
+            g2: Vec::new(),
+        }
+    }
+}
+
+impl<P: Pairing> PairingBatch<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the equation $\prod_i e(A_i, B_i) = 1$, where $A$ is `g1` and $B$ is `g2`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `g1` and `g2` have different lengths.
+    pub fn add_equation(&mut self, g1: &[P::G1], g2: &[P::G2]) {
+        assert_eq!(
+            g1.len(),
+            g2.len(),
+            "Pairing equation of mismatched lengths."
+        );
+        self.g1.push(g1.to_vec());
+        self.g2.push(g2.to_vec());
+    }
+
+    /// Squeeze the batching challenge from `transcript` and check all the equations at once.
+    ///
+    /// Return [`ProofError::InvalidProof`] if the check fails.
+    pub fn check(self, transcript: &mut impl FieldChallenges<P::ScalarField>) -> ProofResult<()> {
+        let [gamma] = transcript.challenge_scalars()?;
+        let powers = Powers::new(P::ScalarField::ONE, gamma);
+        let g1 = self
+            .g1
+            .into_iter()
+            .zip(powers)
+            .flat_map(|(equation, gamma_j)| equation.into_iter().map(move |a| a * gamma_j))
+            .collect::<Vec<_>>();
+        let g2 = self.g2.into_iter().flatten().collect::<Vec<_>>();
+        if P::multi_pairing(g1, g2).is_zero() {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidProof)
+        }
+    }
+}
+
+impl<P, H, U> PairingBatchIOPattern<P> for IOPattern<H, U>
+where
+    P: Pairing,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: FieldIOPattern<P::ScalarField>,
+{
+    fn challenge_pairing_batch(self, label: &str) -> Self {
+        self.challenge_scalars(1, label)
+    }
+}
@@ -0,0 +1,400 @@
+//! A transcript interface for polynomial commitment schemes,
+//! so that polynomial IOPs can be compiled once against any of them.
+//!
+//! A [`PolynomialCommitmentScheme`] fixes the types of the messages of the scheme,
+//! while [`PcsIOPattern`], [`PcsWriter`], [`PcsReader`], and [`PcsChallenges`] fix how they are absorbed, read, and squeezed.
+//! The transcript of an opening of `count` polynomials has the following shape:
+//!
+//! ```text
+//!     A<count C>commitments ... S<F>point A<count F>evaluations S<F>gamma (A<round>round S<F>challenge)^k A<final>proof
+//! ```
+//! The commitments are sent first, possibly followed by any other message of the polynomial IOP.
+//! Then the verifier sends the evaluation point (of [`PolynomialCommitmentScheme::point_length`] scalars),
+//! and the prover sends the claimed evaluations.
+//! The verifier replies with a challenge $\gamma$ used to combine the polynomials,
+//! and the opening proof follows: $k$ rounds of [`PolynomialCommitmentScheme::opening_rounds`],
+//! each one a prover message and a verifier challenge, and a final message.
+//!
+//! For instance, [`Kzg`] openings have no rounds and a single point as final message,
+//! while [`Ipa`] openings have $\log_2 n$ rounds of two points and two scalars as final message.
+//! Checking the opening proof is left to the caller.
+//!
+//! ```
+//! use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+//! use ark_ec::PrimeGroup;
+//! use ark_ff::Field;
+//! use nimue::plugins::ark::pcs::{
+//!     Kzg, PcsChallenges, PcsIOPattern, PcsReader, PcsWriter, PolynomialCommitmentScheme,
+//! };
+//! use nimue::{DefaultHash, IOPattern, ProofResult};
+//!
+//! // the prover of a (trivial) polynomial IOP, written once for any scheme
+//! fn prove<S: PolynomialCommitmentScheme>(
+//!     merlin: &mut impl PcsWriter<S>,
+//!     scheme: &S,
+//!     commitment: S::Commitment,
+//!     evaluate: impl Fn(&[S::Scalar]) -> S::Scalar,
+//!     proof: S::FinalMessage,
+//! ) -> ProofResult<()> {
+//!     merlin.add_pcs_commitments(scheme, &[commitment])?;
+//!     let point = merlin.challenge_pcs_point(scheme)?;
+//!     merlin.add_pcs_evaluations(scheme, &[evaluate(&point)])?;
+//!     merlin.challenge_pcs_batching(scheme)?;
+//!     merlin.add_pcs_final(scheme, proof)
+//! }
+//!
+//! let scheme = Kzg::<Bls12_381>::new();
+//! let io = IOPattern::<DefaultHash>::new("📝")
+//!     .add_pcs_commitments(&scheme, 1, "pcs")
+//!     .challenge_pcs_point(&scheme, "pcs")
+//!     .add_pcs_evaluations(&scheme, 1, "pcs")
+//!     .add_pcs_opening(&scheme, "pcs");
+//! let mut merlin = io.to_merlin();
+//! // a (bogus) commitment and opening proof, for the sake of the example
+//! let g = G1::generator();
+//! prove(&mut merlin, &scheme, g, |z: &[Fr]| z[0].square(), g).unwrap();
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let commitments = arthur.next_pcs_commitments(&scheme, 1).unwrap();
+//! let point = arthur.challenge_pcs_point(&scheme).unwrap();
+//! let evaluations = arthur.next_pcs_evaluations(&scheme, 1).unwrap();
+//! let opening = arthur.next_pcs_opening(&scheme).unwrap();
+//! assert_eq!(commitments, [g]);
+//! assert_eq!(evaluations, [point[0].square()]);
+//! assert_eq!(opening.last, g);
+//! ```
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+
+use super::ipa::ipa_rounds;
+use super::{
+    FieldChallenges, FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupReader,
+    GroupWriter,
+};
+use crate::{DuplexHash, IOPattern, IOPatternError, ProofResult, Unit};
+
+/// The messages of a polynomial commitment scheme.
+///
+/// The scheme (`&self`) carries the parameters the shape of the transcript depends on.
+pub trait PolynomialCommitmentScheme {
+    /// The field the polynomials are defined over.
+    type Scalar: PrimeField;
+    type Commitment: Clone;
+    /// The prover message of each round of an opening proof.
+    type RoundMessage;
+    /// The last prover message of an opening proof.
+    type FinalMessage;
+
+    /// The number of scalars of an evaluation point: 1 for univariate polynomials,
+    /// the number of variables for multilinear ones.
+    fn point_length(&self) -> usize {
+        1
+    }
+
+    /// The number of rounds of an opening proof.
+    fn opening_rounds(&self) -> usize;
+}
+
+/// An opening proof, as read by the verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PcsOpening<S: PolynomialCommitmentScheme> {
+    /// The challenge $\gamma$ used to combine the polynomials.
+    pub gamma: S::Scalar,
+    /// The prover message and the verifier challenge of each round.
+    pub rounds: Vec<(S::RoundMessage, S::Scalar)>,
+    /// The final prover message.
+    pub last: S::FinalMessage,
+}
+
+/// Declare commitments and openings of the polynomial commitment scheme `S` in the IO pattern.
+pub trait PcsIOPattern<S: PolynomialCommitmentScheme>: FieldIOPattern<S::Scalar> + Sized {
+    /// Declare `count` commitments.
+    fn add_pcs_commitments(self, scheme: &S, count: usize, label: &str) -> Self;
+    /// Declare the rounds and the final message of an opening proof.
+    fn add_pcs_opening_proof(self, scheme: &S, label: &str) -> Self;
+
+    /// Declare the evaluation point.
+    fn challenge_pcs_point(self, scheme: &S, label: &str) -> Self {
+        self.challenge_scalars(scheme.point_length(), &format!("{}:point", label))
+    }
+
+    /// Declare the claimed evaluations of `count` polynomials.
+    fn add_pcs_evaluations(self, _scheme: &S, count: usize, label: &str) -> Self {
+        self.add_scalars(count, &format!("{}:evaluations", label))
+    }
+
+    /// Declare the combining challenge, followed by the opening proof.
+    fn add_pcs_opening(self, scheme: &S, label: &str) -> Self {
+        self.challenge_scalars(1, &format!("{}:gamma", label))
+            .add_pcs_opening_proof(scheme, label)
+    }
+}
+
+/// The verifier messages of the polynomial commitment scheme `S`, following the layout of [`PcsIOPattern`].
+pub trait PcsChallenges<S: PolynomialCommitmentScheme>: FieldChallenges<S::Scalar> {
+    /// Return the evaluation point, of [`PolynomialCommitmentScheme::point_length`] scalars.
+    fn challenge_pcs_point(&mut self, scheme: &S) -> ProofResult<Vec<S::Scalar>> {
+        let mut point = vec![S::Scalar::default(); scheme.point_length()];
+        self.fill_challenge_scalars(&mut point)?;
+        Ok(point)
+    }
+
+    /// Return the challenge $\gamma$ used to combine the polynomials.
+    fn challenge_pcs_batching(&mut self, _scheme: &S) -> ProofResult<S::Scalar> {
+        let [gamma] = self.challenge_scalars()?;
+        Ok(gamma)
+    }
+}
+
+/// Prover's side of the polynomial commitment scheme `S`, following the layout of [`PcsIOPattern`].
+pub trait PcsWriter<S: PolynomialCommitmentScheme>:
+    FieldWriter<S::Scalar> + PcsChallenges<S>
+{
+    fn add_pcs_commitments(&mut self, scheme: &S, commitments: &[S::Commitment])
+        -> ProofResult<()>;
+    /// Add the prover message of one round, and return the round challenge.
+    fn add_pcs_round(&mut self, scheme: &S, message: S::RoundMessage) -> ProofResult<S::Scalar>;
+    fn add_pcs_final(&mut self, scheme: &S, message: S::FinalMessage) -> ProofResult<()>;
+
+    fn add_pcs_evaluations(&mut self, _scheme: &S, evaluations: &[S::Scalar]) -> ProofResult<()> {
+        self.add_scalars(evaluations)
+    }
+}
+
+/// Verifier's side of the polynomial commitment scheme `S`, following the layout of [`PcsIOPattern`].
+pub trait PcsReader<S: PolynomialCommitmentScheme>:
+    FieldReader<S::Scalar> + PcsChallenges<S>
+{
+    fn next_pcs_commitments(&mut self, scheme: &S, count: usize)
+        -> ProofResult<Vec<S::Commitment>>;
+    /// Read the prover message of one round, and return it along with the round challenge.
+    fn next_pcs_round(&mut self, scheme: &S) -> ProofResult<(S::RoundMessage, S::Scalar)>;
+    fn next_pcs_final(&mut self, scheme: &S) -> ProofResult<S::FinalMessage>;
+
+    fn next_pcs_evaluations(&mut self, _scheme: &S, count: usize) -> ProofResult<Vec<S::Scalar>> {
+        let mut evaluations = vec![S::Scalar::default(); count];
+        self.fill_next_scalars(&mut evaluations)?;
+        Ok(evaluations)
+    }
+
+    /// Read the combining challenge and the opening proof.
+    fn next_pcs_opening(&mut self, scheme: &S) -> ProofResult<PcsOpening<S>> {
+        let gamma = self.challenge_pcs_batching(scheme)?;
+        let rounds = (0..scheme.opening_rounds())
+            .map(|_| self.next_pcs_round(scheme))
+            .collect::<ProofResult<Vec<_>>>()?;
+        let last = self.next_pcs_final(scheme)?;
+        Ok(PcsOpening {
+            gamma,
+            rounds,
+            last,
+        })
+    }
+}
+
+impl<S, T> PcsChallenges<S> for T
+where
+    S: PolynomialCommitmentScheme,
+    T: FieldChallenges<S::Scalar>,
+{
+}
+
+/// The [KZG](super::kzg) polynomial commitment scheme over the pairing `P`.
+///
+/// Opening proofs have no rounds, and their final message is a point of $\mathbb{G}_1$.
+#[derive(Clone, Copy, Debug)]
+pub struct Kzg<P>(PhantomData<P>);
+
+impl<P: Pairing> Kzg<P> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: Pairing> Default for Kzg<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Pairing> PolynomialCommitmentScheme for Kzg<P> {
+    type Scalar = P::ScalarField;
+    type Commitment = P::G1;
+    type RoundMessage = Infallible;
+    type FinalMessage = P::G1;
+
+    fn opening_rounds(&self) -> usize {
+        0
+    }
+}
+
+impl<P, H, U> PcsIOPattern<Kzg<P>> for IOPattern<H, U>
+where
+    P: Pairing,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: GroupIOPattern<P::G1> + FieldIOPattern<P::ScalarField>,
+{
+    fn add_pcs_commitments(self, _scheme: &Kzg<P>, count: usize, label: &str) -> Self {
+        self.add_points(count, &format!("{}:commitments", label))
+    }
+
+    fn add_pcs_opening_proof(self, _scheme: &Kzg<P>, label: &str) -> Self {
+        self.add_points(1, &format!("{}:proof", label))
+    }
+}
+
+impl<P, T> PcsWriter<Kzg<P>> for T
+where
+    P: Pairing,
+    T: GroupWriter<P::G1> + FieldWriter<P::ScalarField> + FieldChallenges<P::ScalarField>,
+{
+    fn add_pcs_commitments(&mut self, _scheme: &Kzg<P>, commitments: &[P::G1]) -> ProofResult<()> {
+        self.add_points(commitments)
+    }
+
+    fn add_pcs_round(
+        &mut self,
+        _scheme: &Kzg<P>,
+        message: Infallible,
+    ) -> ProofResult<P::ScalarField> {
+        match message {}
+    }
+
+    fn add_pcs_final(&mut self, _scheme: &Kzg<P>, message: P::G1) -> ProofResult<()> {
+        self.add_points(&[message])
+    }
+}
+
+impl<P, T> PcsReader<Kzg<P>> for T
+where
+    P: Pairing,
+    T: GroupReader<P::G1> + FieldReader<P::ScalarField> + FieldChallenges<P::ScalarField>,
+{
+    fn next_pcs_commitments(&mut self, _scheme: &Kzg<P>, count: usize) -> ProofResult<Vec<P::G1>> {
+        let mut commitments = vec![P::G1::default(); count];
+        self.fill_next_points(&mut commitments)?;
+        Ok(commitments)
+    }
+
+    fn next_pcs_round(&mut self, _scheme: &Kzg<P>) -> ProofResult<(Infallible, P::ScalarField)> {
+        Err(IOPatternError::from("KZG openings have no rounds").into())
+    }
+
+    fn next_pcs_final(&mut self, _scheme: &Kzg<P>) -> ProofResult<P::G1> {
+        let [proof] = self.next_points()?;
+        Ok(proof)
+    }
+}
+
+/// The (Bulletproofs-style) [inner-product argument](super::ipa) over the group `G`, for polynomials of `len` coefficients.
+///
+/// Opening proofs have $\log_2$ `len` rounds of two points, and their final message is the two folded scalars.
+#[derive(Clone, Copy, Debug)]
+pub struct Ipa<G> {
+    len: usize,
+    _group: PhantomData<G>,
+}
+
+impl<G: CurveGroup> Ipa<G> {
+    /// # Panics
+    ///
+    /// Panics if `len` is not a power of two.
+    pub fn new(len: usize) -> Self {
+        ipa_rounds(len);
+        Self {
+            len,
+            _group: PhantomData,
+        }
+    }
+}
+
+impl<G: CurveGroup> PolynomialCommitmentScheme for Ipa<G> {
+    type Scalar = G::ScalarField;
+    type Commitment = G;
+    type RoundMessage = (G, G);
+    type FinalMessage = (G::ScalarField, G::ScalarField);
+
+    fn opening_rounds(&self) -> usize {
+        ipa_rounds(self.len)
+    }
+}
+
+impl<G, H, U> PcsIOPattern<Ipa<G>> for IOPattern<H, U>
+where
+    G: CurveGroup,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: GroupIOPattern<G> + FieldIOPattern<G::ScalarField>,
+{
+    fn add_pcs_commitments(self, _scheme: &Ipa<G>, count: usize, label: &str) -> Self {
+        self.add_points(count, &format!("{}:commitments", label))
+    }
+
+    fn add_pcs_opening_proof(mut self, scheme: &Ipa<G>, label: &str) -> Self {
+        for _ in 0..scheme.opening_rounds() {
+            self = self
+                .add_points(2, &format!("{}:round", label))
+                .challenge_scalars(1, &format!("{}:challenge", label));
+        }
+        self.add_scalars(2, &format!("{}:last", label))
+    }
+}
+
+impl<G, T> PcsWriter<Ipa<G>> for T
+where
+    G: CurveGroup,
+    T: GroupWriter<G> + FieldWriter<G::ScalarField> + FieldChallenges<G::ScalarField>,
+{
+    fn add_pcs_commitments(&mut self, _scheme: &Ipa<G>, commitments: &[G]) -> ProofResult<()> {
+        self.add_points(commitments)
+    }
+
+    fn add_pcs_round(
+        &mut self,
+        _scheme: &Ipa<G>,
+        (left, right): (G, G),
+    ) -> ProofResult<G::ScalarField> {
+        self.add_points(&[left, right])?;
+        let [challenge] = self.challenge_scalars()?;
+        Ok(challenge)
+    }
+
+    fn add_pcs_final(
+        &mut self,
+        _scheme: &Ipa<G>,
+        (a, b): (G::ScalarField, G::ScalarField),
+    ) -> ProofResult<()> {
+        self.add_scalars(&[a, b])
+    }
+}
+
+impl<G, T> PcsReader<Ipa<G>> for T
+where
+    G: CurveGroup,
+    T: GroupReader<G> + FieldReader<G::ScalarField> + FieldChallenges<G::ScalarField>,
+{
+    fn next_pcs_commitments(&mut self, _scheme: &Ipa<G>, count: usize) -> ProofResult<Vec<G>> {
+        let mut commitments = vec![G::default(); count];
+        self.fill_next_points(&mut commitments)?;
+        Ok(commitments)
+    }
+
+    fn next_pcs_round(&mut self, _scheme: &Ipa<G>) -> ProofResult<((G, G), G::ScalarField)> {
+        let [left, right] = self.next_points()?;
+        let [challenge] = self.challenge_scalars()?;
+        Ok(((left, right), challenge))
+    }
+
+    fn next_pcs_final(
+        &mut self,
+        _scheme: &Ipa<G>,
+    ) -> ProofResult<(G::ScalarField, G::ScalarField)> {
+        let [a, b] = self.next_scalars()?;
+        Ok((a, b))
+    }
+}
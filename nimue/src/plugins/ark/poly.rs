@@ -0,0 +1,192 @@
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::polynomial::univariate::{DensePolynomial, SparsePolynomial};
+use ark_poly::{DenseMultilinearExtension, DenseUVPolynomial, Polynomial};
+
+use super::{FieldIOPattern, FieldReader, FieldWriter};
+use crate::{DuplexHash, IOPattern, IOPatternError, ProofError, ProofResult, Unit};
+
+/// Declare polynomials in the IO pattern.
+///
+/// Polynomials are absorbed as a sequence of scalars whose length depends only on the bound given here:
+/// - a univariate polynomial of degree at most `degree` is absorbed as its degree followed by `degree + 1` coefficients
+///   (lowest degree first, padded with zeros);
+/// - a sparse univariate polynomial with at most `terms` non-zero terms is absorbed as its number of terms followed by
+///   `terms` pairs `(degree, coefficient)`, sorted by degree and padded with `(0, 0)`;
+/// - a multilinear polynomial in `num_vars` variables is absorbed as `num_vars` followed by its `2^num_vars` evaluations over the boolean hypercube.
+///
+/// Panics if the number of scalars absorbed does not fit in a `usize`.
+pub trait PolynomialIOPattern<F: PrimeField> {
+    fn add_poly(self, degree: usize, label: &str) -> Self;
+    fn add_sparse_poly(self, terms: usize, label: &str) -> Self;
+    fn add_multilinear(self, num_vars: usize, label: &str) -> Self;
+}
+
+/// Add polynomials to the protocol transcript, following the layout of [`PolynomialIOPattern`].
+///
+/// Polynomials must be trimmed, i.e. dense polynomials cannot have leading zero coefficients.
+pub trait PolynomialWriter<F: PrimeField> {
+    fn add_poly(&mut self, poly: &DensePolynomial<F>, degree: usize) -> ProofResult<()>;
+    fn add_sparse_poly(&mut self, poly: &SparsePolynomial<F>, terms: usize) -> ProofResult<()>;
+    fn add_multilinear(&mut self, poly: &DenseMultilinearExtension<F>) -> ProofResult<()>;
+}
+
+/// Retrieve polynomials from the protocol transcript, following the layout of [`PolynomialIOPattern`].
+///
+/// The implementation of this trait **MUST** reject non-canonical encodings,
+/// such as a declared degree that does not match the coefficients.
+pub trait PolynomialReader<F: PrimeField> {
+    fn next_poly(&mut self, degree: usize) -> ProofResult<DensePolynomial<F>>;
+    fn next_sparse_poly(&mut self, terms: usize) -> ProofResult<SparsePolynomial<F>>;
+    fn next_multilinear(&mut self, num_vars: usize) -> ProofResult<DenseMultilinearExtension<F>>;
+}
+
+/// Interpret a scalar as a (small) integer, if possible.
+pub(super) fn scalar_to_usize<F: PrimeField>(scalar: F) -> Option<usize> {
+    let bigint = scalar.into_bigint();
+    (bigint.num_bits() <= usize::BITS).then(|| bigint.as_ref()[0] as usize)
+}
+
+/// The number of scalars absorbed for a dense polynomial of degree at most `degree`.
+fn dense_size(degree: usize) -> Result<usize, IOPatternError> {
+    degree
+        .checked_add(2)
+        .ok_or_else(|| "Polynomial degree bound too large".into())
+}
+
+/// The number of scalars absorbed for a sparse polynomial with at most `terms` terms.
+fn sparse_size(terms: usize) -> Result<usize, IOPatternError> {
+    terms
+        .checked_mul(2)
+        .and_then(|size| size.checked_add(1))
+        .ok_or_else(|| "Polynomial terms bound too large".into())
+}
+
+/// The number of evaluations of a multilinear polynomial in `num_vars` variables.
+fn hypercube_size(num_vars: usize) -> Result<usize, IOPatternError> {
+    u32::try_from(num_vars)
+        .ok()
+        .and_then(|num_vars| 1usize.checked_shl(num_vars))
+        .ok_or_else(|| "Too many variables for a multilinear polynomial".into())
+}
+
+/// The number of scalars absorbed for a multilinear polynomial in `num_vars` variables.
+fn multilinear_size(num_vars: usize) -> Result<usize, IOPatternError> {
+    hypercube_size(num_vars)?
+        .checked_add(1)
+        .ok_or_else(|| "Too many variables for a multilinear polynomial".into())
+}
+
+impl<F, H, U> PolynomialIOPattern<F> for IOPattern<H, U>
+where
+    F: PrimeField,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: FieldIOPattern<F>,
+{
+    fn add_poly(self, degree: usize, label: &str) -> Self {
+        let size = dense_size(degree).unwrap_or_else(|e| panic!("{}", e));
+        self.add_scalars(size, label)
+    }
+
+    fn add_sparse_poly(self, terms: usize, label: &str) -> Self {
+        let size = sparse_size(terms).unwrap_or_else(|e| panic!("{}", e));
+        self.add_scalars(size, label)
+    }
+
+    fn add_multilinear(self, num_vars: usize, label: &str) -> Self {
+        let size = multilinear_size(num_vars).unwrap_or_else(|e| panic!("{}", e));
+        self.add_scalars(size, label)
+    }
+}
+
+impl<F, T> PolynomialWriter<F> for T
+where
+    F: PrimeField,
+    T: FieldWriter<F>,
+{
+    fn add_poly(&mut self, poly: &DensePolynomial<F>, degree: usize) -> ProofResult<()> {
+        // `degree` (and the encoding of the degree) assume a trimmed polynomial
+        if poly.coeffs.last().is_some_and(|coeff| coeff.is_zero()) {
+            return Err(IOPatternError::from("Polynomial has leading zero coefficients").into());
+        }
+        if poly.degree() > degree {
+            return Err(
+                IOPatternError::from("Polynomial degree exceeds the declared bound").into(),
+            );
+        }
+        let mut scalars = vec![F::ZERO; dense_size(degree)?];
+        scalars[0] = F::from(poly.degree() as u64);
+        scalars[1..=poly.coeffs.len()].copy_from_slice(&poly.coeffs);
+        self.add_scalars(&scalars)
+    }
+
+    fn add_sparse_poly(&mut self, poly: &SparsePolynomial<F>, terms: usize) -> ProofResult<()> {
+        if poly.len() > terms {
+            return Err(IOPatternError::from("Too many terms for the declared bound").into());
+        }
+        let mut scalars = vec![F::ZERO; sparse_size(terms)?];
+        scalars[0] = F::from(poly.len() as u64);
+        for (i, (degree, coeff)) in poly.iter().enumerate() {
+            scalars[2 * i + 1] = F::from(*degree as u64);
+            scalars[2 * i + 2] = *coeff;
+        }
+        self.add_scalars(&scalars)
+    }
+
+    fn add_multilinear(&mut self, poly: &DenseMultilinearExtension<F>) -> ProofResult<()> {
+        self.add_scalars(&[F::from(poly.num_vars as u64)])?;
+        self.add_scalars(&poly.evaluations)
+    }
+}
+
+impl<F, T> PolynomialReader<F> for T
+where
+    F: PrimeField,
+    T: FieldReader<F>,
+{
+    fn next_poly(&mut self, degree: usize) -> ProofResult<DensePolynomial<F>> {
+        let mut scalars = vec![F::ZERO; dense_size(degree)?];
+        self.fill_next_scalars(&mut scalars)?;
+        let poly = DensePolynomial::from_coefficients_slice(&scalars[1..]);
+        // the trimmed polynomial must have exactly the declared degree
+        (scalar_to_usize(scalars[0]) == Some(poly.degree()))
+            .then_some(poly)
+            .ok_or(ProofError::SerializationError)
+    }
+
+    fn next_sparse_poly(&mut self, terms: usize) -> ProofResult<SparsePolynomial<F>> {
+        let mut scalars = vec![F::ZERO; sparse_size(terms)?];
+        self.fill_next_scalars(&mut scalars)?;
+        let len = scalar_to_usize(scalars[0])
+            .filter(|&len| len <= terms)
+            .ok_or(ProofError::SerializationError)?;
+        let (pairs, padding) = scalars[1..].split_at(2 * len);
+        if padding.iter().any(|x| !x.is_zero()) {
+            return Err(ProofError::SerializationError);
+        }
+        let mut coeffs = Vec::with_capacity(len);
+        for pair in pairs.chunks(2) {
+            let degree = scalar_to_usize(pair[0]).ok_or(ProofError::SerializationError)?;
+            // degrees must be strictly increasing, and coefficients non-zero
+            if pair[1].is_zero() || coeffs.last().is_some_and(|&(last, _)| last >= degree) {
+                return Err(ProofError::SerializationError);
+            }
+            coeffs.push((degree, pair[1]));
+        }
+        Ok(SparsePolynomial::from_coefficients_vec(coeffs))
+    }
+
+    fn next_multilinear(&mut self, num_vars: usize) -> ProofResult<DenseMultilinearExtension<F>> {
+        let size = hypercube_size(num_vars)?;
+        let [declared] = self.next_scalars()?;
+        if scalar_to_usize(declared) != Some(num_vars) {
+            return Err(ProofError::SerializationError);
+        }
+        let mut evaluations = vec![F::ZERO; size];
+        self.fill_next_scalars(&mut evaluations)?;
+        Ok(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars,
+            evaluations,
+        ))
+    }
+}
@@ -1,11 +1,11 @@
 use ark_ec::short_weierstrass::{Affine as SWAffine, Projective as SWCurve, SWCurveConfig};
 use ark_ec::twisted_edwards::{Affine as EdwardsAffine, Projective as EdwardsCurve, TECurveConfig};
-use ark_ec::CurveGroup;
+use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::Field;
 use ark_ff::{Fp, FpConfig};
-use ark_serialize::CanonicalDeserialize;
 
-use super::{FieldReader, GroupReader};
+use super::common::{deserialize_point, deserialize_with, encoded_size, point_size, with_buffer};
+use super::{FieldPublic, FieldReader, GroupReader};
 use crate::traits::*;
 use crate::{Arthur, DuplexHash, ProofResult};
 
@@ -15,13 +15,15 @@ where
     H: DuplexHash,
 {
     fn fill_next_scalars(&mut self, output: &mut [F]) -> ProofResult<()> {
-        let point_size = F::default().compressed_size();
-        let mut buf = vec![0u8; point_size];
-        for o in output.iter_mut() {
-            self.fill_next_bytes(&mut buf)?;
-            *o = F::deserialize_compressed(buf.as_slice())?;
-        }
-        Ok(())
+        let point_size = encoded_size(&F::default(), self.encoding);
+        with_buffer(point_size, |buf| {
+            for o in output.iter_mut() {
+                self.read_element(buf)?;
+                self.public_units(buf)?;
+                *o = deserialize_with(&buf[..], self.encoding)?;
+            }
+            Ok(())
+        })
     }
 }
 
@@ -31,24 +33,29 @@ where
     H: DuplexHash,
 {
     fn fill_next_points(&mut self, output: &mut [G]) -> ProofResult<()> {
-        let point_size = G::default().compressed_size();
-        let mut buf = vec![0u8; point_size];
-
-        for o in output.iter_mut() {
-            self.fill_next_units(&mut buf)?;
-            *o = G::deserialize_compressed(buf.as_slice())?;
-        }
-        Ok(())
+        let size = point_size::<G::Affine>(self.encoding);
+        with_buffer(size, |buf| {
+            for o in output.iter_mut() {
+                self.read_element(buf)?;
+                self.public_units(buf)?;
+                *o = deserialize_point::<G::Affine>(&buf[..], self.encoding)?.into_group();
+            }
+            Ok(())
+        })
     }
 }
 
-impl<H, C, const N: usize> FieldReader<Fp<C, N>> for Arthur<'_, H, Fp<C, N>>
+impl<F, H, C, const N: usize> FieldReader<F> for Arthur<'_, H, Fp<C, N>>
 where
+    F: Field<BasePrimeField = Fp<C, N>>,
     C: FpConfig<N>,
     H: DuplexHash<Fp<C, N>>,
 {
-    fn fill_next_scalars(&mut self, output: &mut [Fp<C, N>]) -> crate::ProofResult<()> {
-        self.fill_next_units(output)?;
+    fn fill_next_scalars(&mut self, output: &mut [F]) -> crate::ProofResult<()> {
+        for o in output.iter_mut() {
+            *o = deserialize_with(&mut self.transcript, self.encoding)?;
+            self.public_scalars(&[*o])?;
+        }
         Ok(())
     }
 }
@@ -61,7 +68,8 @@ where
 {
     fn fill_next_points(&mut self, output: &mut [EdwardsCurve<P>]) -> ProofResult<()> {
         for o in output.iter_mut() {
-            let o_affine = EdwardsAffine::deserialize_compressed(&mut self.transcript)?;
+            let o_affine: EdwardsAffine<P> =
+                deserialize_point(&mut self.transcript, self.encoding)?;
             *o = o_affine.into();
             self.public_units(&[o.x, o.y])?;
         }
@@ -77,7 +85,7 @@ where
 {
     fn fill_next_points(&mut self, output: &mut [SWCurve<P>]) -> ProofResult<()> {
         for o in output.iter_mut() {
-            let o_affine = SWAffine::deserialize_compressed(&mut self.transcript)?;
+            let o_affine: SWAffine<P> = deserialize_point(&mut self.transcript, self.encoding)?;
             *o = o_affine.into();
             self.public_units(&[o.x, o.y])?;
         }
@@ -0,0 +1,65 @@
+use ark_ff::PrimeField;
+use rand::{CryptoRng, RngCore};
+
+use crate::{Arthur, DuplexHash, Merlin, ProofResult, Unit};
+
+/// Pack the serialization of `units` into scalars, $\lfloor (\log_2 p - 1) / 8 \rfloor$ little-endian bytes each,
+/// so that the map is injective.
+fn units_to_scalars<F: PrimeField, U: Unit>(units: &[U]) -> Vec<F> {
+    let mut bytes = Vec::new();
+    // write never fails on Vec<u8>
+    U::write(units, &mut bytes).unwrap();
+    let chunk_size = ((F::MODULUS_BIT_SIZE - 1) / 8) as usize;
+    bytes
+        .chunks(chunk_size)
+        .map(F::from_le_bytes_mod_order)
+        .collect()
+}
+
+impl<H, U, R> Merlin<H, U, R>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+    R: RngCore + CryptoRng,
+{
+    /// Ratchet the verifier's state and export it as scalars in `F`.
+    ///
+    /// The IO pattern must declare a ratchet at this point.
+    /// After the ratchet, the capacity of the sponge determines its whole state,
+    /// so that absorbing the exported scalars (for instance, as public inputs of the circuit of the next proof)
+    /// binds everything the transcript processed so far: the inner transcript is continued inside the outer one.
+    /// The capacity is serialized and packed into scalars of $\lfloor (\log_2 p - 1) / 8 \rfloor$ bytes each.
+    ///
+    /// The verifier gets the same scalars with [`Arthur::squeeze_state_as_scalars`].
+    /// Return an error if the hash function does not support exporting its state.
+    ///
+    /// ```
+    /// use ark_curve25519::Fr;
+    /// use nimue::{ByteIOPattern, ByteReader, ByteWriter, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(1, "inner").ratchet();
+    /// let mut merlin = io.to_merlin();
+    /// merlin.add_bytes(b"!").unwrap();
+    /// let state = merlin.squeeze_state_as_scalars::<Fr>().unwrap();
+    ///
+    /// let mut arthur = io.to_arthur(merlin.transcript());
+    /// arthur.next_bytes::<1>().unwrap();
+    /// assert_eq!(arthur.squeeze_state_as_scalars::<Fr>().unwrap(), state);
+    /// ```
+    pub fn squeeze_state_as_scalars<F: PrimeField>(&mut self) -> ProofResult<Vec<F>> {
+        let state = self.safe.ratchet_and_export()?;
+        Ok(units_to_scalars(&state))
+    }
+}
+
+impl<H, U> Arthur<'_, H, U>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    /// Ratchet the verifier's state and export it as scalars in `F`, see [`Merlin::squeeze_state_as_scalars`].
+    pub fn squeeze_state_as_scalars<F: PrimeField>(&mut self) -> ProofResult<Vec<F>> {
+        let state = self.safe.ratchet_and_export()?;
+        Ok(units_to_scalars(&state))
+    }
+}
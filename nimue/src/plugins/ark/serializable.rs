@@ -0,0 +1,66 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::common::{deserialize_with, encoded_size, serialize_with};
+use crate::{
+    Arthur, ByteIOPattern, ByteWriter, DuplexHash, IOPattern, ProofResult, Unit, UnitEncoding,
+    UnitTranscript,
+};
+
+/// Declare arbitrary serializable values in the IO pattern.
+///
+/// The value is only used to compute its serialized length (following the [`Encoding`](crate::Encoding) of the IO pattern),
+/// which is bound into the IO pattern: any value of the same shape can be absorbed later on.
+/// This allows to absorb composite structures (commitment keys, proofs of sub-protocols, ...) in one call.
+pub trait SerializableIOPattern {
+    fn add_serializable(self, value: &impl CanonicalSerialize, label: &str) -> Self;
+}
+
+/// Add serializable values to the protocol transcript.
+pub trait SerializableWriter {
+    fn add_serializable(&mut self, value: &impl CanonicalSerialize) -> ProofResult<()>;
+}
+
+/// Retrieve (and validate) serializable values from the protocol transcript.
+///
+/// The length of the value is determined by its deserialization:
+/// a value whose length differs from the one declared in the IO pattern results in an error.
+pub trait SerializableReader {
+    fn next_deserializable<T: CanonicalDeserialize>(&mut self) -> ProofResult<T>;
+}
+
+impl<H, U> SerializableIOPattern for IOPattern<H, U>
+where
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: ByteIOPattern,
+{
+    fn add_serializable(self, value: &impl CanonicalSerialize, label: &str) -> Self {
+        let size = encoded_size(value, self.encoding());
+        self.add_bytes(size, label)
+    }
+}
+
+impl<T> SerializableWriter for T
+where
+    T: ByteWriter + UnitEncoding,
+{
+    fn add_serializable(&mut self, value: &impl CanonicalSerialize) -> ProofResult<()> {
+        let mut buf = Vec::new();
+        serialize_with(value, self.encoding(), &mut buf)?;
+        self.add_bytes(&buf)?;
+        Ok(())
+    }
+}
+
+impl<H: DuplexHash> SerializableReader for Arthur<'_, H> {
+    fn next_deserializable<T: CanonicalDeserialize>(&mut self) -> ProofResult<T> {
+        let mut remaining = self.transcript;
+        let value = deserialize_with(&mut remaining, self.encoding)?;
+        let (bytes, rest) = self
+            .transcript
+            .split_at(self.transcript.len() - remaining.len());
+        self.public_units(bytes)?;
+        self.transcript = rest;
+        Ok(value)
+    }
+}
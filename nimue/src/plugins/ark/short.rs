@@ -0,0 +1,64 @@
+use ark_ff::Field;
+
+use crate::{ByteChallenges, ByteIOPattern, DuplexHash, IOPattern, ProofResult, Unit};
+
+/// Squeeze short (128-bit) scalar challenges in the IO pattern.
+///
+/// Each challenge is 16 bytes long, interpreted as a little-endian integer,
+/// whatever the [`Endianness`](crate::Endianness) of the IO pattern.
+pub trait ShortFieldIOPattern<F: Field> {
+    fn challenge_scalars_short(self, count: usize, label: &str) -> Self;
+}
+
+/// Interpret verifier messages as scalars in $[0, 2^{128})$.
+///
+/// Short challenges make scalar multiplications cheaper for the verifier
+/// (for instance, by skipping the decomposition step with GLV endomorphisms, or halving the number of doublings).
+/// However, the challenge space has size $2^{128}$ instead of $|F|$:
+/// this is fine for protocols whose soundness error is inversely proportional to the challenge space (e.g., Schnorr proofs), at the 128-bit security level,
+/// but it is not a replacement for [`FieldChallenges`](super::FieldChallenges) when the challenge is e.g. an evaluation point for a polynomial of large degree $d$
+/// (the soundness error becoming $d / 2^{128}$), or when the security proof requires field-wide uniformity.
+pub trait ShortFieldChallenges<F: Field> {
+    fn fill_challenge_scalars_short(&mut self, output: &mut [F]) -> ProofResult<()>;
+
+    fn challenge_scalars_short<const N: usize>(&mut self) -> ProofResult<[F; N]> {
+        let mut output = [F::default(); N];
+        self.fill_challenge_scalars_short(&mut output)
+            .map(|()| output)
+    }
+
+    /// Squeeze `n` short scalars, for when their number is known only at runtime.
+    fn challenge_scalars_short_vec(&mut self, n: usize) -> ProofResult<Vec<F>> {
+        let mut output = vec![F::default(); n];
+        self.fill_challenge_scalars_short(&mut output)
+            .map(|()| output)
+    }
+}
+
+impl<F, H, U> ShortFieldIOPattern<F> for IOPattern<H, U>
+where
+    F: Field,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: ByteIOPattern,
+{
+    fn challenge_scalars_short(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(count * 16, label)
+    }
+}
+
+impl<F, T> ShortFieldChallenges<F> for T
+where
+    F: Field,
+    T: ByteChallenges,
+{
+    fn fill_challenge_scalars_short(&mut self, output: &mut [F]) -> ProofResult<()> {
+        // squeeze all the bytes at once, as declared in the IO pattern
+        let mut buf = vec![0u8; output.len() * 16];
+        self.fill_challenge_bytes(&mut buf)?;
+        for (o, chunk) in output.iter_mut().zip(buf.chunks(16)) {
+            *o = F::from(u128::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        Ok(())
+    }
+}
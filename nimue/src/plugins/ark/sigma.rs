@@ -0,0 +1,135 @@
+//! Schnorr's proof of knowledge of a discrete logarithm, as a [`SigmaProtocol`] over any arkworks [`CurveGroup`].
+//!
+//! Challenges are 16 bytes, interpreted as a little-endian integer, for a knowledge error of $2^{-128}$.
+//! Points and scalars are serialized in compressed form.
+//! Composed with [`And`](crate::protocols::sigma::And) and [`Or`](crate::protocols::sigma::Or),
+//! this gives proofs of knowledge of one out of many, or all of many, discrete logarithms.
+//!
+//! ```
+//! use ark_curve25519::EdwardsProjective as G;
+//! use ark_ec::PrimeGroup;
+//! use ark_std::UniformRand;
+//! use nimue::plugins::ark::sigma::Schnorr;
+//! use nimue::protocols::sigma::{prove, verify, Or, OrWitness, SigmaIOPattern};
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! let mut rng = ark_std::test_rng();
+//! let x = <G as PrimeGroup>::ScalarField::rand(&mut rng);
+//! let left = Schnorr::new(G::generator(), G::rand(&mut rng));
+//! let right = Schnorr::new(G::generator(), G::generator() * x);
+//! // prove knowledge of the discrete logarithm of one of the two public keys
+//! let protocol = Or::new(left, right).unwrap();
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝").add_sigma_protocol(&protocol, "or");
+//! let mut merlin = io.to_merlin();
+//! prove(&protocol, &mut merlin, &OrWitness::Right(x)).unwrap();
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! assert!(verify(&protocol, &mut arthur).is_ok());
+//! ```
+use ark_ec::CurveGroup;
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
+
+use crate::protocols::sigma::SigmaProtocol;
+use crate::{ProofError, ProofResult};
+
+/// The size of the challenges, in bytes.
+const CHALLENGE_LENGTH: usize = 16;
+
+/// Knowledge of $x$ such that $X = xG$.
+#[derive(Clone, Copy, Debug)]
+pub struct Schnorr<G> {
+    pub generator: G,
+    pub public: G,
+}
+
+impl<G: CurveGroup> Schnorr<G> {
+    /// The statement $X = xG$, with `generator` $G$ and `public` $X$.
+    pub fn new(generator: G, public: G) -> Self {
+        Self { generator, public }
+    }
+}
+
+impl<G: CurveGroup> SigmaProtocol for Schnorr<G> {
+    type Witness = G::ScalarField;
+    type Commitment = G;
+    /// The witness and the nonce.
+    type ProverState = (G::ScalarField, G::ScalarField);
+    type Response = G::ScalarField;
+
+    fn commitment_length(&self) -> usize {
+        G::default().compressed_size()
+    }
+
+    fn challenge_length(&self) -> usize {
+        CHALLENGE_LENGTH
+    }
+
+    fn response_length(&self) -> usize {
+        G::ScalarField::zero().compressed_size()
+    }
+
+    fn prover_commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::ProverState) {
+        let k = G::ScalarField::rand(rng);
+        (self.generator * k, (*witness, k))
+    }
+
+    fn prover_response(
+        &self,
+        state: Self::ProverState,
+        challenge: &[u8],
+    ) -> ProofResult<Self::Response> {
+        let (x, k) = state;
+        let c = G::ScalarField::from_le_bytes_mod_order(challenge);
+        Ok(k + c * x)
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &[u8],
+        response: &Self::Response,
+    ) -> ProofResult<()> {
+        let c = G::ScalarField::from_le_bytes_mod_order(challenge);
+        if self.generator * response == *commitment + self.public * c {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidProof)
+        }
+    }
+
+    fn simulate(
+        &self,
+        challenge: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::Response) {
+        let c = G::ScalarField::from_le_bytes_mod_order(challenge);
+        let r = G::ScalarField::rand(rng);
+        (self.generator * r - self.public * c, r)
+    }
+
+    fn serialize_commitment(&self, commitment: &Self::Commitment) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        commitment.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn deserialize_commitment(&self, bytes: &[u8]) -> ProofResult<Self::Commitment> {
+        Ok(G::deserialize_compressed(bytes)?)
+    }
+
+    fn serialize_response(&self, response: &Self::Response) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        response.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn deserialize_response(&self, bytes: &[u8]) -> ProofResult<Self::Response> {
+        Ok(G::ScalarField::deserialize_compressed(bytes)?)
+    }
+}
@@ -0,0 +1,199 @@
+//! Schnorr signatures over any arkworks [`CurveGroup`], with any [`DuplexHash`] over bytes.
+//!
+//! A signature on a message $m$ under the public key $X = xG$ is the transcript of a Schnorr proof,
+//! whose statement is the public key and a 32-byte digest of the message (see [`message_digest`]):
+//!
+//! ```text
+//!     A<G>public-key A32message R A<G>commitment S<F>challenge A<F>response
+//! ```
+//! The public key and the digest are absorbed but not written: a signature is just $(K, r)$,
+//! and it is valid if $rG = K + cX$, where $c$ is derived from $(X, m, K)$.
+//! Since the challenge only depends on the (joint) public key and the (joint) commitment,
+//! this layout is also the one of multi-signatures such as MuSig, where $X$ and $K$ are aggregated.
+//!
+//! Nonces are hedged (see [`Merlin::hedged_nonce`]): they are derived from the secret key, the message, and fresh randomness.
+//!
+//! ```
+//! use ark_curve25519::EdwardsProjective as G;
+//! use nimue::plugins::ark::signatures::{keygen, sign, verify, SchnorrIOPattern};
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! let io = SchnorrIOPattern::<G>::add_schnorr_signature(IOPattern::<DefaultHash>::new("📝"));
+//! let (sk, pk) = keygen::<G>(&mut rand::rngs::OsRng);
+//! let signature = sign::<G, _>(&io, sk, b"hello").unwrap();
+//! assert!(verify(&io, pk, b"hello", &signature).is_ok());
+//! assert!(verify(&io, pk, b"goodbye", &signature).is_err());
+//! ```
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::{CryptoRng, Rng, RngCore};
+
+use super::{
+    FieldChallenges, FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupPublic,
+    GroupReader, GroupWriter,
+};
+use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, BytePublic, DuplexHash, IOPattern, Merlin, ProofError,
+    ProofResult,
+};
+
+/// Declare a Schnorr signature in the IO pattern.
+pub trait SchnorrIOPattern<G: CurveGroup> {
+    fn add_schnorr_signature(self) -> Self;
+}
+
+impl<G, H> SchnorrIOPattern<G> for IOPattern<H>
+where
+    G: CurveGroup,
+    H: DuplexHash,
+    IOPattern<H>: GroupIOPattern<G> + FieldIOPattern<G::ScalarField>,
+{
+    fn add_schnorr_signature(self) -> Self {
+        self.add_points(1, "public key (X)")
+            .add_bytes(32, "message (m)")
+            .ratchet()
+            .add_points(1, "commitment (K)")
+            .challenge_scalars(1, "challenge (c)")
+            .add_scalars(1, "response (r)")
+    }
+}
+
+/// The 32-byte digest of `message` with the hash `H`, absorbed in the signature transcript.
+pub fn message_digest<H: DuplexHash>(message: &[u8]) -> [u8; 32] {
+    let mut io = IOPattern::<H>::new("nimue/signatures/message").absorb(8, "length");
+    if !message.is_empty() {
+        io = io.absorb(message.len(), "message");
+    }
+    let io = io.squeeze(32, "digest");
+    let mut merlin = io.to_merlin();
+    // the IO pattern is built from the message, hence it is always followed
+    merlin
+        .public_bytes(&(message.len() as u64).to_le_bytes())
+        .unwrap();
+    if !message.is_empty() {
+        merlin.public_bytes(message).unwrap();
+    }
+    merlin.challenge_bytes().unwrap()
+}
+
+/// Generate a secret key $x$ and the corresponding public key $xG$.
+pub fn keygen<G: CurveGroup>(rng: &mut (impl CryptoRng + RngCore)) -> (G::ScalarField, G) {
+    let sk = G::ScalarField::rand(rng);
+    (sk, G::generator() * sk)
+}
+
+/// Sign `message` with the secret key `sk`, following `io_pattern` (declared with [`SchnorrIOPattern`]).
+pub fn sign<G, H>(
+    io_pattern: &IOPattern<H>,
+    sk: G::ScalarField,
+    message: &[u8],
+) -> ProofResult<Vec<u8>>
+where
+    G: CurveGroup,
+    H: DuplexHash,
+    Merlin<H>: GroupWriter<G> + FieldWriter<G::ScalarField> + FieldChallenges<G::ScalarField>,
+{
+    let mut merlin = io_pattern.to_merlin();
+    merlin.public_points(&[G::generator() * sk])?;
+    merlin.public_bytes(&message_digest::<H>(message))?;
+    merlin.ratchet()?;
+
+    let mut secret = Vec::new();
+    sk.serialize_compressed(&mut secret)?;
+    let mut nonce = [0u8; 64];
+    merlin.hedged_nonce(&secret, message, &mut nonce);
+    let k = G::ScalarField::from_le_bytes_mod_order(&nonce);
+    zeroize::Zeroize::zeroize(&mut secret);
+    zeroize::Zeroize::zeroize(&mut nonce);
+
+    merlin.add_points(&[G::generator() * k])?;
+    let [c] = merlin.challenge_scalars()?;
+    merlin.add_scalars(&[k + c * sk])?;
+    Ok(merlin.transcript().to_vec())
+}
+
+/// Read the commitment, challenge, and response of a signature.
+fn read_signature<G, H>(
+    io_pattern: &IOPattern<H>,
+    pk: G,
+    message: &[u8],
+    signature: &[u8],
+) -> ProofResult<(G, G::ScalarField, G::ScalarField)>
+where
+    G: CurveGroup,
+    H: DuplexHash,
+    for<'a> Arthur<'a, H>: GroupReader<G>
+        + GroupPublic<G>
+        + FieldReader<G::ScalarField>
+        + FieldChallenges<G::ScalarField>,
+{
+    let mut arthur = io_pattern.to_arthur(signature);
+    arthur.public_points(&[pk])?;
+    arthur.public_bytes(&message_digest::<H>(message))?;
+    arthur.ratchet()?;
+    let [commitment] = arthur.next_points()?;
+    let [c] = arthur.challenge_scalars()?;
+    let [r] = arthur.next_scalars()?;
+    Ok((commitment, c, r))
+}
+
+/// Verify `signature` on `message` under the public key `pk`.
+pub fn verify<G, H>(
+    io_pattern: &IOPattern<H>,
+    pk: G,
+    message: &[u8],
+    signature: &[u8],
+) -> ProofResult<()>
+where
+    G: CurveGroup,
+    H: DuplexHash,
+    for<'a> Arthur<'a, H>: GroupReader<G>
+        + GroupPublic<G>
+        + FieldReader<G::ScalarField>
+        + FieldChallenges<G::ScalarField>,
+{
+    let (commitment, c, r) = read_signature(io_pattern, pk, message, signature)?;
+    if G::generator() * r == commitment + pk * c {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidProof)
+    }
+}
+
+/// Verify many signatures at once, given as triples `(pk, message, signature)`.
+///
+/// The verification equations are combined with random 128-bit coefficients drawn from `rng`
+/// and checked with a single multi-scalar multiplication.
+/// If one of the signatures is invalid, the check passes with probability at most $2^{-128}$.
+/// The randomness **MUST** be unknown to the signers.
+pub fn batch_verify<G, H>(
+    io_pattern: &IOPattern<H>,
+    signatures: &[(G, &[u8], &[u8])],
+    rng: &mut (impl CryptoRng + RngCore),
+) -> ProofResult<()>
+where
+    G: CurveGroup,
+    H: DuplexHash,
+    for<'a> Arthur<'a, H>: GroupReader<G>
+        + GroupPublic<G>
+        + FieldReader<G::ScalarField>
+        + FieldChallenges<G::ScalarField>,
+{
+    // the equations z (r G - K - c X) = 0, summed up
+    let mut bases = vec![G::generator()];
+    let mut scalars = vec![G::ScalarField::zero()];
+    for &(pk, message, signature) in signatures {
+        let (commitment, c, r) = read_signature(io_pattern, pk, message, signature)?;
+        let z = G::ScalarField::from(rng.gen::<u128>());
+        scalars[0] += z * r;
+        bases.extend([commitment, pk]);
+        scalars.extend([-z, -z * c]);
+    }
+    let bases = G::normalize_batch(&bases);
+    if G::msm_unchecked(&bases, &scalars).is_zero() {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidProof)
+    }
+}
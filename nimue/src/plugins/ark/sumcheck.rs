@@ -0,0 +1,229 @@
+//! Transcript helpers for the sumcheck protocol.
+//!
+//! To prove that $\sum_{x \in \{0, 1\}^v} g(x) = s$ for a $v$-variate polynomial $g$ of degree at most $d$ in each variable,
+//! the protocol runs in $v$ rounds.
+//! In round $i$, the prover sends the coefficients of the univariate polynomial $g_i$ (lowest degree first)
+//! and the verifier replies with a random evaluation point $r_i$:
+//!
+//! ```text
+//!     (A<d+1 F>round S<F>challenge)^v
+//! ```
+//! The verifier checks that $g_1(0) + g_1(1) = s$ and, for every subsequent round, that $g_{i+1}(0) + g_{i+1}(1) = g_i(r_i)$.
+//! At the end of the protocol, it is left with the claim $g(r_1, \dots, r_v) = g_v(r_v)$, which must be checked by other means.
+//!
+//! Multilinear protocols often start by squeezing a random point $r \in F^\mu$ ([`MultilinearChallenges`]),
+//! to reduce a claim on a table of $2^\mu$ values to a sumcheck weighted by $\mathrm{eq}(r, \cdot)$,
+//! the multilinear extension of the equality over the boolean hypercube ([`MultilinearPoint::eq_weights`]).
+use std::sync::OnceLock;
+
+use ark_ff::Field;
+
+use super::{FieldChallenges, FieldIOPattern, FieldReader, FieldWriter};
+use crate::{DuplexHash, IOPattern, ProofError, ProofResult, Unit};
+
+/// The outcome of the sumcheck protocol for the verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SumcheckClaim<F: Field> {
+    /// The random point $(r_1, \dots, r_v)$.
+    pub point: Vec<F>,
+    /// The claimed evaluation of the polynomial at [`SumcheckClaim::point`].
+    pub evaluation: F,
+}
+
+/// A point $(r_1, \dots, r_\mu)$, on the extension of the boolean hypercube $\{0, 1\}^\mu$.
+///
+/// Vertices of the hypercube are indexed in little-endian order: the vertex $x$ has index $\sum_i x_i 2^{i - 1}$.
+#[derive(Clone, Debug)]
+pub struct MultilinearPoint<F: Field> {
+    coordinates: Vec<F>,
+    eq_weights: OnceLock<Vec<F>>,
+}
+
+impl<F: Field> MultilinearPoint<F> {
+    /// The point of coordinates `coordinates`, e.g., the point of a [`SumcheckClaim`].
+    pub fn new(coordinates: Vec<F>) -> Self {
+        Self {
+            coordinates,
+            eq_weights: OnceLock::new(),
+        }
+    }
+
+    /// The coordinates $(r_1, \dots, r_\mu)$.
+    pub fn coordinates(&self) -> &[F] {
+        &self.coordinates
+    }
+
+    /// The number of variables $\mu$.
+    pub fn num_vars(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    /// Evaluate $\mathrm{eq}(r, x) = \prod_i (r_i x_i + (1 - r_i)(1 - x_i))$ at the point `x`.
+    ///
+    /// Return `None` if `x` does not have $\mu$ coordinates.
+    pub fn eq(&self, x: &[F]) -> Option<F> {
+        (x.len() == self.num_vars()).then(|| {
+            self.coordinates
+                .iter()
+                .zip(x)
+                .map(|(r, x)| *r * x + (F::ONE - r) * (F::ONE - x))
+                .product()
+        })
+    }
+
+    /// The weights $\mathrm{eq}(r, x)$ of the $2^\mu$ vertices $x$ of the hypercube, in order.
+    ///
+    /// The multilinear extension $\tilde{f}$ of a table $f$ of $2^\mu$ values is evaluated at $r$
+    /// as the inner product of $f$ and the weights.
+    /// The weights are computed the first time they are needed, with $2^\mu$ multiplications.
+    pub fn eq_weights(&self) -> &[F] {
+        self.eq_weights.get_or_init(|| {
+            let mut weights = Vec::with_capacity(1 << self.num_vars());
+            weights.push(F::ONE);
+            for r in &self.coordinates {
+                let high = weights.iter().map(|w| *w * r).collect::<Vec<_>>();
+                weights
+                    .iter_mut()
+                    .zip(&high)
+                    .for_each(|(w, high)| *w -= high);
+                weights.extend(high);
+            }
+            weights
+        })
+    }
+}
+
+impl<F: Field> PartialEq for MultilinearPoint<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.coordinates == other.coordinates
+    }
+}
+
+impl<F: Field> Eq for MultilinearPoint<F> {}
+
+/// Declare random points for multilinear polynomials in the IO pattern.
+pub trait MultilinearIOPattern<F: Field> {
+    /// Declare a random point over `num_vars` variables, squeezed at once.
+    fn challenge_multilinear_point(self, num_vars: usize, label: &str) -> Self;
+}
+
+/// Squeeze random points for multilinear polynomials, see [`MultilinearPoint`].
+pub trait MultilinearChallenges<F: Field> {
+    /// Squeeze a random point over `num_vars` variables.
+    fn challenge_multilinear_point(&mut self, num_vars: usize) -> ProofResult<MultilinearPoint<F>>;
+}
+
+/// Evaluate the polynomial with coefficients `coeffs` (lowest degree first) at `x`.
+fn evaluate<F: Field>(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::ZERO, |acc, &c| acc * x + c)
+}
+
+/// Declare a sumcheck protocol in the IO pattern.
+pub trait SumcheckIOPattern<F: Field> {
+    /// Declare a sumcheck over `num_vars` variables, with round polynomials of degree at most `degree`.
+    fn add_sumcheck(self, num_vars: usize, degree: usize, label: &str) -> Self;
+}
+
+/// Prover's side of the sumcheck protocol.
+pub trait SumcheckWriter<F: Field> {
+    /// Add the coefficients of the round polynomial (lowest degree first, exactly `degree + 1` of them),
+    /// and return the round challenge.
+    fn add_sumcheck_round(&mut self, coeffs: &[F]) -> ProofResult<F>;
+}
+
+/// Verifier's side of the sumcheck protocol.
+pub trait SumcheckReader<F: Field> {
+    /// Read the coefficients of the next round polynomial, and return them along with the round challenge.
+    fn next_sumcheck_round(&mut self, degree: usize) -> ProofResult<(Vec<F>, F)>;
+
+    /// Run the verifier of the sumcheck protocol for the claim `claimed_sum`.
+    ///
+    /// Return an error if any of the round checks fails,
+    /// and otherwise the claim left to be checked.
+    fn next_sumcheck(
+        &mut self,
+        num_vars: usize,
+        degree: usize,
+        claimed_sum: F,
+    ) -> ProofResult<SumcheckClaim<F>> {
+        let mut point = Vec::with_capacity(num_vars);
+        let mut claim = claimed_sum;
+        for _ in 0..num_vars {
+            let (coeffs, challenge) = self.next_sumcheck_round(degree)?;
+            if evaluate(&coeffs, F::ZERO) + evaluate(&coeffs, F::ONE) != claim {
+                return Err(ProofError::InvalidProof);
+            }
+            claim = evaluate(&coeffs, challenge);
+            point.push(challenge);
+        }
+        Ok(SumcheckClaim {
+            point,
+            evaluation: claim,
+        })
+    }
+}
+
+impl<F, H, U> SumcheckIOPattern<F> for IOPattern<H, U>
+where
+    F: Field,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: FieldIOPattern<F>,
+{
+    fn add_sumcheck(mut self, num_vars: usize, degree: usize, label: &str) -> Self {
+        for _ in 0..num_vars {
+            self = self
+                .add_scalars(degree + 1, &format!("{}:round", label))
+                .challenge_scalars(1, &format!("{}:challenge", label));
+        }
+        self
+    }
+}
+
+impl<F, H, U> MultilinearIOPattern<F> for IOPattern<H, U>
+where
+    F: Field,
+    U: Unit,
+    H: DuplexHash<U>,
+    IOPattern<H, U>: FieldIOPattern<F>,
+{
+    fn challenge_multilinear_point(self, num_vars: usize, label: &str) -> Self {
+        self.challenge_scalars(num_vars, label)
+    }
+}
+
+impl<F, T> MultilinearChallenges<F> for T
+where
+    F: Field,
+    T: FieldChallenges<F>,
+{
+    fn challenge_multilinear_point(&mut self, num_vars: usize) -> ProofResult<MultilinearPoint<F>> {
+        self.challenge_scalars_vec(num_vars)
+            .map(MultilinearPoint::new)
+    }
+}
+
+impl<F, T> SumcheckWriter<F> for T
+where
+    F: Field,
+    T: FieldWriter<F> + FieldChallenges<F>,
+{
+    fn add_sumcheck_round(&mut self, coeffs: &[F]) -> ProofResult<F> {
+        self.add_scalars(coeffs)?;
+        let [challenge] = self.challenge_scalars()?;
+        Ok(challenge)
+    }
+}
+
+impl<F, T> SumcheckReader<F> for T
+where
+    F: Field,
+    T: FieldReader<F> + FieldChallenges<F>,
+{
+    fn next_sumcheck_round(&mut self, degree: usize) -> ProofResult<(Vec<F>, F)> {
+        let mut coeffs = vec![F::ZERO; degree + 1];
+        self.fill_next_scalars(&mut coeffs)?;
+        let [challenge] = self.challenge_scalars()?;
+        Ok((coeffs, challenge))
+    }
+}
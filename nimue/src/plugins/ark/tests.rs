@@ -97,3 +97,1695 @@ fn test_arkworks() {
     test_arkworks_end_to_end::<F, DefaultHash>().unwrap();
     test_arkworks_end_to_end::<F2, DefaultHash>().unwrap();
 }
+
+#[test]
+fn test_uncompressed_encoding() {
+    use crate::plugins::ark::{GroupIOPattern, GroupReader, GroupWriter};
+    use crate::Encoding;
+    use ark_bls12_381::G1Projective as G;
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .with_encoding(Encoding::Uncompressed)
+        .add_points(1, "g");
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\x1fencoding=uncompressed\0A96g"
+    );
+
+    let g = G::generator();
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&[g]).unwrap();
+    assert_eq!(merlin.transcript().len(), 96);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [h]: [G; 1] = arthur.next_points().unwrap();
+    assert_eq!(g, h);
+}
+
+#[test]
+fn test_fixed_width_encoding() {
+    use crate::plugins::ark::{GroupIOPattern, GroupReader, GroupWriter};
+    use crate::Encoding;
+    use ark_bls12_381::{G1Affine, G1Projective as G};
+    use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+    use ark_serialize::CanonicalSerialize;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .with_encoding(Encoding::FixedWidthLE)
+        .add_points(3, "g");
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\x1fencoding=fixed-width-le\0A288g"
+    );
+
+    // the generator and its opposite differ in the flags of their uncompressed encodings, but not here
+    let points = [G::generator(), -G::generator(), G::default()];
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&points).unwrap();
+    let transcript = merlin.transcript().to_vec();
+    let mut expected = Vec::new();
+    for point in &points[..2] {
+        let (x, y) = G1Affine::from(*point).xy().unwrap();
+        x.serialize_uncompressed(&mut expected).unwrap();
+        y.serialize_uncompressed(&mut expected).unwrap();
+    }
+    expected.extend([0u8; 96]);
+    assert_eq!(transcript, expected);
+    let mut uncompressed = Vec::new();
+    G::normalize_batch(&points[..2])
+        .serialize_uncompressed(&mut uncompressed)
+        .unwrap();
+    assert_ne!(&transcript[..192], &uncompressed[8..]);
+
+    let mut arthur = io_pattern.to_arthur(&transcript);
+    let decoded: [G; 3] = arthur.next_points().unwrap();
+    assert_eq!(decoded, points);
+
+    // setting the spare bits of y (the flags of arkworks) is rejected
+    let mut flagged = transcript.clone();
+    flagged[95] |= 0x80;
+    let mut arthur = io_pattern.to_arthur(&flagged);
+    assert!(GroupReader::<G>::next_points::<3>(&mut arthur).is_err());
+    let mut flagged = transcript;
+    flagged[287] |= 0x40;
+    let mut arthur = io_pattern.to_arthur(&flagged);
+    assert!(GroupReader::<G>::next_points::<3>(&mut arthur).is_err());
+}
+
+#[test]
+fn test_dedup_codec() {
+    use crate::plugins::ark::{GroupIOPattern, GroupReader, GroupWriter};
+    use ark_curve25519::EdwardsProjective as G;
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .add_points(3, "points")
+        .challenge_bytes(16, "chal");
+    let g = G::generator();
+    let points = [g, g + g, g];
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&points).unwrap();
+    let expected: [u8; 16] = merlin.challenge_bytes().unwrap();
+
+    let mut dedup_merlin = io_pattern.to_merlin().with_dedup();
+    dedup_merlin.add_points(&points).unwrap();
+    let challenge: [u8; 16] = dedup_merlin.challenge_bytes().unwrap();
+    // the challenges are unaffected by the codec
+    assert_eq!(challenge, expected);
+    // two fresh points with a 1-byte tag, and a 5-byte back-reference
+    assert_eq!(dedup_merlin.transcript().len(), 2 * 33 + 5);
+
+    let mut arthur = io_pattern.to_arthur(dedup_merlin.transcript()).with_dedup();
+    let decoded: [G; 3] = arthur.next_points().unwrap();
+    assert_eq!(decoded, points);
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), expected);
+
+    // writing the repeated point as fresh gives a second encoding of the same proof, which is rejected
+    let transcript = dedup_merlin.transcript();
+    let malleated = [&transcript[..2 * 33], &transcript[..33]].concat();
+    let mut arthur = io_pattern.to_arthur(&malleated).with_dedup();
+    assert!(GroupReader::<G>::next_points::<3>(&mut arthur).is_err());
+}
+
+#[test]
+fn test_pairing_points() {
+    use crate::plugins::ark::{PairingIOPattern, PairingReader, PairingWriter};
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = PairingIOPattern::<Bls12_381>::add_g2_points(
+        PairingIOPattern::<Bls12_381>::add_g1_points(
+            IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+            1,
+            "commitment",
+        ),
+        1,
+        "key",
+    );
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A48G1:commitment\0A96G2:key"
+    );
+
+    let (g1, g2) = (G1Projective::generator(), G2Projective::generator());
+    let mut merlin = io_pattern.to_merlin();
+    PairingWriter::<Bls12_381>::add_g1_points(&mut merlin, &[g1]).unwrap();
+    PairingWriter::<Bls12_381>::add_g2_points(&mut merlin, &[g2]).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [h1] = PairingReader::<Bls12_381>::next_g1_points(&mut arthur).unwrap();
+    let [h2] = PairingReader::<Bls12_381>::next_g2_points(&mut arthur).unwrap();
+    assert_eq!((g1, g2), (h1, h2));
+}
+
+#[test]
+fn test_pairing_target_group() {
+    use crate::plugins::ark::{PairingIOPattern, PairingReader, PairingWriter};
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ec::pairing::Pairing;
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = PairingIOPattern::<Bls12_381>::add_gt_elements(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        1,
+        "pairing",
+    )
+    .challenge_bytes(16, "chal");
+    // 12 coefficients over the 381-bit base field
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A576GT:pairing\0S16chal"
+    );
+
+    let gt = Bls12_381::pairing(G1Projective::generator(), G2Projective::generator());
+    let mut merlin = io_pattern.to_merlin();
+    PairingWriter::<Bls12_381>::add_gt_elements(&mut merlin, &[gt]).unwrap();
+    let merlin_chal: [u8; 16] = merlin.challenge_bytes().unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [gt_read] = PairingReader::<Bls12_381>::next_gt_elements(&mut arthur).unwrap();
+    assert_eq!(gt, gt_read);
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), merlin_chal);
+
+    // elements of the target field outside of the subgroup of order r are rejected
+    let outside = ark_ec::pairing::PairingOutput::<Bls12_381>(gt.0 + gt.0);
+    let mut merlin = io_pattern.to_merlin();
+    PairingWriter::<Bls12_381>::add_gt_elements(&mut merlin, &[outside]).unwrap();
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert!(matches!(
+        PairingReader::<Bls12_381>::next_gt_elements::<1>(&mut arthur),
+        Err(crate::ProofError::SerializationError)
+    ));
+}
+
+#[test]
+fn test_polynomials() {
+    use crate::plugins::ark::{PolynomialIOPattern, PolynomialReader, PolynomialWriter};
+    use ark_curve25519::Fr;
+    use ark_poly::polynomial::univariate::{DensePolynomial, SparsePolynomial};
+    use ark_poly::{DenseMultilinearExtension, DenseUVPolynomial};
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = PolynomialIOPattern::<Fr>::add_poly(io_pattern, 3, "dense");
+    let io_pattern = PolynomialIOPattern::<Fr>::add_sparse_poly(io_pattern, 2, "sparse");
+    let io_pattern = PolynomialIOPattern::<Fr>::add_multilinear(io_pattern, 2, "mle");
+    // 5, 5, and 5 scalars of 32 bytes each
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A160dense\0A160sparse\0A160mle"
+    );
+
+    let dense = DensePolynomial::from_coefficients_vec(vec![Fr::from(1), Fr::from(2)]);
+    let sparse = SparsePolynomial::from_coefficients_vec(vec![(7, Fr::from(3))]);
+    let mle = DenseMultilinearExtension::from_evaluations_vec(
+        2,
+        (0..4u64).map(Fr::from).collect::<Vec<_>>(),
+    );
+
+    let mut merlin = io_pattern.to_merlin();
+    // the degree bound must be respected
+    assert!(merlin.add_poly(&dense, 0).is_err());
+    // as well as trimming
+    let untrimmed = DensePolynomial {
+        coeffs: vec![Fr::from(1), Fr::from(0)],
+    };
+    assert!(merlin.add_poly(&untrimmed, 3).is_err());
+    merlin.add_poly(&dense, 3).unwrap();
+    merlin.add_sparse_poly(&sparse, 2).unwrap();
+    merlin.add_multilinear(&mle).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_poly(3).unwrap(), dense);
+    assert_eq!(arthur.next_sparse_poly(2).unwrap(), sparse);
+    assert_eq!(arthur.next_multilinear(2).unwrap(), mle);
+
+    // bounds whose size overflows are errors, not panics
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert!(PolynomialReader::<Fr>::next_poly(&mut arthur, usize::MAX).is_err());
+    assert!(PolynomialReader::<Fr>::next_sparse_poly(&mut arthur, usize::MAX).is_err());
+    assert!(PolynomialReader::<Fr>::next_multilinear(&mut arthur, 64).is_err());
+    assert!(std::panic::catch_unwind(|| {
+        PolynomialIOPattern::<Fr>::add_multilinear(IOPattern::<DefaultHash>::new("mle"), 64, "mle")
+    })
+    .is_err());
+}
+
+#[test]
+fn test_scalar_matrix() {
+    use crate::plugins::ark::{MatrixIOPattern, MatrixReader, MatrixWriter};
+    use ark_curve25519::Fr;
+
+    let io_pattern = MatrixIOPattern::<Fr>::add_scalar_matrix(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        2,
+        3,
+        "matrix",
+    );
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A192matrix[2x3]"
+    );
+
+    let matrix = (0..2u64)
+        .map(|i| (0..3u64).map(|j| Fr::from(3 * i + j)).collect())
+        .collect::<Vec<Vec<_>>>();
+    let mut merlin = io_pattern.to_merlin();
+    // the shape must match the declared dimensions
+    assert!(merlin.add_scalar_matrix(3, 2, &matrix).is_err());
+    merlin.add_scalar_matrix(2, 3, &matrix).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    // a matrix without columns has empty rows, and reads nothing
+    let empty: Vec<Vec<Fr>> = arthur.next_scalar_matrix(2, 0).unwrap();
+    assert_eq!(empty, [vec![], vec![]]);
+    let matrix_read: Vec<Vec<Fr>> = arthur.next_scalar_matrix(2, 3).unwrap();
+    assert_eq!(matrix_read, matrix);
+}
+
+#[test]
+fn test_uniform_challenges() {
+    use crate::plugins::ark::{UniformFieldChallenges, UniformFieldIOPattern};
+    use ark_bls12_381::Fr;
+
+    let io_pattern = UniformFieldIOPattern::<Fr>::challenge_scalars_uniform(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        1,
+        "chal",
+    );
+    // 2^255 - p < 2^252, so 43 candidates of 32 bytes are enough
+    assert_eq!(io_pattern.as_bytes(), b"github.com/mmaker/nimue\0S1376chal");
+
+    let mut merlin = io_pattern.to_merlin();
+    let [merlin_chal]: [Fr; 1] = merlin.challenge_scalars_uniform().unwrap();
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [arthur_chal]: [Fr; 1] = arthur.challenge_scalars_uniform().unwrap();
+    assert_eq!(merlin_chal, arthur_chal);
+}
+
+#[test]
+fn test_batch_normalized_points() {
+    use crate::plugins::ark::{GroupIOPattern, GroupReader, GroupWriter};
+    use ark_bls12_381::G1Projective as G;
+    use ark_ec::{AdditiveGroup, PrimeGroup};
+    use ark_serialize::CanonicalSerialize;
+
+    let g = G::generator();
+    let points = [g, G::ZERO, g.double(), g * ark_bls12_381::Fr::from(5)];
+    let io_pattern =
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").add_points(points.len(), "points");
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&points).unwrap();
+
+    // the batched conversion to affine does not change the encoding of each point
+    let mut expected = Vec::new();
+    for point in &points {
+        point.serialize_compressed(&mut expected).unwrap();
+    }
+    assert_eq!(merlin.transcript(), expected);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let decoded: [G; 4] = arthur.next_points().unwrap();
+    assert_eq!(decoded, points);
+}
+
+#[test]
+fn test_batched_point_validation() {
+    use crate::plugins::ark::{BatchedGroupReader, GroupIOPattern, GroupWriter};
+    use ark_bls12_381::{Fq, G1Affine, G1Projective as G};
+    use ark_ec::PrimeGroup;
+    use ark_serialize::CanonicalSerialize;
+
+    let io_pattern =
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").add_points(3, "points");
+    let g = G::generator();
+    let points = [g, g + g, g + g + g];
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&points).unwrap();
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let mut decoded = [G::default(); 3];
+    arthur
+        .fill_next_points_batched(&mut decoded, &mut rand::rngs::OsRng)
+        .unwrap();
+    assert_eq!(decoded, points);
+
+    // a point on the curve, but outside of the prime-order subgroup
+    let invalid = (0u64..)
+        .filter_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), true))
+        .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap();
+    let mut transcript = merlin.transcript()[..2 * 48].to_vec();
+    invalid.serialize_compressed(&mut transcript).unwrap();
+    let mut arthur = io_pattern.to_arthur(&transcript);
+    assert!(arthur
+        .fill_next_points_batched(&mut decoded, &mut rand::rngs::OsRng)
+        .is_err());
+}
+
+#[test]
+fn test_rfc9380_hash_to_field() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use crate::HashToField;
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+            .with_hash_to_field(HashToField::Rfc9380),
+        1,
+        "chal",
+    );
+    // L = ceil((255 + 128) / 8)
+    assert_eq!(io_pattern.as_bytes(), b"github.com/mmaker/nimue\0S48chal");
+
+    let mut merlin = io_pattern.to_merlin();
+    let [c]: [Fr; 1] = merlin.challenge_scalars().unwrap();
+
+    // the same bytes, interpreted as a big-endian integer
+    let byte_pattern =
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").challenge_bytes(48, "chal");
+    let bytes: [u8; 48] = byte_pattern.to_merlin().challenge_bytes().unwrap();
+    assert_eq!(c, Fr::from_be_bytes_mod_order(&bytes));
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [c_arthur]: [Fr; 1] = arthur.challenge_scalars().unwrap();
+    assert_eq!(c, c_arthur);
+}
+
+#[test]
+fn test_challenge_endianness() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use crate::Endianness;
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+            .with_challenge_endianness(Endianness::Little),
+        1,
+        "chal",
+    );
+    // the byte order is recorded after the domain separator
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\x1fendianness=little\0S48chal"
+    );
+    let [c]: [Fr; 1] = io_pattern.to_merlin().challenge_scalars().unwrap();
+
+    // the same bytes, interpreted as a little-endian integer
+    let byte_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .with_challenge_endianness(Endianness::Little)
+        .challenge_bytes(48, "chal");
+    let bytes: [u8; 48] = byte_pattern.to_merlin().challenge_bytes().unwrap();
+    assert_eq!(c, Fr::from_le_bytes_mod_order(&bytes));
+    assert_ne!(c, Fr::from_be_bytes_mod_order(&bytes));
+
+    let mut arthur = io_pattern.to_arthur(&[]);
+    let [c_arthur]: [Fr; 1] = arthur.challenge_scalars().unwrap();
+    assert_eq!(c, c_arthur);
+}
+
+#[test]
+fn test_challenge_powers() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use ark_bls12_381::Fr;
+    use ark_ff::Field;
+
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        1,
+        "batching",
+    );
+    let powers: Vec<Fr> = io_pattern.to_merlin().challenge_powers_vec(4).unwrap();
+    let [c]: [Fr; 1] = io_pattern.to_merlin().challenge_scalars().unwrap();
+    assert_eq!(powers, vec![Fr::ONE, c, c.square(), c.pow([3])]);
+
+    let mut arthur = io_pattern.to_arthur(&[]);
+    let lazy = FieldChallenges::<Fr>::challenge_powers_iter(&mut arthur).unwrap();
+    assert!(lazy.take(4).eq(powers));
+}
+
+#[test]
+fn test_short_challenges() {
+    use crate::plugins::ark::{ShortFieldChallenges, ShortFieldIOPattern};
+    use ark_bls12_381::Fr;
+    use ark_ff::{BigInteger, PrimeField};
+
+    let io_pattern = ShortFieldIOPattern::<Fr>::challenge_scalars_short(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        2,
+        "chal",
+    );
+    assert_eq!(io_pattern.as_bytes(), b"github.com/mmaker/nimue\0S32chal");
+
+    let mut merlin = io_pattern.to_merlin();
+    let challenges: [Fr; 2] = merlin.challenge_scalars_short().unwrap();
+    assert!(challenges.iter().all(|c| c.into_bigint().num_bits() <= 128));
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let arthur_challenges: [Fr; 2] = arthur.challenge_scalars_short().unwrap();
+    assert_eq!(arthur_challenges, challenges);
+}
+
+#[test]
+fn test_kzg_batch_opening() {
+    use crate::plugins::ark::kzg::{KzgIOPattern, KzgReader, KzgWriter};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = KzgIOPattern::<Bls12_381>::add_kzg_batch_opening(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        2,
+        "kzg",
+    );
+    assert_eq!(
+        io_pattern.as_bytes(),
+        &b"github.com/mmaker/nimue\0A96kzg:commitments\0S48kzg:point\0A64kzg:evaluations\0S48kzg:gamma\0A48kzg:proof"[..]
+    );
+
+    let g = G1::generator();
+    let commitments = [g, g + g];
+    let evaluations = [Fr::from(3), Fr::from(4)];
+    let mut merlin = io_pattern.to_merlin();
+    let point = KzgWriter::<Bls12_381>::add_kzg_commitments(&mut merlin, &commitments).unwrap();
+    let gamma =
+        KzgWriter::<Bls12_381>::add_kzg_batch_evaluations(&mut merlin, &evaluations).unwrap();
+    KzgWriter::<Bls12_381>::add_kzg_proof(&mut merlin, g).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let opening = KzgReader::<Bls12_381>::next_kzg_batch_opening(&mut arthur, 2).unwrap();
+    assert_eq!((opening.point, opening.gamma), (point, gamma));
+    assert_eq!(
+        opening.combine(),
+        (g + (g + g) * gamma, Fr::from(3) + Fr::from(4) * gamma)
+    );
+}
+
+#[test]
+fn test_ipa_transcript() {
+    use crate::plugins::ark::ipa::{IpaIOPattern, IpaReader, IpaWriter};
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = IpaIOPattern::<G>::add_ipa(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        4,
+        "ipa",
+    );
+    assert_eq!(
+        io_pattern.as_bytes(),
+        &b"github.com/mmaker/nimue\0A64ipa:round\0S48ipa:challenge\0A64ipa:round\0S48ipa:challenge\0A64ipa:last"[..]
+    );
+
+    let g = G::generator();
+    let mut merlin = io_pattern.to_merlin();
+    let x0 = merlin.add_ipa_round(g, g + g).unwrap();
+    let x1 = merlin.add_ipa_round(g + g, g).unwrap();
+    merlin.add_ipa_last(Fr::from(1), Fr::from(2)).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let transcript = IpaReader::<G>::next_ipa(&mut arthur, 4).unwrap();
+    assert_eq!(transcript.rounds, vec![(g, g + g), (g + g, g)]);
+    assert_eq!(transcript.challenges, vec![x0, x1]);
+    assert_eq!(transcript.last, (Fr::from(1), Fr::from(2)));
+}
+
+#[test]
+fn test_sumcheck() {
+    use crate::plugins::ark::sumcheck::{SumcheckIOPattern, SumcheckReader, SumcheckWriter};
+    use crate::ProofError;
+    use ark_curve25519::Fr;
+
+    // g(x_1, x_2) = 1 + 2 x_1 + 3 x_1 x_2, of degree 1 in each variable
+    let g = |x1: Fr, x2: Fr| Fr::from(1) + Fr::from(2) * x1 + Fr::from(3) * x1 * x2;
+    let (zero, one) = (Fr::from(0), Fr::from(1));
+    let sum = g(zero, zero) + g(zero, one) + g(one, zero) + g(one, one);
+
+    let io_pattern = SumcheckIOPattern::<Fr>::add_sumcheck(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        2,
+        1,
+        "sumcheck",
+    );
+    let mut merlin = io_pattern.to_merlin();
+    // g_1(X) = g(X, 0) + g(X, 1) = 2 + 7 X
+    let r1 = merlin
+        .add_sumcheck_round(&[Fr::from(2), Fr::from(7)])
+        .unwrap();
+    // g_2(X) = g(r_1, X) = 1 + 2 r_1 + 3 r_1 X
+    let r2 = merlin
+        .add_sumcheck_round(&[Fr::from(1) + Fr::from(2) * r1, Fr::from(3) * r1])
+        .unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let claim = arthur.next_sumcheck(2, 1, sum).unwrap();
+    assert_eq!(claim.point, vec![r1, r2]);
+    assert_eq!(claim.evaluation, g(r1, r2));
+
+    // a wrong claimed sum is rejected
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert!(matches!(
+        arthur.next_sumcheck(2, 1, sum + one),
+        Err(ProofError::InvalidProof)
+    ));
+}
+
+#[test]
+fn test_multilinear_point() {
+    use crate::plugins::ark::sumcheck::{
+        MultilinearChallenges, MultilinearIOPattern, MultilinearPoint,
+    };
+    use crate::plugins::ark::FieldChallenges;
+    use ark_curve25519::Fr;
+
+    let io_pattern = MultilinearIOPattern::<Fr>::challenge_multilinear_point(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        2,
+        "point",
+    );
+    let point: MultilinearPoint<Fr> = io_pattern
+        .to_merlin()
+        .challenge_multilinear_point(2)
+        .unwrap();
+    let [r1, r2]: [Fr; 2] = io_pattern.to_arthur(&[]).challenge_scalars().unwrap();
+    assert_eq!(point.coordinates(), [r1, r2]);
+
+    // the weights are eq(r, x) on the hypercube, indexed little-endian
+    let (zero, one) = (Fr::from(0), Fr::from(1));
+    let vertices = [[zero, zero], [one, zero], [zero, one], [one, one]];
+    let weights = point.eq_weights();
+    assert_eq!(weights.len(), 4);
+    for (weight, x) in weights.iter().zip(&vertices) {
+        assert_eq!(Some(*weight), point.eq(x));
+    }
+    assert_eq!(weights.iter().sum::<Fr>(), one);
+    assert_eq!(point.eq(&[r1]), None);
+
+    // the inner product with the weights evaluates the multilinear extension
+    // of g(x_1, x_2) = 1 + 2 x_1 + 3 x_1 x_2
+    let g = |x1: Fr, x2: Fr| Fr::from(1) + Fr::from(2) * x1 + Fr::from(3) * x1 * x2;
+    let evaluation: Fr = vertices
+        .iter()
+        .zip(weights)
+        .map(|([x1, x2], weight)| g(*x1, *x2) * weight)
+        .sum();
+    assert_eq!(evaluation, g(r1, r2));
+}
+
+#[test]
+fn test_gkr_layer() {
+    use crate::plugins::ark::gkr::{GkrIOPattern, GkrReader, GkrWriter};
+    use crate::plugins::ark::sumcheck::SumcheckWriter;
+    use crate::plugins::ark::FieldChallenges;
+    use ark_curve25519::Fr;
+
+    let io_pattern = GkrIOPattern::<Fr>::add_gkr(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        &[1, 1],
+        "gkr",
+    );
+    let zero = Fr::from(0);
+
+    // a (trivial) proof that the output layer evaluates to zero
+    let mut merlin = io_pattern.to_merlin();
+    let [r0]: [Fr; 1] = merlin.challenge_scalars().unwrap();
+    let b = merlin.add_sumcheck_round(&[zero; 3]).unwrap();
+    let c = merlin.add_sumcheck_round(&[zero; 3]).unwrap();
+    let (alpha, beta) = merlin.add_gkr_claims(Fr::from(5), Fr::from(6)).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [r0_arthur]: [Fr; 1] = arthur.challenge_scalars().unwrap();
+    assert_eq!(r0, r0_arthur);
+    let layer = arthur.next_gkr_layer(1, zero).unwrap();
+    assert_eq!(layer.points(), (&[b][..], &[c][..]));
+    assert_eq!(layer.next_claim(), alpha * Fr::from(5) + beta * Fr::from(6));
+    assert!(layer.check_claims(Fr::from(5), Fr::from(6)).is_ok());
+    assert!(layer.check_claims(Fr::from(6), Fr::from(5)).is_err());
+}
+
+#[test]
+fn test_serializable() {
+    use crate::plugins::ark::{SerializableIOPattern, SerializableReader, SerializableWriter};
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+
+    // a composite structure, e.g. a commitment key together with some public scalars
+    let value = (G1Projective::generator(), vec![Fr::from(1), Fr::from(2)]);
+    let io_pattern =
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").add_serializable(&value, "value");
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_serializable(&value).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let read: (G1Projective, Vec<Fr>) = arthur.next_deserializable().unwrap();
+    assert_eq!(read, value);
+
+    // a longer value does not fit the IO pattern
+    let longer = (value.0, vec![Fr::from(1), Fr::from(2), Fr::from(3)]);
+    let mut merlin = io_pattern.to_merlin();
+    assert!(merlin.add_serializable(&longer).is_err());
+}
+
+#[test]
+fn test_affine_points() {
+    use crate::plugins::ark::{AffineGroupWriter, GroupIOPattern, GroupReader, GroupWriter};
+    use ark_bls12_381::G1Projective as G;
+    use ark_ec::{AdditiveGroup, CurveGroup, PrimeGroup};
+
+    let g = G::generator();
+    let points = [g, G::ZERO, g.double()];
+    let affine_points = G::normalize_batch(&points);
+    let io_pattern =
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").add_points(points.len(), "points");
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&points).unwrap();
+    let mut merlin_affine = io_pattern.to_merlin();
+    AffineGroupWriter::<G>::add_points_affine(&mut merlin_affine, &affine_points).unwrap();
+    assert_eq!(merlin.transcript(), merlin_affine.transcript());
+
+    let mut arthur = io_pattern.to_arthur(merlin_affine.transcript());
+    let decoded: [G; 3] = arthur.next_points().unwrap();
+    assert_eq!(decoded, points);
+}
+
+#[test]
+fn test_pairing_batch() {
+    use crate::plugins::ark::{PairingBatch, PairingBatchIOPattern};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1, G2Projective as G2};
+    use ark_ec::PrimeGroup;
+
+    let io_pattern = PairingBatchIOPattern::<Bls12_381>::challenge_pairing_batch(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        "gamma",
+    );
+    let (g1, g2) = (G1::generator(), G2::generator());
+    let a = Fr::from(42);
+
+    // e(a g1, g2) e(-g1, a g2) = 1 and e(g1, g2) e(g1, -g2) = 1
+    let mut batch = PairingBatch::<Bls12_381>::new();
+    batch.add_equation(&[g1 * a, -g1], &[g2, g2 * a]);
+    batch.add_equation(&[g1, g1], &[g2, -g2]);
+    let mut invalid = batch.clone();
+
+    let mut arthur = io_pattern.to_arthur(b"");
+    assert!(batch.check(&mut arthur).is_ok());
+
+    // e(g1, g2) = 1 does not hold
+    invalid.add_equation(&[g1], &[g2]);
+    let mut arthur = io_pattern.to_arthur(b"");
+    assert!(invalid.check(&mut arthur).is_err());
+}
+
+#[test]
+fn test_statistical_hash_to_field() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use crate::HashToField;
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+
+    // the reduction from 47 bytes leaves 376 - 255 bits of statistical security
+    assert_eq!(
+        HashToField::Reduce.statistical_security(Fr::MODULUS_BIT_SIZE),
+        121
+    );
+    assert_eq!(
+        HashToField::Statistical(128).statistical_security(Fr::MODULUS_BIT_SIZE),
+        129
+    );
+    // the default leaves at least 128 bits
+    assert_eq!(
+        HashToField::default().statistical_security(Fr::MODULUS_BIT_SIZE),
+        129
+    );
+
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+            .with_hash_to_field(HashToField::Statistical(64)),
+        2,
+        "chal",
+    );
+    // L = ceil((255 + 64) / 8), for each scalar
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\x1fhash-to-field=statistical-64\0S80chal"
+    );
+
+    let mut merlin = io_pattern.to_merlin();
+    let challenges: [Fr; 2] = merlin.challenge_scalars().unwrap();
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let arthur_challenges: [Fr; 2] = arthur.challenge_scalars().unwrap();
+    assert_eq!(challenges, arthur_challenges);
+}
+
+#[test]
+fn test_schnorr_signatures() {
+    use super::signatures::{batch_verify, keygen, sign, verify, SchnorrIOPattern};
+    use ark_curve25519::EdwardsProjective as G;
+
+    let mut rng = ark_std::test_rng();
+    let io = SchnorrIOPattern::<G>::add_schnorr_signature(IOPattern::<DefaultHash>::new(
+        "github.com/mmaker/nimue",
+    ));
+    let keys = (0..3).map(|_| keygen::<G>(&mut rng)).collect::<Vec<_>>();
+    let messages: [&[u8]; 3] = [b"", b"hello", b"a somewhat longer message"];
+    let signatures = keys
+        .iter()
+        .zip(messages)
+        .map(|(&(sk, _), message)| sign::<G, DefaultHash>(&io, sk, message).unwrap())
+        .collect::<Vec<_>>();
+
+    // signatures are a point and a scalar
+    assert!(signatures.iter().all(|signature| signature.len() == 64));
+    for ((&(_, pk), message), signature) in keys.iter().zip(messages).zip(&signatures) {
+        assert!(verify(&io, pk, message, signature).is_ok());
+    }
+    // the signature does not verify under another key, or for another message
+    assert!(verify(&io, keys[1].1, messages[0], &signatures[0]).is_err());
+    assert!(verify(&io, keys[0].1, messages[1], &signatures[0]).is_err());
+
+    let mut batch = keys
+        .iter()
+        .zip(messages)
+        .zip(&signatures)
+        .map(|((&(_, pk), message), signature)| (pk, message, signature.as_slice()))
+        .collect::<Vec<_>>();
+    assert!(batch_verify(&io, &batch, &mut rng).is_ok());
+    batch.swap(0, 1);
+    batch[0].1 = messages[0];
+    assert!(batch_verify(&io, &batch, &mut rng).is_err());
+}
+
+#[cfg(feature = "eip2537")]
+#[test]
+fn test_eip2537_encoding() {
+    use super::eip2537::{decode_g1, encode_g1, Eip2537IOPattern, Eip2537Reader, Eip2537Writer};
+    use ark_bls12_381::{Fr, G1Affine, G1Projective as G1, G2Projective as G2};
+    use ark_ec::{AffineRepr, PrimeGroup};
+
+    // the generator of G1, as in the EIP-2537 test vectors
+    let generator = encode_g1(&G1Affine::generator());
+    assert_eq!(hex::encode(&generator[..64]), format!("{}{}", "00".repeat(16), "17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"));
+    assert_eq!(encode_g1(&G1Affine::identity()), [0u8; 128]);
+
+    let io = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .add_eip2537_g1_points(2, "g1")
+        .add_eip2537_g2_points(1, "g2")
+        .add_eip2537_scalars(1, "scalar");
+    let points = [G1::generator(), G1::default()];
+    let mut merlin = io.to_merlin();
+    merlin.add_eip2537_g1_points(&points).unwrap();
+    merlin.add_eip2537_g2_points(&[G2::generator()]).unwrap();
+    merlin.add_eip2537_scalars(&[-Fr::from(1)]).unwrap();
+
+    let mut arthur = io.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_eip2537_g1_points().unwrap(), points);
+    assert_eq!(arthur.next_eip2537_g2_points().unwrap(), [G2::generator()]);
+    assert_eq!(arthur.next_eip2537_scalars().unwrap(), [-Fr::from(1)]);
+
+    // non-zero padding, and points off the curve, are rejected
+    let mut padded = generator;
+    padded[0] = 1;
+    assert!(decode_g1(&padded).is_err());
+    let mut off_curve = generator;
+    off_curve[127] ^= 1;
+    assert!(decode_g1(&off_curve).is_err());
+}
+
+#[cfg(feature = "evm")]
+#[test]
+fn test_evm_transcript() {
+    use super::evm::{solidity_library, EvmIOPattern, EvmTranscript};
+    use ark_bn254::{Fr, G1Projective as G1};
+    use ark_ec::PrimeGroup;
+    use ark_ff::MontFp;
+
+    let io = IOPattern::<DefaultHash>::new("evm");
+    let io = EvmIOPattern::<G1>::add_evm_points(io, 1, "commitment");
+    let io = EvmIOPattern::<G1>::challenge_evm_scalars(io, 2, "challenge");
+    let io = EvmIOPattern::<G1>::add_evm_scalars(io, 1, "response");
+    let io = EvmIOPattern::<G1>::challenge_evm_scalars(io, 1, "final");
+    assert_eq!(
+        io.as_bytes(),
+        b"evm\0A64commitment\0S64challenge\0A32response\0S32final"
+    );
+
+    // the prover and the verifier run the same transcript on the same elements
+    let transcript = |points: &[G1], scalars: &[Fr]| {
+        let mut transcript = EvmTranscript::new(&io);
+        transcript.add_points(points).unwrap();
+        let challenges: [Fr; 2] = transcript.challenge_scalars().unwrap();
+        transcript.add_scalars(scalars).unwrap();
+        let [last]: [Fr; 1] = transcript.challenge_scalars().unwrap();
+        (challenges, last, transcript.state())
+    };
+    let (challenges, last, state) = transcript(&[G1::generator()], &[Fr::from(42)]);
+    assert_eq!(
+        transcript(&[G1::generator()], &[Fr::from(42)]),
+        (challenges, last, state)
+    );
+    assert_ne!(transcript(&[G1::generator()], &[Fr::from(43)]).1, last);
+
+    // keccak256 over the 32-byte big-endian words (1, 2) of the generator, and then 42
+    let expected: [Fr; 3] = [
+        MontFp!("0x05d6c1b077f25a4e0934da33f52f5fda29a44a88107f42441ed7eb14fd94d152"),
+        MontFp!("0x0c7cfaa19952c85a4efbeaf8de63c5f489ed906e434323be16ad54cae0975733"),
+        MontFp!("0x0765281117f11f66b996c1405a78e42ad1167bd5843f63cc0461af0a6b5b8773"),
+    ];
+    assert_eq!([challenges[0], challenges[1], last], expected);
+    assert_eq!(
+        hex::encode(state),
+        "37c97683f922bf9071e706f6dbfa3c87f94a641dfdf8d45d4843a49e5b5b8774"
+    );
+
+    // operations out of order are rejected
+    let mut transcript = EvmTranscript::new(&io);
+    assert!(transcript.challenge_scalars::<Fr, 1>().is_err());
+    assert!(transcript.add_points(&[G1::generator()]).is_err());
+
+    let solidity = solidity_library::<Fr, _>(&io, "Transcript").unwrap();
+    assert!(solidity.contains(
+        "bytes32 internal constant INITIAL_STATE = 0xb27ed88e9778bcbf51e7061697f12f5b0daec4d9773c06cdbe30523600dd7f55;"
+    ));
+    assert!(solidity.contains(
+        "uint256 internal constant MODULUS = 21888242871839275222246405745257275088548364400416034343698204186575808495617;"
+    ));
+    assert!(solidity.contains(
+        "function squeeze_0_challenge(bytes32 state, uint256[2] memory absorbed)\n        internal pure returns (bytes32 next, uint256[2] memory challenges)"
+    ));
+    assert!(solidity.contains(
+        "function squeeze_1_final(bytes32 state, uint256[1] memory absorbed)\n        internal pure returns (bytes32 next, uint256[1] memory challenges)"
+    ));
+
+    let unaligned = IOPattern::<DefaultHash>::new("evm").absorb(31, "unaligned");
+    assert!(solidity_library::<Fr, _>(&unaligned, "Transcript").is_err());
+}
+
+#[cfg(feature = "gnark")]
+#[test]
+fn test_gnark_transcript() {
+    use super::gnark::GnarkSha256;
+    use ark_bn254::{Fr, G1Projective as G1};
+    use ark_ec::PrimeGroup;
+    use ark_ff::MontFp;
+
+    let mut transcript = GnarkSha256::new(&["gamma", "beta", "alpha"]);
+    transcript.bind_points("gamma", &[G1::generator()]).unwrap();
+    transcript.bind_scalars("gamma", &[Fr::from(5)]).unwrap();
+    transcript.bind("beta", b"raw").unwrap();
+    // binding to, or computing, a challenge that was not declared is an error
+    assert!(transcript.bind("delta", b"raw").is_err());
+    assert!(transcript.compute_challenge("delta").is_err());
+    // challenges are computed in the order they were declared
+    assert!(transcript.compute_challenge("beta").is_err());
+
+    // as gnark's `ComputeChallenge`, with SHA-256:
+    // gamma = H("gamma" || 1 || 2 || 5), beta = H("beta" || gamma || "raw"), alpha = H("alpha" || beta),
+    // with 32-byte big-endian elements
+    let gamma = transcript.compute_challenge("gamma").unwrap();
+    let beta = transcript.compute_challenge("beta").unwrap();
+    let alpha = transcript.compute_challenge("alpha").unwrap();
+    assert_eq!(
+        hex::encode(&gamma),
+        "7b809cfb536ff917af3d3134a2780f23a7c8519bd4c69fe6a6f63e9dce99480d"
+    );
+    assert_eq!(
+        hex::encode(&beta),
+        "0b1e06a81397860eae48a443200f54070547abf0a5dc86a9398a6d9b7d93892e"
+    );
+    assert_eq!(
+        hex::encode(&alpha),
+        "be5076ef960a5d290f64fe2dc4df6935963bcc7f79d6637794650903714af20e"
+    );
+    // field challenges are reduced modulo the characteristic
+    let expected: [Fr; 3] = [
+        MontFp!("0x1ab80015910cb8c43e9ca5c79f755e695760810ae153bec41f325375ee99480b"),
+        MontFp!("0x0b1e06a81397860eae48a443200f54070547abf0a5dc86a9398a6d9b7d93892e"),
+        MontFp!("0x2d238b96f2757cabe6742d0a405b601e1da013a60caa11c3c8bf2847a14af20b"),
+    ];
+    let challenges: [Fr; 3] = [
+        transcript.challenge_scalar("gamma").unwrap(),
+        transcript.challenge_scalar("beta").unwrap(),
+        transcript.challenge_scalar("alpha").unwrap(),
+    ];
+    assert_eq!(challenges, expected);
+    // computed challenges cannot be bound to anymore
+    assert!(transcript.bind("alpha", b"late").is_err());
+
+    // the bindings are hashed in the order they were added
+    let mut swapped = GnarkSha256::new(&["gamma"]);
+    swapped.bind_scalars("gamma", &[Fr::from(5)]).unwrap();
+    swapped.bind_points("gamma", &[G1::generator()]).unwrap();
+    assert_eq!(
+        hex::encode(swapped.compute_challenge("gamma").unwrap()),
+        "31784b94dc96a37f6538bc524cb2320a5ef601206fbcb5c52946f9b70e35f850"
+    );
+}
+
+#[cfg(feature = "barretenberg")]
+#[test]
+fn test_barretenberg_transcript() {
+    use super::barretenberg::BarretenbergTranscript;
+    use ark_bn254::{Fr, G1Projective as G1};
+    use ark_ec::PrimeGroup;
+    use ark_ff::{MontFp, Zero};
+
+    // the first round of an `UltraKeccakFlavor` transcript, with 3 challenges:
+    // h_0 = keccak256(circuit_size || W_1) and h_1 = keccak256(h_0), reduced modulo r;
+    // eta and eta_two are the lower and upper halves of h_0, and eta_three the lower half of h_1 only
+    let mut prover = BarretenbergTranscript::new();
+    prover
+        .send_to_verifier("circuit_size", &[Fr::from(1u64 << 10)])
+        .unwrap();
+    prover
+        .send_points_to_verifier("W_1", &[G1::generator()])
+        .unwrap();
+    let etas: [Fr; 3] = prover
+        .get_challenges(&["eta", "eta_two", "eta_three"])
+        .unwrap();
+    let expected: [Fr; 3] = [
+        MontFp!("0x14d8f67ea6a3d2fcec50eab785da5828"),
+        MontFp!("0x06c1c24a58ed10cb4ea4afa807110e63"),
+        MontFp!("0x210891246168be5453a71a63f9b75da9"),
+    ];
+    assert_eq!(etas, expected);
+    assert_eq!(
+        hex::encode(prover.proof_data()),
+        concat!(
+            "0000000000000000000000000000000000000000000000000000000000000400",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+        )
+    );
+    // the next round chains on the whole of h_1, whose upper half was not used
+    prover.send_to_verifier("z", &[Fr::from(7)]).unwrap();
+    let [beta]: [Fr; 1] = prover.get_challenges(&["beta"]).unwrap();
+    assert_eq!(beta, MontFp!("0x5046535822988b7d963dcbb83812f59e"));
+
+    // the verifier reads the same elements and derives the same challenges
+    let mut verifier = BarretenbergTranscript::from_proof(prover.proof_data());
+    let [circuit_size]: [Fr; 1] = verifier.receive_from_prover("circuit_size").unwrap();
+    assert_eq!(circuit_size, Fr::from(1u64 << 10));
+    let [w_1]: [G1; 1] = verifier.receive_points_from_prover("W_1").unwrap();
+    assert_eq!(w_1, G1::generator());
+    let verifier_etas: [Fr; 3] = verifier
+        .get_challenges(&["eta", "eta_two", "eta_three"])
+        .unwrap();
+    assert_eq!(verifier_etas, etas);
+    let [_z]: [Fr; 1] = verifier.receive_from_prover("z").unwrap();
+    assert_eq!(verifier.get_challenges(&["beta"]).unwrap(), [beta]);
+    assert_eq!(prover.manifest(), verifier.manifest());
+    assert!(verifier.receive_from_prover::<Fr, 1>("extra").is_err());
+
+    // the point at infinity is sent as (0, 0), and read back
+    let mut prover = BarretenbergTranscript::new();
+    prover
+        .send_points_to_verifier("W_1", &[G1::zero()])
+        .unwrap();
+    assert_eq!(prover.proof_data(), [0u8; 64]);
+    let mut verifier = BarretenbergTranscript::from_proof(prover.proof_data());
+    let [infinity]: [G1; 1] = verifier.receive_points_from_prover("W_1").unwrap();
+    assert!(infinity.is_zero());
+    // points not on the curve, and non-canonical coordinates, are rejected
+    let mut off_curve = [0u8; 64];
+    off_curve[31] = 1;
+    off_curve[63] = 3;
+    for proof_data in [&off_curve, &[0xff; 64]] {
+        let mut verifier = BarretenbergTranscript::from_proof(proof_data);
+        let result: ProofResult<[G1; 1]> = verifier.receive_points_from_prover("W_1");
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn test_sigma_composition() {
+    use super::sigma::Schnorr;
+    use crate::protocols::sigma::{prove, verify, And, Or, OrWitness, SigmaIOPattern};
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::PrimeGroup;
+    use ark_std::UniformRand;
+
+    let mut rng = ark_std::test_rng();
+    let (x0, x1) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+    let left = Schnorr::new(G::generator(), G::generator() * x0);
+    let right = Schnorr::new(G::generator(), G::generator() * x1);
+
+    let and = And::new(left, right).unwrap();
+    let io =
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").add_sigma_protocol(&and, "and");
+    let mut merlin = io.to_merlin();
+    prove(&and, &mut merlin, &(x0, x1)).unwrap();
+    assert!(verify(&and, &mut io.to_arthur(merlin.transcript())).is_ok());
+    let mut merlin = io.to_merlin();
+    prove(&and, &mut merlin, &(x0, x0)).unwrap();
+    assert!(verify(&and, &mut io.to_arthur(merlin.transcript())).is_err());
+
+    let or = Or::new(left, right).unwrap();
+    let io = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").add_sigma_protocol(&or, "or");
+    for witness in [OrWitness::Left(x0), OrWitness::Right(x1)] {
+        let mut merlin = io.to_merlin();
+        prove(&or, &mut merlin, &witness).unwrap();
+        assert!(verify(&or, &mut io.to_arthur(merlin.transcript())).is_ok());
+    }
+    // a wrong witness gives a rejected proof
+    let mut merlin = io.to_merlin();
+    prove(&or, &mut merlin, &OrWitness::Left(x1)).unwrap();
+    assert!(verify(&or, &mut io.to_arthur(merlin.transcript())).is_err());
+
+    // compositions nest
+    let nested = Or::new(and, or).unwrap();
+    let io = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .add_sigma_protocol(&nested, "nested");
+    let mut merlin = io.to_merlin();
+    prove(
+        &nested,
+        &mut merlin,
+        &OrWitness::Right(OrWitness::Right(x1)),
+    )
+    .unwrap();
+    assert!(verify(&nested, &mut io.to_arthur(merlin.transcript())).is_ok());
+}
+
+#[test]
+fn test_musig_session() {
+    use super::musig::{nonce_commitments, KeyAggregation, SigningSession};
+    use super::signatures::{keygen, verify, SchnorrIOPattern};
+    use ark_curve25519::EdwardsProjective as G;
+
+    let mut rng = ark_std::test_rng();
+    let io = SchnorrIOPattern::<G>::add_schnorr_signature(IOPattern::<DefaultHash>::new(
+        "github.com/mmaker/nimue",
+    ));
+    let mut signers = (0..4).map(|_| keygen::<G>(&mut rng)).collect::<Vec<_>>();
+    let keys = signers.iter().map(|(_, pk)| *pk).collect::<Vec<_>>();
+    let aggregation = KeyAggregation::<G>::new::<DefaultHash>(&keys).unwrap();
+    // the aggregation does not depend on the order of the keys
+    let reversed = keys.iter().rev().copied().collect::<Vec<_>>();
+    let other = KeyAggregation::<G>::new::<DefaultHash>(&reversed).unwrap();
+    assert_eq!(aggregation.aggregate_key, other.aggregate_key);
+    assert!(KeyAggregation::<G>::new::<DefaultHash>(&[keys[0], keys[0]]).is_err());
+
+    signers.sort_by_key(|(_, pk)| aggregation.position(pk).unwrap());
+    let nonces = (0..signers.len())
+        .map(|_| nonce_commitments::<G>(&mut rng))
+        .collect::<Vec<_>>();
+    let commitments = nonces.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+    let session = SigningSession::new(&io, &aggregation, &commitments, b"message").unwrap();
+    let mut partials = signers
+        .iter()
+        .zip(&nonces)
+        .enumerate()
+        .map(|(i, ((sk, _), (secret, _)))| session.sign(&aggregation, i, *sk, *secret))
+        .collect::<Vec<_>>();
+    for (i, partial) in partials.iter().enumerate() {
+        assert!(session.verify_partial(&aggregation, i, *partial).is_ok());
+    }
+    let signature = session.aggregate(&io, &aggregation, &partials).unwrap();
+    assert!(verify(&io, aggregation.aggregate_key, b"message", &signature).is_ok());
+
+    // a misbehaving signer is identified
+    partials[2] += partials[1];
+    assert!(session
+        .verify_partial(&aggregation, 2, partials[2])
+        .is_err());
+    let signature = session.aggregate(&io, &aggregation, &partials).unwrap();
+    assert!(verify(&io, aggregation.aggregate_key, b"message", &signature).is_err());
+}
+
+#[test]
+fn test_pcs_transcript() {
+    use crate::plugins::ark::pcs::{
+        Ipa, PcsChallenges, PcsIOPattern, PcsReader, PcsWriter, PolynomialCommitmentScheme,
+    };
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::PrimeGroup;
+
+    fn io_pattern<S>(scheme: &S, count: usize) -> IOPattern
+    where
+        S: PolynomialCommitmentScheme,
+        IOPattern: PcsIOPattern<S>,
+    {
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+            .add_pcs_commitments(scheme, count, "pcs")
+            .challenge_pcs_point(scheme, "pcs")
+            .add_pcs_evaluations(scheme, count, "pcs")
+            .add_pcs_opening(scheme, "pcs")
+    }
+
+    let scheme = Ipa::<G>::new(4);
+    let io_pattern = io_pattern(&scheme, 2);
+    assert_eq!(
+        io_pattern.as_bytes(),
+        &b"github.com/mmaker/nimue\0A64pcs:commitments\0S48pcs:point\0A64pcs:evaluations\0S48pcs:gamma\0A64pcs:round\0S48pcs:challenge\0A64pcs:round\0S48pcs:challenge\0A64pcs:last"[..]
+    );
+
+    let g = G::generator();
+    let evaluations = [Fr::from(3), Fr::from(4)];
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_pcs_commitments(&scheme, &[g, g + g]).unwrap();
+    let point = merlin.challenge_pcs_point(&scheme).unwrap();
+    merlin.add_pcs_evaluations(&scheme, &evaluations).unwrap();
+    let gamma = merlin.challenge_pcs_batching(&scheme).unwrap();
+    let x0 = merlin.add_pcs_round(&scheme, (g, g + g)).unwrap();
+    let x1 = merlin.add_pcs_round(&scheme, (g + g, g)).unwrap();
+    merlin
+        .add_pcs_final(&scheme, (Fr::from(1), Fr::from(2)))
+        .unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert_eq!(
+        arthur.next_pcs_commitments(&scheme, 2).unwrap(),
+        vec![g, g + g]
+    );
+    assert_eq!(arthur.challenge_pcs_point(&scheme).unwrap(), point);
+    assert_eq!(
+        arthur.next_pcs_evaluations(&scheme, 2).unwrap(),
+        evaluations
+    );
+    let opening = arthur.next_pcs_opening(&scheme).unwrap();
+    assert_eq!(opening.gamma, gamma);
+    assert_eq!(opening.rounds, vec![((g, g + g), x0), ((g + g, g), x1)]);
+    assert_eq!(opening.last, (Fr::from(1), Fr::from(2)));
+}
+
+#[test]
+fn test_designated_verifier() {
+    use crate::plugins::ark::designated::{
+        DesignatedVerifierIOPattern, DesignatedVerifierReader, DesignatedVerifierWriter,
+    };
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::PrimeGroup;
+    use ark_std::UniformRand;
+
+    let mut rng = ark_std::test_rng();
+    let sk = Fr::rand(&mut rng);
+    let pk = G::generator() * sk;
+    let io_pattern = DesignatedVerifierIOPattern::<G>::add_designated_verifier(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        "dv",
+    )
+    .challenge_bytes(16, "challenge");
+    assert_eq!(
+        io_pattern.as_bytes(),
+        &b"github.com/mmaker/nimue\0A32dv:ephemeral\0A32dv:designated-key\0S16challenge"[..]
+    );
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_designated_verifier(&pk).unwrap();
+    let challenge = merlin.challenge_bytes::<16>().unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    DesignatedVerifierReader::<G>::next_designated_verifier(&mut arthur, &sk).unwrap();
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    // without the secret key of the verifier, the challenge cannot be recomputed
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    DesignatedVerifierReader::<G>::next_designated_verifier(&mut arthur, &Fr::rand(&mut rng))
+        .unwrap();
+    assert_ne!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+}
+
+#[test]
+fn test_state_export() {
+    use ark_bls12_381::Fr;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .add_bytes(1, "inner")
+        .ratchet()
+        .challenge_bytes(1, "outer");
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_bytes(&[1]).unwrap();
+    let state = merlin.squeeze_state_as_scalars::<Fr>().unwrap();
+    // the 64 bytes of Keccak's capacity, in chunks of 31 bytes
+    assert_eq!(state.len(), 3);
+    let challenge = merlin.challenge_bytes::<1>().unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    arthur.next_bytes::<1>().unwrap();
+    assert_eq!(arthur.squeeze_state_as_scalars::<Fr>().unwrap(), state);
+    assert_eq!(arthur.challenge_bytes::<1>().unwrap(), challenge);
+
+    let mut other = io_pattern.to_merlin();
+    other.add_bytes(&[2]).unwrap();
+    assert_ne!(other.squeeze_state_as_scalars::<Fr>().unwrap(), state);
+    // the state can only be exported where the IO pattern declares a ratchet
+    assert!(other.squeeze_state_as_scalars::<Fr>().is_err());
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_absorb() {
+    use crate::{Absorb, AbsorbPattern, AbsorbReader, AbsorbWriter};
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::{CurveGroup, PrimeGroup};
+
+    #[derive(Absorb, Clone, Debug, PartialEq)]
+    struct Commitment<G: CurveGroup> {
+        point: G,
+        nonce: [u8; 4],
+    }
+
+    #[derive(Absorb, Clone, Debug, PartialEq)]
+    struct Response(Fr, [Fr; 2]);
+
+    #[derive(Absorb, Clone, Debug, PartialEq)]
+    struct Proof {
+        commitments: [Commitment<G>; 2],
+        response: Response,
+    }
+
+    let io_pattern = Proof::add_to_pattern(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        1,
+        "proof",
+    );
+    assert_eq!(
+        io_pattern.as_bytes(),
+        &b"github.com/mmaker/nimue\0A32proof.commitments.point\0A4proof.commitments.nonce\0A32proof.commitments.point\0A4proof.commitments.nonce\0A32proof.response.0\0A64proof.response.1"[..]
+    );
+
+    let g = G::generator();
+    let proof = Proof {
+        commitments: [
+            Commitment {
+                point: g,
+                nonce: [1, 2, 3, 4],
+            },
+            Commitment {
+                point: g + g,
+                nonce: [5, 6, 7, 8],
+            },
+        ],
+        response: Response(Fr::from(1), [Fr::from(2), Fr::from(3)]),
+    };
+    let mut merlin = io_pattern.to_merlin();
+    proof.add_to_transcript(&mut merlin).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert_eq!(Proof::read_from_transcript(&mut arthur).unwrap(), proof);
+}
+
+#[test]
+fn test_composite_messages() {
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::PrimeGroup;
+
+    type Message = (Vec<G>, [Fr; 2], Option<Fr>);
+
+    let shape = ((2, ()), (), Some(()));
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue")
+        .add_message::<Message>(&shape, "m");
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A64m.0\0A64m.1\0A32m.2"
+    );
+
+    let g = G::generator();
+    let message: Message = (
+        vec![g, g + g],
+        [Fr::from(1), Fr::from(2)],
+        Some(Fr::from(3)),
+    );
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_message(&message).unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_message::<Message>(&shape).unwrap(), message);
+}
+
+#[test]
+fn test_runtime_length_scalars() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use ark_bls12_381::Fr;
+
+    let columns = 3;
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        columns,
+        "columns",
+    );
+    let mut merlin = io_pattern.to_merlin();
+    let challenges: Vec<Fr> = merlin.challenge_scalars_vec(columns).unwrap();
+    assert_eq!(challenges.len(), columns);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [c0, c1, c2]: [Fr; 3] = arthur.challenge_scalars().unwrap();
+    assert_eq!(challenges, [c0, c1, c2]);
+
+    // short and uniform challenges follow the same naming
+    use crate::plugins::ark::{
+        ShortFieldChallenges, ShortFieldIOPattern, UniformFieldChallenges, UniformFieldIOPattern,
+    };
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern =
+        ShortFieldIOPattern::<Fr>::challenge_scalars_short(io_pattern, columns, "short");
+    let io_pattern =
+        UniformFieldIOPattern::<Fr>::challenge_scalars_uniform(io_pattern, columns, "uniform");
+    let mut merlin = io_pattern.to_merlin();
+    let short: Vec<Fr> = merlin.challenge_scalars_short_vec(columns).unwrap();
+    let uniform: Vec<Fr> = merlin.challenge_scalars_uniform_vec(columns).unwrap();
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let short_array: [Fr; 3] = arthur.challenge_scalars_short().unwrap();
+    let uniform_array: [Fr; 3] = arthur.challenge_scalars_uniform().unwrap();
+    assert_eq!(
+        (short, uniform),
+        (short_array.to_vec(), uniform_array.to_vec())
+    );
+}
+
+#[test]
+fn test_scratch_buffer_reuse() {
+    use crate::plugins::ark::{FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern};
+    use crate::plugins::ark::{GroupReader, GroupWriter};
+    use ark_curve25519::{EdwardsProjective as G, Fr};
+    use ark_ec::PrimeGroup;
+    use ark_serialize::CanonicalSerialize;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = FieldIOPattern::<Fr>::add_scalars(io_pattern, 3, "large");
+    let io_pattern = GroupIOPattern::<G>::add_points(io_pattern, 1, "point");
+    let io_pattern = FieldIOPattern::<Fr>::add_scalars(io_pattern, 1, "small");
+    let scalars = [Fr::from(1), Fr::from(2), Fr::from(3)];
+    let point = G::generator();
+
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_scalars(&scalars).unwrap();
+    merlin.add_points(&[point]).unwrap();
+    // a smaller message after a larger one does not carry over stale bytes
+    merlin.add_scalars(&scalars[..1]).unwrap();
+    // a message out of the IO pattern writes nothing
+    let len = merlin.transcript().len();
+    assert!(merlin.add_scalars(&scalars).is_err());
+    assert_eq!(merlin.transcript().len(), len);
+
+    let mut expected = Vec::new();
+    for scalar in scalars {
+        scalar.serialize_compressed(&mut expected).unwrap();
+    }
+    point.serialize_compressed(&mut expected).unwrap();
+    scalars[0].serialize_compressed(&mut expected).unwrap();
+    assert_eq!(merlin.transcript(), expected);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let decoded: [Fr; 3] = arthur.next_scalars().unwrap();
+    assert_eq!(decoded, scalars);
+    let [decoded]: [G; 1] = arthur.next_points().unwrap();
+    assert_eq!(decoded, point);
+    let [decoded]: [Fr; 1] = arthur.next_scalars().unwrap();
+    assert_eq!(decoded, scalars[0]);
+}
+
+#[test]
+fn test_large_absorption() {
+    use crate::plugins::ark::{FieldIOPattern, FieldReader, FieldWriter};
+    use ark_bls12_381::Fr;
+    use ark_serialize::CanonicalSerialize;
+
+    // above the threshold of the feature `parallel`
+    const LEN: usize = 2048;
+    let io_pattern = FieldIOPattern::<Fr>::add_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        LEN,
+        "large",
+    );
+    let scalars: Vec<Fr> = (0..LEN as u64).map(Fr::from).collect();
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_scalars(&scalars).unwrap();
+
+    let mut expected = Vec::new();
+    for scalar in &scalars {
+        scalar.serialize_compressed(&mut expected).unwrap();
+    }
+    assert_eq!(merlin.transcript(), expected);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let mut decoded = vec![Fr::from(0); LEN];
+    arthur.fill_next_scalars(&mut decoded).unwrap();
+    assert_eq!(decoded, scalars);
+}
+
+#[test]
+fn test_batched_scalar_challenges() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern};
+    use ark_bls12_381::Fr;
+
+    const LEN: usize = 64;
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(
+        IOPattern::<DefaultHash>::new("github.com/mmaker/nimue"),
+        LEN,
+        "challenges",
+    );
+    let batched: [Fr; LEN] = io_pattern.to_merlin().challenge_scalars().unwrap();
+
+    // squeezing all the challenges at once is equivalent to squeezing them one by one
+    let mut arthur = io_pattern.to_arthur(&[]);
+    for expected in batched {
+        let [challenge]: [Fr; 1] = arthur.challenge_scalars().unwrap();
+        assert_eq!(challenge, expected);
+    }
+    // an empty slice does not squeeze
+    let empty: [Fr; 0] = arthur.challenge_scalars().unwrap();
+    assert_eq!(empty, []);
+}
+
+#[test]
+fn test_allocation_free_verification() {
+    use crate::plugins::ark::{FieldChallenges, FieldIOPattern, FieldReader, FieldWriter};
+    use crate::tests::count_allocations;
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = FieldIOPattern::<Fr>::add_scalars(io_pattern, 2, "com");
+    // more challenges than the stack buffer holds at once
+    let io_pattern = FieldIOPattern::<Fr>::challenge_scalars(io_pattern, 20, "chal");
+    let scalars = [Fr::from(1), Fr::from(2)];
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_scalars(&scalars).unwrap();
+    let challenges: [Fr; 20] = merlin.challenge_scalars().unwrap();
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let (result, allocations) = count_allocations(|| {
+        let decoded: [Fr; 2] = arthur.next_scalars()?;
+        let challenges: [Fr; 20] = arthur.challenge_scalars()?;
+        crate::ProofResult::Ok((decoded, challenges))
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(result.unwrap(), (scalars, challenges));
+
+    // the reduction of the challenges is the one of arkworks
+    let bytes = [0xff; 48];
+    assert_eq!(
+        super::common::from_be_bytes_mod_order::<Fr>(&bytes),
+        Fr::from_be_bytes_mod_order(&bytes)
+    );
+    assert_eq!(
+        super::common::from_be_bytes_mod_order::<Fr>(&bytes[..13]),
+        Fr::from_be_bytes_mod_order(&bytes[..13])
+    );
+}
+
+#[test]
+fn test_non_canonical_scalars() {
+    use crate::plugins::ark::{FieldIOPattern, FieldReader};
+    use crate::Encoding;
+    use ark_bls12_381::Fr;
+    use ark_ff::{BigInteger, PrimeField};
+
+    for encoding in [Encoding::Compressed, Encoding::Uncompressed] {
+        let io_pattern =
+            IOPattern::<DefaultHash>::new("github.com/mmaker/nimue").with_encoding(encoding);
+        let io_pattern = FieldIOPattern::<Fr>::add_scalars(io_pattern, 1, "scalar");
+
+        // the largest canonical encoding is accepted
+        let mut largest = Fr::MODULUS;
+        largest.sub_with_borrow(&1u64.into());
+        let mut arthur = io_pattern.to_arthur(&largest.to_bytes_le());
+        let [scalar]: [Fr; 1] = arthur.next_scalars().unwrap();
+        assert_eq!(scalar, -Fr::from(1));
+
+        // the modulus encodes zero, but is rejected rather than reduced
+        let modulus = Fr::MODULUS.to_bytes_le();
+        let mut arthur = io_pattern.to_arthur(&modulus);
+        assert!(FieldReader::<Fr>::next_scalars::<1>(&mut arthur).is_err());
+    }
+}
+
+#[test]
+#[cfg(not(any(feature = "group", feature = "dalek")))]
+fn test_prelude() {
+    use crate::prelude::*;
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+
+    // declaring the pattern names the group and the field
+    let io = {
+        use crate::plugins::ark::{FieldIOPattern, GroupIOPattern};
+        let io = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+        let io = GroupIOPattern::<G1Projective>::add_points(io, 1, "commitment");
+        let io = FieldIOPattern::<Fr>::challenge_scalars(io, 1, "challenge");
+        FieldIOPattern::<Fr>::add_scalars(io, 1, "response")
+    };
+    // the prelude is enough to prove and verify
+    let mut merlin = io.to_merlin();
+    merlin.add_points(&[G1Projective::generator()]).unwrap();
+    let [challenge]: [Fr; 1] = merlin.challenge_scalars().unwrap();
+    merlin.add_scalars(&[challenge]).unwrap();
+
+    let mut arthur = io.to_arthur(merlin.transcript());
+    let [commitment]: [G1Projective; 1] = arthur.next_points().unwrap();
+    let [challenge_arthur]: [Fr; 1] = arthur.challenge_scalars().unwrap();
+    let [response]: [Fr; 1] = arthur.next_scalars().unwrap();
+    assert_eq!(commitment, G1Projective::generator());
+    assert_eq!((challenge_arthur, response), (challenge, challenge));
+}
+
+#[test]
+fn test_matrix_challenges() {
+    use crate::plugins::ark::{FieldChallenges, MatrixChallenges, MatrixIOPattern};
+    use ark_bls12_381::Fr;
+    use ark_ff::Field;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = MatrixIOPattern::<Fr>::challenge_scalar_matrix(io_pattern, 2, 3, "random");
+    let io_pattern = MatrixIOPattern::<Fr>::challenge_vandermonde_matrix(io_pattern, 2, 4, "rs");
+    let io_pattern = MatrixIOPattern::<Fr>::challenge_tensor_matrix(io_pattern, 2, 2, "tensor");
+    // one squeeze each, bound to the shape
+    let pattern = String::from_utf8(io_pattern.as_bytes().to_vec()).unwrap();
+    assert_eq!(pattern.matches("\0S").count(), 3);
+    assert!(pattern.contains("random[2x3]"));
+    assert!(pattern.contains("rs[vandermonde 2x4]"));
+    assert!(pattern.contains("tensor[tensor 2x2]"));
+
+    let mut merlin = io_pattern.to_merlin();
+    let random: Vec<Vec<Fr>> = merlin.challenge_scalar_matrix(2, 3).unwrap();
+    let vandermonde: Vec<Vec<Fr>> = merlin.challenge_vandermonde_matrix(2, 4).unwrap();
+    let tensor: Vec<Vec<Fr>> = merlin.challenge_tensor_matrix(2, 2).unwrap();
+    assert!(random.iter().all(|row| row.len() == 3) && random.len() == 2);
+
+    // the matrices are derived from the same scalars as plain challenges
+    let mut arthur = io_pattern.to_arthur(&[]);
+    let scalars: [Fr; 6] = arthur.challenge_scalars().unwrap();
+    assert_eq!(random.concat(), scalars);
+    let [c0, c1]: [Fr; 2] = arthur.challenge_scalars().unwrap();
+    for (row, c) in vandermonde.iter().zip([c0, c1]) {
+        assert_eq!(row, &[Fr::ONE, c, c.square(), c.pow([3])]);
+    }
+    let [a0, a1, b0, b1]: [Fr; 4] = arthur.challenge_scalars().unwrap();
+    assert_eq!(tensor, [[a0 * b0, a0 * b1], [a1 * b0, a1 * b1]]);
+}
+
+/// A toy duplex hash over field elements, evaluating everything absorbed as a polynomial at 7.
+#[derive(Clone, Default, zeroize::Zeroize)]
+struct Horner(ark_bls12_381::Fr);
+
+impl DuplexHash<ark_bls12_381::Fr> for Horner {
+    fn new(_: [u8; 32]) -> Self {
+        Self::default()
+    }
+
+    fn absorb_unchecked(&mut self, input: &[ark_bls12_381::Fr]) -> &mut Self {
+        for x in input {
+            self.0 = self.0 * ark_bls12_381::Fr::from(7u64) + x;
+        }
+        self
+    }
+
+    fn squeeze_unchecked(&mut self, output: &mut [ark_bls12_381::Fr]) -> &mut Self {
+        for o in output.iter_mut() {
+            *o = self.0;
+            self.0 += ark_bls12_381::Fr::from(1u64);
+        }
+        self
+    }
+
+    fn ratchet_unchecked(&mut self) -> &mut Self {
+        self
+    }
+}
+
+#[test]
+fn test_field_length_framing() {
+    use ark_bls12_381::Fr;
+
+    let iop = IOPattern::<Horner, Fr>::new("example.com")
+        .absorb(2, "first")
+        .absorb(1, "second")
+        .squeeze(1, "challenge")
+        .with_length_framing();
+    // units far above the byte range
+    let message = [Fr::from(3u64), -Fr::from(1u64), -Fr::from(2u64)];
+
+    let mut merlin = iop.to_merlin();
+    merlin.add_units(&message).unwrap();
+    let mut challenge = [Fr::from(0u64)];
+    merlin.fill_challenge_units(&mut challenge).unwrap();
+
+    // each length is absorbed as a single field element
+    let absorbed = [
+        Fr::from(2u64),
+        message[0],
+        message[1],
+        Fr::from(1u64),
+        message[2],
+    ];
+    let mut sponge = Horner::default();
+    sponge.absorb_unchecked(&absorbed);
+    let mut expected = [Fr::from(0u64)];
+    sponge.squeeze_unchecked(&mut expected);
+    assert_eq!(challenge, expected);
+
+    // the messages can be read in any split
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    let mut first = [Fr::from(0u64)];
+    let mut rest = [Fr::from(0u64); 2];
+    arthur.fill_next_units(&mut first).unwrap();
+    arthur.fill_next_units(&mut rest).unwrap();
+    assert_eq!([first[0], rest[0], rest[1]], message);
+    let mut verifier_challenge = [Fr::from(0u64)];
+    arthur
+        .fill_challenge_units(&mut verifier_challenge)
+        .unwrap();
+    assert_eq!(verifier_challenge, challenge);
+}
+
+#[test]
+fn test_field_label_policy() {
+    use crate::LabelPolicy;
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+
+    let iop = IOPattern::<Horner, Fr>::new("example.com")
+        .absorb(1, "message")
+        .squeeze(1, "alpha")
+        .squeeze(2, "beta")
+        .with_label_policy(LabelPolicy::BindChallenges);
+    let message = -Fr::from(1u64);
+
+    let mut merlin = iop.to_merlin();
+    merlin.add_units(&[message]).unwrap();
+    let mut challenges = [Fr::from(0u64); 3];
+    merlin.fill_challenge_units(&mut challenges[..1]).unwrap();
+    merlin.fill_challenge_units(&mut challenges[1..]).unwrap();
+
+    // each label is absorbed as a single field element, right before its challenge
+    let mut sponge = Horner::default();
+    let mut expected = [Fr::from(0u64); 3];
+    sponge.absorb_unchecked(&[message, Fr::from_le_bytes_mod_order(b"alpha")]);
+    sponge.squeeze_unchecked(&mut expected[..1]);
+    sponge.absorb_unchecked(&[Fr::from_le_bytes_mod_order(b"beta")]);
+    sponge.squeeze_unchecked(&mut expected[1..]);
+    assert_eq!(challenges, expected);
+
+    // a squeeze can't span two challenges
+    let mut merlin = iop.to_merlin();
+    merlin.add_units(&[message]).unwrap();
+    assert!(merlin
+        .fill_challenge_units(&mut [Fr::from(0u64); 3])
+        .is_err());
+}
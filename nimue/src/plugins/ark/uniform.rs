@@ -0,0 +1,151 @@
+use ark_ff::{BigInteger, Field, Fp, FpConfig, PrimeField};
+use rand::{CryptoRng, RngCore};
+
+use super::common::fill_challenge_scalars_modp;
+use crate::plugins::bytes_modp;
+use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, DuplexHash, IOPattern, IOPatternError, Merlin,
+    ProofResult, UnitTranscript,
+};
+
+/// Squeeze exactly uniform field elements in the IO pattern.
+///
+/// Over byte-oriented hashes, [`FieldChallenges`](super::FieldChallenges) reduces
+/// `log2(p) + 128` bits modulo `p`, which is only statistically close to uniform.
+/// The challenges declared here are instead obtained via rejection sampling:
+/// the pattern reserves enough candidates for sampling to fail with probability at most $2^{-128}$.
+pub trait UniformFieldIOPattern<F: Field> {
+    fn challenge_scalars_uniform(self, count: usize, label: &str) -> Self;
+}
+
+/// Interpret verifier messages as exactly uniform field elements, see [`UniformFieldIOPattern`].
+pub trait UniformFieldChallenges<F: Field> {
+    fn fill_challenge_scalars_uniform(&mut self, output: &mut [F]) -> ProofResult<()>;
+
+    fn challenge_scalars_uniform<const N: usize>(&mut self) -> ProofResult<[F; N]> {
+        let mut output = [F::default(); N];
+        self.fill_challenge_scalars_uniform(&mut output)
+            .map(|()| output)
+    }
+
+    /// Squeeze `n` uniform scalars, for when their number is known only at runtime.
+    fn challenge_scalars_uniform_vec(&mut self, n: usize) -> ProofResult<Vec<F>> {
+        let mut output = vec![F::default(); n];
+        self.fill_challenge_scalars_uniform(&mut output)
+            .map(|()| output)
+    }
+}
+
+/// The number of candidates to squeeze so that rejection sampling an element of `F` fails with probability at most $2^{-128}$.
+///
+/// Candidates are `n`-bit integers, where `n` is the bit size of the modulus `p`.
+/// If $2^n - p < 2^m$, each candidate is rejected with probability less than $2^{m-n}$.
+fn rejection_attempts<F: PrimeField>() -> usize {
+    let n = F::MODULUS_BIT_SIZE as usize;
+    // compute 2^n - p as the two's complement of p over n bits
+    let mut complement = F::MODULUS.to_bits_le()[..n]
+        .iter()
+        .map(|bit| !bit)
+        .collect::<Vec<_>>();
+    for bit in complement.iter_mut() {
+        *bit = !*bit;
+        if *bit {
+            break;
+        }
+    }
+    let m = complement.iter().rposition(|&bit| bit).map_or(0, |i| i + 1);
+    128usize.div_ceil(n - m)
+}
+
+/// Return the first candidate in `buf` that, read as a little-endian `n`-bit integer, is smaller than the modulus.
+fn first_accepted<F: PrimeField>(buf: &[u8]) -> Option<F> {
+    let n = F::MODULUS_BIT_SIZE as usize;
+    buf.chunks(bytes_modp(F::MODULUS_BIT_SIZE))
+        .find_map(|candidate| {
+            let bits = candidate
+                .iter()
+                .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+                .take(n)
+                .collect::<Vec<_>>();
+            F::from_bigint(F::BigInt::from_bits_le(&bits))
+        })
+}
+
+impl<F, H> UniformFieldIOPattern<F> for IOPattern<H>
+where
+    F: Field,
+    H: DuplexHash,
+{
+    fn challenge_scalars_uniform(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(
+            count
+                * F::extension_degree() as usize
+                * rejection_attempts::<F::BasePrimeField>()
+                * bytes_modp(F::BasePrimeField::MODULUS_BIT_SIZE),
+            label,
+        )
+    }
+}
+
+impl<F, C, H, const N: usize> UniformFieldIOPattern<F> for IOPattern<H, Fp<C, N>>
+where
+    F: Field<BasePrimeField = Fp<C, N>>,
+    C: FpConfig<N>,
+    H: DuplexHash<Fp<C, N>>,
+{
+    /// Native field elements are already uniformly distributed.
+    fn challenge_scalars_uniform(self, count: usize, label: &str) -> Self {
+        self.squeeze(count * F::extension_degree() as usize, label)
+    }
+}
+
+impl<F, T> UniformFieldChallenges<F> for T
+where
+    F: Field,
+    T: UnitTranscript<u8>,
+{
+    fn fill_challenge_scalars_uniform(&mut self, output: &mut [F]) -> ProofResult<()> {
+        let mut buf = vec![
+            0u8;
+            rejection_attempts::<F::BasePrimeField>()
+                * bytes_modp(F::BasePrimeField::MODULUS_BIT_SIZE)
+        ];
+        let mut coefficients = Vec::with_capacity(F::extension_degree() as usize);
+
+        for o in output.iter_mut() {
+            coefficients.clear();
+            for _ in 0..F::extension_degree() {
+                self.fill_challenge_bytes(&mut buf)?;
+                let coefficient = first_accepted(&buf)
+                    .ok_or(IOPatternError::from("Rejection sampling failed"))?;
+                coefficients.push(coefficient);
+            }
+            *o = F::from_base_prime_field_elems(coefficients.iter().copied())
+                .expect("Could not convert");
+        }
+        Ok(())
+    }
+}
+
+impl<F, H, C, const N: usize> UniformFieldChallenges<F> for Arthur<'_, H, Fp<C, N>>
+where
+    F: Field<BasePrimeField = Fp<C, N>>,
+    C: FpConfig<N>,
+    H: DuplexHash<Fp<C, N>>,
+{
+    fn fill_challenge_scalars_uniform(&mut self, output: &mut [F]) -> ProofResult<()> {
+        fill_challenge_scalars_modp(self, output)
+    }
+}
+
+impl<F, H, C, R, const N: usize> UniformFieldChallenges<F> for Merlin<H, Fp<C, N>, R>
+where
+    F: Field<BasePrimeField = Fp<C, N>>,
+    C: FpConfig<N>,
+    H: DuplexHash<Fp<C, N>>,
+    R: CryptoRng + RngCore,
+{
+    fn fill_challenge_scalars_uniform(&mut self, output: &mut [F]) -> ProofResult<()> {
+        fill_challenge_scalars_modp(self, output)
+    }
+}
@@ -1,29 +1,61 @@
 use ark_ec::CurveGroup;
 use ark_ff::{Field, Fp, FpConfig};
-use ark_serialize::CanonicalSerialize;
+use ark_serialize::SerializationError;
 use rand::{CryptoRng, RngCore};
 
+use super::common::{
+    encoded_size, point_size, serialize_all, serialize_all_points, serialize_point, serialize_with,
+};
 use super::{FieldPublic, FieldWriter, GroupPublic, GroupWriter};
 use crate::{
-    Arthur, BytePublic, ByteReader, ByteWriter, DuplexHash, IOPatternError, Merlin, ProofResult,
-    Unit, UnitTranscript,
+    Arthur, BytePublic, ByteReader, ByteWriter, DuplexHash, Encoding, IOPatternError, Merlin,
+    ProofResult, Unit,
 };
 
+impl<H: DuplexHash, R: RngCore + CryptoRng> Merlin<H, u8, R> {
+    /// Absorb the elements serialized by `serialize`, and write them (each of `size` bytes) to the transcript.
+    ///
+    /// Elements are serialized into the scratch buffer of the prover,
+    /// so that proving loops do not allocate a buffer per message.
+    fn add_serialized(
+        &mut self,
+        size: usize,
+        serialize: impl FnOnce(&mut Vec<u8>, Encoding) -> Result<(), SerializationError>,
+    ) -> ProofResult<()> {
+        let mut buf = core::mem::take(&mut self.scratch);
+        buf.clear();
+        let result = match serialize(&mut buf, self.encoding) {
+            Ok(()) => self.public_bytes(&buf).map_err(Into::into),
+            Err(error) => Err(error.into()),
+        }
+        .and_then(|()| {
+            buf.chunks(size)
+                .try_for_each(|element| self.write_element(element))
+                .map_err(Into::into)
+        });
+        self.scratch = buf;
+        result
+    }
+}
+
 impl<F: Field, H: DuplexHash, R: RngCore + CryptoRng> FieldWriter<F> for Merlin<H, u8, R> {
     fn add_scalars(&mut self, input: &[F]) -> ProofResult<()> {
-        let serialized = self.public_scalars(input);
-        self.transcript.extend(serialized?);
-        Ok(())
+        let size = encoded_size(&F::default(), self.encoding);
+        self.add_serialized(size, |buf, encoding| serialize_all(input, encoding, buf))
     }
 }
 
-impl<C: FpConfig<N>, H: DuplexHash<Fp<C, N>>, R: RngCore + CryptoRng, const N: usize>
-    FieldWriter<Fp<C, N>> for Merlin<H, Fp<C, N>, R>
+impl<F, H, R, C, const N: usize> FieldWriter<F> for Merlin<H, Fp<C, N>, R>
+where
+    F: Field<BasePrimeField = Fp<C, N>>,
+    H: DuplexHash<Fp<C, N>>,
+    R: RngCore + CryptoRng,
+    C: FpConfig<N>,
 {
-    fn add_scalars(&mut self, input: &[Fp<C, N>]) -> ProofResult<()> {
-        self.public_units(input)?;
+    fn add_scalars(&mut self, input: &[F]) -> ProofResult<()> {
+        self.public_scalars(input)?;
         for i in input {
-            i.serialize_compressed(&mut self.transcript)?;
+            serialize_with(i, self.encoding, &mut self.transcript)?;
         }
         Ok(())
     }
@@ -34,13 +66,14 @@ where
     G: CurveGroup,
     H: DuplexHash,
     R: RngCore + CryptoRng,
-    Merlin<H, u8, R>: GroupPublic<G, Repr = Vec<u8>>,
 {
     #[inline(always)]
     fn add_points(&mut self, input: &[G]) -> ProofResult<()> {
-        let serialized = self.public_points(input);
-        self.transcript.extend(serialized?);
-        Ok(())
+        let size = point_size::<G::Affine>(self.encoding);
+        self.add_serialized(size, |buf, encoding| {
+            // a single inversion for the whole slice, instead of one per point
+            serialize_all_points(&G::normalize_batch(input), encoding, buf)
+        })
     }
 }
 
@@ -55,8 +88,8 @@ where
     #[inline(always)]
     fn add_points(&mut self, input: &[G]) -> ProofResult<()> {
         self.public_points(input).map(|_| ())?;
-        for i in input {
-            i.serialize_compressed(&mut self.transcript)?;
+        for i in G::normalize_batch(input) {
+            serialize_point(&i, self.encoding, &mut self.transcript)?;
         }
         Ok(())
     }
@@ -70,7 +103,7 @@ where
 {
     fn add_bytes(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
         self.public_bytes(input)?;
-        self.transcript.extend(input);
+        self.transcript.extend_from_slice(input);
         Ok(())
     }
 }
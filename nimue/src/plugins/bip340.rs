@@ -0,0 +1,269 @@
+//! [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki) Schnorr signatures over secp256k1,
+//! with feature flag `bip340`.
+//!
+//! BIP-340 derives the Fiat-Shamir challenge with a *tagged hash*
+//! $\mathsf{SHA256}(\mathsf{SHA256}(\mathit{tag}) \Vert \mathsf{SHA256}(\mathit{tag}) \Vert x)$
+//! over 32-byte x-only points, instead of a duplex sponge.
+//! A duplex sponge cannot reproduce it (the IV alone differs), so this module provides a second transcript mode,
+//! [`TaggedTranscript`]: it follows an [`IOPattern`] as [`Merlin`](crate::Merlin) and [`Arthur`](crate::Arthur) do,
+//! but hashes the absorbed bytes with the tagged hash, with the domain separator of the IO pattern as tag.
+//! Its only squeeze is the 32-byte hash itself, so the IO pattern must end with it.
+//! The IO patterns of the three hashes of the specification are [`challenge_io_pattern`],
+//! [`aux_io_pattern`], and [`nonce_io_pattern`].
+//! Signatures produced with [`sign`] are byte-for-byte those of the specification,
+//! and verify with existing Bitcoin libraries (and vice versa with [`verify`]).
+//!
+//! ```
+//! use nimue::plugins::bip340;
+//!
+//! // test vector 0 of BIP-340
+//! let mut secret = [0u8; 32];
+//! secret[31] = 3;
+//! let public_key = bip340::public_key(&secret).unwrap();
+//! let signature = bip340::sign(&secret, &[0u8; 32], &[0u8; 32]).unwrap();
+//! assert_eq!(
+//!     hex::encode_upper(public_key),
+//!     "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"
+//! );
+//! assert_eq!(
+//!     hex::encode_upper(signature),
+//!     "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+//!      25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0"
+//! );
+//! assert!(bip340::verify(&public_key, &[0u8; 32], &signature).is_ok());
+//! assert!(bip340::verify(&public_key, &[1u8; 32], &signature).is_err());
+//! ```
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::elliptic_curve::subtle::{Choice, ConditionallySelectable};
+use k256::elliptic_curve::{Field, PrimeField};
+use k256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar, U256};
+use sha2::{Digest, Sha256};
+
+use crate::iopattern::{Op, OpCursor};
+use crate::{ByteIOPattern, DuplexHash, IOPattern, IOPatternError, ProofError, ProofResult};
+
+/// The tag of the challenge hash.
+pub const CHALLENGE_TAG: &str = "BIP0340/challenge";
+/// The tag of the hash masking the secret key with the auxiliary randomness.
+pub const AUX_TAG: &str = "BIP0340/aux";
+/// The tag of the nonce derivation hash.
+pub const NONCE_TAG: &str = "BIP0340/nonce";
+
+/// The size of a tagged hash, of an x-only point, and of a scalar, in bytes.
+const SIZE: usize = 32;
+
+/// The IO pattern of the challenge hash, over a message of `message_len` bytes.
+pub fn challenge_io_pattern(message_len: usize) -> IOPattern {
+    let io_pattern = IOPattern::new(CHALLENGE_TAG)
+        .add_bytes(SIZE, "nonce commitment")
+        .add_bytes(SIZE, "public key");
+    with_message(io_pattern, message_len).challenge_bytes(SIZE, "challenge")
+}
+
+/// The IO pattern of the hash masking the secret key.
+pub fn aux_io_pattern() -> IOPattern {
+    IOPattern::new(AUX_TAG)
+        .add_bytes(SIZE, "auxiliary randomness")
+        .challenge_bytes(SIZE, "mask")
+}
+
+/// The IO pattern of the nonce derivation hash, over a message of `message_len` bytes.
+pub fn nonce_io_pattern(message_len: usize) -> IOPattern {
+    let io_pattern = IOPattern::new(NONCE_TAG)
+        .add_bytes(SIZE, "masked secret key")
+        .add_bytes(SIZE, "public key");
+    with_message(io_pattern, message_len).challenge_bytes(SIZE, "nonce")
+}
+
+/// Absorb the message, if not empty (BIP-340 signs messages of any length).
+fn with_message(io_pattern: IOPattern, message_len: usize) -> IOPattern {
+    match message_len {
+        0 => io_pattern,
+        _ => io_pattern.add_bytes(message_len, "message"),
+    }
+}
+
+/// A transcript hashed with a BIP-340 tagged hash, following an [`IOPattern`].
+///
+/// Points are absorbed as their 32-byte x coordinate (their y coordinate is implicitly even),
+/// and the transcript is finalized into a single 32-byte hash, possibly reduced into a scalar.
+/// Only the domain separator and the operations of the IO pattern are used: its configuration does not apply.
+#[derive(Clone)]
+pub struct TaggedTranscript {
+    hasher: Sha256,
+    cursor: OpCursor,
+}
+
+impl TaggedTranscript {
+    /// Start a transcript following `io_pattern`, with its domain separator as tag.
+    pub fn new<H: DuplexHash>(io_pattern: &IOPattern<H>) -> Self {
+        let tag_hash = Sha256::digest(io_pattern.domain_separator().as_bytes());
+        let mut hasher = Sha256::new();
+        hasher.update(tag_hash);
+        hasher.update(tag_hash);
+        Self {
+            hasher,
+            cursor: OpCursor::new(io_pattern),
+        }
+    }
+
+    /// Absorb `input` as is.
+    ///
+    /// Return an error if the IO pattern does not expect `input.len()` bytes to be absorbed next.
+    pub fn add_bytes(&mut self, input: &[u8]) -> Result<&mut Self, IOPatternError> {
+        if !input.is_empty() {
+            self.cursor.consume(Op::Absorb(input.len()))?;
+            self.hasher.update(input);
+        }
+        Ok(self)
+    }
+
+    /// Absorb the x-only encoding of `point`.
+    ///
+    /// Return an error if `point` is the point at infinity,
+    /// or if the IO pattern does not expect 32 bytes to be absorbed next.
+    pub fn add_point(&mut self, point: &AffinePoint) -> ProofResult<&mut Self> {
+        let (x, _) = x_only(point)?;
+        Ok(self.add_bytes(&x)?)
+    }
+
+    /// Return the tagged hash of the bytes absorbed.
+    ///
+    /// Return an error if the IO pattern does not end with a 32-byte squeeze, expected next.
+    pub fn finalize(mut self) -> Result<[u8; SIZE], IOPatternError> {
+        self.cursor.consume(Op::Squeeze(SIZE))?;
+        if !self.cursor.is_finished() {
+            return Err("The tagged hash must be the last operation of the IO pattern".into());
+        }
+        Ok(self.hasher.finalize().into())
+    }
+
+    /// Return the tagged hash, interpreted as a big-endian integer and reduced modulo the group order.
+    pub fn challenge_scalar(self) -> Result<Scalar, IOPatternError> {
+        self.finalize().map(reduce)
+    }
+}
+
+/// The tagged hash of `input` under `tag`.
+///
+/// Panics if `tag` is not a valid domain separator (see [`IOPattern::new`]).
+pub fn tagged_hash(tag: &str, input: &[u8]) -> [u8; SIZE] {
+    let mut io_pattern = IOPattern::<crate::DefaultHash>::new(tag);
+    if !input.is_empty() {
+        io_pattern = io_pattern.add_bytes(input.len(), "input");
+    }
+    let mut transcript = TaggedTranscript::new(&io_pattern.challenge_bytes(SIZE, "hash"));
+    // the transcript follows the IO pattern just built
+    transcript.add_bytes(input).unwrap();
+    transcript.finalize().unwrap()
+}
+
+/// The BIP-340 challenge $e$ for the nonce commitment `r`, the public key `public_key` and the message `message`.
+pub fn challenge(r: &[u8; SIZE], public_key: &[u8; SIZE], message: &[u8]) -> Scalar {
+    let mut transcript = TaggedTranscript::new(&challenge_io_pattern(message.len()));
+    // the transcript follows the IO pattern just built
+    transcript
+        .add_bytes(r)
+        .and_then(|t| t.add_bytes(public_key))
+        .and_then(|t| t.add_bytes(message))
+        .unwrap();
+    transcript.challenge_scalar().unwrap()
+}
+
+/// The x-only public key of the 32-byte secret key `secret`.
+///
+/// Return [`ProofError::SerializationError`] if `secret` is zero or not smaller than the group order.
+pub fn public_key(secret: &[u8; SIZE]) -> ProofResult<[u8; SIZE]> {
+    let d = nonzero_scalar(secret)?;
+    let (x, _) = x_only(&(ProjectivePoint::GENERATOR * d).to_affine())?;
+    Ok(x)
+}
+
+/// Sign `message` with the 32-byte secret key `secret`, as per BIP-340.
+///
+/// `aux` is the auxiliary randomness; it should be 32 fresh random bytes
+/// (e.g. from [`Merlin::rng`](crate::Merlin::rng)), but signing is secure even if it is constant.
+/// Return [`ProofError::SerializationError`] if `secret` is not a valid secret key.
+pub fn sign(secret: &[u8; SIZE], message: &[u8], aux: &[u8; SIZE]) -> ProofResult<[u8; 64]> {
+    let d = nonzero_scalar(secret)?;
+    let (public_key, odd) = x_only(&(ProjectivePoint::GENERATOR * d).to_affine())?;
+    // negate without branching on the secret key
+    let d = Scalar::conditional_select(&d, &-d, Choice::from(odd as u8));
+
+    let mut mask = TaggedTranscript::new(&aux_io_pattern());
+    mask.add_bytes(aux)?;
+    let mut t: [u8; SIZE] = d.to_repr().into();
+    t.iter_mut()
+        .zip(mask.finalize()?)
+        .for_each(|(t, m)| *t ^= m);
+    let mut nonce = TaggedTranscript::new(&nonce_io_pattern(message.len()));
+    nonce
+        .add_bytes(&t)?
+        .add_bytes(&public_key)?
+        .add_bytes(message)?;
+    let k = nonce.challenge_scalar()?;
+    if bool::from(k.is_zero()) {
+        return Err(ProofError::InvalidProof);
+    }
+    let (r, odd) = x_only(&(ProjectivePoint::GENERATOR * k).to_affine())?;
+    let k = Scalar::conditional_select(&k, &-k, Choice::from(odd as u8));
+
+    let e = challenge(&r, &public_key, message);
+    let s = k + e * d;
+    let mut signature = [0u8; 64];
+    signature[..SIZE].copy_from_slice(&r);
+    signature[SIZE..].copy_from_slice(&s.to_repr());
+    Ok(signature)
+}
+/// Verify the BIP-340 `signature` on `message` under the x-only public key `public_key`.
+///
+/// Return [`ProofError::InvalidProof`] if the signature does not verify,
+/// and [`ProofError::SerializationError`] if the public key or the signature are not canonically encoded.
+pub fn verify(public_key: &[u8; SIZE], message: &[u8], signature: &[u8; 64]) -> ProofResult<()> {
+    let p = lift_x(public_key)?;
+    let r: [u8; SIZE] = signature[..SIZE].try_into().unwrap();
+    // r must be the x coordinate of a point: lifting it checks that it is a canonical field element.
+    lift_x(&r)?;
+    let s = Option::from(Scalar::from_repr(FieldBytes::clone_from_slice(
+        &signature[SIZE..],
+    )))
+    .ok_or(ProofError::SerializationError)?;
+
+    let e = challenge(&r, public_key, message);
+    let big_r = (ProjectivePoint::GENERATOR * s - ProjectivePoint::from(p) * e).to_affine();
+    match x_only(&big_r) {
+        Ok((x, false)) if crate::ct::eq(&x, &r) => Ok(()),
+        _ => Err(ProofError::InvalidProof),
+    }
+}
+
+/// The x coordinate of `point`, and whether its y coordinate is odd.
+fn x_only(point: &AffinePoint) -> ProofResult<([u8; SIZE], bool)> {
+    let encoded = point.to_encoded_point(true);
+    let bytes = encoded.as_bytes();
+    if encoded.is_identity() {
+        return Err(ProofError::InvalidProof);
+    }
+    Ok((bytes[1..].try_into().unwrap(), bytes[0] == 0x03))
+}
+
+/// The point with x coordinate `x` and even y coordinate.
+fn lift_x(x: &[u8; SIZE]) -> ProofResult<AffinePoint> {
+    let mut compressed = [0x02; 33];
+    compressed[1..].copy_from_slice(x);
+    let encoded =
+        EncodedPoint::from_bytes(compressed).map_err(|_| ProofError::SerializationError)?;
+    Option::from(AffinePoint::from_encoded_point(&encoded)).ok_or(ProofError::SerializationError)
+}
+
+fn nonzero_scalar(bytes: &[u8; SIZE]) -> ProofResult<Scalar> {
+    let scalar: Option<Scalar> = Scalar::from_repr(FieldBytes::clone_from_slice(bytes)).into();
+    scalar
+        .filter(|d| !bool::from(d.is_zero()))
+        .ok_or(ProofError::SerializationError)
+}
+
+fn reduce(bytes: [u8; SIZE]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(bytes))
+}
@@ -0,0 +1,41 @@
+use curve25519_dalek::{RistrettoPoint, Scalar};
+
+use super::{FieldChallenges, FieldPublic, GroupPublic};
+use crate::{ByteChallenges, BytePublic, ProofResult};
+
+/// The number of bytes squeezed for each scalar challenge.
+pub(super) const WIDE_SCALAR_BYTES: usize = 64;
+
+impl<T: ByteChallenges> FieldChallenges<Scalar> for T {
+    fn fill_challenge_scalars(&mut self, output: &mut [Scalar]) -> ProofResult<()> {
+        let mut buf = [0u8; WIDE_SCALAR_BYTES];
+        for o in output.iter_mut() {
+            self.fill_challenge_bytes(&mut buf)?;
+            *o = Scalar::from_bytes_mod_order_wide(&buf);
+        }
+        Ok(())
+    }
+}
+
+impl<T: BytePublic> FieldPublic<Scalar> for T {
+    type Repr = Vec<u8>;
+
+    fn public_scalars(&mut self, input: &[Scalar]) -> ProofResult<Self::Repr> {
+        let buf = input.iter().flat_map(Scalar::to_bytes).collect::<Vec<_>>();
+        self.public_bytes(&buf)?;
+        Ok(buf)
+    }
+}
+
+impl<T: BytePublic> GroupPublic<RistrettoPoint> for T {
+    type Repr = Vec<u8>;
+
+    fn public_points(&mut self, input: &[RistrettoPoint]) -> ProofResult<Self::Repr> {
+        let buf = input
+            .iter()
+            .flat_map(|point| point.compress().to_bytes())
+            .collect::<Vec<_>>();
+        self.public_bytes(&buf)?;
+        Ok(buf)
+    }
+}
@@ -0,0 +1,143 @@
+//! Ed448-Goldilocks and Decaf448, for protocols targeting the 224-bit security level.
+//!
+//! Scalars are encoded in 57 bytes, as in RFC 8032, and points with their canonical compressed encodings:
+//! 56 bytes for Decaf448 and 57 bytes for Ed448.
+//! Scalar challenges are obtained by reducing 114 bytes modulo the group order
+//! (as [`Scalar::from_bytes_mod_order_wide`]).
+use ed448_goldilocks_plus::{DecafPoint, EdwardsPoint, Scalar, WideScalarBytes};
+use group::ff::{Field, PrimeField};
+use group::GroupEncoding;
+use rand::{CryptoRng, RngCore};
+
+use super::{
+    DalekField, DalekGroup, FieldChallenges, FieldIOPattern, FieldPublic, FieldReader, FieldWriter,
+    GroupIOPattern, GroupPublic, GroupReader, GroupWriter,
+};
+use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, BytePublic, ByteReader, DuplexHash, IOPattern, Merlin,
+    ProofError, ProofResult, UnitTranscript,
+};
+
+/// The number of bytes of an encoded scalar.
+const SCALAR_BYTES: usize = 57;
+/// The number of bytes squeezed for each scalar challenge.
+const WIDE_SCALAR_BYTES: usize = 114;
+
+impl DalekField for Scalar {
+    const ONE: Self = <Scalar as Field>::ONE;
+}
+
+impl DalekGroup for DecafPoint {}
+
+impl DalekGroup for EdwardsPoint {}
+
+impl<H: DuplexHash> FieldIOPattern<Scalar> for IOPattern<H> {
+    fn add_scalars(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * SCALAR_BYTES, label)
+    }
+
+    fn challenge_scalars(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(count * WIDE_SCALAR_BYTES, label)
+    }
+}
+
+impl<T: ByteChallenges> FieldChallenges<Scalar> for T {
+    fn fill_challenge_scalars(&mut self, output: &mut [Scalar]) -> ProofResult<()> {
+        let mut buf = WideScalarBytes::default();
+        for o in output.iter_mut() {
+            self.fill_challenge_bytes(&mut buf)?;
+            *o = Scalar::from_bytes_mod_order_wide(&buf);
+        }
+        Ok(())
+    }
+}
+
+impl<T: BytePublic> FieldPublic<Scalar> for T {
+    type Repr = Vec<u8>;
+
+    fn public_scalars(&mut self, input: &[Scalar]) -> ProofResult<Self::Repr> {
+        let buf = input
+            .iter()
+            .flat_map(|scalar| scalar.to_repr())
+            .collect::<Vec<_>>();
+        self.public_bytes(&buf)?;
+        Ok(buf)
+    }
+}
+
+impl<H, R> FieldWriter<Scalar> for Merlin<H, u8, R>
+where
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    fn add_scalars(&mut self, input: &[Scalar]) -> ProofResult<()> {
+        let serialized = self.public_scalars(input)?;
+        self.transcript.extend_from_slice(&serialized);
+        Ok(())
+    }
+}
+
+impl<H: DuplexHash> FieldReader<Scalar> for Arthur<'_, H> {
+    fn fill_next_scalars(&mut self, output: &mut [Scalar]) -> ProofResult<()> {
+        let mut repr = <Scalar as PrimeField>::Repr::default();
+        for o in output.iter_mut() {
+            self.fill_next_bytes(&mut repr)?;
+            *o = Option::from(Scalar::from_repr(repr)).ok_or(ProofError::SerializationError)?;
+        }
+        Ok(())
+    }
+}
+
+/// Implement the group traits of the plugin for a point type with a canonical [`GroupEncoding`].
+macro_rules! impl_ed448_group {
+    ($Point:ty, $size:expr) => {
+        impl<H: DuplexHash> GroupIOPattern<$Point> for IOPattern<H> {
+            fn add_points(self, count: usize, label: &str) -> Self {
+                self.add_bytes(count * $size, label)
+            }
+        }
+
+        impl<T: BytePublic> GroupPublic<$Point> for T {
+            type Repr = Vec<u8>;
+
+            fn public_points(&mut self, input: &[$Point]) -> ProofResult<Self::Repr> {
+                let buf = input
+                    .iter()
+                    .flat_map(|point| point.to_bytes())
+                    .collect::<Vec<_>>();
+                self.public_bytes(&buf)?;
+                Ok(buf)
+            }
+        }
+
+        impl<H, R> GroupWriter<$Point> for Merlin<H, u8, R>
+        where
+            H: DuplexHash,
+            R: RngCore + CryptoRng,
+        {
+            fn add_points(&mut self, input: &[$Point]) -> ProofResult<()> {
+                let serialized = self.public_points(input)?;
+                serialized
+                    .chunks($size)
+                    .try_for_each(|element| self.write_element(element))?;
+                Ok(())
+            }
+        }
+
+        impl<H: DuplexHash> GroupReader<$Point> for Arthur<'_, H> {
+            fn fill_next_points(&mut self, output: &mut [$Point]) -> ProofResult<()> {
+                let mut repr = <$Point as GroupEncoding>::Repr::default();
+                for o in output.iter_mut() {
+                    self.read_element(repr.as_mut())?;
+                    self.public_units(repr.as_ref())?;
+                    *o = Option::from(<$Point>::from_bytes(&repr))
+                        .ok_or(ProofError::SerializationError)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_ed448_group!(DecafPoint, 56);
+impl_ed448_group!(EdwardsPoint, 57);
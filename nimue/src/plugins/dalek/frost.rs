@@ -0,0 +1,441 @@
+//! The transcript of [FROST](https://www.rfc-editor.org/rfc/rfc9591) threshold signatures,
+//! for the ciphersuites FROST(Ed25519, SHA-512) and FROST(ristretto255, SHA-512), with feature flag `frost`.
+//!
+//! FROST fixes its own hash functions, so this module does not use a duplex sponge:
+//! it provides the hashes $H_1, \dots, H_5$ of each [`Ciphersuite`] and the pieces of RFC 9591 built on them,
+//! each following the corresponding section of the RFC:
+//!
+//! - nonce generation and commitment ([`commit`], §5.1);
+//! - binding factors over the commitment list ([`compute_binding_factors`], §4.4);
+//! - the group commitment and challenge ([`compute_group_commitment`], §4.5, and [`compute_challenge`], §4.6);
+//! - signature shares, their verification, and their aggregation ([`sign`], [`verify_signature_share`], [`aggregate`], §5.2-5.4);
+//! - key generation with a trusted dealer ([`trusted_dealer_keygen`], Appendix C).
+//!
+//! Identifiers are non-zero scalars, serialized in 32 bytes little-endian, as in the RFC.
+//!
+//! ```
+//! use curve25519_dalek::Scalar;
+//! use nimue::plugins::dalek::frost::*;
+//!
+//! type C = FrostRistretto255Sha512;
+//! let mut rng = rand::rngs::OsRng;
+//! let (shares, group_key) = trusted_dealer_keygen::<C>(Scalar::from(42u64), 3, 2, &mut rng).unwrap();
+//!
+//! // round one: signers 1 and 3 commit to their nonces
+//! let signers = [&shares[0], &shares[2]];
+//! let (nonces, commitments): (Vec<_>, Vec<_>) =
+//!     signers.iter().map(|share| commit::<C>(share, &mut rng)).unzip();
+//!
+//! // round two: they sign, and the coordinator aggregates
+//! let message = b"hello";
+//! let signature_shares = signers
+//!     .iter()
+//!     .zip(&nonces)
+//!     .map(|(share, nonce)| sign::<C>(share, group_key, nonce, message, &commitments).unwrap())
+//!     .collect::<Vec<_>>();
+//! let signature = aggregate::<C>(&commitments, message, group_key, &signature_shares).unwrap();
+//! assert!(verify::<C>(group_key, message, &signature).is_ok());
+//! ```
+use core::ops::{Add, Mul, Sub};
+
+use curve25519_dalek::constants::{ED25519_BASEPOINT_POINT, RISTRETTO_BASEPOINT_POINT};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::ristretto::CompressedRistretto;
+use curve25519_dalek::traits::Identity;
+use curve25519_dalek::{EdwardsPoint, RistrettoPoint, Scalar};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+use crate::{IOPatternError, ProofError, ProofResult};
+
+/// A FROST ciphersuite: a prime-order group and the hash functions $H_1, \dots, H_5$.
+pub trait Ciphersuite {
+    type Point: Copy
+        + Eq
+        + Identity
+        + Add<Output = Self::Point>
+        + Sub<Output = Self::Point>
+        + Mul<Scalar, Output = Self::Point>;
+
+    /// The context string of the ciphersuite.
+    const CONTEXT: &'static [u8];
+
+    fn generator() -> Self::Point;
+    fn serialize_point(point: &Self::Point) -> [u8; 32];
+    fn deserialize_point(bytes: &[u8; 32]) -> Option<Self::Point>;
+
+    /// Clear the cofactor, for the verification equation.
+    fn clear_cofactor(point: Self::Point) -> Self::Point;
+
+    fn h1(message: &[u8]) -> Scalar {
+        hash_to_scalar(&[Self::CONTEXT, b"rho", message])
+    }
+
+    fn h2(message: &[u8]) -> Scalar {
+        hash_to_scalar(&[Self::CONTEXT, b"chal", message])
+    }
+
+    fn h3(message: &[u8]) -> Scalar {
+        hash_to_scalar(&[Self::CONTEXT, b"nonce", message])
+    }
+
+    fn h4(message: &[u8]) -> [u8; 64] {
+        hash(&[Self::CONTEXT, b"msg", message])
+    }
+
+    fn h5(message: &[u8]) -> [u8; 64] {
+        hash(&[Self::CONTEXT, b"com", message])
+    }
+}
+
+/// FROST(Ed25519, SHA-512), producing signatures that verify as Ed25519 signatures.
+#[derive(Clone, Copy, Debug)]
+pub struct FrostEd25519Sha512;
+
+impl Ciphersuite for FrostEd25519Sha512 {
+    type Point = EdwardsPoint;
+    const CONTEXT: &'static [u8] = b"FROST-ED25519-SHA512-v1";
+
+    fn generator() -> Self::Point {
+        ED25519_BASEPOINT_POINT
+    }
+
+    fn serialize_point(point: &Self::Point) -> [u8; 32] {
+        point.compress().to_bytes()
+    }
+
+    fn deserialize_point(bytes: &[u8; 32]) -> Option<Self::Point> {
+        let point = CompressedEdwardsY(*bytes).decompress()?;
+        // reject non-canonical encodings, the identity, and points outside of the prime-order subgroup
+        let valid = point.compress().to_bytes() == *bytes
+            && point != EdwardsPoint::identity()
+            && point.is_torsion_free();
+        valid.then_some(point)
+    }
+
+    fn clear_cofactor(point: Self::Point) -> Self::Point {
+        point.mul_by_cofactor()
+    }
+
+    /// As in Ed25519 (SHA-512 without the context string).
+    fn h2(message: &[u8]) -> Scalar {
+        hash_to_scalar(&[message])
+    }
+}
+
+/// FROST(ristretto255, SHA-512).
+#[derive(Clone, Copy, Debug)]
+pub struct FrostRistretto255Sha512;
+
+impl Ciphersuite for FrostRistretto255Sha512 {
+    type Point = RistrettoPoint;
+    const CONTEXT: &'static [u8] = b"FROST-RISTRETTO255-SHA512-v1";
+
+    fn generator() -> Self::Point {
+        RISTRETTO_BASEPOINT_POINT
+    }
+
+    fn serialize_point(point: &Self::Point) -> [u8; 32] {
+        point.compress().to_bytes()
+    }
+
+    fn deserialize_point(bytes: &[u8; 32]) -> Option<Self::Point> {
+        let point = CompressedRistretto(*bytes).decompress()?;
+        (point != RistrettoPoint::identity()).then_some(point)
+    }
+
+    fn clear_cofactor(point: Self::Point) -> Self::Point {
+        point
+    }
+}
+
+fn hash(parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&hash(parts))
+}
+
+/// The secret key share of a participant.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyShare {
+    pub identifier: Scalar,
+    pub secret: Scalar,
+}
+
+/// The secret nonces of a participant, for one signature only.
+#[derive(Clone, Copy, Debug)]
+pub struct Nonces {
+    pub hiding: Scalar,
+    pub binding: Scalar,
+}
+
+/// The commitment of a participant to its nonces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment<P> {
+    pub identifier: Scalar,
+    pub hiding: P,
+    pub binding: P,
+}
+
+/// Generate a nonce from `secret` and fresh randomness (§4.1).
+pub fn nonce_generate<C: Ciphersuite>(
+    secret: &Scalar,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Scalar {
+    let mut random_bytes = [0u8; 32];
+    rng.fill_bytes(&mut random_bytes);
+    let nonce = C::h3(&[&random_bytes[..], secret.as_bytes()].concat());
+    zeroize::Zeroize::zeroize(&mut random_bytes);
+    nonce
+}
+
+/// Generate the nonces of `share` and commit to them (§5.1).
+pub fn commit<C: Ciphersuite>(
+    share: &KeyShare,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> (Nonces, Commitment<C::Point>) {
+    let nonces = Nonces {
+        hiding: nonce_generate::<C>(&share.secret, rng),
+        binding: nonce_generate::<C>(&share.secret, rng),
+    };
+    let commitment = Commitment {
+        identifier: share.identifier,
+        hiding: C::generator() * nonces.hiding,
+        binding: C::generator() * nonces.binding,
+    };
+    (nonces, commitment)
+}
+
+/// Check that the commitments are sorted by (non-zero) identifier, with no duplicates, as required by the RFC.
+fn check_commitment_list<P>(commitments: &[Commitment<P>]) -> ProofResult<()> {
+    if commitments.iter().any(|c| c.identifier == Scalar::ZERO) {
+        return Err(IOPatternError::from("Identifiers must be non-zero").into());
+    }
+    let sorted = commitments.windows(2).all(|pair| {
+        pair[0]
+            .identifier
+            .as_bytes()
+            .iter()
+            .rev()
+            .lt(pair[1].identifier.as_bytes().iter().rev())
+    });
+    if commitments.is_empty() || !sorted {
+        return Err(IOPatternError::from("Commitments must be sorted by identifier").into());
+    }
+    Ok(())
+}
+
+/// The encoding of the commitment list (§4.3).
+pub fn encode_group_commitment_list<C: Ciphersuite>(
+    commitments: &[Commitment<C::Point>],
+) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(commitments.len() * 96);
+    for commitment in commitments {
+        encoded.extend_from_slice(commitment.identifier.as_bytes());
+        encoded.extend_from_slice(&C::serialize_point(&commitment.hiding));
+        encoded.extend_from_slice(&C::serialize_point(&commitment.binding));
+    }
+    encoded
+}
+
+/// The binding factor of each participant of `commitments`, as pairs `(identifier, binding factor)` (§4.4).
+pub fn compute_binding_factors<C: Ciphersuite>(
+    group_key: C::Point,
+    commitments: &[Commitment<C::Point>],
+    message: &[u8],
+) -> Vec<(Scalar, Scalar)> {
+    let mut rho_input_prefix = C::serialize_point(&group_key).to_vec();
+    rho_input_prefix.extend_from_slice(&C::h4(message));
+    rho_input_prefix.extend_from_slice(&C::h5(&encode_group_commitment_list::<C>(commitments)));
+    commitments
+        .iter()
+        .map(|commitment| {
+            let rho_input = [&rho_input_prefix[..], commitment.identifier.as_bytes()].concat();
+            (commitment.identifier, C::h1(&rho_input))
+        })
+        .collect()
+}
+
+fn binding_factor_for(
+    binding_factors: &[(Scalar, Scalar)],
+    identifier: &Scalar,
+) -> ProofResult<Scalar> {
+    binding_factors
+        .iter()
+        .find(|(id, _)| id == identifier)
+        .map(|(_, factor)| *factor)
+        .ok_or_else(|| IOPatternError::from("Participant not in the commitment list").into())
+}
+
+/// The group commitment $R = \sum_i D_i + \rho_i E_i$ (§4.5).
+pub fn compute_group_commitment<C: Ciphersuite>(
+    commitments: &[Commitment<C::Point>],
+    binding_factors: &[(Scalar, Scalar)],
+) -> ProofResult<C::Point> {
+    let mut group_commitment = C::Point::identity();
+    for commitment in commitments {
+        let factor = binding_factor_for(binding_factors, &commitment.identifier)?;
+        group_commitment = group_commitment + commitment.hiding + commitment.binding * factor;
+    }
+    Ok(group_commitment)
+}
+
+/// The challenge $c = H_2(R \Vert Y \Vert m)$ (§4.6).
+pub fn compute_challenge<C: Ciphersuite>(
+    group_commitment: C::Point,
+    group_key: C::Point,
+    message: &[u8],
+) -> Scalar {
+    let input = [
+        &C::serialize_point(&group_commitment)[..],
+        &C::serialize_point(&group_key)[..],
+        message,
+    ]
+    .concat();
+    C::h2(&input)
+}
+
+/// The Lagrange coefficient of `identifier` among the participants of `commitments`, at zero (§4.2).
+pub fn derive_interpolating_value<P>(
+    commitments: &[Commitment<P>],
+    identifier: &Scalar,
+) -> ProofResult<Scalar> {
+    if !commitments.iter().any(|c| c.identifier == *identifier) {
+        return Err(IOPatternError::from("Participant not in the commitment list").into());
+    }
+    let (numerator, denominator) = commitments
+        .iter()
+        .filter(|c| c.identifier != *identifier)
+        .fold((Scalar::ONE, Scalar::ONE), |(num, den), c| {
+            (num * c.identifier, den * (c.identifier - identifier))
+        });
+    Ok(numerator * denominator.invert())
+}
+
+/// The signature share of `share`, with the nonces `nonces` committed in `commitments` (§5.2).
+///
+/// Return an error if the commitments are not sorted by identifier, or do not include the signer.
+pub fn sign<C: Ciphersuite>(
+    share: &KeyShare,
+    group_key: C::Point,
+    nonces: &Nonces,
+    message: &[u8],
+    commitments: &[Commitment<C::Point>],
+) -> ProofResult<Scalar> {
+    check_commitment_list(commitments)?;
+    let binding_factors = compute_binding_factors::<C>(group_key, commitments, message);
+    let binding_factor = binding_factor_for(&binding_factors, &share.identifier)?;
+    let group_commitment = compute_group_commitment::<C>(commitments, &binding_factors)?;
+    let lambda = derive_interpolating_value(commitments, &share.identifier)?;
+    let challenge = compute_challenge::<C>(group_commitment, group_key, message);
+    Ok(nonces.hiding + nonces.binding * binding_factor + lambda * share.secret * challenge)
+}
+
+/// Verify the signature share of `identifier`, with public key share `public_share` (§5.4).
+///
+/// Return an error if the participant misbehaved.
+pub fn verify_signature_share<C: Ciphersuite>(
+    identifier: &Scalar,
+    public_share: C::Point,
+    signature_share: &Scalar,
+    commitments: &[Commitment<C::Point>],
+    group_key: C::Point,
+    message: &[u8],
+) -> ProofResult<()> {
+    check_commitment_list(commitments)?;
+    let commitment = commitments
+        .iter()
+        .find(|c| c.identifier == *identifier)
+        .ok_or_else(|| IOPatternError::from("Participant not in the commitment list"))?;
+    let binding_factors = compute_binding_factors::<C>(group_key, commitments, message);
+    let binding_factor = binding_factor_for(&binding_factors, identifier)?;
+    let group_commitment = compute_group_commitment::<C>(commitments, &binding_factors)?;
+    let challenge = compute_challenge::<C>(group_commitment, group_key, message);
+    let lambda = derive_interpolating_value(commitments, identifier)?;
+
+    let commitment_share = commitment.hiding + commitment.binding * binding_factor;
+    if C::generator() * *signature_share == commitment_share + public_share * (challenge * lambda) {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidProof)
+    }
+}
+
+/// Aggregate the signature shares into a 64-byte signature $(R, z)$ (§5.3).
+pub fn aggregate<C: Ciphersuite>(
+    commitments: &[Commitment<C::Point>],
+    message: &[u8],
+    group_key: C::Point,
+    signature_shares: &[Scalar],
+) -> ProofResult<[u8; 64]> {
+    check_commitment_list(commitments)?;
+    let binding_factors = compute_binding_factors::<C>(group_key, commitments, message);
+    let group_commitment = compute_group_commitment::<C>(commitments, &binding_factors)?;
+    let z = signature_shares.iter().sum::<Scalar>();
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&C::serialize_point(&group_commitment));
+    signature[32..].copy_from_slice(z.as_bytes());
+    Ok(signature)
+}
+
+/// Verify a signature under `group_key`, with the cofactored equation of the RFC (§6).
+pub fn verify<C: Ciphersuite>(
+    group_key: C::Point,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> ProofResult<()> {
+    let group_commitment = C::deserialize_point(signature[..32].try_into().unwrap())
+        .ok_or(ProofError::SerializationError)?;
+    let z = Option::<Scalar>::from(Scalar::from_canonical_bytes(
+        signature[32..].try_into().unwrap(),
+    ))
+    .ok_or(ProofError::SerializationError)?;
+    let challenge = compute_challenge::<C>(group_commitment, group_key, message);
+    let lhs = C::clear_cofactor(C::generator() * z);
+    let rhs = C::clear_cofactor(group_commitment + group_key * challenge);
+    if lhs == rhs {
+        Ok(())
+    } else {
+        Err(ProofError::InvalidProof)
+    }
+}
+
+/// Split `secret` into `max_participants` shares, any `min_participants` of which can sign (Appendix C).
+///
+/// Return the shares, with identifiers $1, \dots, n$, and the group public key.
+pub fn trusted_dealer_keygen<C: Ciphersuite>(
+    secret: Scalar,
+    max_participants: u64,
+    min_participants: u64,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> ProofResult<(Vec<KeyShare>, C::Point)> {
+    if min_participants < 2 || min_participants > max_participants {
+        return Err(IOPatternError::from("Invalid threshold").into());
+    }
+    let mut coefficients = vec![secret];
+    for _ in 1..min_participants {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        coefficients.push(Scalar::from_bytes_mod_order_wide(&bytes));
+    }
+    let shares = (1..=max_participants)
+        .map(|i| {
+            let identifier = Scalar::from(i);
+            // Horner's method
+            let secret = coefficients
+                .iter()
+                .rev()
+                .fold(Scalar::ZERO, |acc, coefficient| {
+                    acc * identifier + coefficient
+                });
+            KeyShare { identifier, secret }
+        })
+        .collect();
+    Ok((shares, C::generator() * secret))
+}
@@ -0,0 +1,21 @@
+use curve25519_dalek::{RistrettoPoint, Scalar};
+
+use super::common::WIDE_SCALAR_BYTES;
+use super::{FieldIOPattern, GroupIOPattern};
+use crate::{ByteIOPattern, DuplexHash, IOPattern};
+
+impl<H: DuplexHash> FieldIOPattern<Scalar> for IOPattern<H> {
+    fn add_scalars(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * 32, label)
+    }
+
+    fn challenge_scalars(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(count * WIDE_SCALAR_BYTES, label)
+    }
+}
+
+impl<H: DuplexHash> GroupIOPattern<RistrettoPoint> for IOPattern<H> {
+    fn add_points(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * 32, label)
+    }
+}
@@ -0,0 +1,63 @@
+//! This adds support for [curve25519-dalek](https://github.com/dalek-cryptography/curve25519-dalek)'s
+//! Ristretto group, with feature flag `dalek`.
+//! With feature flag `ed448`, the same traits also support Ed448-Goldilocks and Decaf448
+//! (see [`Ed448Scalar`], [`Decaf448Point`], and [`Ed448Point`]).
+//!
+//! Points and scalars are encoded in 32 bytes, as canonical (compressed) encodings.
+//! Scalar challenges are obtained by reducing 64 bytes modulo the group order
+//! (as [`Scalar::from_bytes_mod_order_wide`]).
+//!
+//! ```
+//! use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT as G, RistrettoPoint, Scalar};
+//! use nimue::plugins::dalek::*;
+//! use nimue::{DefaultHash, IOPattern};
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝");
+//! let io = GroupIOPattern::<RistrettoPoint>::add_points(io, 1, "commitment");
+//! let io = FieldIOPattern::<Scalar>::challenge_scalars(io, 1, "challenge");
+//!
+//! let mut merlin = io.to_merlin();
+//! merlin.add_points(&[G]).unwrap();
+//! let [c]: [Scalar; 1] = merlin.challenge_scalars().unwrap();
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let [commitment]: [RistrettoPoint; 1] = arthur.next_points().unwrap();
+//! let [c_arthur]: [Scalar; 1] = arthur.challenge_scalars().unwrap();
+//! assert_eq!((commitment, c_arthur), (G, c));
+//! ```
+mod common;
+mod iopattern;
+mod reader;
+mod writer;
+
+#[cfg(feature = "ed448")]
+mod ed448;
+#[cfg(feature = "frost")]
+pub mod frost;
+
+/// Tests for dalek.
+#[cfg(test)]
+mod tests;
+
+pub use curve25519_dalek::{RistrettoPoint, Scalar};
+#[cfg(feature = "ed448")]
+pub use ed448_goldilocks_plus::{
+    DecafPoint as Decaf448Point, EdwardsPoint as Ed448Point, Scalar as Ed448Scalar,
+};
+
+/// Scalar fields supported by this plugin.
+pub trait DalekField: Copy + Default + core::ops::MulAssign {
+    const ONE: Self;
+}
+
+impl DalekField for Scalar {
+    const ONE: Self = Scalar::ONE;
+}
+
+/// Groups supported by this plugin.
+pub trait DalekGroup: Copy + Default {}
+
+impl DalekGroup for RistrettoPoint {}
+
+super::traits::field_traits!(DalekField);
+super::traits::group_traits!(DalekGroup, Scalar: DalekField);
@@ -0,0 +1,31 @@
+use curve25519_dalek::ristretto::CompressedRistretto;
+use curve25519_dalek::{RistrettoPoint, Scalar};
+
+use super::{FieldReader, GroupReader};
+use crate::{Arthur, ByteReader, DuplexHash, ProofError, ProofResult, UnitTranscript};
+
+impl<H: DuplexHash> FieldReader<Scalar> for Arthur<'_, H> {
+    fn fill_next_scalars(&mut self, output: &mut [Scalar]) -> ProofResult<()> {
+        let mut buf = [0u8; 32];
+        for o in output.iter_mut() {
+            self.fill_next_bytes(&mut buf)?;
+            *o = Option::from(Scalar::from_canonical_bytes(buf))
+                .ok_or(ProofError::SerializationError)?;
+        }
+        Ok(())
+    }
+}
+
+impl<H: DuplexHash> GroupReader<RistrettoPoint> for Arthur<'_, H> {
+    fn fill_next_points(&mut self, output: &mut [RistrettoPoint]) -> ProofResult<()> {
+        let mut buf = [0u8; 32];
+        for o in output.iter_mut() {
+            self.read_element(&mut buf)?;
+            self.public_units(&buf)?;
+            *o = CompressedRistretto(buf)
+                .decompress()
+                .ok_or(ProofError::SerializationError)?;
+        }
+        Ok(())
+    }
+}
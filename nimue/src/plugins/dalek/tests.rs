@@ -0,0 +1,167 @@
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::{RistrettoPoint, Scalar};
+
+use super::{
+    FieldChallenges, FieldIOPattern, FieldReader, FieldWriter, GroupIOPattern, GroupReader,
+    GroupWriter,
+};
+use crate::{DefaultHash, IOPattern};
+
+#[test]
+fn test_schnorr_transcript() {
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = GroupIOPattern::<RistrettoPoint>::add_points(io_pattern, 1, "commitment");
+    let io_pattern = FieldIOPattern::<Scalar>::challenge_scalars(io_pattern, 1, "challenge");
+    let io_pattern = FieldIOPattern::<Scalar>::add_scalars(io_pattern, 1, "response");
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A32commitment\0S64challenge\0A32response"
+    );
+
+    let x = Scalar::from(0x42u64);
+    let k = Scalar::from(0x1337u64);
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&[RISTRETTO_BASEPOINT_POINT * k]).unwrap();
+    let [c]: [Scalar; 1] = merlin.challenge_scalars().unwrap();
+    merlin.add_scalars(&[k + c * x]).unwrap();
+    assert_eq!(merlin.transcript().len(), 64);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [commitment]: [RistrettoPoint; 1] = arthur.next_points().unwrap();
+    let [c_arthur]: [Scalar; 1] = arthur.challenge_scalars().unwrap();
+    let [response]: [Scalar; 1] = arthur.next_scalars().unwrap();
+    assert_eq!(c, c_arthur);
+    assert_eq!(
+        RISTRETTO_BASEPOINT_POINT * response,
+        commitment + RISTRETTO_BASEPOINT_POINT * x * c
+    );
+}
+
+#[test]
+fn test_invalid_encodings() {
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = GroupIOPattern::<RistrettoPoint>::add_points(io_pattern, 1, "point");
+    let io_pattern = FieldIOPattern::<Scalar>::add_scalars(io_pattern, 1, "scalar");
+
+    // 0xff..ff is neither a valid Ristretto encoding nor a canonical scalar
+    let transcript = [0xff; 64];
+    let mut arthur = io_pattern.to_arthur(&transcript);
+    assert!(GroupReader::<RistrettoPoint>::next_points::<1>(&mut arthur).is_err());
+
+    let mut transcript = RISTRETTO_BASEPOINT_POINT.compress().to_bytes().to_vec();
+    transcript.extend([0xff; 32]);
+    let mut arthur = io_pattern.to_arthur(&transcript);
+    let [_]: [RistrettoPoint; 1] = arthur.next_points().unwrap();
+    assert!(FieldReader::<Scalar>::next_scalars::<1>(&mut arthur).is_err());
+}
+
+#[cfg(feature = "ed448")]
+#[test]
+fn test_decaf448_schnorr_transcript() {
+    use super::{Decaf448Point, Ed448Scalar};
+    use group::Group;
+
+    let io_pattern = IOPattern::<DefaultHash>::new("github.com/mmaker/nimue");
+    let io_pattern = GroupIOPattern::<Decaf448Point>::add_points(io_pattern, 1, "commitment");
+    let io_pattern = FieldIOPattern::<Ed448Scalar>::challenge_scalars(io_pattern, 1, "challenge");
+    let io_pattern = FieldIOPattern::<Ed448Scalar>::add_scalars(io_pattern, 1, "response");
+    assert_eq!(
+        io_pattern.as_bytes(),
+        b"github.com/mmaker/nimue\0A56commitment\0S114challenge\0A57response"
+    );
+
+    let g = Decaf448Point::generator();
+    let x = Ed448Scalar::from(0x42u64);
+    let k = Ed448Scalar::from(0x1337u64);
+    let mut merlin = io_pattern.to_merlin();
+    merlin.add_points(&[g * k]).unwrap();
+    let [c]: [Ed448Scalar; 1] = merlin.challenge_scalars().unwrap();
+    merlin.add_scalars(&[k + c * x]).unwrap();
+    assert_eq!(merlin.transcript().len(), 56 + 57);
+
+    let mut arthur = io_pattern.to_arthur(merlin.transcript());
+    let [commitment]: [Decaf448Point; 1] = arthur.next_points().unwrap();
+    let [c_arthur]: [Ed448Scalar; 1] = arthur.challenge_scalars().unwrap();
+    let [response]: [Ed448Scalar; 1] = arthur.next_scalars().unwrap();
+    assert_eq!(c, c_arthur);
+    assert_eq!(g * response, commitment + g * x * c);
+
+    // 0xff..ff is not a canonical scalar
+    let mut transcript = merlin.transcript()[..56].to_vec();
+    transcript.extend([0xff; 57]);
+    let mut arthur = io_pattern.to_arthur(&transcript);
+    let [_]: [Decaf448Point; 1] = arthur.next_points().unwrap();
+    let [_]: [Ed448Scalar; 1] = arthur.challenge_scalars().unwrap();
+    assert!(FieldReader::<Ed448Scalar>::next_scalars::<1>(&mut arthur).is_err());
+}
+
+#[cfg(feature = "frost")]
+#[test]
+fn test_frost_signing() {
+    use super::frost::*;
+
+    fn check<C: Ciphersuite>() {
+        let mut rng = rand::rngs::OsRng;
+        let secret = Scalar::from(0x42u64);
+        let (shares, group_key) = trusted_dealer_keygen::<C>(secret, 5, 3, &mut rng).unwrap();
+        assert!(trusted_dealer_keygen::<C>(secret, 2, 3, &mut rng).is_err());
+
+        let signers = [&shares[0], &shares[2], &shares[4]];
+        let (nonces, commitments): (Vec<_>, Vec<_>) = signers
+            .iter()
+            .map(|share| commit::<C>(share, &mut rng))
+            .unzip();
+        // any 3 of the 5 shares interpolate the secret
+        let interpolated = signers
+            .iter()
+            .map(|share| {
+                derive_interpolating_value(&commitments, &share.identifier).unwrap() * share.secret
+            })
+            .sum::<Scalar>();
+        assert_eq!(interpolated, secret);
+
+        let message = b"test message";
+        let mut signature_shares = signers
+            .iter()
+            .zip(&nonces)
+            .map(|(share, nonces)| {
+                sign::<C>(share, group_key, nonces, message, &commitments).unwrap()
+            })
+            .collect::<Vec<_>>();
+        for (share, signature_share) in signers.iter().zip(&signature_shares) {
+            let public_share = C::generator() * share.secret;
+            assert!(verify_signature_share::<C>(
+                &share.identifier,
+                public_share,
+                signature_share,
+                &commitments,
+                group_key,
+                message
+            )
+            .is_ok());
+        }
+        let signature =
+            aggregate::<C>(&commitments, message, group_key, &signature_shares).unwrap();
+        assert!(verify::<C>(group_key, message, &signature).is_ok());
+        assert!(verify::<C>(group_key, b"another message", &signature).is_err());
+
+        // misbehaving signers are detected
+        signature_shares[1] += Scalar::ONE;
+        assert!(verify_signature_share::<C>(
+            &signers[1].identifier,
+            C::generator() * signers[1].secret,
+            &signature_shares[1],
+            &commitments,
+            group_key,
+            message
+        )
+        .is_err());
+        // commitments must be sorted
+        let mut unsorted = commitments.clone();
+        unsorted.swap(0, 1);
+        assert!(sign::<C>(signers[0], group_key, &nonces[0], message, &unsorted).is_err());
+    }
+
+    check::<FrostEd25519Sha512>();
+    check::<FrostRistretto255Sha512>();
+}
@@ -0,0 +1,31 @@
+use curve25519_dalek::{RistrettoPoint, Scalar};
+use rand::{CryptoRng, RngCore};
+
+use super::{FieldPublic, FieldWriter, GroupPublic, GroupWriter};
+use crate::{DuplexHash, Merlin, ProofResult};
+
+impl<H, R> FieldWriter<Scalar> for Merlin<H, u8, R>
+where
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    fn add_scalars(&mut self, input: &[Scalar]) -> ProofResult<()> {
+        let serialized = self.public_scalars(input)?;
+        self.transcript.extend_from_slice(&serialized);
+        Ok(())
+    }
+}
+
+impl<H, R> GroupWriter<RistrettoPoint> for Merlin<H, u8, R>
+where
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    fn add_points(&mut self, input: &[RistrettoPoint]) -> ProofResult<()> {
+        let serialized = self.public_points(input)?;
+        serialized
+            .chunks(32)
+            .try_for_each(|element| self.write_element(element))?;
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
-use crate::{ByteChallenges, BytePublic, ProofResult};
+use crate::{ByteChallenges, BytePublic, Endianness, ProofResult, UnitEncoding};
 use group::ff::PrimeField;
+use group::{Group, GroupEncoding};
 
-use super::{FieldChallenges, FieldPublic};
-use crate::plugins::bytes_uniform_modp;
+use super::{FieldChallenges, FieldPublic, GroupPublic};
+use crate::plugins::bytes_challenge_modp;
 
 /// Convert a byte array to a field element.
 ///
@@ -19,13 +20,16 @@ fn from_bytes_mod_order<F: PrimeField>(bytes: &[u8]) -> F {
 impl<F, T> FieldChallenges<F> for T
 where
     F: PrimeField,
-    T: ByteChallenges,
+    T: ByteChallenges + UnitEncoding,
 {
     fn fill_challenge_scalars(&mut self, output: &mut [F]) -> ProofResult<()> {
-        let mut buf = vec![0; bytes_uniform_modp(F::NUM_BITS)];
+        let mut buf = vec![0; bytes_challenge_modp(F::NUM_BITS, self.hash_to_field())];
 
         for o in output {
             self.fill_challenge_bytes(&mut buf)?;
+            if self.challenge_endianness() == Endianness::Little {
+                buf.reverse();
+            }
             *o = from_bytes_mod_order(&buf);
         }
 
@@ -47,3 +51,21 @@ where
         Ok(buf)
     }
 }
+
+impl<G, T> GroupPublic<G> for T
+where
+    G: Group + GroupEncoding,
+    G::Repr: AsRef<[u8]>,
+    T: BytePublic,
+{
+    type Repr = Vec<u8>;
+
+    fn public_points(&mut self, input: &[G]) -> ProofResult<Self::Repr> {
+        let mut buf = Vec::new();
+        for p in input.iter() {
+            buf.extend_from_slice(<G as GroupEncoding>::to_bytes(p).as_ref());
+        }
+        self.public_bytes(&buf)?;
+        Ok(buf)
+    }
+}
@@ -1,8 +1,8 @@
 use group::{ff::PrimeField, Group, GroupEncoding};
 
 use crate::{
-    plugins::{bytes_modp, bytes_uniform_modp},
-    ByteIOPattern, DuplexHash, IOPattern,
+    plugins::{bytes_challenge_modp, bytes_modp},
+    ByteIOPattern, DuplexHash, IOPattern, UnitEncoding,
 };
 
 use super::{FieldIOPattern, GroupIOPattern};
@@ -17,7 +17,8 @@ where
     }
 
     fn challenge_scalars(self, count: usize, label: &str) -> Self {
-        self.challenge_bytes(count * bytes_uniform_modp(F::NUM_BITS), label)
+        let size = bytes_challenge_modp(F::NUM_BITS, self.hash_to_field());
+        self.challenge_bytes(count * size, label)
     }
 }
 
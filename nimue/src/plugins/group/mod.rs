@@ -1,4 +1,11 @@
-//! This adds support also for [curve25519-dalek](https://github.com/dalek-cryptography/curve25519-dalek) with feature flag `group`.
+//! This adds support for any prime field implementing [`ff::PrimeField`](group::ff::PrimeField)
+//! and any group implementing [`Group`](group::Group) and [`GroupEncoding`](group::GroupEncoding),
+//! with feature flag `group`.
+//! This includes the curves of the zkcrypto ecosystem (e.g., `bls12_381`, `jubjub`, `pasta_curves`),
+//! and [curve25519-dalek](https://github.com/dalek-cryptography/curve25519-dalek).
+//!
+//! Scalars and points are written using their canonical representation ([`PrimeField::to_repr`](group::ff::PrimeField::to_repr)
+//! and [`GroupEncoding::to_bytes`](group::GroupEncoding::to_bytes)), and are validated when read.
 mod common;
 mod iopattern;
 mod reader;
@@ -1,17 +1,34 @@
-use super::FieldReader;
-use crate::{Arthur, ByteReader, DuplexHash, ProofError};
+use super::{FieldReader, GroupReader};
+use crate::{Arthur, ByteReader, DuplexHash, ProofError, UnitTranscript};
 use group::ff::PrimeField;
+use group::{Group, GroupEncoding};
 
-impl<'a, F, H, const N: usize> FieldReader<F> for Arthur<'a, H>
+impl<F, H> FieldReader<F> for Arthur<'_, H>
 where
     H: DuplexHash,
-    F: PrimeField<Repr = [u8; N]>,
+    F: PrimeField,
 {
     fn fill_next_scalars(&mut self, output: &mut [F]) -> crate::ProofResult<()> {
-        let mut buf = [0u8; N];
+        let mut repr = F::Repr::default();
         for o in output.iter_mut() {
-            self.fill_next_bytes(&mut buf)?;
-            *o = F::from_repr_vartime(buf).ok_or(ProofError::SerializationError)?;
+            self.fill_next_bytes(repr.as_mut())?;
+            *o = Option::from(F::from_repr(repr)).ok_or(ProofError::SerializationError)?;
+        }
+        Ok(())
+    }
+}
+
+impl<G, H> GroupReader<G> for Arthur<'_, H>
+where
+    H: DuplexHash,
+    G: Group + GroupEncoding + Default,
+{
+    fn fill_next_points(&mut self, output: &mut [G]) -> crate::ProofResult<()> {
+        let mut repr = G::Repr::default();
+        for o in output.iter_mut() {
+            self.read_element(repr.as_mut())?;
+            self.public_units(repr.as_ref())?;
+            *o = Option::from(G::from_bytes(&repr)).ok_or(ProofError::SerializationError)?;
         }
         Ok(())
     }
@@ -2,7 +2,7 @@ use group::{ff::PrimeField, Group, GroupEncoding};
 use rand::{CryptoRng, RngCore};
 
 use super::{FieldPublic, FieldWriter, GroupPublic, GroupWriter};
-use crate::{ByteWriter, DuplexHash, Merlin, ProofResult};
+use crate::{DuplexHash, Merlin, ProofResult};
 
 impl<F, H, R> FieldWriter<F> for Merlin<H, u8, R>
 where
@@ -12,29 +12,11 @@ where
 {
     fn add_scalars(&mut self, input: &[F]) -> ProofResult<()> {
         let serialized = self.public_scalars(input);
-        self.transcript.extend(serialized?);
+        self.transcript.extend_from_slice(&serialized?);
         Ok(())
     }
 }
 
-impl<G, H, R> GroupPublic<G> for Merlin<H, u8, R>
-where
-    G: Group + GroupEncoding,
-    G::Repr: AsRef<[u8]>,
-    H: DuplexHash,
-    R: RngCore + CryptoRng,
-{
-    type Repr = Vec<u8>;
-    fn public_points(&mut self, input: &[G]) -> crate::ProofResult<Self::Repr> {
-        let mut buf = Vec::new();
-        for p in input.iter() {
-            buf.extend_from_slice(&<G as GroupEncoding>::to_bytes(p).as_ref());
-        }
-        self.add_bytes(&buf)?;
-        Ok(buf)
-    }
-}
-
 impl<G, H, R> GroupWriter<G> for Merlin<H, u8, R>
 where
     G: Group + GroupEncoding,
@@ -43,8 +25,11 @@ where
     R: RngCore + CryptoRng,
 {
     fn add_points(&mut self, input: &[G]) -> crate::ProofResult<()> {
-        let serialized = self.public_points(input);
-        self.transcript.extend(serialized?);
+        let serialized = self.public_points(input)?;
+        let size = G::Repr::default().as_ref().len();
+        serialized
+            .chunks(size)
+            .try_for_each(|element| self.write_element(element))?;
         Ok(())
     }
 }
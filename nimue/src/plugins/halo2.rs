@@ -0,0 +1,221 @@
+//! A transcript reproducing [halo2](https://github.com/zcash/halo2)'s `Blake2bWrite`/`Blake2bRead`
+//! with `Challenge255` challenges, with feature flag `halo2`.
+//!
+//! Halo2's transcript is not a duplex sponge: it is a running BLAKE2b-512 hash
+//! (personalized with `Halo2-Transcript`), where each operation is prefixed with a byte
+//! (`0` for challenges, `1` for points, `2` for scalars),
+//! points are hashed as their affine coordinates and written compressed,
+//! and challenges are obtained by reducing the 64-byte digest modulo the group order.
+//! Since this cannot be expressed with an [`IOPattern`](crate::IOPattern),
+//! [`Halo2Writer`] and [`Halo2Reader`] are standalone, with the same conventions as [`Merlin`](crate::Merlin) and [`Arthur`](crate::Arthur):
+//! the writer accumulates the protocol transcript, and the reader consumes it and validates the encodings.
+//!
+//! ```
+//! use nimue::plugins::halo2::{Halo2Reader, Halo2Writer};
+//! use pasta_curves::group::prime::PrimeCurveAffine;
+//! use pasta_curves::EqAffine;
+//!
+//! let g = EqAffine::generator();
+//! let mut writer = Halo2Writer::<EqAffine>::new();
+//! writer.write_point(g).unwrap();
+//! let c = writer.squeeze_challenge();
+//! writer.write_scalar(c);
+//! let proof = writer.finalize();
+//!
+//! let mut reader = Halo2Reader::<EqAffine>::new(&proof);
+//! assert_eq!(reader.read_point().unwrap(), g);
+//! assert_eq!(reader.squeeze_challenge(), c);
+//! assert_eq!(reader.read_scalar().unwrap(), c);
+//! ```
+use core::marker::PhantomData;
+
+use blake2b_simd::{Params, State};
+use pasta_curves::arithmetic::CurveAffine;
+use pasta_curves::group::ff::{FromUniformBytes, PrimeField};
+use pasta_curves::group::GroupEncoding;
+
+use crate::{IOPatternError, ProofError, ProofResult};
+
+/// The BLAKE2b personalization string of halo2 transcripts.
+pub const PERSONALIZATION: &[u8; 16] = b"Halo2-Transcript";
+
+/// Prefix byte of a challenge.
+const PREFIX_CHALLENGE: u8 = 0;
+/// Prefix byte of a point.
+const PREFIX_POINT: u8 = 1;
+/// Prefix byte of a scalar.
+const PREFIX_SCALAR: u8 = 2;
+
+/// The hash state shared by [`Halo2Writer`] and [`Halo2Reader`].
+#[derive(Clone, Debug)]
+struct Blake2bState<C> {
+    state: State,
+    _curve: PhantomData<C>,
+}
+
+impl<C: CurveAffine> Blake2bState<C>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    fn new() -> Self {
+        let state = Params::new()
+            .hash_length(64)
+            .personal(PERSONALIZATION)
+            .to_state();
+        Self {
+            state,
+            _curve: PhantomData,
+        }
+    }
+
+    fn common_point(&mut self, point: C) -> ProofResult<()> {
+        let coordinates: Option<_> = point.coordinates().into();
+        let coordinates =
+            coordinates.ok_or_else(|| IOPatternError::from("Cannot hash the point at infinity"))?;
+        self.state.update(&[PREFIX_POINT]);
+        self.state.update(coordinates.x().to_repr().as_ref());
+        self.state.update(coordinates.y().to_repr().as_ref());
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) {
+        self.state.update(&[PREFIX_SCALAR]);
+        self.state.update(scalar.to_repr().as_ref());
+    }
+
+    fn squeeze_challenge(&mut self) -> C::Scalar {
+        self.state.update(&[PREFIX_CHALLENGE]);
+        let digest: [u8; 64] = self.state.clone().finalize().as_bytes().try_into().unwrap();
+        C::Scalar::from_uniform_bytes(&digest)
+    }
+}
+
+/// The prover side of a halo2 Blake2b transcript, as `Blake2bWrite<_, C, Challenge255<C>>`.
+#[derive(Clone, Debug)]
+pub struct Halo2Writer<C> {
+    state: Blake2bState<C>,
+    transcript: Vec<u8>,
+}
+
+impl<C: CurveAffine> Default for Halo2Writer<C>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    fn default() -> Self {
+        Self {
+            state: Blake2bState::new(),
+            transcript: Vec::new(),
+        }
+    }
+}
+
+impl<C: CurveAffine> Halo2Writer<C>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hash `point` without writing it in the protocol transcript.
+    ///
+    /// Return an error if `point` is the point at infinity.
+    pub fn common_point(&mut self, point: C) -> ProofResult<()> {
+        self.state.common_point(point)
+    }
+
+    /// Hash `scalar` without writing it in the protocol transcript.
+    pub fn common_scalar(&mut self, scalar: C::Scalar) {
+        self.state.common_scalar(scalar)
+    }
+
+    /// Hash `point` and write its compressed encoding to the protocol transcript.
+    pub fn write_point(&mut self, point: C) -> ProofResult<()> {
+        self.state.common_point(point)?;
+        self.transcript.extend_from_slice(point.to_bytes().as_ref());
+        Ok(())
+    }
+
+    /// Hash `scalar` and write its canonical encoding to the protocol transcript.
+    pub fn write_scalar(&mut self, scalar: C::Scalar) {
+        self.state.common_scalar(scalar);
+        self.transcript.extend_from_slice(scalar.to_repr().as_ref());
+    }
+
+    pub fn squeeze_challenge(&mut self) -> C::Scalar {
+        self.state.squeeze_challenge()
+    }
+
+    /// The protocol transcript written so far.
+    pub fn transcript(&self) -> &[u8] {
+        &self.transcript
+    }
+
+    /// Return the protocol transcript, i.e. the halo2 proof.
+    pub fn finalize(self) -> Vec<u8> {
+        self.transcript
+    }
+}
+
+/// The verifier side of a halo2 Blake2b transcript, as `Blake2bRead<_, C, Challenge255<C>>`.
+#[derive(Clone, Debug)]
+pub struct Halo2Reader<'a, C> {
+    state: Blake2bState<C>,
+    transcript: &'a [u8],
+}
+
+impl<'a, C: CurveAffine> Halo2Reader<'a, C>
+where
+    C::Scalar: FromUniformBytes<64>,
+{
+    pub fn new(transcript: &'a [u8]) -> Self {
+        Self {
+            state: Blake2bState::new(),
+            transcript,
+        }
+    }
+
+    /// Hash `point` without reading it from the protocol transcript.
+    pub fn common_point(&mut self, point: C) -> ProofResult<()> {
+        self.state.common_point(point)
+    }
+
+    /// Hash `scalar` without reading it from the protocol transcript.
+    pub fn common_scalar(&mut self, scalar: C::Scalar) {
+        self.state.common_scalar(scalar)
+    }
+
+    /// Read a compressed point from the protocol transcript and hash it.
+    pub fn read_point(&mut self) -> ProofResult<C> {
+        let mut repr = C::Repr::default();
+        self.fill_next(repr.as_mut())?;
+        let point: Option<C> = C::from_bytes(&repr).into();
+        let point = point.ok_or(ProofError::SerializationError)?;
+        self.state.common_point(point)?;
+        Ok(point)
+    }
+
+    /// Read a canonically-encoded scalar from the protocol transcript and hash it.
+    pub fn read_scalar(&mut self) -> ProofResult<C::Scalar> {
+        let mut repr = <C::Scalar as PrimeField>::Repr::default();
+        self.fill_next(repr.as_mut())?;
+        let scalar: Option<C::Scalar> = C::Scalar::from_repr(repr).into();
+        let scalar = scalar.ok_or(ProofError::SerializationError)?;
+        self.state.common_scalar(scalar);
+        Ok(scalar)
+    }
+
+    pub fn squeeze_challenge(&mut self) -> C::Scalar {
+        self.state.squeeze_challenge()
+    }
+
+    fn fill_next(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        if self.transcript.len() < output.len() {
+            return Err("Insufficient transcript remaining".into());
+        }
+        let (head, tail) = self.transcript.split_at(output.len());
+        output.copy_from_slice(head);
+        self.transcript = tail;
+        Ok(())
+    }
+}
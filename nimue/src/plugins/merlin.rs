@@ -0,0 +1,134 @@
+//! Bridges between nimue and dalek's [merlin](https://github.com/dalek-cryptography/merlin) transcripts,
+//! with feature flag `merlin`.
+//!
+//! - [`MerlinBridge`] implements nimue's [`UnitTranscript`] (and thus [`BytePublic`] and [`ByteChallenges`],
+//!   and the plugins' challenge traits built upon them) on top of a [`merlin::Transcript`].
+//!   For instance, the `dalek` plugin's scalar challenges squeezed from a [`MerlinBridge`]
+//!   coincide with those obtained with `challenge_bytes` and `Scalar::from_bytes_mod_order_wide`, as done by Bulletproofs.
+//! - [`MerlinTranscript`] exposes merlin's `append_message`/`challenge_bytes` interface,
+//!   and is implemented both by [`merlin::Transcript`] and by nimue's [`Merlin`](crate::Merlin) and [`Arthur`](crate::Arthur),
+//!   so code written against merlin can be made generic and migrate gradually.
+//!   With nimue, labels are ignored at runtime: messages and challenges must instead be declared in the [`IOPattern`](crate::IOPattern)
+//!   (with [`ByteIOPattern::add_bytes`](crate::ByteIOPattern::add_bytes) and [`ByteIOPattern::challenge_bytes`](crate::ByteIOPattern::challenge_bytes)),
+//!   and messages must have the declared length.
+//!
+//! ```
+//! use nimue::plugins::merlin::MerlinTranscript;
+//! use nimue::{ByteIOPattern, DefaultHash, IOPattern};
+//!
+//! fn prove(transcript: &mut impl MerlinTranscript) -> [u8; 16] {
+//!     transcript.append_message(b"commitment", b"hello");
+//!     let mut challenge = [0u8; 16];
+//!     transcript.challenge_bytes(b"challenge", &mut challenge);
+//!     challenge
+//! }
+//!
+//! // the same code runs on top of merlin...
+//! prove(&mut merlin::Transcript::new(b"example"));
+//! // ... and of nimue
+//! let io = IOPattern::<DefaultHash>::new("example")
+//!     .add_bytes(5, "commitment")
+//!     .challenge_bytes(16, "challenge");
+//! prove(&mut io.to_merlin());
+//! ```
+use crate::{ByteChallenges, BytePublic, IOPatternError, UnitTranscript};
+
+/// The label used by [`MerlinBridge`] for absorbs, if none is given.
+pub const DEFAULT_MESSAGE_LABEL: &[u8] = b"nimue-message";
+/// The label used by [`MerlinBridge`] for squeezes, if none is given.
+pub const DEFAULT_CHALLENGE_LABEL: &[u8] = b"nimue-challenge";
+
+/// A nimue byte transcript, backed by a [`merlin::Transcript`].
+///
+/// There is no IO pattern: operations are forwarded to merlin (which takes care of framing them) and thus never fail.
+/// Each absorb is a merlin message, and each squeeze is a merlin challenge, with the labels of the bridge.
+pub struct MerlinBridge {
+    transcript: merlin::Transcript,
+    message_label: &'static [u8],
+    challenge_label: &'static [u8],
+}
+
+impl MerlinBridge {
+    pub fn new(transcript: merlin::Transcript) -> Self {
+        Self {
+            transcript,
+            message_label: DEFAULT_MESSAGE_LABEL,
+            challenge_label: DEFAULT_CHALLENGE_LABEL,
+        }
+    }
+
+    /// Set the labels used for the following absorbs and squeezes.
+    pub fn with_labels(
+        &mut self,
+        message_label: &'static [u8],
+        challenge_label: &'static [u8],
+    ) -> &mut Self {
+        self.message_label = message_label;
+        self.challenge_label = challenge_label;
+        self
+    }
+
+    /// Return the underlying merlin transcript.
+    pub fn into_inner(self) -> merlin::Transcript {
+        self.transcript
+    }
+}
+
+impl From<merlin::Transcript> for MerlinBridge {
+    fn from(transcript: merlin::Transcript) -> Self {
+        Self::new(transcript)
+    }
+}
+
+impl UnitTranscript<u8> for MerlinBridge {
+    fn public_units(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.transcript.append_message(self.message_label, input);
+        Ok(())
+    }
+
+    fn fill_challenge_units(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        self.transcript
+            .challenge_bytes(self.challenge_label, output);
+        Ok(())
+    }
+}
+
+/// The interface of [`merlin::Transcript`].
+pub trait MerlinTranscript {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]);
+
+    fn append_u64(&mut self, label: &'static [u8], x: u64) {
+        self.append_message(label, &x.to_le_bytes());
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]);
+}
+
+impl MerlinTranscript for merlin::Transcript {
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        merlin::Transcript::append_message(self, label, message)
+    }
+
+    fn append_u64(&mut self, label: &'static [u8], x: u64) {
+        merlin::Transcript::append_u64(self, label, x)
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        merlin::Transcript::challenge_bytes(self, label, dest)
+    }
+}
+
+/// # Panics
+///
+/// As merlin's interface is infallible, this implementation panics if the operations do not follow the IO pattern.
+impl<T: BytePublic + ByteChallenges> MerlinTranscript for T {
+    fn append_message(&mut self, _label: &'static [u8], message: &[u8]) {
+        self.public_bytes(message)
+            .expect("Message does not follow the IO pattern")
+    }
+
+    fn challenge_bytes(&mut self, _label: &'static [u8], dest: &mut [u8]) {
+        self.fill_challenge_bytes(dest)
+            .expect("Challenge does not follow the IO pattern")
+    }
+}
@@ -1,7 +1,7 @@
 //!  Bindings for some popular libearies using zero-knowledge.
 
 /// Extension traits macros, for both arkworks and group.
-#[cfg(any(feature = "ark", feature = "group"))]
+#[cfg(any(feature = "ark", feature = "group", feature = "dalek"))]
 mod traits;
 
 #[cfg(feature = "ark")]
@@ -13,10 +13,65 @@ pub mod ark;
 /// This plugin is experimental and has not yet been thoroughly tested.
 pub mod group;
 
-/// Bits needed in order to obtain a uniformly distributed random element of `modulus_bits`
+/// The [group](https://github.com/zkcrypto/group) bindings, for the zkcrypto ecosystem (`bls12_381`, `jubjub`, `pasta_curves`, ...).
+#[cfg(feature = "group")]
+pub use self::group as zkcrypto;
+
+#[cfg(feature = "dalek")]
+/// [curve25519-dalek](https://github.com/dalek-cryptography/curve25519-dalek)'s Ristretto bindings.
+pub mod dalek;
+
+#[cfg(feature = "elliptic-curve")]
+/// RustCrypto's [elliptic-curve](https://github.com/RustCrypto/traits/tree/master/elliptic-curve) bindings, with SEC1 point encoding.
+pub mod rustcrypto;
+
+#[cfg(feature = "bip340")]
+/// [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki) Schnorr signatures over secp256k1, with tagged-hash challenges.
+pub mod bip340;
+
+#[cfg(feature = "plonky3")]
+/// Adapters between [plonky3](https://github.com/Plonky3/Plonky3)'s challengers and nimue transcripts.
+pub mod plonky3;
+
+#[cfg(feature = "halo2")]
+/// A [halo2](https://github.com/zcash/halo2)-compatible Blake2b transcript.
+pub mod halo2;
+
+#[cfg(feature = "merlin")]
+/// Bridges with dalek's [merlin](https://github.com/dalek-cryptography/merlin) (STROBE-based) transcripts.
+pub mod merlin;
+
+/// Iterator over the (shifted) powers `a, a c, a c^2, ...` of a field element `c`.
+#[cfg(any(feature = "ark", feature = "group", feature = "dalek"))]
+#[derive(Clone, Debug)]
+pub struct Powers<F> {
+    base: F,
+    next: F,
+}
+
+#[cfg(any(feature = "ark", feature = "group", feature = "dalek"))]
+impl<F> Powers<F> {
+    /// Iterate over the powers of `base`, multiplied by `first`.
+    pub fn new(first: F, base: F) -> Self {
+        Self { base, next: first }
+    }
+}
+
+#[cfg(any(feature = "ark", feature = "group", feature = "dalek"))]
+impl<F: Copy + core::ops::MulAssign> Iterator for Powers<F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        let current = self.next;
+        self.next *= self.base;
+        Some(current)
+    }
+}
+
+/// Bytes squeezed in order to obtain a field element challenge with the given [`HashToField`](crate::HashToField) method.
 #[allow(unused)]
-pub(super) const fn bytes_uniform_modp(modulus_bits: u32) -> usize {
-    (modulus_bits as usize + 128) / 8
+pub(super) const fn bytes_challenge_modp(modulus_bits: u32, method: crate::HashToField) -> usize {
+    method.challenge_bytes(modulus_bits)
 }
 
 /// Number of uniformly random bytes of in a uniformly-distributed element in `[0, b)`.
@@ -0,0 +1,186 @@
+//! Interoperability with [plonky3](https://github.com/Plonky3/Plonky3)'s challengers, with feature flag `plonky3`.
+//!
+//! - [`NimueChallenger`] implements plonky3's [`CanObserve`], [`CanSample`], and [`CanSampleBits`]
+//!   on top of a [`Merlin`](crate::Merlin) or [`Arthur`](crate::Arthur),
+//!   so that a plonky3 prover (or verifier) can run inside a nimue-managed transcript.
+//!   The observations and samples of the plonky3 protocol must be declared in the [`IOPattern`]
+//!   with [`Plonky3IOPattern`].
+//! - [`ChallengerTranscript`] implements nimue's [`UnitTranscript`] on top of a byte-oriented plonky3 challenger
+//!   (e.g., a [`HashChallenger`](p3_challenger::HashChallenger) over `u8`),
+//!   so that code written against nimue's traits can run inside a plonky3-managed transcript.
+//!
+//! Field elements are observed as their canonical representation, as 8 little-endian bytes.
+//! Sampled field elements are obtained by reducing 24 bytes (interpreted as a big-endian integer)
+//! modulo the characteristic, which is statistically close to uniform.
+//!
+//! Plonky3's challenger traits are infallible: [`NimueChallenger`] records the first error
+//! (e.g., an observation that does not follow the IO pattern),
+//! returns zeroes from then on, and reports the error in [`NimueChallenger::finish`].
+//! ```
+//! use nimue::plugins::plonky3::{NimueChallenger, Plonky3IOPattern};
+//! use nimue::{DefaultHash, IOPattern};
+//! use p3_baby_bear::BabyBear;
+//! use p3_challenger::{CanObserve, CanSample};
+//! use p3_field::PrimeCharacteristicRing;
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝");
+//! let io = Plonky3IOPattern::<BabyBear>::observe_elements(io, 2, "commitment");
+//! let io = Plonky3IOPattern::<BabyBear>::sample_elements(io, 1, "alpha");
+//!
+//! let mut merlin = io.to_merlin();
+//! let mut challenger = NimueChallenger::<_, BabyBear>::new(&mut merlin);
+//! challenger.observe_slice(&[BabyBear::ONE, BabyBear::TWO]);
+//! let alpha: BabyBear = challenger.sample();
+//! challenger.finish().unwrap();
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let mut challenger = NimueChallenger::<_, BabyBear>::new(&mut arthur);
+//! challenger.observe_slice(&[BabyBear::ONE, BabyBear::TWO]);
+//! assert_eq!(challenger.sample(), alpha);
+//! challenger.finish().unwrap();
+//! ```
+use core::marker::PhantomData;
+
+use p3_challenger::{CanObserve, CanSample, CanSampleBits};
+use p3_field::{PrimeCharacteristicRing, PrimeField64};
+
+use crate::{
+    ByteChallenges, ByteIOPattern, BytePublic, DuplexHash, IOPattern, IOPatternError, ProofResult,
+    UnitTranscript,
+};
+
+/// Bytes used to encode an observed field element.
+const ELEMENT_BYTES: usize = 8;
+/// Bytes squeezed for each sampled field element.
+const SAMPLE_BYTES: usize = 24;
+/// Bytes squeezed for each call to [`CanSampleBits::sample_bits`].
+const SAMPLE_BITS_BYTES: usize = 8;
+
+/// Declare the observations and samples of a plonky3 challenger in the IO pattern.
+pub trait Plonky3IOPattern<F: PrimeField64> {
+    /// Declare `count` calls to [`CanObserve::observe`].
+    fn observe_elements(self, count: usize, label: &str) -> Self;
+    /// Declare `count` calls to [`CanSample::sample`].
+    fn sample_elements(self, count: usize, label: &str) -> Self;
+    /// Declare `count` calls to [`CanSampleBits::sample_bits`].
+    fn sample_bits(self, count: usize, label: &str) -> Self;
+}
+
+impl<F: PrimeField64, H: DuplexHash> Plonky3IOPattern<F> for IOPattern<H> {
+    fn observe_elements(self, count: usize, label: &str) -> Self {
+        self.add_bytes(count * ELEMENT_BYTES, label)
+    }
+
+    fn sample_elements(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(count * SAMPLE_BYTES, label)
+    }
+
+    fn sample_bits(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(count * SAMPLE_BITS_BYTES, label)
+    }
+}
+
+/// A plonky3 challenger over the field `F`, backed by a nimue transcript.
+///
+/// Observed elements are absorbed in the sponge (via [`BytePublic`]) but not written in the protocol transcript:
+/// as in plonky3, it is up to the caller to send them to the verifier.
+pub struct NimueChallenger<'a, T, F> {
+    transcript: &'a mut T,
+    error: Option<IOPatternError>,
+    _field: PhantomData<F>,
+}
+
+impl<'a, T, F> NimueChallenger<'a, T, F>
+where
+    T: BytePublic + ByteChallenges,
+    F: PrimeField64,
+{
+    pub fn new(transcript: &'a mut T) -> Self {
+        Self {
+            transcript,
+            error: None,
+            _field: PhantomData,
+        }
+    }
+
+    /// Return the first error encountered by the challenger, if any.
+    pub fn finish(self) -> ProofResult<()> {
+        self.error.map_or(Ok(()), |e| Err(e.into()))
+    }
+
+    fn absorb(&mut self, input: &[u8]) {
+        if self.error.is_none() {
+            if let Err(e) = self.transcript.public_bytes(input) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn squeeze<const N: usize>(&mut self) -> [u8; N] {
+        if self.error.is_none() {
+            match self.transcript.challenge_bytes() {
+                Ok(bytes) => return bytes,
+                Err(e) => self.error = Some(e),
+            }
+        }
+        [0u8; N]
+    }
+}
+
+impl<T, F> CanObserve<F> for NimueChallenger<'_, T, F>
+where
+    T: BytePublic + ByteChallenges,
+    F: PrimeField64,
+{
+    fn observe(&mut self, value: F) {
+        self.absorb(&value.as_canonical_u64().to_le_bytes());
+    }
+}
+
+impl<T, F> CanSample<F> for NimueChallenger<'_, T, F>
+where
+    T: BytePublic + ByteChallenges,
+    F: PrimeField64,
+{
+    fn sample(&mut self) -> F {
+        let bytes = self.squeeze::<SAMPLE_BYTES>();
+        let order = F::ORDER_U64 as u128;
+        let reduced = bytes
+            .iter()
+            .fold(0u128, |acc, &b| ((acc << 8) | b as u128) % order);
+        F::from_u64(reduced as u64)
+    }
+}
+
+impl<T, F> CanSampleBits<usize> for NimueChallenger<'_, T, F>
+where
+    T: BytePublic + ByteChallenges,
+    F: PrimeField64,
+{
+    fn sample_bits(&mut self, bits: usize) -> usize {
+        assert!(bits < usize::BITS as usize);
+        let bytes = self.squeeze::<SAMPLE_BITS_BYTES>();
+        (u64::from_le_bytes(bytes) as usize) & ((1 << bits) - 1)
+    }
+}
+
+/// A nimue byte transcript, backed by a plonky3 challenger over bytes.
+///
+/// There is no IO pattern: absorbs and squeezes are forwarded to the challenger as they come,
+/// and thus never fail.
+pub struct ChallengerTranscript<C>(pub C);
+
+impl<C> UnitTranscript<u8> for ChallengerTranscript<C>
+where
+    C: CanObserve<u8> + CanSample<u8>,
+{
+    fn public_units(&mut self, input: &[u8]) -> Result<(), IOPatternError> {
+        self.0.observe_slice(input);
+        Ok(())
+    }
+
+    fn fill_challenge_units(&mut self, output: &mut [u8]) -> Result<(), IOPatternError> {
+        output.iter_mut().for_each(|o| *o = self.0.sample());
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! This adds support for the curves of [RustCrypto](https://github.com/RustCrypto/elliptic-curves) (e.g., `p256`, `k256`),
+//! generic over [`elliptic_curve::CurveArithmetic`], with feature flag `elliptic-curve`.
+//!
+//! Points are written with their [SEC1](https://www.secg.org/sec1-v2.pdf) encoding:
+//! compressed (`0x02` or `0x03` followed by the $x$ coordinate) with [`Encoding::Compressed`],
+//! and uncompressed (`0x04` followed by the $x$ and $y$ coordinates) with [`Encoding::Uncompressed`].
+//! With [`Encoding::FixedWidthLE`], the tag is dropped and each coordinate is written in little-endian order.
+//! The point at infinity cannot be written.
+//!
+//! Scalars are handled by the [group plugin](super::group), since [`CurveArithmetic::Scalar`] implements [`ff::PrimeField`](group::ff::PrimeField):
+//! they are written in their canonical (big-endian) representation,
+//! and challenges are obtained by reducing $\lceil \log_2 q \rceil + 128$ bits modulo the curve order $q$.
+//!
+//! ```
+//! use nimue::plugins::group::{FieldChallenges, FieldIOPattern};
+//! use nimue::plugins::rustcrypto::{Sec1IOPattern, Sec1Reader, Sec1Writer};
+//! use nimue::{DefaultHash, Encoding, IOPattern};
+//! use p256::{AffinePoint, NistP256, Scalar};
+//!
+//! let io = Sec1IOPattern::<NistP256>::add_sec1_points(IOPattern::<DefaultHash>::new("📝"), 1, "commitment");
+//! let io = FieldIOPattern::<Scalar>::challenge_scalars(io, 1, "challenge");
+//!
+//! let mut merlin = io.to_merlin();
+//! Sec1Writer::<NistP256>::add_sec1_points(&mut merlin, &[AffinePoint::GENERATOR]).unwrap();
+//! let [c]: [Scalar; 1] = merlin.challenge_scalars().unwrap();
+//! assert_eq!(merlin.transcript()[0], 0x03);
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let [g] = Sec1Reader::<NistP256>::next_sec1_points::<1>(&mut arthur).unwrap();
+//! let [c_arthur]: [Scalar; 1] = arthur.challenge_scalars().unwrap();
+//! assert_eq!((g, c_arthur), (AffinePoint::GENERATOR, c));
+//!
+//! // a byte prefix that is not a valid SEC1 tag is rejected
+//! let mut invalid = merlin.transcript().to_vec();
+//! invalid[0] = 0x05;
+//! let mut arthur = io.to_arthur(&invalid);
+//! assert!(Sec1Reader::<NistP256>::next_sec1_points::<1>(&mut arthur).is_err());
+//!
+//! // without the tag, and with the coordinates in little-endian order
+//! let io = IOPattern::<DefaultHash>::new("📝").with_encoding(Encoding::FixedWidthLE);
+//! let io = Sec1IOPattern::<NistP256>::add_sec1_points(io, 1, "commitment");
+//! let mut merlin = io.to_merlin();
+//! Sec1Writer::<NistP256>::add_sec1_points(&mut merlin, &[AffinePoint::GENERATOR]).unwrap();
+//! // the x coordinate of the generator of P-256 ends with 0xc296
+//! assert_eq!(merlin.transcript().len(), 64);
+//! assert_eq!(merlin.transcript()[..2], [0x96, 0xc2]);
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! let [g] = Sec1Reader::<NistP256>::next_sec1_points::<1>(&mut arthur).unwrap();
+//! assert_eq!(g, AffinePoint::GENERATOR);
+//! ```
+use elliptic_curve::generic_array::typenum::Unsigned;
+use elliptic_curve::generic_array::GenericArray;
+use elliptic_curve::sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use elliptic_curve::{CurveArithmetic, FieldBytesSize};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Arthur, ByteIOPattern, DuplexHash, Encoding, IOPattern, IOPatternError, Merlin, ProofError,
+    ProofResult, UnitEncoding, UnitTranscript,
+};
+
+/// The length of the SEC1 encoding of a point of `C` (other than the point at infinity).
+fn sec1_size<C>(encoding: Encoding) -> usize
+where
+    C: CurveArithmetic,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let field_bytes = FieldBytesSize::<C>::USIZE;
+    match encoding {
+        Encoding::Compressed => 1 + field_bytes,
+        Encoding::Uncompressed => 1 + 2 * field_bytes,
+        Encoding::FixedWidthLE => 2 * field_bytes,
+    }
+}
+
+/// Reverse the byte order of each coordinate of `coordinates`, from big-endian to little-endian or back.
+fn reverse_coordinates(coordinates: &mut [u8], field_bytes: usize) {
+    coordinates
+        .chunks_mut(field_bytes)
+        .for_each(|coordinate| coordinate.reverse());
+}
+
+/// Send SEC1-encoded points in the IO pattern.
+pub trait Sec1IOPattern<C: CurveArithmetic> {
+    fn add_sec1_points(self, count: usize, label: &str) -> Self;
+}
+
+/// Add SEC1-encoded points to the protocol transcript.
+pub trait Sec1Writer<C: CurveArithmetic> {
+    /// Add `input` to the protocol transcript.
+    ///
+    /// Return an error if one of the points is the point at infinity.
+    fn add_sec1_points(&mut self, input: &[C::AffinePoint]) -> ProofResult<()>;
+}
+
+/// Receive (and validate) SEC1-encoded points from the protocol transcript.
+pub trait Sec1Reader<C: CurveArithmetic> {
+    fn fill_next_sec1_points(&mut self, output: &mut [C::AffinePoint]) -> ProofResult<()>;
+
+    fn next_sec1_points<const N: usize>(&mut self) -> ProofResult<[C::AffinePoint; N]> {
+        let mut output = [C::AffinePoint::default(); N];
+        self.fill_next_sec1_points(&mut output).map(|()| output)
+    }
+}
+
+impl<C, H> Sec1IOPattern<C> for IOPattern<H>
+where
+    C: CurveArithmetic,
+    FieldBytesSize<C>: ModulusSize,
+    H: DuplexHash,
+{
+    fn add_sec1_points(self, count: usize, label: &str) -> Self {
+        let size = sec1_size::<C>(self.encoding());
+        self.add_bytes(count * size, label)
+    }
+}
+
+impl<C, H, R> Sec1Writer<C> for Merlin<H, u8, R>
+where
+    C: CurveArithmetic,
+    C::AffinePoint: ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    fn add_sec1_points(&mut self, input: &[C::AffinePoint]) -> ProofResult<()> {
+        let compress = self.encoding == Encoding::Compressed;
+        for point in input {
+            let encoded = point.to_encoded_point(compress);
+            if encoded.is_identity() {
+                return Err(IOPatternError::from("Cannot encode the point at infinity").into());
+            }
+            let mut bytes = encoded.as_bytes().to_vec();
+            if self.encoding == Encoding::FixedWidthLE {
+                // drop the tag 0x04
+                bytes.remove(0);
+                reverse_coordinates(&mut bytes, FieldBytesSize::<C>::USIZE);
+            }
+            self.public_units(&bytes)?;
+            self.write_element(&bytes)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C, H> Sec1Reader<C> for Arthur<'_, H>
+where
+    C: CurveArithmetic,
+    C::AffinePoint: FromEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+    H: DuplexHash,
+{
+    fn fill_next_sec1_points(&mut self, output: &mut [C::AffinePoint]) -> ProofResult<()> {
+        let mut buf = vec![0u8; sec1_size::<C>(self.encoding)];
+        for o in output.iter_mut() {
+            self.read_element(&mut buf)?;
+            self.public_units(&buf)?;
+            let encoded = if self.encoding == Encoding::FixedWidthLE {
+                let mut coordinates = buf.clone();
+                reverse_coordinates(&mut coordinates, FieldBytesSize::<C>::USIZE);
+                let (x, y) = coordinates.split_at(FieldBytesSize::<C>::USIZE);
+                EncodedPoint::<C>::from_affine_coordinates(
+                    GenericArray::from_slice(x),
+                    GenericArray::from_slice(y),
+                    false,
+                )
+            } else {
+                EncodedPoint::<C>::from_bytes(&buf).map_err(|_| ProofError::SerializationError)?
+            };
+            *o = Option::from(C::AffinePoint::from_encoded_point(&encoded))
+                .ok_or(ProofError::SerializationError)?;
+        }
+        Ok(())
+    }
+}
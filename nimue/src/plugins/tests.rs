@@ -135,3 +135,41 @@ where
     let group_scalar_bytes = group_chal_scalar.to_repr();
     assert_eq!(&ark_scalar_bytes, group_scalar_bytes.as_ref());
 }
+
+#[test]
+fn test_group_reader() {
+    use group::Group;
+    use plugins::zkcrypto::{FieldIOPattern, FieldReader, FieldWriter, GroupReader, GroupWriter};
+
+    type G = bls12_381::G1Projective;
+    type F = bls12_381::Scalar;
+
+    let io = group_iopattern::<G, Keccak>();
+    let scalar = F::from(0x42u64);
+    let point = G::generator() * F::from(0x1337u64);
+
+    let mut merlin = io.to_merlin();
+    merlin.add_scalars(&[scalar]).unwrap();
+    let merlin_chal: [u8; 16] = merlin.challenge_bytes().unwrap();
+    merlin.add_points(&[point]).unwrap();
+    let merlin_chal2: [u8; 16] = merlin.challenge_bytes().unwrap();
+    let [merlin_scalar]: [F; 1] =
+        plugins::zkcrypto::FieldChallenges::challenge_scalars(&mut merlin).unwrap();
+    // points are written only once
+    assert_eq!(merlin.transcript().len(), 32 + 48);
+
+    let mut arthur = io.to_arthur(merlin.transcript());
+    let [scalar_arthur]: [F; 1] = arthur.next_scalars().unwrap();
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), merlin_chal);
+    let [point_arthur]: [G; 1] = arthur.next_points().unwrap();
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), merlin_chal2);
+    let [arthur_scalar]: [F; 1] =
+        plugins::zkcrypto::FieldChallenges::challenge_scalars(&mut arthur).unwrap();
+    assert_eq!((scalar_arthur, point_arthur), (scalar, point));
+    assert_eq!(merlin_scalar, arthur_scalar);
+
+    // non-canonical scalars are rejected
+    let io = FieldIOPattern::<F>::add_scalars(IOPattern::<Keccak>::new("test"), 1, "scalar");
+    let mut arthur = io.to_arthur(&[0xff; 32]);
+    assert!(FieldReader::<F>::next_scalars::<1>(&mut arthur).is_err());
+}
@@ -17,6 +17,26 @@ macro_rules! field_traits {
                 let mut output = [F::default(); N];
                 self.fill_challenge_scalars(&mut output).map(|()| output)
             }
+
+            /// Squeeze `n` scalars, for when their number is known only at runtime.
+            fn challenge_scalars_vec(&mut self, n: usize) -> crate::ProofResult<Vec<F>> {
+                let mut output = vec![F::default(); n];
+                self.fill_challenge_scalars(&mut output).map(|()| output)
+            }
+
+            /// Squeeze a single challenge `c` and return `[1, c, c^2, ..., c^{n-1}]`.
+            ///
+            /// In the IO pattern, this corresponds to one scalar challenge.
+            fn challenge_powers_vec(&mut self, n: usize) -> crate::ProofResult<Vec<F>> {
+                self.challenge_powers_iter()
+                    .map(|powers| powers.take(n).collect())
+            }
+
+            /// Squeeze a single challenge `c` and return the (infinite) iterator over `1, c, c^2, ...`.
+            fn challenge_powers_iter(&mut self) -> crate::ProofResult<$crate::plugins::Powers<F>> {
+                let [c] = self.challenge_scalars()?;
+                Ok($crate::plugins::Powers::new(F::ONE, c))
+            }
         }
 
         /// Add field elements as shared public information.
@@ -34,6 +54,8 @@ macro_rules! field_traits {
         ///
         /// The implementation of this trait **MUST** ensure that the field elements
         /// are correct encodings.
+        /// In particular, byte strings encoding an integer larger than the modulus are rejected rather than reduced:
+        /// otherwise, every element would have alternative encodings, and proofs would be malleable.
         pub trait FieldReader<F: $Field>: FieldPublic<F> {
             fn fill_next_scalars(&mut self, output: &mut [F]) -> crate::ProofResult<()>;
 
@@ -86,5 +108,5 @@ macro_rules! group_traits {
     };
 }
 
-#[cfg(any(feature = "group", feature = "ark"))]
+#[cfg(any(feature = "group", feature = "ark", feature = "dalek"))]
 pub(super) use {field_traits, group_traits};
@@ -0,0 +1,46 @@
+//! The traits (and types) needed by most protocols, to be glob-imported:
+//!
+//! ```
+//! use nimue::prelude::*;
+//!
+//! let io = IOPattern::<DefaultHash>::new("📝")
+//!     .add_bytes(1, "message")
+//!     .challenge_bytes(16, "challenge");
+//! let mut merlin = io.to_merlin();
+//! merlin.add_bytes(&[42]).unwrap();
+//! let challenge = merlin.challenge_bytes::<16>().unwrap();
+//!
+//! let mut arthur = io.to_arthur(merlin.transcript());
+//! assert_eq!(arthur.next_bytes().unwrap(), [42]);
+//! assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+//! ```
+//!
+//! With the features `ark`, `group`, and `dalek`, the prelude also brings the field and group traits of the plugins in scope.
+//! The plugins define traits with the same names (e.g., `FieldChallenges`), so these are imported anonymously:
+//! their methods resolve, and bounds refer to them through their plugin (e.g., `nimue::plugins::ark::FieldChallenges`).
+//! With more than one of these features, calls that more than one plugin could serve are ambiguous,
+//! and must name the trait (e.g., `FieldIOPattern::<Fr>::add_scalars(io, 1, "scalar")`).
+
+pub use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, BytePublic, ByteReader, ByteWriter, CommonTranscript,
+    DefaultHash, DefaultRng, DuplexHash, IOPattern, IOPatternError, Merlin, ProofError,
+    ProofResult, Unit, UnitEncoding, UnitTranscript,
+};
+
+#[cfg(feature = "ark")]
+pub use crate::plugins::ark::{
+    FieldChallenges as _, FieldIOPattern as _, FieldPublic as _, FieldReader as _,
+    FieldWriter as _, GroupIOPattern as _, GroupPublic as _, GroupReader as _, GroupWriter as _,
+};
+
+#[cfg(feature = "group")]
+pub use crate::plugins::group::{
+    FieldChallenges as _, FieldIOPattern as _, FieldPublic as _, FieldReader as _,
+    FieldWriter as _, GroupIOPattern as _, GroupPublic as _, GroupReader as _, GroupWriter as _,
+};
+
+#[cfg(feature = "dalek")]
+pub use crate::plugins::dalek::{
+    FieldChallenges as _, FieldIOPattern as _, FieldPublic as _, FieldReader as _,
+    FieldWriter as _, GroupIOPattern as _, GroupPublic as _, GroupReader as _, GroupWriter as _,
+};
@@ -0,0 +1,201 @@
+use crate::{
+    ByteChallenges, ByteIOPattern, BytePublic, DefaultHash, DuplexHash, IOPattern, IOPatternError,
+    ProofError, ProofResult, Unit,
+};
+
+/// The magic bytes starting the binary encoding of a [`Proof`].
+const MAGIC: &[u8; 4] = b"NMUE";
+/// The version of the framing.
+const VERSION: u8 = 1;
+/// The size of the digest of the IO pattern, in bytes.
+const PATTERN_SIZE: usize = 32;
+/// The size of the fingerprint of the hash function, in bytes.
+const HASH_SIZE: usize = 16;
+
+/// The digest of `input`, in the domain `domain`.
+fn digest<const N: usize>(domain: &str, input: &[u8]) -> [u8; N] {
+    let io = IOPattern::<DefaultHash>::new(domain)
+        .add_bytes(input.len(), "input")
+        .challenge_bytes(N, "digest");
+    let mut merlin = io.to_merlin();
+    // the IO pattern is followed exactly
+    merlin.public_bytes(input).unwrap();
+    merlin.challenge_bytes().unwrap()
+}
+
+/// The digest of the IO pattern `io_pattern`, as carried by proofs.
+pub(crate) fn pattern_digest<H: DuplexHash<U>, U: Unit>(
+    io_pattern: &IOPattern<H, U>,
+) -> [u8; PATTERN_SIZE] {
+    digest("nimue/proof/pattern", io_pattern.as_bytes())
+}
+
+/// Split the first `len` bytes off `bytes`.
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> ProofResult<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(ProofError::SerializationError);
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+/// The fingerprint of `H`: the digest of its output on a fixed input.
+///
+/// Hash functions are identified by their behavior rather than by their name,
+/// so that the identifier does not depend on how they are called in the code.
+fn fingerprint<H: DuplexHash<U>, U: Unit + Default>() -> [u8; HASH_SIZE] {
+    let mut output = vec![U::default(); 8];
+    H::new([0; 32]).squeeze_unchecked(&mut output);
+    let mut bytes = Vec::new();
+    // write never fails on Vec<u8>
+    U::write(&output, &mut bytes).unwrap();
+    digest("nimue/proof/hash", &bytes)
+}
+
+/// A protocol transcript, framed for transport.
+///
+/// Alongside the transcript, a proof carries the version of the framing,
+/// a digest of the IO pattern, and a fingerprint of the hash function it was produced with,
+/// so that proofs for a different protocol (or a different version of it) are rejected
+/// by [`Proof::transcript_for`] before verification even starts.
+///
+/// Proofs have a self-describing binary encoding ([`Proof::to_bytes`]) and,
+/// with the feature `serde`, implement `Serialize` and `Deserialize`.
+/// In human-readable formats (e.g., JSON), the byte fields are hex-encoded.
+///
+/// ```
+/// use nimue::{ByteIOPattern, ByteReader, ByteWriter, DefaultHash, IOPattern, Proof};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(1, "message");
+/// let mut merlin = io.to_merlin();
+/// merlin.add_bytes(&[42]).unwrap();
+/// let proof = Proof::new(&io, merlin.transcript());
+///
+/// let proof = Proof::from_bytes(&proof.to_bytes()).unwrap();
+/// let mut arthur = io.to_arthur(proof.transcript_for(&io).unwrap());
+/// assert_eq!(arthur.next_bytes().unwrap(), [42]);
+///
+/// let other = IOPattern::<DefaultHash>::new("📝").add_bytes(2, "message");
+/// assert!(proof.transcript_for(&other).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proof {
+    version: u8,
+    #[cfg_attr(feature = "serde", serde(with = "bytes"))]
+    pattern: [u8; PATTERN_SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "bytes"))]
+    hash: [u8; HASH_SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "bytes"))]
+    transcript: Vec<u8>,
+}
+
+impl Proof {
+    /// Frame the protocol transcript `transcript`, produced following `io_pattern`.
+    pub fn new<H: DuplexHash<U>, U: Unit + Default>(
+        io_pattern: &IOPattern<H, U>,
+        transcript: &[u8],
+    ) -> Self {
+        Self {
+            version: VERSION,
+            pattern: pattern_digest(io_pattern),
+            hash: fingerprint::<H, U>(),
+            transcript: transcript.to_vec(),
+        }
+    }
+
+    /// Return the protocol transcript, checking that it was produced following `io_pattern`.
+    ///
+    /// Return an error if the version, the IO pattern, or the hash function mismatch.
+    pub fn transcript_for<H: DuplexHash<U>, U: Unit + Default>(
+        &self,
+        io_pattern: &IOPattern<H, U>,
+    ) -> ProofResult<&[u8]> {
+        if self.version != VERSION {
+            return Err(IOPatternError::from(format!(
+                "Unsupported proof version {}",
+                self.version
+            ))
+            .into());
+        }
+        if self.pattern != pattern_digest(io_pattern) {
+            return Err(IOPatternError::from("The proof follows a different IO pattern").into());
+        }
+        if self.hash != fingerprint::<H, U>() {
+            return Err(IOPatternError::from("The proof uses a different hash function").into());
+        }
+        Ok(&self.transcript)
+    }
+
+    /// Encode the proof as `NMUE || version || pattern digest || hash fingerprint || length || transcript`,
+    /// where the length of the transcript is a 64-bit little-endian integer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            MAGIC.len() + 1 + PATTERN_SIZE + HASH_SIZE + 8 + self.transcript.len(),
+        );
+        bytes.extend(MAGIC);
+        bytes.push(self.version);
+        bytes.extend(self.pattern);
+        bytes.extend(self.hash);
+        bytes.extend((self.transcript.len() as u64).to_le_bytes());
+        bytes.extend(&self.transcript);
+        bytes
+    }
+
+    /// Decode a proof encoded with [`Proof::to_bytes`].
+    ///
+    /// Return an error if the encoding is malformed or of an unsupported version.
+    pub fn from_bytes(mut bytes: &[u8]) -> ProofResult<Self> {
+        if take(&mut bytes, MAGIC.len())? != MAGIC {
+            return Err(ProofError::SerializationError);
+        }
+        let version = take(&mut bytes, 1)?[0];
+        if version != VERSION {
+            return Err(ProofError::SerializationError);
+        }
+        let pattern = take(&mut bytes, PATTERN_SIZE)?.try_into().unwrap();
+        let hash = take(&mut bytes, HASH_SIZE)?.try_into().unwrap();
+        let len = u64::from_le_bytes(take(&mut bytes, 8)?.try_into().unwrap());
+        let len = usize::try_from(len).map_err(|_| ProofError::SerializationError)?;
+        let transcript = take(&mut bytes, len)?.to_vec();
+        if !bytes.is_empty() {
+            return Err(ProofError::SerializationError);
+        }
+        Ok(Self {
+            version,
+            pattern,
+            hash,
+            transcript,
+        })
+    }
+}
+
+/// Serialization of byte fields: hex strings in human-readable formats, sequences of bytes otherwise.
+#[cfg(feature = "serde")]
+pub(crate) mod bytes {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        bytes: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(bytes))
+        } else {
+            bytes.as_ref().serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: TryFrom<Vec<u8>>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            hex::decode(String::deserialize(deserializer)?).map_err(D::Error::custom)?
+        } else {
+            Vec::<u8>::deserialize(deserializer)?
+        };
+        T::try_from(bytes).map_err(|_| D::Error::custom("invalid length"))
+    }
+}
@@ -0,0 +1,145 @@
+//! Authenticated key exchange with a pre-shared key, deriving the session key from the transcript.
+//!
+//! Nimue's sponges are not limited to proof systems: two parties running the same IO pattern
+//! absorb the same messages in the same order, and can then squeeze values only they can compute.
+//! Here, an initiator and a responder sharing a secret key (the *pre-shared key*) agree on a fresh session key:
+//!
+//! ```text
+//!     initiator -> responder: initiator nonce
+//!     responder -> initiator: responder nonce, responder confirmation
+//!     initiator -> responder: initiator confirmation
+//! ```
+//! Each party holds a [`Safe`] sponge over [`io_pattern`], absorbing the pre-shared key first, and then the two nonces.
+//! The confirmations are squeezed from the sponge, and prove to the other party that the sender knows the pre-shared key
+//! and saw the same nonces; they are checked in constant time.
+//! After a ratchet, the session key is squeezed (the sponge acting as a key derivation function),
+//! and is independent of the confirmations sent in the clear.
+//!
+//! ```
+//! use nimue::protocols::kex::{Initiator, Responder};
+//! use nimue::DefaultHash;
+//! use rand::rngs::OsRng;
+//!
+//! let psk = [42u8; 32];
+//! let (initiator, initiator_nonce) = Initiator::<DefaultHash>::new(&psk, &mut OsRng).unwrap();
+//! let (responder, responder_nonce, responder_confirmation) =
+//!     Responder::<DefaultHash>::new(&psk, &initiator_nonce, &mut OsRng).unwrap();
+//! let (initiator_confirmation, initiator_key) = initiator
+//!     .finish(&responder_nonce, &responder_confirmation)
+//!     .unwrap();
+//! let responder_key = responder.finish(&initiator_confirmation).unwrap();
+//! assert_eq!(initiator_key, responder_key);
+//! ```
+//!
+//! The exchange authenticates both parties and provides key confirmation, but no forward secrecy:
+//! whoever learns the pre-shared key can recompute past session keys from the nonces.
+use rand::{CryptoRng, RngCore};
+
+use crate::{DuplexHash, IOPattern, IOPatternError, ProofError, ProofResult, Safe};
+
+/// The domain separator of the key exchange.
+pub const DOMAIN_SEPARATOR: &str = "nimue/psk-kex/v1";
+/// The size of the pre-shared key, of nonces, of confirmations, and of the session key, in bytes.
+pub const KEY_SIZE: usize = 32;
+
+/// The IO pattern of the key exchange, see the [module documentation](self).
+pub fn io_pattern<H: DuplexHash>() -> IOPattern<H> {
+    IOPattern::new(DOMAIN_SEPARATOR)
+        .absorb(KEY_SIZE, "psk")
+        .absorb(KEY_SIZE, "initiator-nonce")
+        .absorb(KEY_SIZE, "responder-nonce")
+        .squeeze(KEY_SIZE, "responder-confirmation")
+        .squeeze(KEY_SIZE, "initiator-confirmation")
+        .ratchet()
+        .squeeze(KEY_SIZE, "session-key")
+}
+
+/// Squeeze `KEY_SIZE` bytes from `safe`.
+fn squeeze<H: DuplexHash>(safe: &mut Safe<H>) -> Result<[u8; KEY_SIZE], IOPatternError> {
+    let mut output = [0u8; KEY_SIZE];
+    safe.squeeze(&mut output)?;
+    Ok(output)
+}
+
+/// Ratchet `safe` and squeeze the session key.
+fn session_key<H: DuplexHash>(safe: &mut Safe<H>) -> Result<[u8; KEY_SIZE], IOPatternError> {
+    safe.ratchet()?;
+    squeeze(safe)
+}
+
+/// The party starting the key exchange.
+pub struct Initiator<H: DuplexHash> {
+    safe: Safe<H>,
+}
+
+impl<H: DuplexHash> Initiator<H> {
+    /// Start the key exchange with the pre-shared key `psk`, and return the initiator nonce to send.
+    pub fn new(
+        psk: &[u8; KEY_SIZE],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<(Self, [u8; KEY_SIZE]), IOPatternError> {
+        let mut safe = Safe::new(&io_pattern());
+        let mut nonce = [0u8; KEY_SIZE];
+        rng.fill_bytes(&mut nonce);
+        safe.absorb(psk)?;
+        safe.absorb(&nonce)?;
+        Ok((Self { safe }, nonce))
+    }
+
+    /// Check the responder's confirmation, and return the initiator confirmation to send along with the session key.
+    ///
+    /// Return an "invalid proof" error if the responder does not know the pre-shared key.
+    pub fn finish(
+        mut self,
+        responder_nonce: &[u8; KEY_SIZE],
+        responder_confirmation: &[u8; KEY_SIZE],
+    ) -> ProofResult<([u8; KEY_SIZE], [u8; KEY_SIZE])> {
+        self.safe.absorb(responder_nonce)?;
+        let expected = squeeze(&mut self.safe)?;
+        if !crate::ct::eq(&expected, responder_confirmation) {
+            self.safe.clear();
+            return Err(ProofError::InvalidProof);
+        }
+        let confirmation = squeeze(&mut self.safe)?;
+        Ok((confirmation, session_key(&mut self.safe)?))
+    }
+}
+
+/// The party answering the key exchange.
+pub struct Responder<H: DuplexHash> {
+    safe: Safe<H>,
+}
+
+impl<H: DuplexHash> Responder<H> {
+    /// Answer the initiator nonce `initiator_nonce` with the pre-shared key `psk`,
+    /// and return the responder nonce and the responder confirmation to send.
+    pub fn new(
+        psk: &[u8; KEY_SIZE],
+        initiator_nonce: &[u8; KEY_SIZE],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<(Self, [u8; KEY_SIZE], [u8; KEY_SIZE]), IOPatternError> {
+        let mut safe = Safe::new(&io_pattern());
+        let mut nonce = [0u8; KEY_SIZE];
+        rng.fill_bytes(&mut nonce);
+        safe.absorb(psk)?;
+        safe.absorb(initiator_nonce)?;
+        safe.absorb(&nonce)?;
+        let confirmation = squeeze(&mut safe)?;
+        Ok((Self { safe }, nonce, confirmation))
+    }
+
+    /// Check the initiator's confirmation, and return the session key.
+    ///
+    /// Return an "invalid proof" error if the initiator does not know the pre-shared key.
+    pub fn finish(
+        mut self,
+        initiator_confirmation: &[u8; KEY_SIZE],
+    ) -> ProofResult<[u8; KEY_SIZE]> {
+        let expected = squeeze(&mut self.safe)?;
+        if !crate::ct::eq(&expected, initiator_confirmation) {
+            self.safe.clear();
+            return Err(ProofError::InvalidProof);
+        }
+        Ok(session_key(&mut self.safe)?)
+    }
+}
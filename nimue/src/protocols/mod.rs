@@ -0,0 +1,12 @@
+//! Protocols that are generic over the underlying groups, compiled with the Fiat-Shamir transform.
+//!
+//! - [`kex`] is an authenticated key exchange with a pre-shared key, showing nimue's sponges beyond proof systems;
+//! - [`ni`] defines the standard shape of non-interactive provers and verifiers;
+//! - [`sigma`] defines sigma protocols, their AND/OR composition, and their compilation into non-interactive proofs.
+
+/// Authenticated key exchange with a pre-shared key.
+pub mod kex;
+/// Non-interactive provers and verifiers.
+pub mod ni;
+/// Sigma protocols and their AND/OR composition.
+pub mod sigma;
@@ -0,0 +1,173 @@
+//! A standard shape for non-interactive provers and verifiers.
+//!
+//! A protocol declares its IO pattern for a given statement ([`NiProtocol`]),
+//! the prover writes its proof in the transcript of a [`Merlin`] ([`NiProver`]),
+//! and the verifier reads it from the transcript of an [`Arthur`] ([`NiVerifier`]).
+//! Generic utilities can then handle any protocol of this shape:
+//! [`prove`] and [`verify`] run a protocol from the statement to the proof and back,
+//! and [`round_trip`] checks that honest proofs verify, typically in tests.
+//!
+//! ```
+//! use nimue::protocols::ni::{self, NiProtocol, NiProver, NiVerifier};
+//! use nimue::{
+//!     Arthur, ByteIOPattern, ByteReader, ByteWriter, DefaultHash, IOPattern, Merlin, ProofError,
+//!     ProofResult,
+//! };
+//! use rand::{CryptoRng, RngCore};
+//!
+//! /// A (non zero-knowledge) proof that the prover knows the preimage of a byte under XOR with the statement.
+//! struct Xor;
+//!
+//! impl NiProtocol for Xor {
+//!     type Statement = u8;
+//!
+//!     fn io_pattern(&self, _statement: &u8) -> IOPattern {
+//!         IOPattern::new("example.com/xor").add_bytes(1, "preimage")
+//!     }
+//! }
+//!
+//! impl NiProver for Xor {
+//!     type Witness = u8;
+//!
+//!     fn prove<R: RngCore + CryptoRng>(
+//!         &self,
+//!         merlin: &mut Merlin<DefaultHash, u8, R>,
+//!         _statement: &u8,
+//!         witness: &u8,
+//!     ) -> ProofResult<()> {
+//!         Ok(merlin.add_bytes(&[*witness])?)
+//!     }
+//! }
+//!
+//! impl NiVerifier for Xor {
+//!     fn verify(&self, arthur: &mut Arthur<'_, DefaultHash>, statement: &u8) -> ProofResult<()> {
+//!         let [preimage] = arthur.next_bytes()?;
+//!         ((preimage ^ 0x5a) == *statement).then_some(()).ok_or(ProofError::InvalidProof)
+//!     }
+//! }
+//!
+//! let proof = ni::round_trip(&Xor, &0x5b, &0x01).unwrap();
+//! assert!(ni::verify(&Xor, &0x5c, &proof).is_err());
+//! ```
+use rand::{CryptoRng, RngCore};
+
+use crate::{Arthur, DefaultHash, DuplexHash, IOPattern, Merlin, ProofError, ProofResult, Unit};
+
+/// A non-interactive protocol: its IO pattern, for each statement.
+pub trait NiProtocol<H = DefaultHash, U = u8>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    /// The public input, shared by prover and verifier.
+    type Statement: ?Sized;
+
+    /// The IO pattern of the proofs of `statement`.
+    ///
+    /// The domain separator should identify the protocol (see [`crate::registry`]);
+    /// the statement can also be bound in the pattern, e.g., its size.
+    fn io_pattern(&self, statement: &Self::Statement) -> IOPattern<H, U>;
+}
+
+/// The prover of a [`NiProtocol`].
+pub trait NiProver<H = DefaultHash, U = u8>: NiProtocol<H, U>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    /// The private input of the prover.
+    type Witness: ?Sized;
+
+    /// Prove `statement` with the witness `witness`, writing the proof in the transcript of `merlin`.
+    ///
+    /// `merlin` follows the pattern [`NiProtocol::io_pattern`] of `statement`.
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        merlin: &mut Merlin<H, U, R>,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+    ) -> ProofResult<()>;
+}
+
+/// The verifier of a [`NiProtocol`].
+pub trait NiVerifier<H = DefaultHash, U = u8>: NiProtocol<H, U>
+where
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    /// Verify the proof of `statement`, reading it from the transcript of `arthur`.
+    ///
+    /// `arthur` follows the pattern [`NiProtocol::io_pattern`] of `statement`.
+    fn verify(&self, arthur: &mut Arthur<'_, H, U>, statement: &Self::Statement)
+        -> ProofResult<()>;
+}
+
+/// Prove `statement` with `protocol`, and return the proof.
+///
+/// The proof is sealed if the IO pattern has an integrity tag ([`IOPattern::with_integrity_tag`]).
+/// Return an error if the prover fails, or does not perform every operation of the IO pattern.
+pub fn prove<P, H, U>(
+    protocol: &P,
+    statement: &P::Statement,
+    witness: &P::Witness,
+) -> ProofResult<Vec<u8>>
+where
+    P: NiProver<H, U> + ?Sized,
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    let io_pattern = protocol.io_pattern(statement);
+    let mut merlin = io_pattern.to_merlin();
+    protocol.prove(&mut merlin, statement, witness)?;
+    if !merlin.safe.is_finished() {
+        return Err(ProofError::InvalidIO(
+            "The prover did not complete the IO pattern".into(),
+        ));
+    }
+    if io_pattern.integrity_tag() {
+        merlin.seal()?;
+    }
+    Ok(merlin.transcript().to_vec())
+}
+
+/// Verify the proof `proof` of `statement` with `protocol`.
+///
+/// Return an error if the verifier fails, does not perform every operation of the IO pattern,
+/// or if the proof is longer than what the verifier read.
+pub fn verify<P, H, U>(protocol: &P, statement: &P::Statement, proof: &[u8]) -> ProofResult<()>
+where
+    P: NiVerifier<H, U> + ?Sized,
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    let io_pattern = protocol.io_pattern(statement);
+    let mut arthur = Arthur::try_new(&io_pattern, proof)?;
+    protocol.verify(&mut arthur, statement)?;
+    if !arthur.safe.is_finished() {
+        return Err(ProofError::InvalidIO(
+            "The verifier did not complete the IO pattern".into(),
+        ));
+    }
+    if !arthur.transcript.is_empty() {
+        return Err(ProofError::InvalidIO("The proof has trailing bytes".into()));
+    }
+    Ok(())
+}
+
+/// Prove `statement` with `protocol`, check that the proof verifies, and return it.
+///
+/// Meant for tests: every protocol should pass it on honestly generated statements and witnesses.
+pub fn round_trip<P, H, U>(
+    protocol: &P,
+    statement: &P::Statement,
+    witness: &P::Witness,
+) -> ProofResult<Vec<u8>>
+where
+    P: NiProver<H, U> + NiVerifier<H, U> + ?Sized,
+    H: DuplexHash<U>,
+    U: Unit,
+{
+    let proof = prove(protocol, statement, witness)?;
+    verify(protocol, statement, &proof)?;
+    Ok(proof)
+}
@@ -0,0 +1,438 @@
+//! Sigma protocols, their composition, and their compilation with the Fiat-Shamir transform.
+//!
+//! A [`SigmaProtocol`] is a three-move public-coin protocol: the prover sends a commitment,
+//! the verifier a random challenge, and the prover a response.
+//! The statement is the protocol instance itself (`&self`).
+//! Challenges are byte strings of [`SigmaProtocol::challenge_length`] bytes,
+//! so that they can be shared between protocols over different groups.
+//!
+//! [`prove`] and [`verify`] compile any sigma protocol with nimue:
+//! the commitment is written in the transcript, the challenge squeezed, and the response written.
+//! The IO pattern is declared with [`SigmaIOPattern::add_sigma_protocol`].
+//!
+//! Protocols compose with:
+//! - [`And`], proving both statements with the same challenge;
+//! - [`Or`], proving one of the two statements à la Cramer–Damgård–Schoenmakers:
+//!   the prover simulates the branch it has no witness for with a random challenge $c_1$,
+//!   and answers the real branch with $c_0 = c \oplus c_1$, where $c$ is the challenge derived from the transcript.
+//!   The response includes $c_0$, from which the verifier recomputes $c_1$.
+//!
+//! Compositions are sigma protocols, and can be nested.
+//! See [`plugins::ark::sigma`](crate::plugins::ark::sigma) for an implementation of Schnorr's protocol.
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    Arthur, ByteChallenges, ByteIOPattern, ByteReader, ByteWriter, DuplexHash, IOPattern,
+    IOPatternError, Merlin, ProofError, ProofResult,
+};
+
+/// A three-move public-coin protocol, special honest-verifier zero-knowledge.
+pub trait SigmaProtocol {
+    type Witness;
+    type Commitment;
+    /// The prover's state between the commitment and the response.
+    type ProverState;
+    type Response;
+
+    /// The size of the serialized commitment, in bytes.
+    fn commitment_length(&self) -> usize;
+    /// The size of the challenge, in bytes.
+    fn challenge_length(&self) -> usize;
+    /// The size of the serialized response, in bytes.
+    fn response_length(&self) -> usize;
+
+    /// The first message of the prover.
+    fn prover_commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::ProverState);
+
+    /// The second message of the prover, answering `challenge`.
+    fn prover_response(
+        &self,
+        state: Self::ProverState,
+        challenge: &[u8],
+    ) -> ProofResult<Self::Response>;
+
+    /// Check the verification equation.
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &[u8],
+        response: &Self::Response,
+    ) -> ProofResult<()>;
+
+    /// Simulate an accepting transcript for `challenge`, without the witness.
+    fn simulate(
+        &self,
+        challenge: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::Response);
+
+    fn serialize_commitment(&self, commitment: &Self::Commitment) -> Vec<u8>;
+    fn deserialize_commitment(&self, bytes: &[u8]) -> ProofResult<Self::Commitment>;
+    fn serialize_response(&self, response: &Self::Response) -> Vec<u8>;
+    fn deserialize_response(&self, bytes: &[u8]) -> ProofResult<Self::Response>;
+}
+
+/// Declare a sigma protocol in the IO pattern.
+pub trait SigmaIOPattern {
+    fn add_sigma_protocol<P: SigmaProtocol>(self, protocol: &P, label: &str) -> Self;
+}
+
+impl<H: DuplexHash> SigmaIOPattern for IOPattern<H> {
+    fn add_sigma_protocol<P: SigmaProtocol>(self, protocol: &P, label: &str) -> Self {
+        self.add_bytes(protocol.commitment_length(), &format!("{label}-commitment"))
+            .challenge_bytes(protocol.challenge_length(), &format!("{label}-challenge"))
+            .add_bytes(protocol.response_length(), &format!("{label}-response"))
+    }
+}
+
+/// Prove `protocol` with the witness `witness`, writing the proof in the transcript of `merlin`.
+pub fn prove<P, H, R>(
+    protocol: &P,
+    merlin: &mut Merlin<H, u8, R>,
+    witness: &P::Witness,
+) -> ProofResult<()>
+where
+    P: SigmaProtocol,
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    let (commitment, state) = protocol.prover_commit(witness, merlin.rng());
+    merlin.add_bytes(&protocol.serialize_commitment(&commitment))?;
+    let mut challenge = vec![0u8; protocol.challenge_length()];
+    merlin.fill_challenge_bytes(&mut challenge)?;
+    let response = protocol.prover_response(state, &challenge)?;
+    merlin.add_bytes(&protocol.serialize_response(&response))?;
+    Ok(())
+}
+
+/// Verify a proof of `protocol`, reading it from the transcript of `arthur`.
+pub fn verify<P, H>(protocol: &P, arthur: &mut Arthur<'_, H>) -> ProofResult<()>
+where
+    P: SigmaProtocol,
+    H: DuplexHash,
+{
+    let mut commitment = vec![0u8; protocol.commitment_length()];
+    arthur.fill_next_bytes(&mut commitment)?;
+    let commitment = protocol.deserialize_commitment(&commitment)?;
+    let mut challenge = vec![0u8; protocol.challenge_length()];
+    arthur.fill_challenge_bytes(&mut challenge)?;
+    let mut response = vec![0u8; protocol.response_length()];
+    arthur.fill_next_bytes(&mut response)?;
+    let response = protocol.deserialize_response(&response)?;
+    protocol.verifier(&commitment, &challenge, &response)
+}
+
+/// The AND composition of two sigma protocols: both are proven with the same challenge.
+#[derive(Clone, Debug)]
+pub struct And<P, Q>(pub P, pub Q);
+
+impl<P, Q> And<P, Q>
+where
+    P: SigmaProtocol,
+    Q: SigmaProtocol,
+{
+    /// Compose `left` and `right`.
+    ///
+    /// Return an error if their challenges have different lengths.
+    pub fn new(left: P, right: Q) -> Result<Self, IOPatternError> {
+        if left.challenge_length() != right.challenge_length() {
+            return Err("Composed protocols must have the same challenge length".into());
+        }
+        Ok(Self(left, right))
+    }
+}
+
+impl<P, Q> SigmaProtocol for And<P, Q>
+where
+    P: SigmaProtocol,
+    Q: SigmaProtocol,
+{
+    type Witness = (P::Witness, Q::Witness);
+    type Commitment = (P::Commitment, Q::Commitment);
+    type ProverState = (P::ProverState, Q::ProverState);
+    type Response = (P::Response, Q::Response);
+
+    fn commitment_length(&self) -> usize {
+        self.0.commitment_length() + self.1.commitment_length()
+    }
+
+    fn challenge_length(&self) -> usize {
+        self.0.challenge_length()
+    }
+
+    fn response_length(&self) -> usize {
+        self.0.response_length() + self.1.response_length()
+    }
+
+    fn prover_commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::ProverState) {
+        let (left_commitment, left_state) = self.0.prover_commit(&witness.0, rng);
+        let (right_commitment, right_state) = self.1.prover_commit(&witness.1, rng);
+        (
+            (left_commitment, right_commitment),
+            (left_state, right_state),
+        )
+    }
+
+    fn prover_response(
+        &self,
+        state: Self::ProverState,
+        challenge: &[u8],
+    ) -> ProofResult<Self::Response> {
+        Ok((
+            self.0.prover_response(state.0, challenge)?,
+            self.1.prover_response(state.1, challenge)?,
+        ))
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &[u8],
+        response: &Self::Response,
+    ) -> ProofResult<()> {
+        self.0.verifier(&commitment.0, challenge, &response.0)?;
+        self.1.verifier(&commitment.1, challenge, &response.1)
+    }
+
+    fn simulate(
+        &self,
+        challenge: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::Response) {
+        let (left_commitment, left_response) = self.0.simulate(challenge, rng);
+        let (right_commitment, right_response) = self.1.simulate(challenge, rng);
+        (
+            (left_commitment, right_commitment),
+            (left_response, right_response),
+        )
+    }
+
+    fn serialize_commitment(&self, commitment: &Self::Commitment) -> Vec<u8> {
+        let mut bytes = self.0.serialize_commitment(&commitment.0);
+        bytes.extend(self.1.serialize_commitment(&commitment.1));
+        bytes
+    }
+
+    fn deserialize_commitment(&self, bytes: &[u8]) -> ProofResult<Self::Commitment> {
+        let (left, right) = split(bytes, self.0.commitment_length())?;
+        Ok((
+            self.0.deserialize_commitment(left)?,
+            self.1.deserialize_commitment(right)?,
+        ))
+    }
+
+    fn serialize_response(&self, response: &Self::Response) -> Vec<u8> {
+        let mut bytes = self.0.serialize_response(&response.0);
+        bytes.extend(self.1.serialize_response(&response.1));
+        bytes
+    }
+
+    fn deserialize_response(&self, bytes: &[u8]) -> ProofResult<Self::Response> {
+        let (left, right) = split(bytes, self.0.response_length())?;
+        Ok((
+            self.0.deserialize_response(left)?,
+            self.1.deserialize_response(right)?,
+        ))
+    }
+}
+
+/// The witness of an [`Or`] composition: a witness for one of the two statements.
+#[derive(Clone, Debug)]
+pub enum OrWitness<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// The prover's state of an [`Or`] composition: the state of the real branch,
+/// and the challenge and response of the simulated one.
+pub enum OrProverState<P: SigmaProtocol, Q: SigmaProtocol> {
+    Left(P::ProverState, Vec<u8>, Q::Response),
+    Right(Vec<u8>, P::Response, Q::ProverState),
+}
+
+/// The response of an [`Or`] composition: the challenge of the left branch, and the responses of both branches.
+pub struct OrResponse<P: SigmaProtocol, Q: SigmaProtocol> {
+    pub left_challenge: Vec<u8>,
+    pub left: P::Response,
+    pub right: Q::Response,
+}
+
+/// The OR composition of two sigma protocols: one of the two is proven, without revealing which.
+#[derive(Clone, Debug)]
+pub struct Or<P, Q>(pub P, pub Q);
+
+impl<P, Q> Or<P, Q>
+where
+    P: SigmaProtocol,
+    Q: SigmaProtocol,
+{
+    /// Compose `left` and `right`.
+    ///
+    /// Return an error if their challenges have different lengths.
+    pub fn new(left: P, right: Q) -> Result<Self, IOPatternError> {
+        if left.challenge_length() != right.challenge_length() {
+            return Err("Composed protocols must have the same challenge length".into());
+        }
+        Ok(Self(left, right))
+    }
+}
+
+impl<P, Q> SigmaProtocol for Or<P, Q>
+where
+    P: SigmaProtocol,
+    Q: SigmaProtocol,
+{
+    type Witness = OrWitness<P::Witness, Q::Witness>;
+    type Commitment = (P::Commitment, Q::Commitment);
+    type ProverState = OrProverState<P, Q>;
+    type Response = OrResponse<P, Q>;
+
+    fn commitment_length(&self) -> usize {
+        self.0.commitment_length() + self.1.commitment_length()
+    }
+
+    fn challenge_length(&self) -> usize {
+        self.0.challenge_length()
+    }
+
+    fn response_length(&self) -> usize {
+        self.challenge_length() + self.0.response_length() + self.1.response_length()
+    }
+
+    fn prover_commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::ProverState) {
+        let mut simulated_challenge = vec![0u8; self.challenge_length()];
+        rng.fill_bytes(&mut simulated_challenge);
+        match witness {
+            OrWitness::Left(witness) => {
+                let (left_commitment, state) = self.0.prover_commit(witness, rng);
+                let (right_commitment, response) = self.1.simulate(&simulated_challenge, rng);
+                (
+                    (left_commitment, right_commitment),
+                    OrProverState::Left(state, simulated_challenge, response),
+                )
+            }
+            OrWitness::Right(witness) => {
+                let (left_commitment, response) = self.0.simulate(&simulated_challenge, rng);
+                let (right_commitment, state) = self.1.prover_commit(witness, rng);
+                (
+                    (left_commitment, right_commitment),
+                    OrProverState::Right(simulated_challenge, response, state),
+                )
+            }
+        }
+    }
+
+    fn prover_response(
+        &self,
+        state: Self::ProverState,
+        challenge: &[u8],
+    ) -> ProofResult<Self::Response> {
+        match state {
+            OrProverState::Left(state, right_challenge, right) => {
+                let left_challenge = xor(challenge, &right_challenge);
+                let left = self.0.prover_response(state, &left_challenge)?;
+                Ok(OrResponse {
+                    left_challenge,
+                    left,
+                    right,
+                })
+            }
+            OrProverState::Right(left_challenge, left, state) => {
+                let right_challenge = xor(challenge, &left_challenge);
+                let right = self.1.prover_response(state, &right_challenge)?;
+                Ok(OrResponse {
+                    left_challenge,
+                    left,
+                    right,
+                })
+            }
+        }
+    }
+
+    fn verifier(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &[u8],
+        response: &Self::Response,
+    ) -> ProofResult<()> {
+        let right_challenge = xor(challenge, &response.left_challenge);
+        self.0
+            .verifier(&commitment.0, &response.left_challenge, &response.left)?;
+        self.1
+            .verifier(&commitment.1, &right_challenge, &response.right)
+    }
+
+    fn simulate(
+        &self,
+        challenge: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self::Commitment, Self::Response) {
+        let mut left_challenge = vec![0u8; self.challenge_length()];
+        rng.fill_bytes(&mut left_challenge);
+        let right_challenge = xor(challenge, &left_challenge);
+        let (left_commitment, left) = self.0.simulate(&left_challenge, rng);
+        let (right_commitment, right) = self.1.simulate(&right_challenge, rng);
+        (
+            (left_commitment, right_commitment),
+            OrResponse {
+                left_challenge,
+                left,
+                right,
+            },
+        )
+    }
+
+    fn serialize_commitment(&self, commitment: &Self::Commitment) -> Vec<u8> {
+        let mut bytes = self.0.serialize_commitment(&commitment.0);
+        bytes.extend(self.1.serialize_commitment(&commitment.1));
+        bytes
+    }
+
+    fn deserialize_commitment(&self, bytes: &[u8]) -> ProofResult<Self::Commitment> {
+        let (left, right) = split(bytes, self.0.commitment_length())?;
+        Ok((
+            self.0.deserialize_commitment(left)?,
+            self.1.deserialize_commitment(right)?,
+        ))
+    }
+
+    fn serialize_response(&self, response: &Self::Response) -> Vec<u8> {
+        let mut bytes = response.left_challenge.clone();
+        bytes.extend(self.0.serialize_response(&response.left));
+        bytes.extend(self.1.serialize_response(&response.right));
+        bytes
+    }
+
+    fn deserialize_response(&self, bytes: &[u8]) -> ProofResult<Self::Response> {
+        let (left_challenge, bytes) = split(bytes, self.challenge_length())?;
+        let (left, right) = split(bytes, self.0.response_length())?;
+        Ok(OrResponse {
+            left_challenge: left_challenge.to_vec(),
+            left: self.0.deserialize_response(left)?,
+            right: self.1.deserialize_response(right)?,
+        })
+    }
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(a, b)| a ^ b).collect()
+}
+
+fn split(bytes: &[u8], mid: usize) -> ProofResult<(&[u8], &[u8])> {
+    if bytes.len() < mid {
+        return Err(ProofError::SerializationError);
+    }
+    Ok(bytes.split_at(mid))
+}
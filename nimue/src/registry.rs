@@ -0,0 +1,165 @@
+//! A registry of domain separators for protocols built on nimue.
+//!
+//! Two protocols sharing a domain separator share their initialization vectors only if their IO patterns are equal,
+//! but nothing prevents two libraries from picking the same name for different protocols (say, `"schnorr"`)
+//! and then evolving one of them.
+//! A [`Registry`] maps domain separators (and, optionally, digests of full IO patterns) to the protocol they belong to,
+//! and rejects any registration colliding with an existing one:
+//!
+//! ```
+//! use nimue::registry::{self, Registry};
+//! use nimue::{ByteIOPattern, DefaultHash, IOPattern};
+//!
+//! let mut registry = Registry::well_known();
+//! assert_eq!(registry.lookup(registry::SCHNORR).unwrap().name, "schnorr");
+//!
+//! let io = IOPattern::<DefaultHash>::new("example.com/vrf/v1").add_bytes(32, "output");
+//! registry.register_pattern("vrf", &io).unwrap();
+//! assert_eq!(registry.lookup_pattern(&io).unwrap().name, "vrf");
+//! // another protocol can't take the same domain separator
+//! assert!(registry.register("sumcheck", "example.com/vrf/v1").is_err());
+//! ```
+//!
+//! Domain separators should be namespaced, e.g., by a domain name and a version as above.
+//! Well-known protocols use the prefix `nimue/`.
+use crate::proof::pattern_digest;
+use crate::{DuplexHash, IOPattern, IOPatternError, Unit};
+
+/// The domain separator of Schnorr's proof of knowledge of a discrete logarithm.
+pub const SCHNORR: &str = "nimue/schnorr/v1";
+/// The domain separator of KZG polynomial commitment openings.
+pub const KZG: &str = "nimue/kzg/v1";
+/// The domain separator of the sumcheck protocol.
+pub const SUMCHECK: &str = "nimue/sumcheck/v1";
+
+/// A protocol in a [`Registry`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The name of the protocol.
+    pub name: String,
+    /// The domain separator of its IO patterns.
+    pub domain_separator: String,
+    /// The digest of its IO pattern, if registered with [`Registry::register_pattern`].
+    pub digest: Option<[u8; 32]>,
+}
+
+/// A collection of protocols with distinct domain separators, see the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the protocols implemented on nimue: [`SCHNORR`], [`KZG`], and [`SUMCHECK`].
+    pub fn well_known() -> Self {
+        let entry = |name: &str, domain_separator: &str| Entry {
+            name: name.to_string(),
+            domain_separator: domain_separator.to_string(),
+            digest: None,
+        };
+        Self {
+            entries: vec![
+                entry("schnorr", SCHNORR),
+                entry("kzg", KZG),
+                entry("sumcheck", SUMCHECK),
+            ],
+        }
+    }
+
+    /// Register the protocol `name`, with the domain separator `domain_separator`.
+    ///
+    /// Registering the same protocol twice is allowed;
+    /// return an error if the name or the domain separator are taken by another entry.
+    pub fn register(&mut self, name: &str, domain_separator: &str) -> Result<(), IOPatternError> {
+        self.insert(name, domain_separator, None)
+    }
+
+    /// Register the protocol `name`, with the domain separator and the digest of `io_pattern`.
+    ///
+    /// Return an error as in [`Registry::register`],
+    /// or if the protocol was registered with a different IO pattern.
+    pub fn register_pattern<H: DuplexHash<U>, U: Unit>(
+        &mut self,
+        name: &str,
+        io_pattern: &IOPattern<H, U>,
+    ) -> Result<(), IOPatternError> {
+        self.insert(
+            name,
+            io_pattern.domain_separator(),
+            Some(pattern_digest(io_pattern)),
+        )
+    }
+
+    fn insert(
+        &mut self,
+        name: &str,
+        domain_separator: &str,
+        digest: Option<[u8; 32]>,
+    ) -> Result<(), IOPatternError> {
+        let existing = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.name == name || entry.domain_separator == domain_separator);
+        let Some(entry) = existing else {
+            self.entries.push(Entry {
+                name: name.to_string(),
+                domain_separator: domain_separator.to_string(),
+                digest,
+            });
+            return Ok(());
+        };
+        if entry.name != name {
+            return Err(format!(
+                "The domain separator {domain_separator:?} is registered by {:?}",
+                entry.name
+            )
+            .into());
+        }
+        if entry.domain_separator != domain_separator {
+            return Err(format!(
+                "The protocol {name:?} is registered with the domain separator {:?}",
+                entry.domain_separator
+            )
+            .into());
+        }
+        match (entry.digest, digest) {
+            (Some(registered), Some(digest)) if registered != digest => Err(format!(
+                "The protocol {name:?} is registered with a different IO pattern"
+            )
+            .into()),
+            (None, Some(digest)) => {
+                entry.digest = Some(digest);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Look up the protocol using the domain separator `domain_separator`.
+    pub fn lookup(&self, domain_separator: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.domain_separator == domain_separator)
+    }
+
+    /// Look up the protocol registered with the IO pattern `io_pattern`.
+    pub fn lookup_pattern<H: DuplexHash<U>, U: Unit>(
+        &self,
+        io_pattern: &IOPattern<H, U>,
+    ) -> Option<&Entry> {
+        let digest = pattern_digest(io_pattern);
+        self.entries
+            .iter()
+            .find(|entry| entry.digest == Some(digest))
+    }
+
+    /// Iterate over the registered protocols, in the order they were registered.
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::Write;
+
+use crate::iopattern::Op;
+use crate::{DuplexHash, IOPattern, Unit};
+
+/// Escape `text` for the message of a Mermaid diagram.
+fn escape_mermaid(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '#' => "#35;".to_string(),
+            ';' => "#59;".to_string(),
+            '\n' | '\r' => " ".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Escape `text` for a quoted string of the DOT language.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\n', '\r'], " ")
+}
+
+impl<H: DuplexHash<U>, U: Unit> IOPattern<H, U> {
+    /// Render the IO pattern as a [Mermaid](https://mermaid.js.org) sequence diagram.
+    ///
+    /// Absorptions (prover messages and public inputs) are drawn from the prover to the verifier,
+    /// squeezes (challenges) from the verifier to the prover, and ratchets as notes; each message carries its label and its length in units.
+    ///
+    /// ```
+    /// use nimue::{ByteIOPattern, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝")
+    ///     .add_bytes(32, "commitment")
+    ///     .challenge_bytes(16, "challenge");
+    /// assert_eq!(
+    ///     io.to_mermaid(),
+    ///     "sequenceDiagram
+    ///     participant P as Prover
+    ///     participant V as Verifier
+    ///     Note over P,V: 📝
+    ///     P->>V: commitment (32)
+    ///     V->>P: challenge (16)
+    /// "
+    /// );
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut output = String::new();
+        // writing to a String never fails
+        writeln!(output, "sequenceDiagram").unwrap();
+        writeln!(output, "    participant P as Prover").unwrap();
+        writeln!(output, "    participant V as Verifier").unwrap();
+        writeln!(
+            output,
+            "    Note over P,V: {}",
+            escape_mermaid(self.domain_separator())
+        )
+        .unwrap();
+        for (op, label) in self.entries() {
+            let label = escape_mermaid(label);
+            match op {
+                Op::Absorb(length) => writeln!(output, "    P->>V: {label} ({length})"),
+                Op::Squeeze(length) => writeln!(output, "    V->>P: {label} ({length})"),
+                Op::Ratchet => writeln!(output, "    Note over P,V: ratchet"),
+            }
+            .unwrap();
+        }
+        output
+    }
+
+    /// Render the IO pattern as a [Graphviz](https://graphviz.org) graph in the DOT language.
+    ///
+    /// Operations are chained from the domain separator, as in [`IOPattern::to_mermaid`]:
+    /// absorptions are boxes, squeezes are ellipses, and ratchets are diamonds.
+    ///
+    /// ```
+    /// use nimue::{ByteIOPattern, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(32, "commitment").ratchet();
+    /// let dot = io.to_dot();
+    /// assert!(dot.starts_with("digraph IOPattern {"));
+    /// assert!(dot.contains("n1 [shape=box, label=\"P → V: commitment (32)\"];"));
+    /// assert!(dot.contains("n1 -> n2;"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut output = String::new();
+        // writing to a String never fails
+        writeln!(output, "digraph IOPattern {{").unwrap();
+        writeln!(output, "    rankdir=TB;").unwrap();
+        writeln!(
+            output,
+            "    n0 [shape=plaintext, label=\"{}\"];",
+            escape_dot(self.domain_separator())
+        )
+        .unwrap();
+        for (i, (op, label)) in self.entries().enumerate() {
+            let label = escape_dot(label);
+            let node = i + 1;
+            match op {
+                Op::Absorb(length) => writeln!(
+                    output,
+                    "    n{node} [shape=box, label=\"P → V: {label} ({length})\"];"
+                ),
+                Op::Squeeze(length) => writeln!(
+                    output,
+                    "    n{node} [shape=ellipse, label=\"V → P: {label} ({length})\"];"
+                ),
+                Op::Ratchet => writeln!(output, "    n{node} [shape=diamond, label=\"ratchet\"];"),
+            }
+            .unwrap();
+            writeln!(output, "    n{i} -> n{node};").unwrap();
+        }
+        writeln!(output, "}}").unwrap();
+        output
+    }
+}
@@ -1,11 +1,13 @@
 use core::fmt;
 use core::marker::PhantomData;
-use std::collections::vec_deque::VecDeque;
+use std::sync::Arc;
 
+use super::binding::Binding;
 use super::errors::IOPatternError;
 use super::hash::Unit;
 use super::hash::{DuplexHash, Keccak};
 use super::iopattern::{IOPattern, Op};
+use super::vectors::Operation;
 
 /// A (slightly modified) SAFE API for sponge functions.
 ///
@@ -17,7 +19,23 @@ where
     H: DuplexHash<U>,
 {
     sponge: H,
-    stack: VecDeque<Op>,
+    /// The operations of the IO pattern, shared with the pattern (and every sponge built from it).
+    ops: Arc<[Op]>,
+    /// The index of the next operation in `ops`.
+    position: usize,
+    /// The units of the next operation already absorbed or squeezed.
+    consumed: usize,
+    /// The lengths and labels absorbed along with the messages and the challenges.
+    binding: Binding,
+    #[cfg(feature = "tracing")]
+    labels: crate::trace::Labels,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    recording: Option<Vec<Operation>>,
+    /// The outputs of the deferred squeezes, in order (`None` once resolved).
+    /// The ones from index `materialized` on are not computed yet.
+    deferred: Vec<Option<Vec<U>>>,
+    materialized: usize,
     _unit: PhantomData<U>,
 }
 
@@ -25,24 +43,153 @@ impl<U: Unit, H: DuplexHash<U>> Safe<H, U> {
     /// Initialise a SAFE sponge,
     /// setting up the state of the sponge function and parsing the tag string.
     pub fn new(io_pattern: &IOPattern<H, U>) -> Self {
-        let stack = io_pattern.finalize();
         let tag = Self::generate_tag(io_pattern.as_bytes());
-        Self::unchecked_load_with_stack(tag, stack)
+        #[allow(unused_mut)]
+        let mut safe = Self::unchecked_load_with_ops(tag, io_pattern.ops());
+        safe.binding = Binding::new(io_pattern);
+        #[cfg(feature = "tracing")]
+        {
+            safe.labels = crate::trace::Labels::new(io_pattern.as_bytes());
+            safe.span =
+                tracing::debug_span!("nimue", domain_separator = io_pattern.domain_separator());
+        }
+        safe
+    }
+
+    /// Emit an event for the operation `op` of `length` units with outcome `result`,
+    /// within the span of the transcript.
+    ///
+    /// Successful operations are traced along with the labels they were declared with,
+    /// failing ones along with the label that was expected.
+    #[cfg(feature = "tracing")]
+    fn trace(&mut self, op: u8, length: usize, result: &Result<(), IOPatternError>) {
+        let _span = self.span.enter();
+        let name = match op {
+            b'A' => "absorb",
+            b'S' => "squeeze",
+            _ => "ratchet",
+        };
+        match result {
+            Ok(()) => {
+                let label = self.labels.consume(op, length);
+                tracing::trace!(op = name, length, label = %label);
+            }
+            Err(error) => {
+                let expected = self.labels.next().unwrap_or_default();
+                tracing::warn!(op = name, length, expected = %expected, error = %error);
+                self.labels.clear();
+            }
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    #[inline(always)]
+    fn trace(&mut self, _op: u8, _length: usize, _result: &Result<(), IOPatternError>) {}
+
+    /// The next operation to perform, with the units left to absorb or squeeze.
+    fn next_op(&self) -> Option<Op> {
+        self.ops.get(self.position).map(|op| match *op {
+            Op::Absorb(length) => Op::Absorb(length - self.consumed),
+            Op::Squeeze(length) => Op::Squeeze(length - self.consumed),
+            Op::Ratchet => Op::Ratchet,
+        })
+    }
+
+    /// Consume `length` units of the next operation, moving on to the following one once it is complete.
+    fn advance(&mut self, length: usize) {
+        self.consumed += length;
+        if let Some(Op::Absorb(0) | Op::Squeeze(0) | Op::Ratchet) = self.next_op() {
+            self.position += 1;
+            self.consumed = 0;
+        }
+    }
+
+    /// Discard the operations left, after an error.
+    pub(crate) fn clear(&mut self) {
+        self.position = self.ops.len();
+        self.consumed = 0;
+    }
+
+    /// Whether all the operations of the IO pattern were performed.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.position == self.ops.len()
+    }
+
+    /// The number of units absorbed or squeezed so far, and the number of ratchets performed.
+    pub(crate) fn progress(&self) -> (usize, usize) {
+        let done = &self.ops[..self.position];
+        let units = done
+            .iter()
+            .map(|op| match op {
+                Op::Absorb(length) | Op::Squeeze(length) => *length,
+                Op::Ratchet => 0,
+            })
+            .sum::<usize>();
+        let ratchets = done.iter().filter(|op| **op == Op::Ratchet).count();
+        (units + self.consumed, ratchets)
+    }
+
+    /// The operations left, the next one included.
+    fn remaining(&self) -> Vec<Op> {
+        self.next_op()
+            .into_iter()
+            .chain(self.ops.iter().skip(self.position + 1).copied())
+            .collect()
+    }
+
+    /// Start recording the operations performed, see [`TestVector`](crate::TestVector).
+    pub(crate) fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// The operations recorded so far, if recording.
+    pub(crate) fn recording(&self) -> Option<&[Operation]> {
+        self.recording.as_deref()
+    }
+
+    /// Record the operation `op` if recording.
+    fn record(&mut self, op: impl FnOnce() -> Operation) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(op());
+        }
     }
 
     /// Finish the block and compress the state.
     pub fn ratchet(&mut self) -> Result<(), IOPatternError> {
-        if self.stack.pop_front().unwrap() != Op::Ratchet {
-            Err("Invalid tag".into())
-        } else {
-            self.sponge.ratchet_unchecked();
-            Ok(())
+        self.materialize(self.deferred.len());
+        let result = match self.next_op() {
+            Some(Op::Ratchet) => {
+                self.sponge.ratchet_unchecked();
+                self.advance(0);
+                Ok(())
+            }
+            None => {
+                self.clear();
+                Err(format!("Invalid tag. Stack empty, got {:?}", Op::Ratchet).into())
+            }
+            Some(op) => {
+                self.clear();
+                Err(format!("Invalid tag. Got {:?}, expected {:?}", Op::Ratchet, op).into())
+            }
+        };
+        self.trace(b'R', 0, &result);
+        if result.is_ok() {
+            self.record(|| Operation::Ratchet);
         }
+        result
+    }
+
+    /// Ratchet and export the capacity of the sponge state, see [`DuplexHash::export_unchecked`].
+    pub fn ratchet_and_export(&mut self) -> Result<Vec<U>, IOPatternError> {
+        self.ratchet()?;
+        self.sponge
+            .export_unchecked()
+            .ok_or_else(|| "The hash function does not support exporting its state".into())
     }
 
     /// Ratchet and return the sponge state.
     pub fn preprocess(self) -> Result<&'static [U], IOPatternError> {
-        unimplemented!()
+        Err("Preprocessing is not supported yet".into())
         // self.ratchet()?;
         // Ok(self.sponge.tag().clone())
     }
@@ -51,16 +198,11 @@ impl<U: Unit, H: DuplexHash<U>> Safe<H, U> {
     ///
     /// Absorb calls can be batched together, or provided separately for streaming-friendly protocols.
     pub fn absorb(&mut self, input: &[U]) -> Result<(), IOPatternError> {
-        match self.stack.pop_front() {
-            Some(Op::Absorb(length)) if length >= input.len() => {
-                if length > input.len() {
-                    self.stack.push_front(Op::Absorb(length - input.len()));
-                }
-                self.sponge.absorb_unchecked(input);
-                Ok(())
-            }
+        self.materialize(self.deferred.len());
+        let result = match self.next_op() {
+            Some(Op::Absorb(_)) if self.absorbable() >= input.len() => self.absorb_framed(input),
             None => {
-                self.stack.clear();
+                self.clear();
                 Err(format!(
                     "Invalid tag. Stack empty, got {:?}",
                     Op::Absorb(input.len())
@@ -68,7 +210,7 @@ impl<U: Unit, H: DuplexHash<U>> Safe<H, U> {
                 .into())
             }
             Some(op) => {
-                self.stack.clear();
+                self.clear();
                 Err(format!(
                     "Invalid tag. Got {:?}, expected {:?}",
                     Op::Absorb(input.len()),
@@ -76,7 +218,41 @@ impl<U: Unit, H: DuplexHash<U>> Safe<H, U> {
                 )
                 .into())
             }
+        };
+        self.trace(b'A', input.len(), &result);
+        if result.is_ok() {
+            self.record(|| Operation::Absorb(Operation::units(input)));
         }
+        result
+    }
+
+    /// The units that can be absorbed before the next squeeze or ratchet.
+    fn absorbable(&self) -> usize {
+        let mut ops = self.ops[self.position..].iter();
+        core::iter::from_fn(|| match ops.next() {
+            Some(Op::Absorb(length)) => Some(*length),
+            _ => None,
+        })
+        .sum::<usize>()
+            - self.consumed
+    }
+
+    /// Absorb `input` over the next absorptions, prefixing each of them with its length if framing is enabled.
+    ///
+    /// The length of `input` must have been checked against [`Safe::absorbable`].
+    fn absorb_framed(&mut self, mut input: &[U]) -> Result<(), IOPatternError> {
+        while let (Some(Op::Absorb(remaining)), false) = (self.next_op(), input.is_empty()) {
+            if self.consumed == 0 {
+                if let Some(prefix) = self.binding.length_prefix(remaining)? {
+                    self.sponge.absorb_unchecked(&prefix);
+                }
+            }
+            let (chunk, rest) = input.split_at(remaining.min(input.len()));
+            self.sponge.absorb_unchecked(chunk);
+            self.advance(chunk.len());
+            input = rest;
+        }
+        Ok(())
     }
 
     /// Perform a secure squeeze operation, filling the output buffer with uniformly random bytes.
@@ -85,36 +261,108 @@ impl<U: Unit, H: DuplexHash<U>> Safe<H, U> {
     /// However, for algebraic hashes, this operation is non-trivial.
     /// This function provides no guarantee of streaming-friendliness.
     pub fn squeeze(&mut self, output: &mut [U]) -> Result<(), IOPatternError> {
-        match self.stack.pop_front() {
-            Some(Op::Squeeze(length)) if output.len() <= length => {
-                self.sponge.squeeze_unchecked(output);
-                if length != output.len() {
-                    self.stack.push_front(Op::Squeeze(length - output.len()));
+        self.materialize(self.deferred.len());
+        let result = self.check_squeeze(output);
+        if result.is_ok() {
+            self.sponge.squeeze_unchecked(output);
+        }
+        self.trace(b'S', output.len(), &result);
+        if result.is_ok() {
+            self.record(|| Operation::Squeeze(Operation::units(output)));
+        }
+        result
+    }
+
+    /// Check a squeeze into `output` against the IO pattern, and consume it.
+    ///
+    /// If the squeeze starts a new one and labels are bound, its label is absorbed.
+    fn check_squeeze(&mut self, output: &[U]) -> Result<(), IOPatternError> {
+        let length = output.len();
+        match self.next_op() {
+            Some(Op::Squeeze(declared)) if length <= declared => {
+                if !output.is_empty() && self.consumed == 0 {
+                    if let Err(error) = self.absorb_label() {
+                        self.clear();
+                        return Err(error);
+                    }
                 }
+                self.advance(length);
                 Ok(())
             }
             None => {
-                self.stack.clear();
-                Err(format!(
-                    "Invalid tag. Stack empty, got {:?}",
-                    Op::Squeeze(output.len())
-                )
-                .into())
+                self.clear();
+                Err(format!("Invalid tag. Stack empty, got {:?}", Op::Squeeze(length)).into())
             }
             Some(op) => {
-                self.stack.clear();
-                Err(format!(
+                let remaining = &self.ops[self.position + 1..];
+                let error = format!(
                     "Invalid tag. Got {:?}, expected {:?}. The stack remaining is: {:?}",
-                    Op::Squeeze(output.len()),
+                    Op::Squeeze(length),
                     op,
-                    self.stack
-                )
-                .into())
+                    remaining
+                );
+                self.clear();
+                Err(error.into())
             }
         }
     }
 
-    fn generate_tag(iop_bytes: &[u8]) -> [u8; 32] {
+    /// Absorb the label of the next squeeze, if labels are bound.
+    ///
+    /// The deferred squeezes are computed first, as they precede the label.
+    fn absorb_label(&mut self) -> Result<(), IOPatternError> {
+        if let Some(label) = self.binding.next_label()? {
+            self.materialize(self.deferred.len());
+            self.sponge.absorb_unchecked(&label);
+        }
+        Ok(())
+    }
+
+    /// Check a squeeze of `output.len()` units against the IO pattern,
+    /// deferring the computation of `output` until [`Safe::resolve_deferred`] is called
+    /// or the sponge is used again.
+    ///
+    /// Return the index of the deferred squeeze.
+    pub(crate) fn squeeze_deferred(&mut self, output: Vec<U>) -> Result<usize, IOPatternError> {
+        let result = self.check_squeeze(&output);
+        self.trace(b'S', output.len(), &result);
+        result?;
+        self.deferred.push(Some(output));
+        Ok(self.deferred.len() - 1)
+    }
+
+    /// Return the output of the deferred squeeze `index`, computing it (and the ones before it) if needed.
+    pub(crate) fn resolve_deferred(&mut self, index: usize) -> Result<Vec<U>, IOPatternError> {
+        if index >= self.deferred.len() {
+            return Err("Unknown challenge handle".into());
+        }
+        self.materialize(index + 1);
+        self.deferred[index]
+            .take()
+            .ok_or_else(|| "The challenge was already resolved".into())
+    }
+
+    /// Compute the deferred squeezes up to `end` (excluded), in order.
+    fn materialize(&mut self, end: usize) {
+        for index in self.materialized..end {
+            // deferred squeezes are resolved only after being materialized
+            if let Some(output) = self.deferred[index].as_mut() {
+                self.sponge.squeeze_unchecked(output);
+                if let Some(recording) = &mut self.recording {
+                    recording.push(Operation::Squeeze(Operation::units(output)));
+                }
+            }
+        }
+        self.materialized = self.materialized.max(end);
+    }
+
+    /// Derive the initial value of the sponge from the IO pattern `iop_bytes`, of any length.
+    ///
+    /// The pattern is absorbed into Keccak in a single call, which permutes at each block boundary,
+    /// so domain separators and labels have no length limit.
+    /// No padding is needed for the tags of valid patterns to be distinct:
+    /// labels and domain separators cannot contain the separator byte `\0`, so no valid pattern extends another one by zero bytes.
+    pub(crate) fn generate_tag(iop_bytes: &[u8]) -> [u8; 32] {
         let mut keccak = Keccak::default();
         keccak.absorb_unchecked(iop_bytes);
         let mut tag = [0u8; 32];
@@ -122,10 +370,20 @@ impl<U: Unit, H: DuplexHash<U>> Safe<H, U> {
         tag
     }
 
-    fn unchecked_load_with_stack(tag: [u8; 32], stack: VecDeque<Op>) -> Self {
+    fn unchecked_load_with_ops(tag: [u8; 32], ops: Arc<[Op]>) -> Self {
         Self {
             sponge: H::new(tag),
-            stack,
+            ops,
+            position: 0,
+            consumed: 0,
+            binding: Binding::default(),
+            #[cfg(feature = "tracing")]
+            labels: Default::default(),
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
+            recording: None,
+            deferred: Vec::new(),
+            materialized: 0,
             _unit: PhantomData,
         }
     }
@@ -138,8 +396,8 @@ impl<U: Unit, H: DuplexHash<U>> Drop for Safe<H, U> {
         // because any other issue in the protocol transcript causing `Safe` to get out of scope
         // (like another panic) will pollute the traceback.
         // debug_assert!(self.stack.is_empty());
-        if !self.stack.is_empty() {
-            log::error!("Unfinished operations:\n {:?}", self.stack)
+        if !self.is_finished() {
+            log::error!("Unfinished operations:\n {:?}", self.remaining())
         }
         // XXX. is the compiler going to optimize this out?
         self.sponge.zeroize();
@@ -150,7 +408,7 @@ impl<U: Unit, H: DuplexHash<U>> fmt::Debug for Safe<H, U> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Ensure that the state isn't accidentally logged,
         // but provide the remaining IO Pattern for debugging.
-        write!(f, "SAFE sponge with IO: {:?}", self.stack)
+        write!(f, "SAFE sponge with IO: {:?}", self.remaining())
     }
 }
 
@@ -0,0 +1,190 @@
+use crate::{ByteChallenges, ByteIOPattern, IOPatternError};
+
+/// The number of bytes squeezed for each bounded-uniform integer.
+const BOUNDED_BYTES: usize = 16;
+/// The number of bytes squeezed for each discrete Gaussian sample.
+const GAUSSIAN_BYTES: usize = 8;
+/// The tail cut of the discrete Gaussian, in standard deviations.
+const TAIL_CUT: f64 = 12.0;
+/// The largest standard deviation supported, to keep the table small.
+const MAX_SIGMA: f64 = (1 << 20) as f64;
+
+/// Squeeze centered bounded-uniform integers and discrete Gaussians in the IO pattern.
+///
+/// Lattice-based protocols derive structured challenges (masks, short vectors) from the transcript.
+/// For these, the pattern accounts for all the entropy squeezed from the sponge:
+/// - a bounded-uniform integer squeezes 16 bytes;
+/// - a discrete Gaussian sample squeezes 8 bytes.
+pub trait SamplerIOPattern {
+    fn challenge_bounded(self, count: usize, label: &str) -> Self;
+    fn challenge_gaussian(self, count: usize, label: &str) -> Self;
+}
+
+/// Interpret verifier messages as centered bounded-uniform integers and discrete Gaussians, see [`SamplerIOPattern`].
+pub trait SamplerChallenges {
+    /// Fill `output` with integers uniform (up to a statistical distance of $2^{-64}$ each) in $[-B, B]$, where $B$ is `bound`.
+    ///
+    /// A 128-bit integer $r$ is mapped to $\lfloor r (2B + 1) / 2^{128} \rfloor - B$ with multiplications only,
+    /// so sampling is constant-time.
+    ///
+    /// Return an error if `bound` exceeds $2^{62}$.
+    fn fill_challenge_bounded(
+        &mut self,
+        bound: u64,
+        output: &mut [i64],
+    ) -> Result<(), IOPatternError>;
+
+    /// Fill `output` with samples from the discrete Gaussian `gaussian`.
+    fn fill_challenge_gaussian(
+        &mut self,
+        gaussian: &DiscreteGaussian,
+        output: &mut [i64],
+    ) -> Result<(), IOPatternError>;
+
+    /// Return `n` bounded-uniform integers, see [`SamplerChallenges::fill_challenge_bounded`].
+    fn challenge_bounded_vec(&mut self, bound: u64, n: usize) -> Result<Vec<i64>, IOPatternError> {
+        let mut output = vec![0; n];
+        self.fill_challenge_bounded(bound, &mut output)
+            .map(|()| output)
+    }
+
+    /// Return `n` samples from the discrete Gaussian `gaussian`.
+    fn challenge_gaussian_vec(
+        &mut self,
+        gaussian: &DiscreteGaussian,
+        n: usize,
+    ) -> Result<Vec<i64>, IOPatternError> {
+        let mut output = vec![0; n];
+        self.fill_challenge_gaussian(gaussian, &mut output)
+            .map(|()| output)
+    }
+}
+
+impl<T: ByteIOPattern> SamplerIOPattern for T {
+    fn challenge_bounded(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(BOUNDED_BYTES * count, label)
+    }
+
+    fn challenge_gaussian(self, count: usize, label: &str) -> Self {
+        self.challenge_bytes(GAUSSIAN_BYTES * count, label)
+    }
+}
+
+impl<T: ByteChallenges> SamplerChallenges for T {
+    fn fill_challenge_bounded(
+        &mut self,
+        bound: u64,
+        output: &mut [i64],
+    ) -> Result<(), IOPatternError> {
+        if bound > 1 << 62 {
+            return Err("The bound cannot exceed 2^62.".into());
+        }
+        let mut buf = vec![0u8; BOUNDED_BYTES * output.len()];
+        self.fill_challenge_bytes(&mut buf)?;
+        let range = 2 * bound + 1;
+        for (o, chunk) in output.iter_mut().zip(buf.chunks(BOUNDED_BYTES)) {
+            let r = u128::from_le_bytes(chunk.try_into().unwrap());
+            *o = mul_high(r, range) as i64 - bound as i64;
+        }
+        Ok(())
+    }
+
+    fn fill_challenge_gaussian(
+        &mut self,
+        gaussian: &DiscreteGaussian,
+        output: &mut [i64],
+    ) -> Result<(), IOPatternError> {
+        let mut buf = vec![0u8; GAUSSIAN_BYTES * output.len()];
+        self.fill_challenge_bytes(&mut buf)?;
+        for (o, chunk) in output.iter_mut().zip(buf.chunks(GAUSSIAN_BYTES)) {
+            *o = gaussian.sample(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        Ok(())
+    }
+}
+
+/// $\lfloor r m / 2^{128} \rfloor$, computed on 64-bit limbs.
+fn mul_high(r: u128, m: u64) -> u64 {
+    let (hi, lo) = ((r >> 64) as u64, r as u64);
+    let lo_product = lo as u128 * m as u128;
+    let hi_product = hi as u128 * m as u128;
+    ((hi_product + (lo_product >> 64)) >> 64) as u64
+}
+
+/// A discrete Gaussian over the integers, centered in zero, with standard deviation $\sigma$.
+///
+/// Samples are drawn with a cumulative distribution table (CDT) of the magnitude, cut at $12\sigma$,
+/// with 63-bit precision; the remaining bit of each 8-byte sample gives the sign.
+/// The table is computed with floating-point arithmetic, so each probability is only accurate to about $2^{-53}$.
+///
+/// By default, the whole table is scanned for each sample, so that its running time does not depend on the sample.
+/// For public challenges, [`DiscreteGaussian::variable_time`] stops at the first matching entry.
+#[derive(Clone, Debug)]
+pub struct DiscreteGaussian {
+    /// `cdt[i]` is $2^{63} \cdot \Pr[|X| \le i]$.
+    cdt: Vec<u64>,
+    constant_time: bool,
+}
+
+impl DiscreteGaussian {
+    /// The discrete Gaussian of standard deviation `sigma`.
+    ///
+    /// Return an error if `sigma` is not positive or exceeds $2^{20}$.
+    pub fn new(sigma: f64) -> Result<Self, IOPatternError> {
+        if sigma.is_nan() || sigma <= 0.0 || sigma > MAX_SIGMA {
+            return Err("The standard deviation must be in (0, 2^20].".into());
+        }
+        let len = (TAIL_CUT * sigma).ceil() as usize + 1;
+        // the weight of each magnitude, counting both signs for non-zero values
+        let weights = (0..len)
+            .map(|x| {
+                let rho = (-((x * x) as f64) / (2.0 * sigma * sigma)).exp();
+                if x == 0 {
+                    rho
+                } else {
+                    2.0 * rho
+                }
+            })
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f64>();
+        let scale = (1u64 << 63) as f64;
+        let mut cumulative = 0.0;
+        let mut cdt = weights
+            .iter()
+            .map(|weight| {
+                cumulative += weight;
+                ((cumulative / total) * scale).min(scale) as u64
+            })
+            .collect::<Vec<_>>();
+        *cdt.last_mut().unwrap() = 1 << 63;
+        Ok(Self {
+            cdt,
+            constant_time: true,
+        })
+    }
+
+    /// Stop the table lookup at the first matching entry, leaking the magnitude of the sample through timing.
+    pub fn variable_time(mut self) -> Self {
+        self.constant_time = false;
+        self
+    }
+
+    /// Map 64 uniformly random bits to a sample.
+    pub fn sample(&self, bits: u64) -> i64 {
+        let negative = bits >> 63;
+        let r = bits & ((1 << 63) - 1);
+        let magnitude = if self.constant_time {
+            self.cdt
+                .iter()
+                .fold(0u64, |acc, &threshold| acc + (r >= threshold) as u64)
+        } else {
+            self.cdt
+                .iter()
+                .position(|&threshold| r < threshold)
+                .unwrap() as u64
+        } as i64;
+        // conditionally negate, without branching
+        let mask = -(negative as i64);
+        (magnitude ^ mask) - mask
+    }
+}
@@ -0,0 +1,60 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::{Arthur, ByteIOPattern, BytePublic, DuplexHash, IOPattern, IOPatternError, Merlin};
+
+/// The label of the absorption of the session identifier.
+const SESSION_LABEL: &str = "session";
+
+impl<H: DuplexHash> IOPattern<H> {
+    /// Declare a session identifier of `length` bytes, absorbed right after the domain separator.
+    ///
+    /// The domain separator identifies the protocol; the session identifies the instance.
+    /// Deployments proving many instances under the same IO pattern (e.g., one per request, or per epoch)
+    /// bind each proof to its instance with [`Merlin::bind_session`] and [`Arthur::bind_session`],
+    /// so that a proof for one session is rejected in any other.
+    /// The identifier is public and is not written in the protocol transcript.
+    ///
+    /// Panics if the pattern already has operations.
+    ///
+    /// ```
+    /// use nimue::{ByteChallenges, ByteIOPattern, DefaultHash, IOPattern};
+    ///
+    /// let io = IOPattern::<DefaultHash>::new("📝").with_session(16).challenge_bytes(16, "challenge");
+    /// let mut merlin = io.to_merlin();
+    /// merlin.bind_session(b"session #1234567").unwrap();
+    /// let challenge = merlin.challenge_bytes::<16>().unwrap();
+    ///
+    /// let mut arthur = io.to_arthur(merlin.transcript());
+    /// arthur.bind_session(b"session #7654321").unwrap();
+    /// assert_ne!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+    /// ```
+    pub fn with_session(self, length: usize) -> Self {
+        assert!(
+            !self.as_bytes().contains(&0),
+            "The session must follow the domain separator."
+        );
+        self.add_bytes(length, SESSION_LABEL)
+    }
+}
+
+impl<H, R> Merlin<H, u8, R>
+where
+    H: DuplexHash,
+    R: RngCore + CryptoRng,
+{
+    /// Absorb the session identifier `session`, declared with [`IOPattern::with_session`].
+    ///
+    /// It must be the first operation of the prover.
+    pub fn bind_session(&mut self, session: &[u8]) -> Result<(), IOPatternError> {
+        self.public_bytes(session)
+    }
+}
+
+impl<H: DuplexHash> Arthur<'_, H> {
+    /// Absorb the session identifier `session`, declared with [`IOPattern::with_session`].
+    ///
+    /// It must be the first operation of the verifier.
+    pub fn bind_session(&mut self, session: &[u8]) -> Result<(), IOPatternError> {
+        self.public_bytes(session)
+    }
+}
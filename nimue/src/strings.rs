@@ -0,0 +1,80 @@
+use crate::{ByteIOPattern, BytePublic, ByteReader, ByteWriter, IOPatternError};
+
+/// The size of the length prefix of a string, in bytes.
+const PREFIX_SIZE: usize = 8;
+
+/// Declare UTF-8 strings in the IO pattern.
+///
+/// Human-readable context (e.g., ticker symbols, URLs, or policy identifiers) is absorbed as its length in bytes,
+/// a 64-bit little-endian integer, followed by its UTF-8 encoding.
+/// As for any other message, the length is fixed by the pattern:
+/// a string of `len` bytes takes `8 + len` bytes.
+///
+/// ```
+/// use nimue::{DefaultHash, IOPattern, StringIOPattern, StringPublic, StringReader, StringWriter};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝")
+///     .add_string(3, "ticker")
+///     .add_string(5, "note");
+/// let mut merlin = io.to_merlin();
+/// merlin.public_string("BTC").unwrap();
+/// merlin.add_string("hello").unwrap();
+///
+/// let mut arthur = io.to_arthur(merlin.transcript());
+/// arthur.public_string("BTC").unwrap();
+/// assert_eq!(arthur.next_string(5).unwrap(), "hello");
+/// ```
+pub trait StringIOPattern {
+    /// Declare a string of `len` bytes.
+    fn add_string(self, len: usize, label: &str) -> Self;
+}
+
+/// Absorb UTF-8 strings without writing them into the protocol transcript, see [`StringIOPattern`].
+pub trait StringPublic {
+    fn public_string(&mut self, input: &str) -> Result<(), IOPatternError>;
+}
+
+/// Write UTF-8 strings into the protocol transcript, see [`StringIOPattern`].
+pub trait StringWriter {
+    fn add_string(&mut self, input: &str) -> Result<(), IOPatternError>;
+}
+
+/// Read UTF-8 strings from the protocol transcript, see [`StringIOPattern`].
+pub trait StringReader {
+    /// Read a string of `len` bytes.
+    ///
+    /// Return an error if the length prefix is not `len`, or if the string is not valid UTF-8.
+    fn next_string(&mut self, len: usize) -> Result<String, IOPatternError>;
+}
+
+impl<T: ByteIOPattern> StringIOPattern for T {
+    fn add_string(self, len: usize, label: &str) -> Self {
+        self.add_bytes(PREFIX_SIZE + len, label)
+    }
+}
+
+impl<T: BytePublic> StringPublic for T {
+    fn public_string(&mut self, input: &str) -> Result<(), IOPatternError> {
+        self.public_bytes(&(input.len() as u64).to_le_bytes())?;
+        self.public_bytes(input.as_bytes())
+    }
+}
+
+impl<T: ByteWriter> StringWriter for T {
+    fn add_string(&mut self, input: &str) -> Result<(), IOPatternError> {
+        self.add_bytes(&(input.len() as u64).to_le_bytes())?;
+        self.add_bytes(input.as_bytes())
+    }
+}
+
+impl<T: ByteReader> StringReader for T {
+    fn next_string(&mut self, len: usize) -> Result<String, IOPatternError> {
+        let prefix: [u8; PREFIX_SIZE] = self.next_bytes()?;
+        if u64::from_le_bytes(prefix) != len as u64 {
+            return Err("The length of the string differs from the declared one".into());
+        }
+        let mut bytes = vec![0; len];
+        self.fill_next_bytes(&mut bytes)?;
+        String::from_utf8(bytes).map_err(|_| "The string is not valid UTF-8".into())
+    }
+}
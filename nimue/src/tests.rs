@@ -2,6 +2,7 @@ use rand::RngCore;
 
 use crate::hash::keccak::Keccak;
 use crate::hash::legacy::DigestBridge;
+use crate::hash::strobe::Strobe128;
 use crate::{
     ByteChallenges, BytePublic, ByteReader, ByteWriter, DuplexHash, IOPattern, Merlin, Safe,
 };
@@ -10,6 +11,47 @@ type Sha2 = DigestBridge<sha2::Sha256>;
 type Blake2b512 = DigestBridge<blake2::Blake2b512>;
 type Blake2s256 = DigestBridge<blake2::Blake2s256>;
 
+/// A global allocator counting the allocations of each thread, to test the allocation-free paths.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+impl CountingAllocator {
+    fn count() {
+        // fails only while the thread is being destroyed
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        Self::count();
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, size: usize) -> *mut u8 {
+        Self::count();
+        std::alloc::System.realloc(ptr, layout, size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f`, returning its output and the number of allocations it performed.
+pub(crate) fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(|count| count.get());
+    let output = f();
+    let after = ALLOCATIONS.with(|count| count.get());
+    (output, after - before)
+}
+
 /// How should a protocol without actual IO be handled?
 #[test]
 fn test_iopattern() {
@@ -18,6 +60,32 @@ fn test_iopattern() {
     assert!(iop.as_bytes().starts_with(b"example.com"));
 }
 
+#[test]
+fn test_iopattern_from_bytes() {
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .absorb(4, "statement")
+        .ratchet()
+        .squeeze(16, "challenge");
+    let parsed = IOPattern::<Keccak>::from_bytes(iop.as_bytes()).unwrap();
+    assert_eq!(parsed.as_bytes(), iop.as_bytes());
+    assert_eq!(parsed.finalize(), iop.finalize());
+
+    for invalid in [
+        &b"example.com\0"[..],
+        b"example.com\0\0A1x",
+        b"example.com\0A0x",
+        b"example.com\0Xlabel",
+        b"example.com\0R1",
+        b"example.com\0A1\xff",
+        // oversized counts are rejected rather than overflowing
+        b"d\0A99999999999999999999999x",
+    ] {
+        assert!(IOPattern::<Keccak>::from_bytes(invalid).is_err());
+    }
+    let merged = format!("d\0A{}x\0A1y", usize::MAX);
+    assert!(IOPattern::<Keccak>::from_bytes(merged.as_bytes()).is_err());
+}
+
 /// Test Merlin's rng is not doing completely stupid things.
 #[test]
 fn test_merlin_rng_basic() {
@@ -35,6 +103,61 @@ fn test_merlin_rng_basic() {
     assert!(random_bytes.iter().any(|&x| x != random_bytes[0]));
 }
 
+/// Forks continue the transcript, with private coins of their own.
+#[test]
+fn test_merlin_fork() {
+    use crate::ByteIOPattern;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(1, "message")
+        .challenge_bytes(16, "challenge");
+    let mut merlin = Merlin::new(&iop, StdRng::seed_from_u64(0));
+    merlin.add_bytes(&[1]).unwrap();
+    // even forks seeded with the same generator draw different coins from the original
+    let mut fork = merlin.fork(StdRng::seed_from_u64(0));
+    assert_eq!(fork.transcript(), merlin.transcript());
+    assert_ne!(fork.rng().next_u64(), merlin.rng().next_u64());
+    assert_eq!(
+        fork.challenge_bytes::<16>().unwrap(),
+        merlin.challenge_bytes::<16>().unwrap()
+    );
+}
+
+/// Malformed transcripts and out-of-order operations are errors, never panics.
+#[test]
+fn test_no_panics() {
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .absorb(4, "message")
+        .squeeze(4, "challenge")
+        .ratchet();
+
+    // ratchets out of order, or past the end of the pattern
+    let mut arthur = iop.to_arthur(&[1, 2, 3, 4]);
+    assert!(arthur.ratchet().is_err());
+    assert!(arthur.ratchet().is_err());
+    let mut arthur = iop.to_arthur(&[1, 2, 3, 4]);
+    arthur.next_bytes::<4>().unwrap();
+    arthur.challenge_bytes::<4>().unwrap();
+    arthur.ratchet().unwrap();
+    assert!(arthur.ratchet().is_err());
+    assert!(arthur.next_bytes::<1>().is_err());
+    assert!(arthur.challenge_bytes::<1>().is_err());
+
+    // truncated transcripts
+    for len in 0..4 {
+        let transcript = [0; 4];
+        let mut arthur = iop.to_arthur(&transcript[..len]);
+        assert!(arthur.next_bytes::<4>().is_err());
+        assert!(arthur.challenge_bytes::<4>().is_err());
+    }
+    assert!(iop.to_arthur(&[]).preprocess().is_err());
+
+    let mut merlin = iop.to_merlin();
+    assert!(merlin.ratchet().is_err());
+    assert!(merlin.add_bytes(&[0; 4]).is_err());
+}
+
 /// Test adding of public bytes and non-public elements to the transcript.
 #[test]
 fn test_merlin_bytewriter() {
@@ -209,3 +332,1755 @@ fn test_streaming_blake2() {
 fn test_streaming_keccak() {
     test_streaming_absorb_and_squeeze::<Keccak>();
 }
+
+#[test]
+fn test_streaming_strobe() {
+    test_streaming_absorb_and_squeeze::<Strobe128>();
+}
+
+#[test]
+fn test_compliance() {
+    crate::compliance::check_bytes::<Keccak>().unwrap();
+    crate::compliance::check_bytes::<Sha2>().unwrap();
+    crate::compliance::check_bytes::<Blake2b512>().unwrap();
+    crate::compliance::check_bytes::<Strobe128>().unwrap();
+}
+
+/// The conformance test of merlin's STROBE implementation.
+#[test]
+fn test_strobe_conformance() {
+    let mut strobe = Strobe128::new(b"Conformance Test Protocol");
+    strobe.meta_ad(b"ms", false);
+    strobe.meta_ad(b"g", true);
+    strobe.ad(&[99u8; 1024], false);
+    strobe.meta_ad(b"prf", false);
+    let mut prf = [0u8; 32];
+    strobe.prf(&mut prf, false);
+    assert_eq!(
+        hex::encode(prf),
+        "b48e645ca17c667fd5206ba57a6a228d72d8e1903814d3f17f622996d7cfefb0"
+    );
+}
+
+#[test]
+fn test_bit_challenges() {
+    use crate::{BitChallenges, BitIOPattern};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .challenge_bits(12, "bits")
+        .challenge_fixed_weight(64, 10, "challenge");
+    assert!(iop.as_bytes().ends_with(b"S2bits\0S162challenge"));
+
+    let mut merlin = iop.to_merlin();
+    let bits: [bool; 12] = merlin.challenge_bits().unwrap();
+    let challenge = merlin.challenge_fixed_weight(64, 10).unwrap();
+    assert_eq!(challenge.len(), 64);
+    assert_eq!(challenge.iter().filter(|&&x| x != 0).count(), 10);
+    assert!(challenge.iter().all(|x| [-1, 0, 1].contains(x)));
+
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert_eq!(arthur.challenge_bits::<12>().unwrap(), bits);
+    assert_eq!(arthur.challenge_fixed_weight(64, 10).unwrap(), challenge);
+}
+
+#[test]
+fn test_retry_with_aborts() {
+    use crate::{AbortIOPattern, ByteIOPattern};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(2, "statement")
+        .add_retry_counter("attempt")
+        .add_bytes(4, "commitment")
+        .challenge_bytes(1, "challenge")
+        .add_bytes(1, "response");
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(b"hi").unwrap();
+    let mut rejected = 0;
+    let counter = merlin
+        .retry(32, |merlin, counter| {
+            let commitment = merlin.rng().next_u32();
+            merlin.add_bytes(&commitment.to_le_bytes())?;
+            let [challenge] = merlin.challenge_bytes()?;
+            if counter < 3 {
+                rejected += 1;
+                return Ok(None);
+            }
+            merlin.add_bytes(&[challenge])?;
+            Ok(Some(counter))
+        })
+        .unwrap();
+    assert_eq!((counter, rejected), (3, 3));
+    // only the accepted attempt is in the transcript
+    assert_eq!(merlin.transcript().len(), 2 + 4 + 4 + 1);
+
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_bytes().unwrap(), *b"hi");
+    assert_eq!(arthur.retry_counter(32).unwrap(), 3);
+    let _commitment: [u8; 4] = arthur.next_bytes().unwrap();
+    let [challenge] = arthur.challenge_bytes().unwrap();
+    assert_eq!(arthur.next_bytes().unwrap(), [challenge]);
+    // the counter is bounded by the verifier
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    arthur.next_bytes::<2>().unwrap();
+    assert!(arthur.retry_counter(3).is_err());
+
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(b"hi").unwrap();
+    assert!(merlin.retry(4, |_, _| Ok(None::<()>)).is_err());
+}
+
+#[test]
+fn test_permutation_and_subset_challenges() {
+    use crate::{BitChallenges, BitIOPattern};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .challenge_permutation(10, "shuffle")
+        .challenge_subset(40, 5, "opened");
+    assert!(iop.as_bytes().ends_with(b"S160shuffle\0S80opened"));
+
+    let mut merlin = iop.to_merlin();
+    let permutation = merlin.challenge_permutation(10).unwrap();
+    let mut sorted = permutation.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+    let subset = merlin.challenge_subset(40, 5).unwrap();
+    assert_eq!(subset.len(), 5);
+    assert!(subset.iter().all(|&i| i < 40));
+    assert!((1..5).all(|i| !subset[..i].contains(&subset[i])));
+
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert_eq!(arthur.challenge_permutation(10).unwrap(), permutation);
+    assert!(arthur.challenge_subset(4, 5).is_err());
+}
+
+#[test]
+fn test_lattice_samplers() {
+    use crate::{DiscreteGaussian, SamplerChallenges, SamplerIOPattern};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .challenge_bounded(256, "mask")
+        .challenge_gaussian(1000, "error");
+    assert!(iop.as_bytes().ends_with(b"S4096mask\0S8000error"));
+
+    let gaussian = DiscreteGaussian::new(3.0).unwrap();
+    let mut merlin = iop.to_merlin();
+    let mut mask = [0i64; 256];
+    merlin.fill_challenge_bounded(5, &mut mask).unwrap();
+    assert!(mask.iter().all(|x| (-5..=5).contains(x)));
+    assert!(mask.contains(&-5) && mask.contains(&5));
+    let mut error = vec![0i64; 1000];
+    merlin
+        .fill_challenge_gaussian(&gaussian, &mut error)
+        .unwrap();
+    assert!(error.iter().all(|x| x.abs() <= 36));
+    let mean = error.iter().sum::<i64>() as f64 / 1000.0;
+    let variance = error
+        .iter()
+        .map(|&x| (x as f64 - mean).powi(2))
+        .sum::<f64>()
+        / 1000.0;
+    assert!(mean.abs() < 0.5 && (6.0..12.0).contains(&variance));
+
+    // the verifier derives the same samples, also in variable time
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    let mut arthur_mask = [0i64; 256];
+    arthur.fill_challenge_bounded(5, &mut arthur_mask).unwrap();
+    assert_eq!(arthur_mask, mask);
+    let mut arthur_error = vec![0i64; 1000];
+    arthur
+        .fill_challenge_gaussian(&gaussian.variable_time(), &mut arthur_error)
+        .unwrap();
+    assert_eq!(arthur_error, error);
+
+    assert!(DiscreteGaussian::new(-1.0).is_err());
+    assert!(DiscreteGaussian::new(f64::NAN).is_err());
+}
+
+#[test]
+fn test_aggregation() {
+    use crate::{AggregationIOPattern, AggregationVerifier, Aggregator, ByteIOPattern};
+
+    let parent = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(1, "statement")
+        .add_aggregated_proofs(2, "proofs")
+        .challenge_bytes(16, "aggregation");
+    assert!(parent
+        .as_bytes()
+        .ends_with(b"A64proofs:children\0S16aggregation"));
+    let child = IOPattern::<Keccak>::new("child")
+        .add_bytes(1, "message")
+        .challenge_bytes(16, "challenge");
+
+    let mut aggregator = Aggregator::new(&parent, "proofs", 2);
+    aggregator.parent().add_bytes(&[42]).unwrap();
+    let mut challenges = Vec::new();
+    for i in [1, 0] {
+        let challenge = aggregator
+            .prove_child(i, &child, |merlin| {
+                merlin.add_bytes(&[i as u8])?;
+                Ok(merlin.challenge_bytes::<16>()?)
+            })
+            .unwrap();
+        challenges.push(challenge);
+    }
+    // children are namespaced by their position
+    assert_ne!(challenges[0], challenges[1]);
+    assert!(aggregator.prove_child(0, &child, |_| Ok(())).is_err());
+    let (mut merlin, children) = aggregator.finish().unwrap();
+    let challenge = merlin.challenge_bytes::<16>().unwrap();
+
+    let verify = |children: &[Vec<u8>]| {
+        let mut verifier = AggregationVerifier::new(&parent, merlin.transcript(), "proofs", 2);
+        assert_eq!(verifier.parent().next_bytes().unwrap(), [42]);
+        for (i, transcript) in children.iter().enumerate() {
+            verifier
+                .verify_child(i, &child, transcript, |arthur| {
+                    arthur.next_bytes::<1>()?;
+                    Ok(arthur.challenge_bytes::<16>()?)
+                })
+                .unwrap();
+        }
+        verifier.finish().unwrap().challenge_bytes::<16>().unwrap()
+    };
+    assert_eq!(verify(&children), challenge);
+    // the aggregation challenge depends on the children
+    let tampered = [children[0].clone(), vec![0]];
+    assert_ne!(verify(&tampered), challenge);
+
+    // all the children must be bound
+    let mut aggregator = Aggregator::new(&parent, "proofs", 2);
+    aggregator.parent().add_bytes(&[42]).unwrap();
+    aggregator
+        .prove_child(0, &child, |merlin| {
+            merlin.add_bytes(&[0])?;
+            Ok(merlin.challenge_bytes::<16>()?)
+        })
+        .unwrap();
+    assert!(aggregator.finish().is_err());
+
+    // namespaces do not run into the domain separator
+    let left = IOPattern::<Keccak>::new("c").namespaced("ab");
+    let right = IOPattern::<Keccak>::new("bc").namespaced("a");
+    assert_ne!(left.as_bytes(), right.as_bytes());
+    assert_ne!(
+        Safe::<Keccak>::generate_tag(left.as_bytes()),
+        Safe::<Keccak>::generate_tag(right.as_bytes())
+    );
+}
+
+#[test]
+fn test_composite_messages() {
+    type Message = (u8, Vec<[u8; 2]>, Option<Vec<u8>>, Option<u8>);
+
+    let shape = ((), (3, ()), Some((4, ())), None);
+    let io = IOPattern::<Keccak>::new("example.com").add_message::<Message>(&shape, "message");
+    assert_eq!(
+        io.as_bytes(),
+        b"example.com\0A1message.0\0A6message.1\0A4message.2"
+    );
+    // lengths are bound in the IO pattern
+    let other = IOPattern::<Keccak>::new("example.com")
+        .add_message::<Message>(&((), (2, ()), Some((4, ())), None), "message");
+    assert_ne!(io.as_bytes(), other.as_bytes());
+
+    let message: Message = (
+        1,
+        vec![[2, 3], [4, 5], [6, 7]],
+        Some(vec![8, 9, 10, 11]),
+        None,
+    );
+    let mut merlin = io.to_merlin();
+    merlin.add_message(&message).unwrap();
+    assert_eq!(merlin.transcript(), &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+
+    let mut arthur = io.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_message::<Message>(&shape).unwrap(), message);
+
+    // empty vectors are not declared
+    let io = IOPattern::<Keccak>::new("example.com")
+        .add_message::<(Vec<u8>, u8)>(&((0, ()), ()), "message");
+    assert_eq!(io.as_bytes(), b"example.com\0A1message.1");
+}
+
+#[test]
+fn test_runtime_length_challenges() {
+    use crate::{BitChallenges, BitIOPattern, ByteIOPattern};
+
+    let columns = 5;
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .challenge_bytes(columns, "bytes")
+        .challenge_bits(8 * columns, "bits");
+
+    let mut merlin = iop.to_merlin();
+    let bytes = merlin.challenge_bytes_vec(columns).unwrap();
+    let bits = merlin.challenge_bits_vec(8 * columns).unwrap();
+    assert_eq!(bytes.len(), columns);
+    assert_eq!(bits.len(), 8 * columns);
+
+    // runtime-length challenges match the const-generic ones
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert_eq!(arthur.challenge_bytes::<5>().unwrap().to_vec(), bytes);
+    assert_eq!(arthur.challenge_bits::<40>().unwrap().to_vec(), bits);
+}
+
+#[test]
+fn test_common_transcript() {
+    use crate::{ByteIOPattern, CommonTranscript};
+
+    fn statement<T: CommonTranscript<u8>>(transcript: &mut T) -> crate::ProofResult<[u8; 16]> {
+        transcript.public_bytes(b"statement")?;
+        transcript.ratchet()?;
+        Ok(transcript.challenge_bytes()?)
+    }
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(9, "statement")
+        .ratchet()
+        .challenge_bytes(16, "challenge");
+    let mut merlin = iop.to_merlin();
+    let challenge = statement(&mut merlin).unwrap();
+    assert_eq!(merlin.transcript(), b"");
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert_eq!(statement(&mut arthur).unwrap(), challenge);
+
+    // the ratchet must be declared in the IO pattern
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(9, "statement")
+        .challenge_bytes(16, "challenge");
+    assert!(statement(&mut iop.to_merlin()).is_err());
+    assert!(statement(&mut iop.to_arthur(b"")).is_err());
+}
+
+#[test]
+fn test_type_erased_transcripts() {
+    use crate::{AnyArthur, AnyMerlin, ByteIOPattern, CommonTranscript};
+
+    let iop = IOPattern::<Sha2>::new("example.com")
+        .add_bytes(4, "statement")
+        .ratchet()
+        .add_bytes(1, "message")
+        .challenge_bytes(16, "challenge");
+
+    let mut merlin = AnyMerlin::from(iop.to_merlin());
+    merlin.public_bytes(b"1234").unwrap();
+    merlin.ratchet().unwrap();
+    merlin
+        .add_bytes(&[(merlin.rng().next_u32() % 256) as u8])
+        .unwrap();
+    let challenge = merlin.challenge_bytes::<16>().unwrap();
+    // the IO pattern is still enforced
+    assert!(merlin.add_bytes(&[0]).is_err());
+
+    let mut arthur = AnyArthur::from(iop.to_arthur(merlin.transcript()));
+    arthur.public_bytes(b"1234").unwrap();
+    arthur.ratchet().unwrap();
+    assert_eq!(arthur.next_bytes().unwrap(), [message]);
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+}
+
+#[test]
+fn test_proof_framing() {
+    use crate::{ByteIOPattern, Proof};
+
+    let iop = IOPattern::<Keccak>::new("example.com").add_bytes(3, "message");
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(b"abc").unwrap();
+    let proof = Proof::new(&iop, merlin.transcript());
+
+    let bytes = proof.to_bytes();
+    assert!(bytes.starts_with(b"NMUE\x01"));
+    assert!(bytes.ends_with(b"abc"));
+    let decoded = Proof::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+    assert_eq!(decoded.transcript_for(&iop).unwrap(), b"abc");
+
+    // truncated, extended, or unversioned encodings are rejected
+    assert!(Proof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Proof::from_bytes(&[&bytes[..], &[0]].concat()).is_err());
+    let mut unversioned = bytes.clone();
+    unversioned[4] = 2;
+    assert!(Proof::from_bytes(&unversioned).is_err());
+
+    // the IO pattern and the hash function are bound
+    let other = IOPattern::<Keccak>::new("example.org").add_bytes(3, "message");
+    assert!(proof.transcript_for(&other).is_err());
+    let sha2 = IOPattern::<Sha2>::new("example.com").add_bytes(3, "message");
+    assert!(proof.transcript_for(&sha2).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_proof_serde() {
+    use crate::{ByteIOPattern, Proof};
+
+    let iop = IOPattern::<Keccak>::new("example.com").add_bytes(3, "message");
+    let proof = Proof::new(&iop, b"abc");
+    let json = serde_json::to_string(&proof).unwrap();
+    assert!(json.contains(r#""transcript":"616263""#));
+    let decoded: Proof = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.transcript_for(&iop).unwrap(), b"abc");
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_trace_labels() {
+    use crate::trace::Labels;
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(2, "first")
+        .add_bytes(3, "second")
+        .ratchet()
+        .challenge_bytes(4, "challenge");
+    let mut labels = Labels::new(iop.as_bytes());
+    assert_eq!(labels.consume(b'A', 1), "first");
+    // absorptions spanning multiple declared operations are attributed to all of them
+    assert_eq!(labels.consume(b'A', 4), "first, second");
+    assert_eq!(labels.next(), Some(""));
+    assert_eq!(labels.consume(b'R', 0), "");
+    assert_eq!(labels.consume(b'S', 4), "challenge");
+    assert_eq!(labels.next(), None);
+
+    // the sponge still accepts the operations when tracing
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[0; 5]).unwrap();
+    merlin.ratchet().unwrap();
+    merlin.challenge_bytes::<4>().unwrap();
+}
+
+#[test]
+fn test_script_mutations() {
+    use crate::fuzz::{check_mutation, Script, ScriptOp};
+
+    let script = Script::new(
+        "example.com",
+        vec![
+            ScriptOp::Message(vec![1, 2, 3]),
+            ScriptOp::Challenge(4),
+            ScriptOp::Ratchet,
+            ScriptOp::Message(vec![4]),
+            ScriptOp::Challenge(8),
+        ],
+    );
+    let iop = script.io_pattern::<Keccak>();
+    let mut merlin = iop.to_merlin();
+    script.prove(&mut merlin).unwrap();
+    assert_eq!(merlin.transcript().len(), 3 + 4 + 1 + 8);
+
+    for position in 0..merlin.transcript().len() {
+        for delta in [1, 0x80, 0xff] {
+            let delta = delta.try_into().unwrap();
+            check_mutation(
+                &iop,
+                merlin.transcript(),
+                |arthur| script.verify(arthur),
+                position,
+                delta,
+            )
+            .unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_duplex_differential() {
+    use crate::fuzz::{check_duplex, DuplexOp};
+
+    let ops = [
+        DuplexOp::Absorb(vec![1; 300]),
+        DuplexOp::Squeeze(0),
+        DuplexOp::Ratchet,
+        DuplexOp::Absorb(vec![]),
+        DuplexOp::Squeeze(500),
+    ];
+    check_duplex::<Keccak, Keccak, u8>([7; 32], &ops).unwrap();
+    let error = check_duplex::<Keccak, Sha2, u8>([7; 32], &ops).unwrap_err();
+    assert!(error.starts_with("Operation 4"));
+    // diverging absorptions are caught by the final squeeze
+    assert!(check_duplex::<Keccak, Sha2, u8>([7; 32], &ops[..1]).is_err());
+}
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn test_random_scripts(
+        script in crate::fuzz::scripts(),
+        position in proptest::prelude::any::<usize>(),
+        delta in 1..=u8::MAX,
+    ) {
+        let iop = script.io_pattern::<Keccak>();
+        let mut merlin = iop.to_merlin();
+        script.prove(&mut merlin).unwrap();
+        let delta = delta.try_into().unwrap();
+        let result = crate::fuzz::check_mutation(
+            &iop,
+            merlin.transcript(),
+            |arthur| script.verify(arthur),
+            position,
+            delta,
+        );
+        proptest::prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+}
+
+#[test]
+fn test_vectors() {
+    use crate::{ByteIOPattern, Operation, TestVector};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(4, "statement")
+        .add_bytes(8, "commitment")
+        .challenge_bytes(16, "challenge");
+    let prove = |merlin: &mut Merlin<Keccak, u8, StdRng>| -> crate::ProofResult<()> {
+        merlin.public_bytes(b"1234")?;
+        let mut commitment = [0u8; 8];
+        merlin.rng().fill_bytes(&mut commitment);
+        // split calls are merged in the vector
+        merlin.add_bytes(&commitment[..4])?;
+        merlin.add_bytes(&commitment[4..])?;
+        merlin.challenge_bytes::<16>()?;
+        Ok(())
+    };
+    let vector = TestVector::record(&iop, StdRng::seed_from_u64(0), prove).unwrap();
+    assert_eq!(vector.operations().len(), 2);
+    assert!(matches!(&vector.operations()[0], Operation::Absorb(units) if units.len() == 12));
+    // recording is deterministic
+    let again = TestVector::record(&iop, StdRng::seed_from_u64(0), prove).unwrap();
+    assert_eq!(again, vector);
+
+    vector.replay(&iop).unwrap();
+    vector
+        .verify(&iop, |arthur| {
+            arthur.public_bytes(b"1234")?;
+            arthur.next_bytes::<8>()?;
+            arthur.challenge_bytes::<16>()?;
+            Ok(())
+        })
+        .unwrap();
+    // the verifier must perform the same operations
+    let result = vector.verify(&iop, |arthur| {
+        arthur.public_bytes(b"4321")?;
+        arthur.next_bytes::<8>()?;
+        arthur.challenge_bytes::<16>()?;
+        Ok(())
+    });
+    assert!(result.is_err());
+    // the vector is bound to the IO pattern
+    let other = IOPattern::<Keccak>::new("example.org")
+        .add_bytes(12, "statement")
+        .challenge_bytes(16, "challenge");
+    assert!(vector.replay(&other).is_err());
+
+    #[cfg(feature = "serde")]
+    assert_eq!(TestVector::from_json(&vector.to_json()).unwrap(), vector);
+}
+
+#[test]
+fn test_lazy_challenges() {
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(1, "message")
+        .challenge_bytes(16, "first")
+        .challenge_bytes(16, "second")
+        .add_bytes(1, "response")
+        .challenge_bytes(16, "third");
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[1]).unwrap();
+    let first: [u8; 16] = merlin.challenge_bytes().unwrap();
+    let second: [u8; 16] = merlin.challenge_bytes().unwrap();
+    merlin.add_bytes(&[2]).unwrap();
+    let third: [u8; 16] = merlin.challenge_bytes().unwrap();
+
+    // resolving a later challenge computes the earlier ones, in order,
+    // and the next operation on the sponge computes the pending ones
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    arthur.next_bytes::<1>().unwrap();
+    let first_handle = arthur.challenge_handle(16).unwrap();
+    let second_handle = arthur.challenge_handle(16).unwrap();
+    assert_eq!(arthur.resolve(second_handle).unwrap(), second);
+    arthur.next_bytes::<1>().unwrap();
+    assert_eq!(arthur.resolve(first_handle).unwrap(), first);
+    let third_handle = arthur.challenge_handle(16).unwrap().map(|c| c[0]);
+    assert_eq!(arthur.resolve(third_handle).unwrap(), third[0]);
+
+    // deferred squeezes are still checked against the IO pattern
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert!(arthur.challenge_handle(16).is_err());
+    let mut other = iop.to_arthur(merlin.transcript());
+    other.next_bytes::<1>().unwrap();
+    let handle = other.challenge_handle(16).unwrap();
+    assert!(arthur.resolve(handle).is_err());
+}
+
+#[test]
+fn test_allocation_free_verification() {
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(16, "message")
+        .challenge_bytes(32, "challenge")
+        .ratchet()
+        .add_bytes(300, "response")
+        .challenge_bytes(16, "challenge");
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[1; 16]).unwrap();
+    let first: [u8; 32] = merlin.challenge_bytes().unwrap();
+    merlin.ratchet().unwrap();
+    merlin.add_bytes(&[2; 300]).unwrap();
+    let second: [u8; 16] = merlin.challenge_bytes().unwrap();
+
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    let (result, allocations) = count_allocations(|| {
+        let message: [u8; 16] = arthur.next_bytes()?;
+        let first: [u8; 32] = arthur.challenge_bytes()?;
+        arthur.ratchet()?;
+        let response: [u8; 300] = arthur.next_bytes()?;
+        let second: [u8; 16] = arthur.challenge_bytes()?;
+        Ok::<_, crate::IOPatternError>((message, first, response, second))
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(result.unwrap(), ([1; 16], first, [2; 300], second));
+}
+
+#[test]
+#[cfg(feature = "smallvec")]
+fn test_inline_transcript() {
+    use crate::ByteIOPattern;
+
+    // the size of a Schnorr signature over a 256-bit curve
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(32, "commitment")
+        .challenge_bytes(32, "challenge")
+        .add_bytes(32, "response");
+    let mut merlin = iop.to_merlin();
+    let (result, allocations) = count_allocations(|| {
+        merlin.add_bytes(&[1; 32])?;
+        let challenge: [u8; 32] = merlin.challenge_bytes()?;
+        merlin.add_bytes(&[2; 32])?;
+        Ok::<_, crate::IOPatternError>(challenge)
+    });
+    assert_eq!(allocations, 0);
+    result.unwrap();
+    assert_eq!(merlin.transcript(), [[1; 32], [2; 32]].concat());
+
+    // larger transcripts spill over to the heap
+    let iop = IOPattern::<Keccak>::new("example.com").add_bytes(300, "message");
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[3; 300]).unwrap();
+    assert_eq!(merlin.transcript(), [3; 300]);
+}
+
+#[test]
+fn test_precompiled_pattern() {
+    use crate::ByteIOPattern;
+
+    let mut iop = IOPattern::<Keccak>::new("example.com");
+    for _ in 0..200 {
+        iop = iop.add_bytes(2, "message").challenge_bytes(1, "challenge");
+    }
+    let mut merlin = iop.to_merlin();
+    let mut challenges = Vec::new();
+    for i in 0..200u8 {
+        // split absorptions walk the same operation
+        merlin.add_bytes(&[i]).unwrap();
+        merlin.add_bytes(&[i]).unwrap();
+        challenges.push(merlin.challenge_bytes::<1>().unwrap());
+    }
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    for (i, challenge) in (0..200u8).zip(challenges) {
+        assert_eq!(arthur.next_bytes().unwrap(), [i, i]);
+        assert_eq!(arthur.challenge_bytes::<1>().unwrap(), challenge);
+    }
+
+    // extending a pattern after its operations were parsed does not reuse them
+    let extended = iop.clone().add_bytes(1, "trailer");
+    let mut merlin = extended.to_merlin();
+    for i in 0..200u8 {
+        merlin.add_bytes(&[i, i]).unwrap();
+        merlin.challenge_bytes::<1>().unwrap();
+    }
+    merlin.add_bytes(&[0]).unwrap();
+    assert!(iop.to_merlin().add_bytes(&[0; 3]).is_err());
+}
+
+#[test]
+fn test_constant_time_helpers() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    assert!(crate::ct::eq(b"nimue", b"nimue"));
+    assert!(!crate::ct::eq(b"nimue", b"nimuE"));
+    assert!(!crate::ct::eq(b"nimue", b"nimu"));
+
+    // the fallible interface of the prover randomness reseeds and ratchets like the infallible one
+    let iop = IOPattern::<Keccak>::new("example.com");
+    let mut merlin = Merlin::new(&iop, StdRng::seed_from_u64(0));
+    let mut other = Merlin::new(&iop, StdRng::seed_from_u64(0));
+    let (mut a, mut b) = ([0u8; 64], [0u8; 64]);
+    merlin.rng().try_fill_bytes(&mut a).unwrap();
+    other.rng().fill_bytes(&mut b);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_ratchet_policy() {
+    use crate::{ByteIOPattern, RatchetPolicy};
+
+    let before_first = IOPattern::<Keccak>::new("example.com")
+        .with_ratchet_policy(RatchetPolicy::BeforeFirstChallenge);
+    let every_round = before_first
+        .clone()
+        .with_ratchet_policy(RatchetPolicy::EveryRound);
+
+    // a challenge squeezed before any absorption needs no ratchet
+    let _ = every_round.clone().challenge_bytes(16, "nonce");
+    // later rounds are unconstrained by the first policy
+    let _ = before_first
+        .clone()
+        .add_bytes(32, "statement")
+        .ratchet()
+        .add_bytes(32, "commitment")
+        .challenge_bytes(16, "challenge")
+        .add_bytes(32, "response")
+        .challenge_bytes(16, "challenge");
+    let _ = every_round
+        .clone()
+        .add_bytes(32, "statement")
+        .ratchet()
+        .challenge_bytes(16, "challenge")
+        .challenge_bytes(16, "challenge")
+        .add_bytes(32, "response")
+        .ratchet()
+        .challenge_bytes(16, "challenge");
+
+    let missing_first = std::panic::catch_unwind(|| {
+        before_first
+            .clone()
+            .add_bytes(32, "statement")
+            .challenge_bytes(16, "challenge")
+    });
+    assert!(missing_first.is_err());
+    let missing_round = std::panic::catch_unwind(|| {
+        every_round
+            .clone()
+            .add_bytes(32, "statement")
+            .ratchet()
+            .challenge_bytes(16, "challenge")
+            .add_bytes(32, "response")
+            .challenge_bytes(16, "challenge")
+    });
+    assert!(missing_round.is_err());
+}
+
+#[test]
+fn test_length_framing() {
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(2, "first")
+        .add_bytes(1, "second")
+        .challenge_bytes(16, "challenge");
+    let framed = iop.clone().with_length_framing();
+
+    let mut merlin = framed.to_merlin();
+    merlin.add_bytes(&[1, 2, 3]).unwrap();
+    let challenge: [u8; 16] = merlin.challenge_bytes().unwrap();
+    assert_eq!(merlin.transcript(), [1, 2, 3]);
+
+    // each message is absorbed after its length
+    let mut sponge = Keccak::new(Safe::<Keccak>::generate_tag(framed.as_bytes()));
+    sponge.absorb_unchecked(&2u64.to_le_bytes());
+    sponge.absorb_unchecked(&[1, 2]);
+    sponge.absorb_unchecked(&1u64.to_le_bytes());
+    sponge.absorb_unchecked(&[3]);
+    let mut expected = [0u8; 16];
+    sponge.squeeze_unchecked(&mut expected);
+    assert_eq!(challenge, expected);
+
+    // the messages can be read in any split
+    let mut arthur = framed.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_bytes().unwrap(), [1]);
+    assert_eq!(arthur.next_bytes().unwrap(), [2, 3]);
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    // framing changes the challenges
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[1, 2, 3]).unwrap();
+    assert_ne!(merlin.challenge_bytes::<16>().unwrap(), challenge);
+}
+
+#[test]
+fn test_session_binding() {
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .with_session(8)
+        .add_bytes(1, "message")
+        .challenge_bytes(16, "challenge");
+    assert!(iop.as_bytes().starts_with(b"example.com\0A8session\0"));
+
+    let prove = |session: &[u8]| {
+        let mut merlin = iop.to_merlin();
+        merlin.bind_session(session).unwrap();
+        merlin.add_bytes(&[42]).unwrap();
+        let challenge: [u8; 16] = merlin.challenge_bytes().unwrap();
+        (merlin.transcript().to_vec(), challenge)
+    };
+    let (transcript, challenge) = prove(&1u64.to_le_bytes());
+    let (other_transcript, other_challenge) = prove(&2u64.to_le_bytes());
+    // same transcript, independent challenges
+    assert_eq!(transcript, other_transcript);
+    assert_ne!(challenge, other_challenge);
+
+    let mut arthur = iop.to_arthur(&transcript);
+    arthur.bind_session(&1u64.to_le_bytes()).unwrap();
+    arthur.next_bytes::<1>().unwrap();
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    // the session must be complete, and come first
+    let mut arthur = iop.to_arthur(&transcript);
+    arthur.bind_session(&[0; 4]).unwrap();
+    assert!(arthur.next_bytes::<1>().is_err());
+    let mut arthur = iop.to_arthur(&transcript);
+    assert!(arthur.next_bytes::<1>().is_err());
+    assert!(std::panic::catch_unwind(|| iop.clone().with_session(8)).is_err());
+}
+
+#[test]
+fn test_strings() {
+    use crate::{ByteIOPattern, StringIOPattern, StringPublic, StringReader, StringWriter};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_string(11, "url")
+        .add_string(4, "note")
+        .challenge_bytes(16, "challenge");
+    let mut merlin = iop.to_merlin();
+    merlin.public_string("example.com").unwrap();
+    merlin.add_string("😀").unwrap();
+    let challenge = merlin.challenge_bytes::<16>().unwrap();
+    let mut transcript = merlin.transcript().to_vec();
+    assert_eq!(transcript[..8], 4u64.to_le_bytes());
+    assert_eq!(transcript[8..], *"😀".as_bytes());
+
+    // strings are absorbed as their length followed by their bytes
+    let mut merlin = iop.to_merlin();
+    merlin.public_bytes(&11u64.to_le_bytes()).unwrap();
+    merlin.public_bytes(b"example.com").unwrap();
+    merlin.add_bytes(&transcript).unwrap();
+    assert_eq!(merlin.challenge_bytes::<16>().unwrap(), challenge);
+
+    let mut arthur = iop.to_arthur(&transcript);
+    arthur.public_string("example.com").unwrap();
+    assert_eq!(arthur.next_string(4).unwrap(), "😀");
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    // invalid UTF-8, and lengths differing from the prefix, are rejected
+    transcript[8] = 0xff;
+    let mut arthur = iop.to_arthur(&transcript);
+    arthur.public_string("example.com").unwrap();
+    assert!(arthur.next_string(4).is_err());
+    transcript[0] = 3;
+    let mut arthur = iop.to_arthur(&transcript);
+    arthur.public_string("example.com").unwrap();
+    assert!(arthur.next_string(4).is_err());
+}
+
+#[test]
+fn test_context_binding() {
+    use crate::{ByteIOPattern, Context, ContextIOPattern, ContextPublic};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_context(Context::Epoch)
+        .challenge_bytes(16, "challenge");
+    let mut merlin = iop.to_merlin();
+    merlin.bind_context(Context::Epoch, 7).unwrap();
+    let challenge = merlin.challenge_bytes::<16>().unwrap();
+    assert!(merlin.transcript().is_empty());
+
+    // values are absorbed as 64-bit little-endian integers, under their own label
+    let expected = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(8, "context.epoch")
+        .challenge_bytes(16, "challenge");
+    assert_eq!(iop.as_bytes(), expected.as_bytes());
+    let mut arthur = iop.to_arthur(b"");
+    arthur.public_bytes(&7u64.to_le_bytes()).unwrap();
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    // the same value bound as another context gives another challenge
+    let other = IOPattern::<Keccak>::new("example.com")
+        .add_context(Context::ChainId)
+        .challenge_bytes(16, "challenge");
+    let mut arthur = other.to_arthur(b"");
+    arthur.bind_context(Context::ChainId, 7).unwrap();
+    assert_ne!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+}
+
+#[test]
+fn test_render_iopattern() {
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(4, "statement; with #")
+        .ratchet()
+        .challenge_bytes(16, "\"challenge\"");
+    let mermaid = iop.to_mermaid();
+    assert_eq!(
+        mermaid.lines().skip(3).collect::<Vec<_>>(),
+        [
+            "    Note over P,V: example.com",
+            "    P->>V: statement#59; with #35; (4)",
+            "    Note over P,V: ratchet",
+            "    V->>P: \"challenge\" (16)",
+        ]
+    );
+
+    let dot = iop.to_dot();
+    assert!(dot.contains(r#"n3 [shape=ellipse, label="V → P: \"challenge\" (16)"];"#));
+    assert!(dot.contains("n2 [shape=diamond, label=\"ratchet\"];"));
+    assert_eq!(dot.matches("->").count(), 3);
+    assert!(dot.ends_with("}\n"));
+}
+
+/// Corrupted and truncated transcripts are rejected before any element is read.
+#[test]
+fn test_integrity_tag() {
+    use crate::ByteIOPattern;
+
+    let iop = IOPattern::<Keccak>::new("domain separator")
+        .with_integrity_tag()
+        .add_bytes(4, "message")
+        .challenge_bytes(16, "challenge");
+    let mut merlin = iop.to_merlin();
+    assert!(merlin.seal().is_err());
+    merlin.add_bytes(&[1, 2, 3, 4]).unwrap();
+    let challenge = merlin.challenge_bytes::<16>().unwrap();
+    merlin.seal().unwrap();
+    assert!(merlin.seal().is_err());
+    let transcript = merlin.transcript().to_vec();
+    assert_eq!(transcript.len(), 4 + 32);
+
+    // the tag does not change the challenges
+    let mut untagged = IOPattern::<Keccak>::new("domain separator")
+        .add_bytes(4, "message")
+        .challenge_bytes(16, "challenge")
+        .to_merlin();
+    untagged.add_bytes(&[1, 2, 3, 4]).unwrap();
+    assert_eq!(untagged.challenge_bytes::<16>().unwrap(), challenge);
+
+    let mut arthur = crate::Arthur::try_new(&iop, &transcript).unwrap();
+    assert_eq!(arthur.next_bytes::<4>().unwrap(), [1, 2, 3, 4]);
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    for i in 0..transcript.len() {
+        let mut corrupted = transcript.clone();
+        corrupted[i] ^= 1;
+        let error = crate::Arthur::try_new(&iop, &corrupted).unwrap_err();
+        assert!(error.to_string().contains("Corrupt proof"));
+        assert!(iop.to_arthur(&corrupted).next_bytes::<4>().is_err());
+    }
+    for len in 0..transcript.len() {
+        assert!(crate::Arthur::try_new(&iop, &transcript[..len]).is_err());
+    }
+}
+
+#[test]
+fn test_registry() {
+    use crate::registry::{self, Registry};
+    use crate::ByteIOPattern;
+
+    let mut registry = Registry::well_known();
+    assert_eq!(registry.lookup(registry::KZG).unwrap().name, "kzg");
+    assert!(registry.lookup("nimue/unknown").is_none());
+    // registering twice is allowed, collisions are not
+    registry.register("schnorr", registry::SCHNORR).unwrap();
+    assert!(registry.register("my-schnorr", registry::SCHNORR).is_err());
+    assert!(registry.register("schnorr", "my-schnorr").is_err());
+
+    let io = IOPattern::<Keccak>::new("example.com/protocol/v1").add_bytes(4, "message");
+    let other = IOPattern::<Keccak>::new("example.com/protocol/v1").add_bytes(8, "message");
+    registry.register_pattern("protocol", &io).unwrap();
+    registry.register_pattern("protocol", &io).unwrap();
+    assert!(registry.register_pattern("protocol", &other).is_err());
+    assert_eq!(registry.lookup_pattern(&io).unwrap().name, "protocol");
+    assert!(registry.lookup_pattern(&other).is_none());
+    assert_eq!(registry.entries().count(), 4);
+}
+
+#[test]
+fn test_label_policy() {
+    use crate::{ByteIOPattern, LabelPolicy};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(1, "message")
+        .challenge_bytes(8, "alpha")
+        .challenge_bytes(8, "beta")
+        .with_label_policy(LabelPolicy::BindChallenges);
+
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[7]).unwrap();
+    let alpha_head: [u8; 4] = merlin.challenge_bytes().unwrap();
+    let alpha_tail: [u8; 4] = merlin.challenge_bytes().unwrap();
+    let beta: [u8; 8] = merlin.challenge_bytes().unwrap();
+
+    // each label is absorbed once, right before its challenge
+    let mut sponge = Keccak::new(Safe::<Keccak>::generate_tag(iop.as_bytes()));
+    let mut expected = [0u8; 16];
+    sponge.absorb_unchecked(&[7]).absorb_unchecked(b"alpha");
+    sponge.squeeze_unchecked(&mut expected[..8]);
+    sponge.absorb_unchecked(b"beta");
+    sponge.squeeze_unchecked(&mut expected[8..]);
+    assert_eq!([alpha_head, alpha_tail].concat(), expected[..8]);
+    assert_eq!(beta, expected[8..]);
+
+    // deferred challenges keep the same order
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    arthur.next_bytes::<1>().unwrap();
+    let alpha = arthur.challenge_handle(8).unwrap();
+    let beta = arthur.challenge_handle(8).unwrap();
+    assert_eq!(arthur.resolve(beta).unwrap(), expected[8..]);
+    assert_eq!(arthur.resolve(alpha).unwrap(), expected[..8]);
+
+    // a squeeze can't span two challenges
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[7]).unwrap();
+    assert!(merlin.challenge_bytes::<16>().is_err());
+}
+
+#[test]
+fn test_commit_and_open() {
+    use crate::{ByteIOPattern, CommitIOPattern, CommitReader, CommitWriter};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_commitment("commitment")
+        .challenge_bytes(16, "challenge")
+        .add_opening(4, "opening");
+    let mut merlin = iop.to_merlin();
+    let commitment = merlin.add_commitment(&[1, 2, 3, 4]).unwrap();
+    merlin.challenge_bytes::<16>().unwrap();
+    // the prover can't open to other data
+    let other = iop.to_merlin().add_commitment(&[1, 2, 3, 4]).unwrap();
+    assert!(iop.to_merlin().add_opening(other, &[1, 2, 3, 5]).is_err());
+    merlin.add_opening(commitment, &[1, 2, 3, 4]).unwrap();
+    let transcript = merlin.transcript().to_vec();
+    assert_eq!(transcript.len(), 32 + 4);
+
+    let mut arthur = iop.to_arthur(&transcript);
+    let commitment = arthur.next_commitment().unwrap();
+    arthur.challenge_bytes::<16>().unwrap();
+    assert_eq!(arthur.next_opening(commitment, 4).unwrap(), [1, 2, 3, 4]);
+
+    // tampering with the commitment or the opening is detected
+    for i in [0, 32] {
+        let mut tampered = transcript.clone();
+        tampered[i] ^= 1;
+        let mut arthur = iop.to_arthur(&tampered);
+        let commitment = arthur.next_commitment().unwrap();
+        arthur.challenge_bytes::<16>().unwrap();
+        assert!(arthur.next_opening(commitment, 4).is_err());
+    }
+}
+
+#[test]
+fn test_verify_until() {
+    use crate::{Arthur, ByteIOPattern, ProofError, ProofResult};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(1, "round")
+        .challenge_bytes(16, "challenge")
+        .ratchet()
+        .add_bytes(1, "round")
+        .challenge_bytes(16, "challenge")
+        .add_bytes(1, "response");
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[1]).unwrap();
+    merlin.challenge_bytes::<16>().unwrap();
+    merlin.ratchet().unwrap();
+    merlin.add_bytes(&[2]).unwrap();
+    merlin.challenge_bytes::<16>().unwrap();
+    merlin.add_bytes(&[3]).unwrap();
+
+    let round = |arthur: &mut Arthur<Keccak>| -> ProofResult<u8> {
+        let [message] = arthur.next_bytes()?;
+        arthur.challenge_bytes::<16>()?;
+        Ok(message)
+    };
+    // rounds are screened one at a time, up to the next round (after the ratchet) and the response
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    arthur.verify_until(&iop, "round", |_| Ok(())).unwrap();
+    assert_eq!(
+        arthur
+            .verify_until(&iop, "challenge", |arthur| Ok(arthur.next_bytes::<1>()?[0]))
+            .unwrap(),
+        1
+    );
+    assert!(arthur
+        .verify_until(&iop, "round", |arthur| {
+            arthur.challenge_bytes::<16>()?;
+            arthur.ratchet()?;
+            Ok(())
+        })
+        .is_ok());
+    assert_eq!(arthur.verify_until(&iop, "response", round).unwrap(), 2);
+    assert_eq!(arthur.next_bytes().unwrap(), [3]);
+
+    // verifiers stopping early, or going past the operation, are rejected
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert!(arthur.verify_until(&iop, "response", round).is_err());
+    assert!(arthur.next_bytes::<1>().is_err());
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert!(arthur.verify_until(&iop, "challenge", round).is_err());
+    // failed checks can't be resumed
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    let result = arthur.verify_until(&iop, "challenge", |_| {
+        Err::<(), _>(ProofError::InvalidProof)
+    });
+    assert!(result.is_err());
+    assert!(arthur.next_bytes::<1>().is_err());
+    assert!(arthur.verify_until(&iop, "unknown", |_| Ok(())).is_err());
+}
+
+#[test]
+fn test_verify_batch() {
+    use crate::{ByteIOPattern, ProofError};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .add_bytes(1, "message")
+        .challenge_bytes(16, "challenge")
+        .add_bytes(16, "response")
+        .with_integrity_tag();
+    let prove = |message: u8, honest: bool| {
+        let mut merlin = iop.to_merlin();
+        merlin.add_bytes(&[message]).unwrap();
+        let challenge = merlin.challenge_bytes::<16>().unwrap();
+        let response = if honest { challenge } else { [0; 16] };
+        merlin.add_bytes(&response).unwrap();
+        merlin.seal().unwrap();
+        merlin.transcript().to_vec()
+    };
+    // a corrupt proof, and a proof rejected by the verifier
+    let mut proofs = (0..8).map(|i| prove(i, i != 5)).collect::<Vec<_>>();
+    proofs[2].pop();
+
+    let report = iop.verify_batch(&proofs, |arthur| {
+        let [message] = arthur.next_bytes()?;
+        let challenge = arthur.challenge_bytes::<16>()?;
+        let response = arthur.next_bytes::<16>()?;
+        (response == challenge)
+            .then_some(message)
+            .ok_or(ProofError::InvalidProof)
+    });
+    assert!(!report.is_valid());
+    let failures = report
+        .failures()
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    assert_eq!(failures, [2, 5]);
+    assert!(matches!(report.results()[2], Err(ProofError::InvalidIO(_))));
+    assert!(matches!(report.results()[5], Err(ProofError::InvalidProof)));
+    assert!(report.to_string().starts_with("2 of 8 proofs failed"));
+    let report = report.into_outputs().unwrap_err();
+
+    // proofs verify in a batch exactly as they do one by one
+    let valid = [0, 1, 3, 4, 6, 7].map(|i| proofs[i].clone());
+    let outputs = iop
+        .verify_batch(&valid, |arthur| {
+            arthur.next_bytes::<1>().map_err(Into::into)
+        })
+        .into_outputs()
+        .unwrap();
+    let one_by_one = valid
+        .iter()
+        .map(|proof| iop.to_arthur(proof).next_bytes::<1>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(outputs, one_by_one);
+    assert_eq!(report.results().len(), 8);
+}
+
+#[test]
+fn test_ni_protocol() {
+    use crate::protocols::ni::{self, NiProtocol, NiProver, NiVerifier};
+    use crate::{Arthur, ByteIOPattern, ProofError, ProofResult};
+    use rand::CryptoRng;
+
+    /// Proves knowledge of `count` bytes summing to the statement, answering a challenge.
+    struct Sum {
+        count: usize,
+        tagged: bool,
+    }
+
+    impl NiProtocol<Keccak> for Sum {
+        type Statement = u8;
+
+        fn io_pattern(&self, _statement: &u8) -> IOPattern<Keccak> {
+            let iop = IOPattern::new("example.com/sum")
+                .add_bytes(self.count, "summands")
+                .challenge_bytes(1, "challenge")
+                .add_bytes(1, "response");
+            if self.tagged {
+                iop.with_integrity_tag()
+            } else {
+                iop
+            }
+        }
+    }
+
+    impl NiProver<Keccak> for Sum {
+        type Witness = [u8];
+
+        fn prove<R: RngCore + CryptoRng>(
+            &self,
+            merlin: &mut Merlin<Keccak, u8, R>,
+            _statement: &u8,
+            witness: &[u8],
+        ) -> ProofResult<()> {
+            merlin.add_bytes(witness)?;
+            let [challenge] = merlin.challenge_bytes()?;
+            merlin.add_bytes(&[challenge])?;
+            Ok(())
+        }
+    }
+
+    impl NiVerifier<Keccak> for Sum {
+        fn verify(&self, arthur: &mut Arthur<'_, Keccak>, statement: &u8) -> ProofResult<()> {
+            let mut summands = vec![0; self.count];
+            arthur.fill_next_bytes(&mut summands)?;
+            let [challenge] = arthur.challenge_bytes()?;
+            let [response] = arthur.next_bytes()?;
+            let sum = summands.iter().fold(0u8, |sum, x| sum.wrapping_add(*x));
+            (sum == *statement && response == challenge)
+                .then_some(())
+                .ok_or(ProofError::InvalidProof)
+        }
+    }
+
+    for tagged in [false, true] {
+        let protocol = Sum { count: 3, tagged };
+        let proof = ni::round_trip(&protocol, &6, &[1, 2, 3]).unwrap();
+        assert_eq!(proof.len(), if tagged { 4 + 32 } else { 4 });
+        assert!(ni::verify(&protocol, &7, &proof).is_err());
+        // proofs must be read entirely
+        let mut longer = proof.clone();
+        longer.insert(4, 0);
+        assert!(ni::verify(&protocol, &6, &longer).is_err());
+    }
+
+    // the prover must complete the pattern
+    let protocol = Sum {
+        count: 3,
+        tagged: false,
+    };
+    assert!(ni::prove(&protocol, &3, &[1, 2]).is_err());
+}
+
+#[test]
+fn test_psk_key_exchange() {
+    use crate::protocols::kex::{Initiator, Responder};
+    use crate::ProofError;
+    use rand::rngs::OsRng;
+
+    let psk = [7u8; 32];
+    let (initiator, initiator_nonce) = Initiator::<Keccak>::new(&psk, &mut OsRng).unwrap();
+    let (responder, responder_nonce, responder_confirmation) =
+        Responder::<Keccak>::new(&psk, &initiator_nonce, &mut OsRng).unwrap();
+    let (initiator_confirmation, key) = initiator
+        .finish(&responder_nonce, &responder_confirmation)
+        .unwrap();
+    assert_eq!(responder.finish(&initiator_confirmation).unwrap(), key);
+    assert_ne!(key, initiator_confirmation);
+    assert_ne!(key, responder_confirmation);
+
+    // a responder without the pre-shared key is rejected
+    let (initiator, initiator_nonce) = Initiator::<Keccak>::new(&psk, &mut OsRng).unwrap();
+    let (_, responder_nonce, responder_confirmation) =
+        Responder::<Keccak>::new(&[8u8; 32], &initiator_nonce, &mut OsRng).unwrap();
+    assert!(matches!(
+        initiator.finish(&responder_nonce, &responder_confirmation),
+        Err(ProofError::InvalidProof)
+    ));
+
+    // an initiator without it can't confirm
+    let (_, initiator_nonce) = Initiator::<Keccak>::new(&[8u8; 32], &mut OsRng).unwrap();
+    let (responder, _, _) = Responder::<Keccak>::new(&psk, &initiator_nonce, &mut OsRng).unwrap();
+    assert!(responder.finish(&[0u8; 32]).is_err());
+}
+
+#[test]
+fn test_sponge_parameters() {
+    assert_eq!(<Keccak as DuplexHash>::RATE, Some(136));
+    assert_eq!(<Keccak as DuplexHash>::CAPACITY, Some(64));
+    assert_eq!(<Keccak as DuplexHash>::SECURITY_LEVEL, 256);
+    assert_eq!(<Strobe128 as DuplexHash>::SECURITY_LEVEL, 128);
+    // legacy hash functions are not sponges, and offer the collision resistance of their digest
+    assert_eq!(<Sha2 as DuplexHash>::RATE, None);
+    assert_eq!(<Sha2 as DuplexHash>::SECURITY_LEVEL, 128);
+    assert_eq!(<Blake2b512 as DuplexHash>::SECURITY_LEVEL, 256);
+
+    let iop = IOPattern::<Blake2s256>::new("example.com");
+    assert_eq!((iop.rate(), iop.capacity()), (None, None));
+    assert_eq!(iop.security_level(), 128);
+
+    // hash functions and units defined outside of the crate can rely on the defaults
+    #[derive(Clone, Default, zeroize::Zeroize)]
+    struct Wide(u32);
+
+    impl crate::Unit for Wide {
+        fn write(_: &[Self], _: &mut impl std::io::Write) -> Result<(), std::io::Error> {
+            Ok(())
+        }
+
+        fn read(_: &mut impl std::io::Read, _: &mut [Self]) -> Result<(), std::io::Error> {
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default, zeroize::Zeroize)]
+    struct Sponge<const CAPACITY: usize>(u8);
+
+    impl<const CAPACITY: usize> DuplexHash<Wide> for Sponge<CAPACITY> {
+        const CAPACITY: Option<usize> = if CAPACITY == 0 { None } else { Some(CAPACITY) };
+
+        fn new(_: [u8; 32]) -> Self {
+            Self(0)
+        }
+
+        fn absorb_unchecked(&mut self, _: &[Wide]) -> &mut Self {
+            self
+        }
+
+        fn squeeze_unchecked(&mut self, _: &mut [Wide]) -> &mut Self {
+            self
+        }
+
+        fn ratchet_unchecked(&mut self) -> &mut Self {
+            self
+        }
+    }
+
+    assert_eq!(<Wide as crate::Unit>::BITS, 32);
+    assert_eq!(<Sponge<16> as DuplexHash<Wide>>::SECURITY_LEVEL, 256);
+    assert_eq!(<Sponge<0> as DuplexHash<Wide>>::SECURITY_LEVEL, 128);
+}
+
+#[test]
+fn test_long_domain_separator() {
+    use crate::ByteIOPattern;
+    use std::collections::HashSet;
+
+    // a protocol identifier embedding several kilobytes of metadata
+    let metadata = (0..256)
+        .map(|i| format!("{{\"parameter-{i}\": \"{:032x}\"}}", i * 7919))
+        .collect::<Vec<_>>()
+        .join(",");
+    let domsep = format!("example.com/protocol[{metadata}]");
+    assert!(domsep.len() > 8 * 1024);
+    let label = "commitment".repeat(512);
+    let iop = IOPattern::<Keccak>::new(&domsep)
+        .absorb(3, &label)
+        .squeeze(16, "challenge");
+
+    let mut merlin = iop.to_merlin();
+    merlin.add_bytes(&[1, 2, 3]).unwrap();
+    let challenge: [u8; 16] = merlin.challenge_bytes().unwrap();
+    let mut arthur = iop.to_arthur(merlin.transcript());
+    assert_eq!(arthur.next_bytes().unwrap(), [1, 2, 3]);
+    assert_eq!(arthur.challenge_bytes::<16>().unwrap(), challenge);
+
+    // the last byte of the separator still matters
+    let other = IOPattern::<Keccak>::new(&format!("example.com/protocol[{metadata}}}"))
+        .absorb(3, &label)
+        .squeeze(16, "challenge");
+    let mut merlin = other.to_merlin();
+    merlin.add_bytes(&[1, 2, 3]).unwrap();
+    assert_ne!(merlin.challenge_bytes::<16>().unwrap(), challenge);
+
+    // patterns around block boundaries get distinct tags
+    let tags = (0..3 * 136 + 16)
+        .map(|length| Safe::<Keccak>::generate_tag(&vec![b'a'; length]))
+        .collect::<HashSet<_>>();
+    assert_eq!(tags.len(), 3 * 136 + 16);
+    // the tag is the one of a single absorption, as for short patterns
+    let mut keccak = Keccak::default();
+    keccak.absorb_unchecked(iop.as_bytes());
+    let mut tag = [0u8; 32];
+    keccak.squeeze_unchecked(&mut tag);
+    assert_eq!(Safe::<Keccak>::generate_tag(iop.as_bytes()), tag);
+    // trailing zero bytes would not change the tag, but they cannot end a valid pattern
+    assert!(IOPattern::<Keccak>::from_bytes(b"example.com\0").is_err());
+}
+
+#[test]
+fn test_label_validation() {
+    use crate::{check_label, escape_label, ByteIOPattern};
+
+    for label in [
+        "",
+        "commitment",
+        "σ-commitment",
+        "clé publique",
+        "round 1",
+        "x[3x4]",
+    ] {
+        assert!(check_label(label).is_ok(), "{label:?}");
+    }
+    for label in [
+        "nul\0",
+        "line\nbreak",
+        "tab\t",
+        "\u{7f}",
+        "\u{85}",
+        "1st",
+        "42",
+    ] {
+        assert!(check_label(label).is_err(), "{label:?}");
+    }
+    assert!(std::panic::catch_unwind(|| IOPattern::<Keccak>::new("📝").absorb(1, "a\nb")).is_err());
+    assert!(
+        std::panic::catch_unwind(|| IOPattern::<Keccak>::new("📝").squeeze(1, "\u{1b}[0m"))
+            .is_err()
+    );
+    // serialized patterns are checked too
+    assert!(IOPattern::<Keccak>::from_bytes(b"\xf0\x9f\x93\x9d\0A1line\nbreak").is_err());
+
+    // escaped labels are valid, and distinct strings stay distinct
+    let strings = [
+        "", "a", "\\", "\\\\", "\0", "\\u{0}", "1", "\\u{31}", "a1", "\t\n", "é", "e\u{301}",
+    ];
+    let escaped = strings.map(escape_label);
+    for label in &escaped {
+        assert!(check_label(label).is_ok(), "{label:?}");
+    }
+    let iops = escaped.map(|label| IOPattern::<Keccak>::new("📝").add_bytes(1, &label));
+    let tags = iops
+        .iter()
+        .map(|iop| Safe::<Keccak>::generate_tag(iop.as_bytes()))
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(tags.len(), strings.len());
+    assert_eq!(escape_label("σ-commitment"), "σ-commitment");
+    assert_eq!(escape_label("1\\"), "\\u{31}\\\\");
+}
+
+#[test]
+fn test_configuration_before_operations() {
+    use crate::{ByteIOPattern, Encoding, Endianness, HashToField, UnitEncoding};
+
+    let iop = IOPattern::<Keccak>::new("example.com")
+        .with_encoding(Encoding::Uncompressed)
+        .with_hash_to_field(HashToField::Reduce)
+        .add_bytes(1, "message");
+    // the plugins already declared the lengths of the messages with the previous configuration
+    assert!(std::panic::catch_unwind(|| iop.clone().with_encoding(Encoding::Compressed)).is_err());
+    assert!(
+        std::panic::catch_unwind(|| iop.clone().with_hash_to_field(HashToField::Rfc9380)).is_err()
+    );
+    assert!(
+        std::panic::catch_unwind(|| iop.clone().with_challenge_endianness(Endianness::Little))
+            .is_err()
+    );
+
+    // the configuration is recorded after the domain separator, and survives serialization
+    assert_eq!(
+        iop.as_bytes(),
+        b"example.com\x1fencoding=uncompressed\x1fhash-to-field=reduce\0A1message"
+    );
+    let parsed = IOPattern::<Keccak>::from_bytes(iop.as_bytes()).unwrap();
+    assert_eq!(parsed.as_bytes(), iop.as_bytes());
+    assert_eq!(
+        (parsed.encoding(), parsed.hash_to_field()),
+        (Encoding::Uncompressed, HashToField::Reduce)
+    );
+    assert_eq!(parsed.domain_separator(), "example.com");
+
+    // so that patterns differing only in their configuration have different tags
+    let configurations = [
+        IOPattern::<Keccak>::new("example.com"),
+        IOPattern::<Keccak>::new("example.com").with_encoding(Encoding::Uncompressed),
+        IOPattern::<Keccak>::new("example.com").with_encoding(Encoding::FixedWidthLE),
+        IOPattern::<Keccak>::new("example.com").with_hash_to_field(HashToField::Statistical(100)),
+        IOPattern::<Keccak>::new("example.com").with_challenge_endianness(Endianness::Little),
+    ];
+    let tags = configurations
+        .iter()
+        .map(|iop| Safe::<Keccak>::generate_tag(iop.clone().add_bytes(1, "message").as_bytes()))
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(tags.len(), configurations.len());
+    for iop in &configurations {
+        let parsed = IOPattern::<Keccak>::from_bytes(iop.as_bytes()).unwrap();
+        assert_eq!(
+            (
+                parsed.encoding(),
+                parsed.hash_to_field(),
+                parsed.challenge_endianness()
+            ),
+            (
+                iop.encoding(),
+                iop.hash_to_field(),
+                iop.challenge_endianness()
+            )
+        );
+    }
+    // setting back the default removes the setting
+    let reset = IOPattern::<Keccak>::new("example.com")
+        .with_encoding(Encoding::Uncompressed)
+        .with_encoding(Encoding::Compressed);
+    assert_eq!(reset.as_bytes(), b"example.com");
+
+    // unknown, default, and reordered settings are rejected
+    for invalid in [
+        &b"example.com\x1fencoding=sparse\0A1message"[..],
+        b"example.com\x1fcolor=blue",
+        b"example.com\x1fencoding=compressed",
+        b"example.com\x1fhash-to-field=reduce\x1fencoding=uncompressed",
+    ] {
+        assert!(IOPattern::<Keccak>::from_bytes(invalid).is_err());
+    }
+    // and domain separators cannot contain the configuration byte
+    assert!(std::panic::catch_unwind(|| IOPattern::<Keccak>::new("example.com\x1f")).is_err());
+}
+
+#[cfg(feature = "bip340")]
+#[test]
+fn test_bip340_vectors() {
+    use crate::plugins::bip340;
+
+    // (secret key, public key, auxiliary randomness, message, signature):
+    // the signing vectors 0 to 3, and 15 (empty message), of the BIP-340 specification
+    let vectors = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+             25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+        ),
+        (
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341\
+             8906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+        ),
+        (
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1B\
+             AB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+        ),
+        (
+            "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+            "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC\
+             97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+        ),
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "",
+            "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF\
+             6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63",
+        ),
+    ];
+    for (secret, public_key, aux, message, signature) in vectors {
+        let secret: [u8; 32] = hex::decode(secret).unwrap().try_into().unwrap();
+        let aux: [u8; 32] = hex::decode(aux).unwrap().try_into().unwrap();
+        let message = hex::decode(message).unwrap();
+        let expected_key = bip340::public_key(&secret).unwrap();
+        assert_eq!(hex::encode_upper(expected_key), public_key);
+        let produced = bip340::sign(&secret, &message, &aux).unwrap();
+        assert_eq!(hex::encode_upper(produced), signature);
+        assert!(bip340::verify(&expected_key, &message, &produced).is_ok());
+    }
+
+    // verification vector 4: the nonce commitment has a high number of leading zeros
+    let public_key: [u8; 32] =
+        hex::decode("D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9")
+            .unwrap()
+            .try_into()
+            .unwrap();
+    let message =
+        hex::decode("4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703").unwrap();
+    let signature: [u8; 64] = hex::decode(
+        "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C63\
+         76AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert!(bip340::verify(&public_key, &message, &signature).is_ok());
+}
+
+#[cfg(feature = "bip340")]
+#[test]
+fn test_bip340_invalid_signatures() {
+    use crate::plugins::bip340;
+    use crate::ProofError;
+    use k256::elliptic_curve::PrimeField;
+
+    // the cases of the verification vectors 5 to 14 of BIP-340, built from the signing vector 1
+    let secret: [u8; 32] =
+        hex::decode("B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF")
+            .unwrap()
+            .try_into()
+            .unwrap();
+    let public_key = bip340::public_key(&secret).unwrap();
+    let message = [0x24u8; 32];
+    let signature = bip340::sign(&secret, &message, &[0u8; 32]).unwrap();
+    assert!(bip340::verify(&public_key, &message, &signature).is_ok());
+
+    // the field size p and the group order n, big-endian
+    let p =
+        hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F").unwrap();
+    let n =
+        hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141").unwrap();
+
+    // a different message
+    let mut other = message;
+    other[0] ^= 1;
+    assert!(matches!(
+        bip340::verify(&public_key, &other, &signature),
+        Err(ProofError::InvalidProof)
+    ));
+    // a different nonce commitment, or the negated response
+    let mut tampered = signature;
+    tampered[0] ^= 1;
+    assert!(bip340::verify(&public_key, &message, &tampered).is_err());
+    let s = k256::Scalar::from_repr(k256::FieldBytes::clone_from_slice(&signature[32..])).unwrap();
+    let mut negated = signature;
+    negated[32..].copy_from_slice(&(-s).to_repr());
+    assert!(matches!(
+        bip340::verify(&public_key, &message, &negated),
+        Err(ProofError::InvalidProof)
+    ));
+    // a nonce commitment equal to the field size, and a response equal to the group order
+    let mut r_overflow = signature;
+    r_overflow[..32].copy_from_slice(&p);
+    assert!(matches!(
+        bip340::verify(&public_key, &message, &r_overflow),
+        Err(ProofError::SerializationError)
+    ));
+    let mut s_overflow = signature;
+    s_overflow[32..].copy_from_slice(&n);
+    assert!(matches!(
+        bip340::verify(&public_key, &message, &s_overflow),
+        Err(ProofError::SerializationError)
+    ));
+    // a public key equal to the field size
+    let p: [u8; 32] = p.try_into().unwrap();
+    assert!(matches!(
+        bip340::verify(&p, &message, &signature),
+        Err(ProofError::SerializationError)
+    ));
+}
+
+#[cfg(feature = "bip340")]
+#[test]
+fn test_bip340_tagged_transcript() {
+    use crate::plugins::bip340::{self, TaggedTranscript};
+
+    // the transcript follows the IO pattern, and hashes as the tagged hash
+    let io_pattern = bip340::challenge_io_pattern(3);
+    let mut transcript = TaggedTranscript::new(&io_pattern);
+    transcript
+        .add_bytes(&[1u8; 32])
+        .unwrap()
+        .add_bytes(&[2u8; 32])
+        .unwrap()
+        .add_bytes(b"abc")
+        .unwrap();
+    let mut input = [[1u8; 32], [2u8; 32]].concat();
+    input.extend(b"abc");
+    assert_eq!(
+        transcript.finalize().unwrap(),
+        bip340::tagged_hash(bip340::CHALLENGE_TAG, &input)
+    );
+
+    // absorbing more bytes than declared, or squeezing early, is an error
+    let mut transcript = TaggedTranscript::new(&io_pattern);
+    assert!(transcript.add_bytes(&[0u8; 68]).is_err());
+    let mut transcript = TaggedTranscript::new(&io_pattern);
+    transcript.add_bytes(&[0u8; 64]).unwrap();
+    assert!(transcript.finalize().is_err());
+    // the hash must be the last operation
+    let io_pattern = IOPattern::<Keccak>::new(bip340::AUX_TAG)
+        .absorb(32, "aux")
+        .squeeze(32, "mask")
+        .absorb(1, "more");
+    let mut transcript = TaggedTranscript::new(&io_pattern);
+    transcript.add_bytes(&[0u8; 32]).unwrap();
+    assert!(transcript.finalize().is_err());
+}
+
+#[cfg(feature = "halo2")]
+#[test]
+fn test_halo2_transcript_vectors() {
+    use crate::plugins::halo2::{Halo2Reader, Halo2Writer};
+    use pasta_curves::group::ff::PrimeField;
+    use pasta_curves::group::prime::PrimeCurveAffine;
+    use pasta_curves::group::Curve;
+    use pasta_curves::{EqAffine, Fp};
+
+    // The challenges and the proof of halo2's `Blake2bWrite<_, EqAffine, Challenge255<EqAffine>>`
+    // for the same sequence of operations, computed from its definition:
+    // BLAKE2b-512 personalized with `Halo2-Transcript`, prefix bytes 0 (challenge), 1 (point), 2 (scalar),
+    // and challenges reducing the little-endian digest modulo the group order.
+    let g = EqAffine::generator();
+    let g2 = (g + g).to_affine();
+    let mut writer = Halo2Writer::<EqAffine>::new();
+    writer.write_point(g).unwrap();
+    writer.write_scalar(Fp::from(5));
+    let c0 = writer.squeeze_challenge();
+    writer.common_point(g2).unwrap();
+    writer.write_scalar(c0);
+    let c1 = writer.squeeze_challenge();
+    writer.common_scalar(Fp::from(7));
+    let c2 = writer.squeeze_challenge();
+    assert_eq!(
+        hex::encode(c0.to_repr()),
+        "4fbac7a52232f38171ec739f39f6137a439b08169c333ae7c072cd0fdc171c1f"
+    );
+    assert_eq!(
+        hex::encode(c1.to_repr()),
+        "1b52f6df36fb257ca47bc9588cd28a98c1794427588e3d829736427997e18f19"
+    );
+    assert_eq!(
+        hex::encode(c2.to_repr()),
+        "4d5cbfe7e100d8e7e1bb88a9200d4094d34a511c0f2138da85ea1065abc25728"
+    );
+    let proof = writer.finalize();
+    assert_eq!(
+        hex::encode(&proof),
+        concat!(
+            "0000000021eb468cdda89409fc98462200000000000000000000000000000040",
+            "0500000000000000000000000000000000000000000000000000000000000000",
+            "4fbac7a52232f38171ec739f39f6137a439b08169c333ae7c072cd0fdc171c1f",
+        )
+    );
+
+    // the reader derives the same challenges
+    let mut reader = Halo2Reader::<EqAffine>::new(&proof);
+    assert_eq!(reader.read_point().unwrap(), g);
+    assert_eq!(reader.read_scalar().unwrap(), Fp::from(5));
+    assert_eq!(reader.squeeze_challenge(), c0);
+    reader.common_point(g2).unwrap();
+    assert_eq!(reader.read_scalar().unwrap(), c0);
+    assert_eq!(reader.squeeze_challenge(), c1);
+    reader.common_scalar(Fp::from(7));
+    assert_eq!(reader.squeeze_challenge(), c2);
+    assert!(reader.read_scalar().is_err());
+
+    // the point at infinity cannot be hashed, and non-canonical scalars are rejected
+    assert!(Halo2Writer::<EqAffine>::new()
+        .write_point(EqAffine::identity())
+        .is_err());
+    assert!(Halo2Reader::<EqAffine>::new(&[0xff; 32])
+        .read_scalar()
+        .is_err());
+}
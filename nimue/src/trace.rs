@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+
+/// The operations declared in the IO pattern, with their labels, to annotate the trace of a transcript.
+///
+/// Unlike the stack of [`Safe`](crate::Safe), consecutive operations are not merged,
+/// so that each unit processed can be attributed to the label it was declared with.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Labels(VecDeque<(u8, usize, String)>);
+
+impl Labels {
+    /// Parse the operations (and their labels) from the IO pattern `io_pattern`.
+    pub(crate) fn new(io_pattern: &[u8]) -> Self {
+        let ops = io_pattern
+            .split(|&b| b == 0)
+            // skip the domain separator
+            .skip(1)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let digits = part[1..].iter().take_while(|b| b.is_ascii_digit()).count();
+                let length = part[1..1 + digits]
+                    .iter()
+                    .fold(0, |acc, x| acc * 10 + (x - b'0') as usize);
+                let label = String::from_utf8_lossy(&part[1 + digits..]).into_owned();
+                (part[0], length, label)
+            })
+            .collect();
+        Self(ops)
+    }
+
+    /// The label of the next operation, if any.
+    pub(crate) fn next(&self) -> Option<&str> {
+        self.0.front().map(|(_, _, label)| label.as_str())
+    }
+
+    /// Consume `length` units of the operation `kind` (`b'A'`, `b'S'`, or `b'R'`),
+    /// and return the labels of the declared operations involved, separated by commas.
+    pub(crate) fn consume(&mut self, kind: u8, mut length: usize) -> String {
+        let mut labels = Vec::new();
+        while let Some((op, remaining, label)) = self.0.front_mut() {
+            if *op != kind {
+                break;
+            }
+            labels.push(label.clone());
+            if kind != b'R' && length < *remaining {
+                *remaining -= length;
+                break;
+            }
+            // ratchets have length zero
+            length -= *remaining;
+            self.0.pop_front();
+            if kind == b'R' || length == 0 {
+                break;
+            }
+        }
+        labels.join(", ")
+    }
+
+    /// Forget all the operations, after an error.
+    pub(crate) fn clear(&mut self) {
+        self.0.clear()
+    }
+}
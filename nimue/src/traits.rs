@@ -1,3 +1,4 @@
+use crate::encoding::UnitEncoding;
 use crate::errors::IOPatternError;
 use crate::Unit;
 
@@ -12,6 +13,49 @@ pub trait UnitTranscript<U: Unit> {
     fn fill_challenge_units(&mut self, output: &mut [U]) -> Result<(), IOPatternError>;
 }
 
+/// The operations shared by the prover and the verifier: public data, challenges, and ratchets.
+///
+/// This trait is implemented by both [`Merlin`](crate::Merlin) and [`Arthur`](crate::Arthur),
+/// so that the symmetric parts of a protocol can be written once, generically,
+/// and only the messages depending on the witness differ between the prover and the verifier.
+/// Public bytes and byte challenges (and, in the plugins, public scalars and points, and scalar challenges)
+/// are available through the blanket implementations on [`UnitTranscript`] and [`UnitEncoding`].
+///
+/// ```
+/// use nimue::{
+///     ByteChallenges, ByteIOPattern, BytePublic, ByteReader, ByteWriter, CommonTranscript,
+///     DefaultHash, IOPattern, ProofResult,
+/// };
+///
+/// /// Bind the statement and squeeze the challenge, for both the prover and the verifier.
+/// fn challenge<T>(transcript: &mut T, statement: &[u8; 4]) -> ProofResult<[u8; 16]>
+/// where
+///     T: CommonTranscript<u8>,
+/// {
+///     transcript.public_bytes(statement)?;
+///     transcript.ratchet()?;
+///     Ok(transcript.challenge_bytes()?)
+/// }
+///
+/// let io = IOPattern::<DefaultHash>::new("📝")
+///     .add_bytes(4, "statement")
+///     .ratchet()
+///     .challenge_bytes(16, "challenge")
+///     .add_bytes(1, "response");
+///
+/// let mut merlin = io.to_merlin();
+/// let c = challenge(&mut merlin, b"1234").unwrap();
+/// merlin.add_bytes(&[c[0]]).unwrap();
+///
+/// let mut arthur = io.to_arthur(merlin.transcript());
+/// let c = challenge(&mut arthur, b"1234").unwrap();
+/// assert_eq!(arthur.next_bytes().unwrap(), [c[0]]);
+/// ```
+pub trait CommonTranscript<U: Unit>: UnitTranscript<U> + UnitEncoding {
+    /// Ratchet the verifier's state.
+    fn ratchet(&mut self) -> Result<(), IOPatternError>;
+}
+
 /// Absorbing bytes from the sponge, without reading or writing them into the protocol transcript.
 ///
 /// This trait is trivial for byte-oriented sponges, but non-trivial for algebraic hashes.
@@ -38,6 +82,12 @@ pub trait ByteChallenges {
         let mut output = [0u8; N];
         self.fill_challenge_bytes(&mut output).map(|()| output)
     }
+
+    /// Squeeze `n` bytes, for when their number is known only at runtime.
+    fn challenge_bytes_vec(&mut self, n: usize) -> Result<Vec<u8>, IOPatternError> {
+        let mut output = vec![0u8; n];
+        self.fill_challenge_bytes(&mut output).map(|()| output)
+    }
 }
 
 /// A trait for absorbing and squeezing bytes from a sponge.
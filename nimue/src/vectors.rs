@@ -0,0 +1,218 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::{Arthur, DuplexHash, IOPattern, Merlin, ProofResult, Safe, Unit};
+
+/// An operation performed on the sponge, with the units absorbed or squeezed (serialized to bytes).
+///
+/// Consecutive absorptions (and squeezes) are merged in a [`TestVector`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase", tag = "op", content = "units")
+)]
+pub enum Operation {
+    Absorb(#[cfg_attr(feature = "serde", serde(with = "crate::proof::bytes"))] Vec<u8>),
+    Squeeze(#[cfg_attr(feature = "serde", serde(with = "crate::proof::bytes"))] Vec<u8>),
+    Ratchet,
+}
+
+impl Operation {
+    /// Serialize `units`.
+    pub(crate) fn units<U: Unit>(units: &[U]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        // write never fails on Vec<u8>
+        U::write(units, &mut bytes).unwrap();
+        bytes
+    }
+}
+
+/// Merge consecutive absorptions and consecutive squeezes,
+/// so that operations do not depend on how the calls to the sponge are split.
+fn merge(operations: &[Operation]) -> Vec<Operation> {
+    let mut merged: Vec<Operation> = Vec::new();
+    for op in operations {
+        match (merged.last_mut(), op) {
+            (Some(Operation::Absorb(last)), Operation::Absorb(units))
+            | (Some(Operation::Squeeze(last)), Operation::Squeeze(units)) => last.extend(units),
+            _ => merged.push(op.clone()),
+        }
+    }
+    merged
+}
+
+/// Deserialize all the units in `bytes`.
+fn decode<U: Unit + Default>(mut bytes: &[u8]) -> Result<Vec<U>, String> {
+    let mut units = Vec::new();
+    while !bytes.is_empty() {
+        let mut unit = [U::default()];
+        U::read(&mut bytes, &mut unit).map_err(|error| error.to_string())?;
+        units.extend(unit);
+    }
+    Ok(units)
+}
+
+/// A golden test vector: the IO pattern, every operation on the sponge
+/// (public and prover messages absorbed, challenges squeezed, ratchets), and the protocol transcript.
+///
+/// Vectors are recorded from a run of the prover with [`TestVector::record`];
+/// tests pin the behavior of the sponge with [`TestVector::replay`], and the one of the verifier with [`TestVector::verify`],
+/// so that crates building on nimue detect changes across versions (and implementations in other languages).
+/// With the feature `serde`, vectors are stored as JSON fixtures with [`TestVector::to_json`].
+///
+/// ```
+/// use nimue::{ByteChallenges, ByteIOPattern, ByteReader, ByteWriter, DefaultHash, IOPattern, TestVector};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let io = IOPattern::<DefaultHash>::new("📝").add_bytes(1, "message").challenge_bytes(8, "challenge");
+/// let vector = TestVector::record(&io, StdRng::seed_from_u64(0), |merlin| {
+///     merlin.add_bytes(&[42])?;
+///     merlin.challenge_bytes::<8>()?;
+///     Ok(())
+/// })
+/// .unwrap();
+///
+/// vector.replay(&io).unwrap();
+/// vector
+///     .verify(&io, |arthur| {
+///         arthur.next_bytes::<1>()?;
+///         arthur.challenge_bytes::<8>()?;
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestVector {
+    #[cfg_attr(feature = "serde", serde(with = "crate::proof::bytes"))]
+    io_pattern: Vec<u8>,
+    operations: Vec<Operation>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::proof::bytes"))]
+    transcript: Vec<u8>,
+}
+
+impl TestVector {
+    /// Record the run of `prove` on the prover state of `io_pattern`, with the random number generator `csrng`.
+    ///
+    /// For the vector to be reproducible, `csrng` should be seeded deterministically.
+    pub fn record<H, U, R>(
+        io_pattern: &IOPattern<H, U>,
+        csrng: R,
+        prove: impl FnOnce(&mut Merlin<H, U, R>) -> ProofResult<()>,
+    ) -> ProofResult<Self>
+    where
+        H: DuplexHash<U>,
+        U: Unit,
+        R: RngCore + CryptoRng,
+    {
+        let mut merlin = Merlin::new(io_pattern, csrng);
+        merlin.safe.start_recording();
+        prove(&mut merlin)?;
+        Ok(Self {
+            io_pattern: io_pattern.as_bytes().to_vec(),
+            // the recording was started above
+            operations: merge(merlin.safe.recording().unwrap()),
+            transcript: merlin.transcript().to_vec(),
+        })
+    }
+
+    /// The IO pattern, as bytes.
+    pub fn io_pattern(&self) -> &[u8] {
+        &self.io_pattern
+    }
+
+    /// The operations performed on the sponge, in order, with consecutive absorptions (and squeezes) merged.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// The protocol transcript.
+    pub fn transcript(&self) -> &[u8] {
+        &self.transcript
+    }
+
+    /// Check that `io_pattern` is the one of the vector.
+    fn check_io_pattern<H: DuplexHash<U>, U: Unit>(
+        &self,
+        io_pattern: &IOPattern<H, U>,
+    ) -> Result<(), String> {
+        if io_pattern.as_bytes() != self.io_pattern {
+            return Err("The IO pattern differs from the one of the vector".to_string());
+        }
+        Ok(())
+    }
+
+    /// Replay the operations on a fresh sponge for `io_pattern`, checking that every challenge matches.
+    ///
+    /// Return a description of the first mismatch.
+    pub fn replay<H, U>(&self, io_pattern: &IOPattern<H, U>) -> Result<(), String>
+    where
+        H: DuplexHash<U>,
+        U: Unit + Default,
+    {
+        self.check_io_pattern(io_pattern)?;
+        let mut safe = Safe::new(io_pattern);
+        for (i, op) in self.operations.iter().enumerate() {
+            match op {
+                Operation::Absorb(units) => safe
+                    .absorb(&decode::<U>(units)?)
+                    .map_err(|error| format!("Operation {i}: {error}"))?,
+                Operation::Squeeze(expected) => {
+                    let mut output = decode::<U>(expected)?;
+                    safe.squeeze(&mut output)
+                        .map_err(|error| format!("Operation {i}: {error}"))?;
+                    if Operation::units(&output) != *expected {
+                        return Err(format!("Operation {i}: the challenge differs"));
+                    }
+                }
+                Operation::Ratchet => safe
+                    .ratchet()
+                    .map_err(|error| format!("Operation {i}: {error}"))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Run `verify` on the transcript of the vector, checking that it accepts,
+    /// and that it performs the same operations on the sponge as the prover.
+    ///
+    /// Return a description of the first mismatch.
+    pub fn verify<H, U>(
+        &self,
+        io_pattern: &IOPattern<H, U>,
+        verify: impl FnOnce(&mut Arthur<'_, H, U>) -> ProofResult<()>,
+    ) -> Result<(), String>
+    where
+        H: DuplexHash<U>,
+        U: Unit,
+    {
+        self.check_io_pattern(io_pattern)?;
+        let mut arthur = io_pattern.to_arthur(&self.transcript);
+        arthur.safe.start_recording();
+        verify(&mut arthur).map_err(|error| format!("The transcript is rejected: {error}"))?;
+        // the recording was started above
+        let operations = merge(arthur.safe.recording().unwrap());
+        let expected = merge(&self.operations);
+        if let Some(i) = (0..expected.len().max(operations.len()))
+            .find(|&i| expected.get(i) != operations.get(i))
+        {
+            return Err(format!("Operation {i} differs"));
+        }
+        Ok(())
+    }
+
+    /// Encode the vector as a JSON fixture.
+    ///
+    /// Bytes are hex-encoded.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        // serializing plain data never fails
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Decode a JSON fixture written with [`TestVector::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|error| error.to_string())
+    }
+}